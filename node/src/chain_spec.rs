@@ -1,9 +1,7 @@
 use sc_service::ChainType;
-use solochain_template_runtime::{AccountId, Signature, WASM_BINARY};
-use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_consensus_grandpa::AuthorityId as GrandpaId;
-use sp_core::{sr25519, Pair, Public};
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use serde_json::Value;
+use solochain_template_runtime::{genesis_config_presets, WASM_BINARY};
+use std::path::Path;
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -11,107 +9,53 @@ use sp_runtime::traits::{IdentifyAccount, Verify};
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec;
 
-/// Generate a crypto pair from seed.
-pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
-	TPublic::Pair::from_string(&format!("//{}", seed), None)
-		.expect("static values are valid; qed")
-		.public()
-}
-
-type AccountPublic = <Signature as Verify>::Signer;
-
-/// Generate an account ID from seed.
-pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId
-where
-	AccountPublic: From<<TPublic::Pair as Pair>::Public>,
-{
-	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
-}
-
-/// Generate an Aura authority key.
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
-	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
-}
-
-pub fn development_config() -> Result<ChainSpec, String> {
-	Ok(ChainSpec::builder(
+/// Development chain seeded with the knowledge base at `knowledge_base`, or the demo one.
+///
+/// The genesis config comes from the runtime's `development` preset. A knowledge base replaces
+/// its `templateModule` section, in the format of
+/// [`genesis_config_presets::DEMO_KNOWLEDGE_BASE`], and can only reference the demo models.
+pub fn development_config(knowledge_base: Option<&Path>) -> Result<ChainSpec, String> {
+	let builder = ChainSpec::builder(
 		WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?,
 		None,
 	)
 	.with_name("Development")
 	.with_id("dev")
-	.with_chain_type(ChainType::Development)
-	.with_genesis_config_patch(testnet_genesis(
-		// Initial PoA authorities
-		vec![authority_keys_from_seed("Alice")],
-		// Sudo account
-		get_account_id_from_seed::<sr25519::Public>("Alice"),
-		// Pre-funded accounts
-		vec![
-			get_account_id_from_seed::<sr25519::Public>("Alice"),
-			get_account_id_from_seed::<sr25519::Public>("Bob"),
-			get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-		],
-		true,
-	))
+	.with_chain_type(ChainType::Development);
+	let preset = sp_genesis_builder::DEV_RUNTIME_PRESET;
+	Ok(match knowledge_base {
+		Some(path) => builder.with_genesis_config_patch(seeded_genesis(preset, path)?),
+		None => builder.with_genesis_config_preset_name(preset),
+	}
 	.build())
 }
 
-pub fn local_testnet_config() -> Result<ChainSpec, String> {
-	Ok(ChainSpec::builder(
+/// Local testnet seeded with the knowledge base at `knowledge_base`, or the demo one.
+pub fn local_testnet_config(knowledge_base: Option<&Path>) -> Result<ChainSpec, String> {
+	let builder = ChainSpec::builder(
 		WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?,
 		None,
 	)
 	.with_name("Local Testnet")
 	.with_id("local_testnet")
-	.with_chain_type(ChainType::Local)
-	.with_genesis_config_patch(testnet_genesis(
-		// Initial PoA authorities
-		vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
-		// Sudo account
-		get_account_id_from_seed::<sr25519::Public>("Alice"),
-		// Pre-funded accounts
-		vec![
-			get_account_id_from_seed::<sr25519::Public>("Alice"),
-			get_account_id_from_seed::<sr25519::Public>("Bob"),
-			get_account_id_from_seed::<sr25519::Public>("Charlie"),
-			get_account_id_from_seed::<sr25519::Public>("Dave"),
-			get_account_id_from_seed::<sr25519::Public>("Eve"),
-			get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-			get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-		],
-		true,
-	))
+	.with_chain_type(ChainType::Local);
+	let preset = sp_genesis_builder::LOCAL_TESTNET_RUNTIME_PRESET;
+	Ok(match knowledge_base {
+		Some(path) => builder.with_genesis_config_patch(seeded_genesis(preset, path)?),
+		None => builder.with_genesis_config_preset_name(preset),
+	}
 	.build())
 }
 
-/// Configure initial storage state for FRAME modules.
-fn testnet_genesis(
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
-	root_key: AccountId,
-	endowed_accounts: Vec<AccountId>,
-	_enable_println: bool,
-) -> serde_json::Value {
-	serde_json::json!({
-		"balances": {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			"balances": endowed_accounts.iter().cloned().map(|k| (k, 1u64 << 60)).collect::<Vec<_>>(),
-		},
-		"aura": {
-			"authorities": initial_authorities.iter().map(|x| (x.0.clone())).collect::<Vec<_>>(),
-		},
-		"grandpa": {
-			"authorities": initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect::<Vec<_>>(),
-		},
-		"sudo": {
-			// Assign network admin rights.
-			"key": Some(root_key),
-		},
-	})
+/// Genesis config of the runtime preset `preset`, with the memory pool seeded from the
+/// knowledge base in the JSON file at `path`.
+fn seeded_genesis(preset: &str, path: &Path) -> Result<Value, String> {
+	let genesis = genesis_config_presets::get_preset(&preset.into())
+		.ok_or_else(|| format!("Genesis preset {preset} not found"))?;
+	let mut genesis: Value = serde_json::from_slice(&genesis).map_err(|e| e.to_string())?;
+	let knowledge_base = std::fs::read(path)
+		.map_err(|e| format!("Cannot read knowledge base {}: {e}", path.display()))?;
+	genesis["templateModule"] = serde_json::from_slice(&knowledge_base)
+		.map_err(|e| format!("Invalid knowledge base {}: {e}", path.display()))?;
+	Ok(genesis)
 }
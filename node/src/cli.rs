@@ -7,6 +7,14 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	#[clap(flatten)]
+	pub roost: crate::inference::RoostParams,
+
+	/// JSON file holding the `templateModule` genesis config to seed the memory pool of the
+	/// `dev` and `local` chains with, instead of the demo knowledge base.
+	#[arg(long, global = true, value_name = "PATH")]
+	pub knowledge_base: Option<std::path::PathBuf>,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -0,0 +1,115 @@
+//! Models run by an inference server.
+//!
+//! Every request is a JSON `POST` to the URL of the model:
+//!
+//! - text embeddings send `{"input": text}` and expect `{"embedding": [f32, ...]}`, as the
+//!   offchain worker of `pallet-embedding` does;
+//! - image embeddings send `{"image": "0x..."}`, the hex encoded image, and expect the same;
+//! - completions send `{"prompt": prompt, "maxTokens": n}` and expect `{"completion": text}`.
+
+use std::time::Duration;
+
+use futures::{future::BoxFuture, FutureExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp_core::Bytes;
+
+use super::{Backend, Error, Input, ModelKind, Output};
+
+/// Seconds an inference server has to answer by default.
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// A model run by an inference server.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct HttpConfig {
+	/// URL the requests for the model are posted to.
+	pub url: String,
+	/// Seconds the server has to answer, 60 by default.
+	pub timeout_secs: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Request<'a> {
+	Text {
+		input: &'a str,
+	},
+	Image {
+		image: Bytes,
+	},
+	Prompt {
+		prompt: &'a str,
+		#[serde(rename = "maxTokens")]
+		max_tokens: u32,
+	},
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+	embedding: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct CompletionResponse {
+	completion: String,
+}
+
+/// Posts the requests for a model to an inference server.
+pub struct HttpBackend {
+	client: reqwest::Client,
+	kind: ModelKind,
+	url: String,
+	timeout: Duration,
+}
+
+impl HttpBackend {
+	/// Post the requests for a model of `kind` to the server of `config`.
+	pub fn new(kind: ModelKind, config: &HttpConfig) -> Self {
+		Self {
+			client: reqwest::Client::new(),
+			kind,
+			url: config.url.clone(),
+			timeout: Duration::from_secs(config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS)),
+		}
+	}
+
+	async fn post<T: DeserializeOwned>(&self, request: &Request<'_>) -> Result<T, Error> {
+		self.client
+			.post(&self.url)
+			.timeout(self.timeout)
+			.json(request)
+			.send()
+			.await
+			.and_then(reqwest::Response::error_for_status)
+			.map_err(|e| Error::Backend(e.to_string()))?
+			.json()
+			.await
+			.map_err(|e| Error::Backend(e.to_string()))
+	}
+
+	async fn run(&self, input: &Input) -> Result<Output, Error> {
+		debug_assert_eq!(input.kind(), self.kind);
+		match input {
+			Input::Text(text) => {
+				let response: EmbeddingResponse = self.post(&Request::Text { input: text }).await?;
+				Ok(Output::Embedding(response.embedding))
+			},
+			Input::Image(image) => {
+				let request = Request::Image { image: image.clone().into() };
+				let response: EmbeddingResponse = self.post(&request).await?;
+				Ok(Output::Embedding(response.embedding))
+			},
+			Input::Prompt { prompt, max_tokens } => {
+				let request = Request::Prompt { prompt, max_tokens: *max_tokens };
+				let response: CompletionResponse = self.post(&request).await?;
+				Ok(Output::Completion(response.completion))
+			},
+		}
+	}
+}
+
+impl Backend for HttpBackend {
+	fn infer<'a>(&'a self, input: &'a Input) -> BoxFuture<'a, Result<Output, Error>> {
+		self.run(input).boxed()
+	}
+}
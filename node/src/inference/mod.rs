@@ -0,0 +1,341 @@
+//! Roost, the inference service of the node.
+//!
+//! Agents embed text and images and complete prompts with the models configured on their node,
+//! over the `roost_*` RPC methods or, from offchain workers, the host functions of
+//! `roost-primitives`. Each model is served by a backend of its own:
+//!
+//! - [`onnx`] runs ONNX embedding models on the CPU of the node;
+//! - [`http`] forwards requests to an inference server, such as a model runner on the same host.
+//!
+//! Requests are queued to a single task spawned by the service, so that inference never competes
+//! with block production for more than one thread.
+
+pub mod http;
+pub mod onnx;
+
+use std::{collections::HashMap, fmt, path::PathBuf, sync::Arc};
+
+use futures::{
+	channel::{mpsc, oneshot},
+	future::BoxFuture,
+	SinkExt, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+
+/// Requests waiting for the service before callers are made to wait.
+const QUEUE_SIZE: usize = 64;
+
+/// Largest completion served when callers set no limit.
+pub const DEFAULT_MAX_TOKENS: u32 = 256;
+
+/// Models served by the node, as read from the file given with `--roost-config`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Config {
+	/// Models by the name requests refer to them with. The first model of each kind serves the
+	/// requests naming no model.
+	pub models: Vec<ModelConfig>,
+}
+
+impl Config {
+	/// Read the configuration from the JSON file at `path`.
+	pub fn load(path: &std::path::Path) -> Result<Self, String> {
+		let file = std::fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+		serde_json::from_slice(&file).map_err(|e| format!("{}: {e}", path.display()))
+	}
+}
+
+/// A model served by the node.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ModelConfig {
+	/// Name requests refer to the model with.
+	pub name: String,
+	/// What the model does.
+	pub kind: ModelKind,
+	/// How the model is run.
+	pub backend: BackendConfig,
+}
+
+/// What a model does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModelKind {
+	/// Embeds text.
+	TextEmbedding,
+	/// Embeds images.
+	ImageEmbedding,
+	/// Completes prompts.
+	Completion,
+}
+
+/// How a model is run.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub enum BackendConfig {
+	/// On the CPU of the node.
+	Onnx(onnx::OnnxConfig),
+	/// By an inference server.
+	Http(http::HttpConfig),
+}
+
+/// A model served by the node, as listed by `roost_models`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInfo {
+	/// Name requests refer to the model with.
+	pub name: String,
+	/// What the model does.
+	pub kind: ModelKind,
+	/// Whether the model serves the requests of its kind naming no model.
+	pub default: bool,
+}
+
+/// What a model is asked for.
+#[derive(Clone, Debug)]
+pub enum Input {
+	/// The embedding of a text.
+	Text(String),
+	/// The embedding of an encoded image, such as a PNG or a JPEG.
+	Image(Vec<u8>),
+	/// The completion of a prompt.
+	Prompt {
+		/// Text to complete.
+		prompt: String,
+		/// Most tokens to complete it with.
+		max_tokens: u32,
+	},
+}
+
+impl Input {
+	/// Kind of the models answering this input.
+	fn kind(&self) -> ModelKind {
+		match self {
+			Input::Text(_) => ModelKind::TextEmbedding,
+			Input::Image(_) => ModelKind::ImageEmbedding,
+			Input::Prompt { .. } => ModelKind::Completion,
+		}
+	}
+}
+
+/// What a model answers with.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Output {
+	/// An embedding.
+	Embedding(Vec<f32>),
+	/// A completion.
+	Completion(String),
+}
+
+/// Runs models.
+pub trait Backend: Send + Sync {
+	/// Answer `input`, whose kind is the kind of the model the backend runs.
+	fn infer<'a>(&'a self, input: &'a Input) -> BoxFuture<'a, Result<Output, Error>>;
+}
+
+/// Why a request failed.
+#[derive(Debug)]
+pub enum Error {
+	/// No model has the requested name.
+	UnknownModel(String),
+	/// No model of the kind is configured.
+	NoModel(ModelKind),
+	/// The model is of another kind than the request.
+	WrongKind(String, ModelKind),
+	/// The backend failed to run the model.
+	Backend(String),
+	/// The service is shutting down.
+	Stopped,
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::UnknownModel(name) => write!(f, "no model named {name}"),
+			Error::NoModel(kind) => write!(f, "no {kind:?} model is configured"),
+			Error::WrongKind(name, kind) => write!(f, "{name} is not a {kind:?} model"),
+			Error::Backend(e) => write!(f, "inference failed: {e}"),
+			Error::Stopped => write!(f, "the inference service is stopped"),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+/// A request and the channel to answer it on.
+type Request = (Option<String>, Input, oneshot::Sender<Result<Output, Error>>);
+
+/// A model and its backend.
+struct Model {
+	kind: ModelKind,
+	backend: Box<dyn Backend>,
+}
+
+/// Models by name, and the names of the default model of each kind.
+struct Models {
+	models: HashMap<String, Model>,
+	defaults: HashMap<ModelKind, String>,
+}
+
+/// Answers the requests made through its [`InferenceHandle`]s.
+pub struct InferenceService {
+	models: Models,
+	requests: mpsc::Receiver<Request>,
+}
+
+/// Makes requests to the [`InferenceService`].
+#[derive(Clone)]
+pub struct InferenceHandle {
+	models: Arc<Vec<ModelInfo>>,
+	requests: mpsc::Sender<Request>,
+}
+
+impl InferenceService {
+	/// Load the models of `config`, returning the service and a handle to make requests to it.
+	pub fn new(config: &Config) -> Result<(Self, InferenceHandle), String> {
+		let mut models = HashMap::new();
+		let mut defaults = HashMap::new();
+		let mut infos = Vec::new();
+		for model in &config.models {
+			let backend: Box<dyn Backend> = match &model.backend {
+				BackendConfig::Onnx(onnx) => Box::new(
+					onnx::OnnxBackend::load(model.kind, onnx)
+						.map_err(|e| format!("model {}: {e}", model.name))?,
+				),
+				BackendConfig::Http(http) => Box::new(http::HttpBackend::new(model.kind, http)),
+			};
+			if models.insert(model.name.clone(), Model { kind: model.kind, backend }).is_some() {
+				return Err(format!("model {} is configured twice", model.name));
+			}
+			let default = !defaults.contains_key(&model.kind);
+			if default {
+				defaults.insert(model.kind, model.name.clone());
+			}
+			infos.push(ModelInfo { name: model.name.clone(), kind: model.kind, default });
+		}
+
+		let (sender, requests) = mpsc::channel(QUEUE_SIZE);
+		let handle = InferenceHandle { models: Arc::new(infos), requests: sender };
+		Ok((Self { models: Models { models, defaults }, requests }, handle))
+	}
+
+	/// Answer requests one at a time until every handle is dropped.
+	pub async fn run(self) {
+		let Self { models, mut requests } = self;
+		while let Some((model, input, reply)) = requests.next().await {
+			let output = models.infer(model, &input).await;
+			if let Err(e) = &output {
+				log::debug!(target: "roost", "Inference failed: {e}");
+			}
+			let _ = reply.send(output);
+		}
+	}
+}
+
+impl Models {
+	/// Answer `input` with `model`, or the default model of its kind.
+	async fn infer(&self, model: Option<String>, input: &Input) -> Result<Output, Error> {
+		let kind = input.kind();
+		let name = match model {
+			Some(name) => name,
+			None => self.defaults.get(&kind).cloned().ok_or(Error::NoModel(kind))?,
+		};
+		let model = self.models.get(&name).ok_or_else(|| Error::UnknownModel(name.clone()))?;
+		if model.kind != kind {
+			return Err(Error::WrongKind(name, kind));
+		}
+		model.backend.infer(input).await
+	}
+}
+
+impl InferenceHandle {
+	/// Models served by the node.
+	pub fn models(&self) -> &[ModelInfo] {
+		&self.models
+	}
+
+	/// Embedding of `text` by `model`, or the default text embedding model.
+	pub async fn embed_text(&self, model: Option<String>, text: String) -> Result<Vec<f32>, Error> {
+		match self.request(model, Input::Text(text)).await? {
+			Output::Embedding(embedding) => Ok(embedding),
+			Output::Completion(_) => Err(Error::Backend("not an embedding".into())),
+		}
+	}
+
+	/// Embedding of the encoded `image` by `model`, or the default image embedding model.
+	pub async fn embed_image(
+		&self,
+		model: Option<String>,
+		image: Vec<u8>,
+	) -> Result<Vec<f32>, Error> {
+		match self.request(model, Input::Image(image)).await? {
+			Output::Embedding(embedding) => Ok(embedding),
+			Output::Completion(_) => Err(Error::Backend("not an embedding".into())),
+		}
+	}
+
+	/// Completion of `prompt` by `model`, or the default completion model.
+	pub async fn complete(
+		&self,
+		model: Option<String>,
+		prompt: String,
+		max_tokens: u32,
+	) -> Result<String, Error> {
+		match self.request(model, Input::Prompt { prompt, max_tokens }).await? {
+			Output::Completion(completion) => Ok(completion),
+			Output::Embedding(_) => Err(Error::Backend("not a completion".into())),
+		}
+	}
+
+	async fn request(&self, model: Option<String>, input: Input) -> Result<Output, Error> {
+		let (reply, output) = oneshot::channel();
+		self.requests
+			.clone()
+			.send((model, input, reply))
+			.await
+			.map_err(|_| Error::Stopped)?;
+		output.await.map_err(|_| Error::Stopped)?
+	}
+}
+
+/// Offchain workers block on the service while it answers them.
+impl roost_primitives::Inference for InferenceHandle {
+	fn embed_text(&self, model: Option<&str>, text: &str) -> Result<Vec<f32>, String> {
+		futures::executor::block_on(self.embed_text(model.map(Into::into), text.into()))
+			.map_err(|e| e.to_string())
+	}
+
+	fn embed_image(&self, model: Option<&str>, image: &[u8]) -> Result<Vec<f32>, String> {
+		futures::executor::block_on(self.embed_image(model.map(Into::into), image.to_vec()))
+			.map_err(|e| e.to_string())
+	}
+
+	fn complete(
+		&self,
+		model: Option<&str>,
+		prompt: &str,
+		max_tokens: u32,
+	) -> Result<String, String> {
+		futures::executor::block_on(self.complete(model.map(Into::into), prompt.into(), max_tokens))
+			.map_err(|e| e.to_string())
+	}
+}
+
+/// Path of the configuration of the inference service.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct RoostParams {
+	/// JSON file listing the models the Roost inference service runs. Without it, the service
+	/// answers every request with an error.
+	#[arg(long, value_name = "PATH")]
+	pub roost_config: Option<PathBuf>,
+}
+
+impl RoostParams {
+	/// The configuration of the inference service.
+	pub fn config(&self) -> Result<Config, String> {
+		self.roost_config
+			.as_deref()
+			.map(Config::load)
+			.unwrap_or_else(|| Ok(Config::default()))
+	}
+}
@@ -0,0 +1,158 @@
+//! Embedding models run on the CPU of the node.
+//!
+//! Text models are sentence encoders in the layout of Hugging Face exports: they take the
+//! `input_ids`, `attention_mask` and optionally `token_type_ids` of a single text and output the
+//! hidden state of every token, mean pooled over the attention mask. Image models take a
+//! normalized `1x3xSxS` RGB image and output its embedding, as CLIP vision encoders do. Both are
+//! normalized to unit length. Completion is left to [`super::http`] backends.
+
+use std::path::PathBuf;
+
+use futures::{future::BoxFuture, FutureExt};
+use image::imageops::FilterType;
+use serde::Deserialize;
+use tokenizers::Tokenizer;
+use tract_onnx::prelude::*;
+
+use super::{Backend, Error, Input, ModelKind, Output};
+
+/// Side in pixels images are resized to by default.
+const DEFAULT_IMAGE_SIZE: u32 = 224;
+
+/// Per channel mean and standard deviation images are normalized with, those of CLIP.
+const IMAGE_MEAN: [f32; 3] = [0.481_454_67, 0.457_827_5, 0.408_210_72];
+const IMAGE_STD: [f32; 3] = [0.268_629_55, 0.261_302_6, 0.275_777_1];
+
+/// An embedding model run on the CPU.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct OnnxConfig {
+	/// ONNX file of the model.
+	pub model: PathBuf,
+	/// `tokenizer.json` of text models.
+	pub tokenizer: Option<PathBuf>,
+	/// Side in pixels images are resized to, 224 by default.
+	pub image_size: Option<u32>,
+}
+
+/// Runs an embedding model with `tract`.
+pub struct OnnxBackend {
+	model: TypedRunnableModel<TypedModel>,
+	preprocessing: Preprocessing,
+}
+
+/// How inputs are turned into tensors.
+enum Preprocessing {
+	Text { tokenizer: Box<Tokenizer>, token_types: bool },
+	Image { size: u32 },
+}
+
+fn backend_error(e: impl ToString) -> Error {
+	Error::Backend(e.to_string())
+}
+
+impl OnnxBackend {
+	/// Load the model of `config`, which must be an embedding model.
+	pub fn load(kind: ModelKind, config: &OnnxConfig) -> Result<Self, String> {
+		let model = tract_onnx::onnx().model_for_path(&config.model).map_err(|e| e.to_string())?;
+		let inputs = model.inputs.len();
+		let model = model
+			.into_optimized()
+			.and_then(|model| model.into_runnable())
+			.map_err(|e| e.to_string())?;
+
+		let preprocessing = match kind {
+			ModelKind::TextEmbedding => {
+				let path = config.tokenizer.as_ref().ok_or("text models need a tokenizer")?;
+				let tokenizer = Tokenizer::from_file(path).map_err(|e| e.to_string())?;
+				Preprocessing::Text { tokenizer: Box::new(tokenizer), token_types: inputs > 2 }
+			},
+			ModelKind::ImageEmbedding => Preprocessing::Image {
+				size: config.image_size.unwrap_or(DEFAULT_IMAGE_SIZE),
+			},
+			ModelKind::Completion => return Err("ONNX models cannot complete prompts".into()),
+		};
+		Ok(Self { model, preprocessing })
+	}
+
+	fn run(&self, input: &Input) -> Result<Output, Error> {
+		let embedding = match (&self.preprocessing, input) {
+			(Preprocessing::Text { tokenizer, token_types }, Input::Text(text)) =>
+				self.embed_text(tokenizer, *token_types, text)?,
+			(Preprocessing::Image { size }, Input::Image(image)) =>
+				self.embed_image(*size, image)?,
+			_ => return Err(Error::Backend("unsupported input".into())),
+		};
+		Ok(Output::Embedding(normalize(embedding)))
+	}
+
+	fn embed_text(
+		&self,
+		tokenizer: &Tokenizer,
+		token_types: bool,
+		text: &str,
+	) -> Result<Vec<f32>, Error> {
+		let encoding = tokenizer.encode(text, true).map_err(backend_error)?;
+		let len = encoding.len();
+		let tensor = |values: &[u32]| -> TValue {
+			tract_ndarray::Array2::from_shape_fn((1, len), |(_, i)| values[i] as i64)
+				.into_tensor()
+				.into()
+		};
+		let mut inputs = tvec![tensor(encoding.get_ids()), tensor(encoding.get_attention_mask())];
+		if token_types {
+			inputs.push(tensor(encoding.get_type_ids()));
+		}
+
+		let outputs = self.model.run(inputs).map_err(backend_error)?;
+		let hidden = outputs[0]
+			.to_array_view::<f32>()
+			.map_err(backend_error)?
+			.into_dimensionality::<tract_ndarray::Ix3>()
+			.map_err(backend_error)?;
+		// Mean of the hidden states of the tokens attended to.
+		let mask = encoding.get_attention_mask();
+		let attended = mask.iter().filter(|m| **m == 1).count().max(1) as f32;
+		let embedding = (0..hidden.shape()[2])
+			.map(|d| {
+				(0..len).filter(|t| mask[*t] == 1).map(|t| hidden[[0, t, d]]).sum::<f32>() /
+					attended
+			})
+			.collect();
+		Ok(embedding)
+	}
+
+	fn embed_image(&self, size: u32, image: &[u8]) -> Result<Vec<f32>, Error> {
+		let image = image::load_from_memory(image)
+			.map_err(backend_error)?
+			.resize_exact(size, size, FilterType::Triangle)
+			.to_rgb8();
+		let pixels = tract_ndarray::Array4::from_shape_fn(
+			(1, 3, size as usize, size as usize),
+			|(_, c, y, x)| {
+				let value = image.get_pixel(x as u32, y as u32)[c] as f32 / 255.0;
+				(value - IMAGE_MEAN[c]) / IMAGE_STD[c]
+			},
+		);
+
+		let outputs = self.model.run(tvec![pixels.into_tensor().into()]).map_err(backend_error)?;
+		let embedding = outputs[0].to_array_view::<f32>().map_err(backend_error)?;
+		Ok(embedding.iter().copied().collect())
+	}
+}
+
+/// `embedding` scaled to unit length.
+fn normalize(mut embedding: Vec<f32>) -> Vec<f32> {
+	let norm = embedding.iter().map(|c| c * c).sum::<f32>().sqrt();
+	if norm > 0.0 {
+		embedding.iter_mut().for_each(|c| *c /= norm);
+	}
+	embedding
+}
+
+impl Backend for OnnxBackend {
+	fn infer<'a>(&'a self, input: &'a Input) -> BoxFuture<'a, Result<Output, Error>> {
+		// Models run on the thread of the service, which is not shared with other tasks.
+		futures::future::ready(self.run(input)).boxed()
+	}
+}
@@ -0,0 +1,253 @@
+//! `memory_subscribe`, a stream of the memories stored and pruned by the template pallet.
+//!
+//! Memories are read from the events of finalized blocks, so the stream is never reverted, and
+//! each is sent along with the metadata of its vector as typed JSON. Subscribers can narrow the
+//! stream down by author, tag or collection, and replay it from a past block to catch up with
+//! what they missed. A replay reads every block it goes through, so one starting more than
+//! [`MAX_SAFE_REPLAY`] blocks behind the last finalized block is unsafe: it is only served on
+//! local interfaces unless the node is started with `--rpc-methods unsafe`.
+//!
+//! Events are decoded with the types of the runtime the node was built with. Blocks whose events
+//! cannot be read, as they were emitted by another runtime version or their state was pruned, are
+//! streamed as [`MemoryNotification::MissedBlock`] for subscribers to know what they lack.
+
+use std::sync::Arc;
+
+use codec::Decode;
+use futures::{
+	future::{self, Either},
+	StreamExt,
+};
+use jsonrpsee::{
+	core::SubscriptionResult, proc_macros::rpc, types::ErrorObjectOwned, Extensions,
+	PendingSubscriptionSink, SubscriptionMessage,
+};
+use pallet_template_proof::VectorRecord;
+use sc_client_api::{BlockchainEvents, StorageKey, StorageProvider};
+use sc_rpc_api::check_if_safe;
+use serde::{Deserialize, Serialize};
+use solochain_template_runtime::{opaque::Block, AccountId, BlockNumber, Hash, RuntimeEvent};
+use sp_blockchain::HeaderBackend;
+use sp_core::{hashing::twox_128, Bytes, H256};
+use sp_runtime::traits::{BlakeTwo256, Hash as HashT};
+
+/// Blocks behind the last finalized block a replay can start from without being unsafe.
+pub const MAX_SAFE_REPLAY: BlockNumber = 256;
+
+/// Which memories to stream. A memory is streamed when it matches every field that is set.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct MemoryFilter {
+	/// Account that stored the memory.
+	pub author: Option<AccountId>,
+	/// Tag attached to the memory, as given when storing it.
+	pub tag: Option<Bytes>,
+	/// Collection the memory belongs to.
+	pub collection: Option<u32>,
+}
+
+/// What happened to a memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MemoryEventKind {
+	/// The memory was stored.
+	Stored,
+	/// The memory was pruned.
+	Pruned,
+}
+
+/// A memory stored or pruned in a finalized block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryEvent {
+	/// What happened to the memory.
+	pub kind: MemoryEventKind,
+	/// Number of the block the event was emitted in.
+	pub block_number: BlockNumber,
+	/// Hash of the block the event was emitted in.
+	pub block_hash: Hash,
+	/// Id of the vector of the memory.
+	pub vector_id: H256,
+	/// Account that stored the memory.
+	pub author: AccountId,
+	/// Ids of the tags attached to the memory.
+	pub tags: Vec<H256>,
+	/// Collection the memory belongs to.
+	pub collection: Option<u32>,
+	/// Encoded components of the vector, `None` if the state of the block is no longer
+	/// available or the vector was pruned in the block it was stored in.
+	pub data: Option<Bytes>,
+}
+
+/// An item of the memory stream.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MemoryNotification {
+	/// A memory matching the filter of the subscription.
+	Memory(MemoryEvent),
+	/// A finalized block whose memories could not be read, whatever the filter.
+	#[serde(rename_all = "camelCase")]
+	MissedBlock {
+		/// Number of the block.
+		block_number: BlockNumber,
+		/// Hash of the block, `None` if the node does not have its header.
+		block_hash: Option<Hash>,
+	},
+}
+
+/// RPC methods streaming memories.
+#[rpc(server)]
+pub trait MemoryApi {
+	/// Stream the memories matching `filter` stored or pruned in finalized blocks, starting with
+	/// the ones of finalized block `from` onwards if given, or else with the next finalized block.
+	#[subscription(
+		name = "memory_subscribe" => "memory_event",
+		unsubscribe = "memory_unsubscribe",
+		item = MemoryNotification,
+		with_extensions
+	)]
+	async fn subscribe(
+		&self,
+		filter: Option<MemoryFilter>,
+		from: Option<BlockNumber>,
+	) -> SubscriptionResult;
+}
+
+/// Streams the memories of the template pallet named `pallet_name` in the runtime.
+pub struct Memory<C, B> {
+	client: Arc<C>,
+	pallet_name: &'static [u8],
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Memory<C, B> {
+	/// Stream the memories of the template pallet named `pallet_name` from `client`.
+	pub fn new(client: Arc<C>, pallet_name: &'static [u8]) -> Self {
+		Self { client, pallet_name, _marker: Default::default() }
+	}
+}
+
+#[jsonrpsee::core::async_trait]
+impl<C, B> MemoryApiServer for Memory<C, B>
+where
+	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
+	C: HeaderBackend<Block> + BlockchainEvents<Block> + StorageProvider<Block, B>,
+	C: Send + Sync + 'static,
+{
+	async fn subscribe(
+		&self,
+		pending: PendingSubscriptionSink,
+		ext: &Extensions,
+		filter: Option<MemoryFilter>,
+		from: Option<BlockNumber>,
+	) -> SubscriptionResult {
+		let finalized_number = self.client.info().finalized_number;
+		if from.is_some_and(|from| from.saturating_add(MAX_SAFE_REPLAY) < finalized_number) {
+			if let Err(e) = check_if_safe(ext) {
+				pending.reject(ErrorObjectOwned::from(e)).await;
+				return Ok(());
+			}
+		}
+
+		let filter = filter.unwrap_or_default();
+		// Listen before replaying, so that no block is finalized unseen in between.
+		let mut finalized = self.client.finality_notification_stream();
+		let sink = pending.accept().await?;
+
+		let mut next = from.unwrap_or(finalized_number + 1);
+		loop {
+			// Notifications can be coalesced, so blocks are walked by number up to the last
+			// finalized one.
+			let last = self.client.info().finalized_number;
+			while next <= last {
+				for notification in self.block_notifications(next)? {
+					if filter.matches(&notification) {
+						sink.send(SubscriptionMessage::from_json(&notification)?).await?;
+					}
+				}
+				next += 1;
+			}
+
+			match future::select(Box::pin(sink.closed()), finalized.next()).await {
+				Either::Left(_) | Either::Right((None, _)) => return Ok(()),
+				Either::Right((Some(_), _)) => {},
+			}
+		}
+	}
+}
+
+impl<C, B> Memory<C, B>
+where
+	B: sc_client_api::Backend<Block>,
+	C: HeaderBackend<Block> + StorageProvider<Block, B>,
+{
+	/// Memories stored or pruned in finalized block `number`, or the block as missed if its
+	/// events cannot be read.
+	fn block_notifications(
+		&self,
+		number: BlockNumber,
+	) -> sp_blockchain::Result<Vec<MemoryNotification>> {
+		let missed = |block_hash| -> sp_blockchain::Result<Vec<MemoryNotification>> {
+			Ok(vec![MemoryNotification::MissedBlock { block_number: number, block_hash }])
+		};
+		let Some(hash) = self.client.hash(number)? else { return missed(None) };
+		let Some(header) = self.client.header(hash)? else { return missed(None) };
+		let key = [twox_128(b"System"), twox_128(b"Events")].concat();
+		let Some(events) = self.client.storage(hash, &StorageKey(key)).ok().flatten() else {
+			return missed(Some(hash));
+		};
+		let Ok(records) =
+			Vec::<frame_system::EventRecord<RuntimeEvent, Hash>>::decode(&mut &events.0[..])
+		else {
+			return missed(Some(hash));
+		};
+
+		let mut memories = Vec::new();
+		for record in records {
+			// Stored vectors are read after their block, pruned ones before theirs.
+			let (kind, vector_id, author, at) = match record.event {
+				RuntimeEvent::TemplateModule(pallet_template::Event::VectorStored {
+					vector_id,
+					author,
+				}) => (MemoryEventKind::Stored, vector_id, author, hash),
+				RuntimeEvent::TemplateModule(pallet_template::Event::VectorPruned {
+					vector_id,
+					author,
+				}) => (MemoryEventKind::Pruned, vector_id, author, header.parent_hash),
+				_ => continue,
+			};
+			let vector = self.vector(at, &vector_id);
+			memories.push(MemoryNotification::Memory(MemoryEvent {
+				kind,
+				block_number: number,
+				block_hash: hash,
+				vector_id,
+				author,
+				tags: vector.as_ref().map(|v| v.tags.clone()).unwrap_or_default(),
+				collection: vector.as_ref().and_then(|v| v.collection),
+				data: vector.map(|v| v.data.into()),
+			}));
+		}
+		Ok(memories)
+	}
+
+	/// Vector `vector_id` in the state of block `at`, if it is still available.
+	fn vector(&self, at: Hash, vector_id: &H256) -> Option<VectorRecord<AccountId>> {
+		let key = pallet_template_proof::vector_key(self.pallet_name, vector_id);
+		let value = self.client.storage(at, &StorageKey(key)).ok()??;
+		VectorRecord::decode(&mut &value.0[..]).ok()
+	}
+}
+
+impl MemoryFilter {
+	/// Whether `notification` is to be streamed. Memories whose metadata could not be read only
+	/// match filters on their author, missed blocks match every filter.
+	fn matches(&self, notification: &MemoryNotification) -> bool {
+		let MemoryNotification::Memory(event) = notification else { return true };
+		self.author.as_ref().map_or(true, |author| *author == event.author) &&
+			self.tag.as_ref().map_or(true, |tag| {
+				event.tags.contains(&BlakeTwo256::hash_of(&tag.to_vec()))
+			}) &&
+			self.collection.map_or(true, |collection| event.collection == Some(collection))
+	}
+}
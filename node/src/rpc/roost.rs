@@ -0,0 +1,121 @@
+//! `roost_*`, the RPC methods of the inference service of the node.
+//!
+//! Inference takes the CPU of the node, so the methods are unsafe: they are only served on
+//! local interfaces unless the node is started with `--rpc-methods unsafe`.
+
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{ErrorObject, ErrorObjectOwned},
+	Extensions,
+};
+use sc_rpc_api::check_if_safe;
+use sp_core::Bytes;
+
+use crate::inference::{self, InferenceHandle, ModelInfo, DEFAULT_MAX_TOKENS};
+
+/// RPC methods of the inference service.
+#[rpc(server)]
+pub trait RoostApi {
+	/// Models served by the node.
+	#[method(name = "roost_models", with_extensions)]
+	fn models(&self) -> RpcResult<Vec<ModelInfo>>;
+
+	/// Embedding of `text` by `model`, or the default text embedding model.
+	#[method(name = "roost_embedText", with_extensions)]
+	async fn embed_text(&self, text: String, model: Option<String>) -> RpcResult<Vec<f32>>;
+
+	/// Embedding of the encoded `image`, such as a PNG or a JPEG, by `model`, or the default
+	/// image embedding model.
+	#[method(name = "roost_embedImage", with_extensions)]
+	async fn embed_image(&self, image: Bytes, model: Option<String>) -> RpcResult<Vec<f32>>;
+
+	/// Completion of `prompt` by `model`, or the default completion model, of at most
+	/// `max_tokens` tokens.
+	#[method(name = "roost_complete", with_extensions)]
+	async fn complete(
+		&self,
+		prompt: String,
+		max_tokens: Option<u32>,
+		model: Option<String>,
+	) -> RpcResult<String>;
+}
+
+/// Error codes of the inference RPC methods.
+pub enum Error {
+	/// The requested model is not served by the node, or not for this kind of request.
+	UnknownModel,
+	/// The model failed to answer.
+	InferenceFailed,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::UnknownModel => 1,
+			Error::InferenceFailed => 2,
+		}
+	}
+}
+
+fn error(e: inference::Error) -> ErrorObjectOwned {
+	let code = match e {
+		inference::Error::UnknownModel(_) |
+		inference::Error::NoModel(_) |
+		inference::Error::WrongKind(..) => Error::UnknownModel,
+		inference::Error::Backend(_) | inference::Error::Stopped => Error::InferenceFailed,
+	};
+	ErrorObject::owned(code.into(), e.to_string(), None::<()>)
+}
+
+/// Serves the inference service of the node.
+pub struct Roost {
+	inference: InferenceHandle,
+}
+
+impl Roost {
+	/// Serve the inference service behind `inference`.
+	pub fn new(inference: InferenceHandle) -> Self {
+		Self { inference }
+	}
+}
+
+#[jsonrpsee::core::async_trait]
+impl RoostApiServer for Roost {
+	fn models(&self, ext: &Extensions) -> RpcResult<Vec<ModelInfo>> {
+		check_if_safe(ext)?;
+		Ok(self.inference.models().to_vec())
+	}
+
+	async fn embed_text(
+		&self,
+		ext: &Extensions,
+		text: String,
+		model: Option<String>,
+	) -> RpcResult<Vec<f32>> {
+		check_if_safe(ext)?;
+		self.inference.embed_text(model, text).await.map_err(error)
+	}
+
+	async fn embed_image(
+		&self,
+		ext: &Extensions,
+		image: Bytes,
+		model: Option<String>,
+	) -> RpcResult<Vec<f32>> {
+		check_if_safe(ext)?;
+		self.inference.embed_image(model, image.0).await.map_err(error)
+	}
+
+	async fn complete(
+		&self,
+		ext: &Extensions,
+		prompt: String,
+		max_tokens: Option<u32>,
+		model: Option<String>,
+	) -> RpcResult<String> {
+		check_if_safe(ext)?;
+		let max_tokens = max_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
+		self.inference.complete(model, prompt, max_tokens).await.map_err(error)
+	}
+}
@@ -0,0 +1,30 @@
+//! Runtime API of the action log pallet, for clients checking that an agent's action was logged.
+//!
+//! Proofs are checked with `pallet_action_log::mmr::verify` against the root returned by
+//! [`ActionLogApi::root`] at the same block.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_action_log::{mmr::MmrProof, ActionRecord};
+
+sp_api::decl_runtime_apis! {
+	pub trait ActionLogApi<AccountId, Hash, BlockNumber>
+	where
+		AccountId: Codec,
+		Hash: Codec,
+		BlockNumber: Codec,
+	{
+		/// Root of the range of logged actions.
+		fn root() -> Hash;
+
+		/// Number of actions logged for `agent`.
+		fn action_count(agent: AccountId) -> u64;
+
+		/// The action `seq` of `agent`, with a proof of its inclusion against the root.
+		fn prove_action(
+			agent: AccountId,
+			seq: u64,
+		) -> Option<(ActionRecord<AccountId, Hash, BlockNumber>, MmrProof<Hash>)>;
+	}
+}
@@ -0,0 +1,50 @@
+//! Benchmarking setup for pallet-action-log
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as ActionLog;
+use frame_benchmarking::v2::*;
+use frame_support::{traits::Hooks, BoundedVec};
+use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
+
+/// Make the next leaf complete 63 mountains, the most it can.
+fn full_range<T: Config>() {
+	let leaf_count = u64::MAX >> 1;
+	LeafCount::<T>::put(leaf_count);
+	let peaks = (0..leaf_count.count_ones()).map(|_| T::Hash::default()).collect();
+	Peaks::<T>::put(BoundedVec::truncate_from(peaks));
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn log_action() {
+		full_range::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		T::BenchmarkHelper::register_agent(caller.clone());
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), T::Hash::default());
+
+		assert_eq!(ActionCount::<T>::get(&caller), 1);
+		assert_eq!(Peaks::<T>::get().len(), 1);
+	}
+
+	#[benchmark]
+	fn update_root() {
+		full_range::<T>();
+		Appended::<T>::put(true);
+
+		#[block]
+		{
+			ActionLog::<T>::on_finalize(BlockNumberFor::<T>::default());
+		}
+
+		assert!(!Appended::<T>::get());
+	}
+
+	impl_benchmark_test_suite!(ActionLog, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,242 @@
+//! # Action Log Pallet
+//!
+//! An append-only log of what agents did, kept apart from the prunable knowledge pool. Every
+//! action is numbered in the log of its agent and appended as a leaf of a Merkle mountain range
+//! whose root is updated at the end of every block that logged actions, so anyone holding the
+//! root can check that an action was logged with a compact proof.
+//!
+//! ## Overview
+//!
+//! - [`Pallet::log_action`] lets an account of [`Config::Agents`] log an action of its own,
+//!   described by the hash of its content.
+//! - Other pallets log what they witness agents doing, such as completing tasks or losing
+//!   disputes, with [`Pallet::append`].
+//! - Actions are never removed nor changed. Each is stored as an [`ActionRecord`] under its agent
+//!   and sequence number in [`Actions`], and its hash is the leaf at
+//!   [`ActionRecord::leaf_index`] of the range.
+//! - [`Root`] is the root of the range as of the end of the last block that logged actions.
+//!   [`Pallet::prove`] proves the inclusion of an action against it, and [`mmr::verify`] checks
+//!   such a proof. Both are exposed by the runtime API of `pallet-action-log-runtime-api`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod mmr;
+pub mod weights;
+pub use weights::WeightInfo;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// Registers agents for the benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<AccountId> {
+	/// Make `who` an agent.
+	fn register_agent(who: AccountId);
+}
+
+/// What an agent did.
+#[derive(Clone, Copy, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug)]
+pub enum ActionKind<Hash> {
+	/// An action logged by the agent itself, described by the hash of its content.
+	Logged(Hash),
+	/// The agent completed the task with the given identifier.
+	TaskCompleted(u64),
+	/// The agent lost the dispute of the result of the task with the given identifier.
+	DisputeLost(u64),
+}
+
+/// An action in the log.
+#[derive(Clone, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug)]
+pub struct ActionRecord<AccountId, Hash, BlockNumber> {
+	/// Agent the action is about.
+	pub agent: AccountId,
+	/// Number of the action in the log of its agent, starting from zero.
+	pub seq: u64,
+	/// What the agent did.
+	pub kind: ActionKind<Hash>,
+	/// Block the action was logged in.
+	pub block: BlockNumber,
+	/// Index of the action among the leaves of the range.
+	pub leaf_index: u64,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{pallet_prelude::*, traits::Contains};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::{Hash, Saturating};
+
+	/// An action of the log of this runtime.
+	pub type ActionRecordOf<T> = ActionRecord<
+		<T as frame_system::Config>::AccountId,
+		<T as frame_system::Config>::Hash,
+		BlockNumberFor<T>,
+	>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Weight information for the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+		/// Accounts allowed to log actions of their own.
+		type Agents: Contains<Self::AccountId>;
+		/// Registers agents for the benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<Self::AccountId>;
+	}
+
+	/// Number of actions logged for each agent, i.e. the sequence number of its next action.
+	#[pallet::storage]
+	pub type ActionCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u64, ValueQuery>;
+
+	/// Logged actions, by agent and sequence number.
+	#[pallet::storage]
+	pub type Actions<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, u64, ActionRecordOf<T>>;
+
+	/// Number of leaves of the range, i.e. of actions logged.
+	#[pallet::storage]
+	pub type LeafCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// Nodes of the range, by position.
+	#[pallet::storage]
+	pub type Nodes<T: Config> = StorageMap<_, Twox64Concat, u64, T::Hash>;
+
+	/// Peaks of the range, from left to right. There is one for each bit set in [`LeafCount`].
+	#[pallet::storage]
+	pub type Peaks<T: Config> = StorageValue<_, BoundedVec<T::Hash, ConstU32<64>>, ValueQuery>;
+
+	/// Root of the range as of the end of the last block that logged actions.
+	#[pallet::storage]
+	pub type Root<T: Config> = StorageValue<_, T::Hash, ValueQuery>;
+
+	/// Whether actions were logged in the current block.
+	#[pallet::storage]
+	pub type Appended<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An action was logged
+		ActionLogged { agent: T::AccountId, seq: u64, kind: ActionKind<T::Hash>, leaf_index: u64 },
+		/// The root of the range was updated with the actions logged in the block
+		RootUpdated { root: T::Hash, leaf_count: u64 },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Only agents can log actions
+		NotAgent,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_: BlockNumberFor<T>) -> Weight {
+			T::WeightInfo::update_root()
+		}
+
+		fn on_finalize(_: BlockNumberFor<T>) {
+			if Appended::<T>::take() {
+				let root = mmr::bag::<T::Hashing>(&Peaks::<T>::get());
+				Root::<T>::put(root);
+				Self::deposit_event(Event::RootUpdated { root, leaf_count: LeafCount::<T>::get() });
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Log an action of the caller, described by the hash of its content.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::log_action())]
+		pub fn log_action(origin: OriginFor<T>, content: T::Hash) -> DispatchResult {
+			let agent = ensure_signed(origin)?;
+			ensure!(T::Agents::contains(&agent), Error::<T>::NotAgent);
+
+			Self::append(agent, ActionKind::Logged(content));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Append an action of `agent` to the log.
+		pub fn append(agent: T::AccountId, kind: ActionKind<T::Hash>) -> ActionRecordOf<T> {
+			let seq = ActionCount::<T>::mutate(&agent, |count| {
+				let current = *count;
+				*count = count.saturating_add(1);
+				current
+			});
+			let record = ActionRecord {
+				agent: agent.clone(),
+				seq,
+				kind,
+				block: frame_system::Pallet::<T>::block_number(),
+				leaf_index: LeafCount::<T>::get(),
+			};
+			Self::push_leaf(T::Hashing::hash_of(&record));
+			Actions::<T>::insert(&agent, seq, &record);
+			Self::deposit_event(Event::ActionLogged {
+				agent,
+				seq,
+				kind,
+				leaf_index: record.leaf_index,
+			});
+			record
+		}
+
+		/// The action `seq` of `agent`, with a proof of its inclusion against [`Root`].
+		pub fn prove(
+			agent: &T::AccountId,
+			seq: u64,
+		) -> Option<(ActionRecordOf<T>, mmr::MmrProof<T::Hash>)> {
+			let record = Actions::<T>::get(agent, seq)?;
+			let proof = mmr::prove::<T::Hashing>(
+				record.leaf_index,
+				LeafCount::<T>::get(),
+				&Peaks::<T>::get(),
+				Nodes::<T>::get,
+			)?;
+			Some((record, proof))
+		}
+
+		/// Append `leaf` to the range, merging the mountains it completes.
+		fn push_leaf(leaf: T::Hash) {
+			let leaf_index = LeafCount::<T>::get();
+			let mut position = mmr::leaf_position(leaf_index);
+			Nodes::<T>::insert(position, leaf);
+			Peaks::<T>::mutate(|peaks| {
+				let mut node = leaf;
+				// Every trailing one of the index is a mountain of the height of the new one.
+				for _ in 0..leaf_index.trailing_ones() {
+					let Some(left) = peaks.pop() else { break };
+					node = mmr::merge::<T::Hashing>(&left, &node);
+					position.saturating_inc();
+					Nodes::<T>::insert(position, node);
+				}
+				// There is at most one peak per bit of the leaf count.
+				let _ = peaks.try_push(node);
+			});
+			LeafCount::<T>::put(leaf_index.saturating_add(1));
+			Appended::<T>::put(true);
+		}
+	}
+}
@@ -0,0 +1,131 @@
+//! Merkle mountain range over the logged actions.
+//!
+//! Leaves are appended from left to right and every full pair of subtrees is merged right away,
+//! leaving a series of perfect binary trees, the mountains, with decreasing heights. Nodes are
+//! numbered in post-order across mountains, so the parent of a right child directly follows it.
+//! The root is the hash of the peaks, bagged from right to left.
+//!
+//! A proof of inclusion holds the siblings on the path from a leaf to the peak of its mountain
+//! and the other peaks, i.e. at most twice the logarithm of the number of leaves. It is checked
+//! with [`verify`], which only needs the root, the leaf and the proof.
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::Hash, RuntimeDebug};
+
+/// Proof that a leaf is part of a mountain range of `leaf_count` leaves.
+#[derive(Clone, Encode, Decode, TypeInfo, PartialEq, Eq, RuntimeDebug)]
+pub struct MmrProof<Hash> {
+	/// Index of the leaf.
+	pub leaf_index: u64,
+	/// Number of leaves of the range the proof is against.
+	pub leaf_count: u64,
+	/// Siblings of the nodes on the path from the leaf to its peak, bottom up.
+	pub siblings: Vec<Hash>,
+	/// Peaks of the other mountains, from left to right.
+	pub peaks: Vec<Hash>,
+}
+
+/// Parent of `left` and `right`.
+pub fn merge<H: Hash>(left: &H::Output, right: &H::Output) -> H::Output {
+	H::hash_of(&(left, right))
+}
+
+/// Position of the node of leaf `leaf_index`.
+pub fn leaf_position(leaf_index: u64) -> u64 {
+	leaf_index.saturating_mul(2).saturating_sub(leaf_index.count_ones().into())
+}
+
+/// Root of a range with `peaks`, from left to right. The root of an empty range is the default
+/// hash.
+pub fn bag<H: Hash>(peaks: &[H::Output]) -> H::Output {
+	let mut peaks = peaks.iter().rev();
+	let Some(last) = peaks.next() else {
+		return Default::default();
+	};
+	peaks.fold(*last, |bagged, peak| merge::<H>(peak, &bagged))
+}
+
+/// Heights of the mountains of a range of `leaf_count` leaves, from left to right.
+fn heights(leaf_count: u64) -> impl Iterator<Item = u32> {
+	(0..u64::BITS).rev().filter(move |height| leaf_count & (1 << height) != 0)
+}
+
+/// Mountain holding leaf `leaf_index` among `leaf_count` leaves: its index among the peaks, its
+/// height, the position of its first node and the index of its first leaf.
+fn mountain_of(leaf_index: u64, leaf_count: u64) -> Option<(usize, u32, u64, u64)> {
+	let (mut first_position, mut first_leaf) = (0u64, 0u64);
+	for (peak, height) in heights(leaf_count).enumerate() {
+		let leaves = 1u64 << height;
+		if leaf_index < first_leaf.saturating_add(leaves) {
+			return Some((peak, height, first_position, first_leaf));
+		}
+		first_leaf = first_leaf.saturating_add(leaves);
+		first_position = first_position.saturating_add(leaves.saturating_mul(2) - 1);
+	}
+	None
+}
+
+/// Positions of the siblings on the path from leaf `leaf_index` to its peak, bottom up, along
+/// with the index of the peak. `None` if the leaf is not among the `leaf_count` leaves.
+pub fn sibling_positions(leaf_index: u64, leaf_count: u64) -> Option<(usize, Vec<u64>)> {
+	let (peak, height, mut first_position, first_leaf) = mountain_of(leaf_index, leaf_count)?;
+	let mut leaf = leaf_index - first_leaf;
+	let mut siblings = Vec::with_capacity(height as usize);
+	// Walk down from the peak, one subtree at a time.
+	for height in (1..=height).rev() {
+		let leaves = 1u64 << (height - 1);
+		let nodes = leaves * 2 - 1;
+		if leaf < leaves {
+			siblings.push(first_position + 2 * nodes - 1);
+		} else {
+			siblings.push(first_position + nodes - 1);
+			first_position += nodes;
+			leaf -= leaves;
+		}
+	}
+	siblings.reverse();
+	Some((peak, siblings))
+}
+
+/// Prove the inclusion of leaf `leaf_index` in a range of `leaf_count` leaves with `peaks`,
+/// looking its siblings up with `node`.
+pub fn prove<H: Hash>(
+	leaf_index: u64,
+	leaf_count: u64,
+	peaks: &[H::Output],
+	node: impl Fn(u64) -> Option<H::Output>,
+) -> Option<MmrProof<H::Output>> {
+	let (peak, positions) = sibling_positions(leaf_index, leaf_count)?;
+	let siblings = positions.into_iter().map(node).collect::<Option<Vec<_>>>()?;
+	let mut peaks = peaks.to_vec();
+	if peak >= peaks.len() {
+		return None;
+	}
+	peaks.remove(peak);
+	Some(MmrProof { leaf_index, leaf_count, siblings, peaks })
+}
+
+/// Whether `proof` shows that `leaf` is part of the range with `root`.
+pub fn verify<H: Hash>(root: &H::Output, leaf: H::Output, proof: &MmrProof<H::Output>) -> bool {
+	let Some((peak, height, _, first_leaf)) = mountain_of(proof.leaf_index, proof.leaf_count)
+	else {
+		return false;
+	};
+	if proof.siblings.len() != height as usize ||
+		proof.peaks.len() + 1 != heights(proof.leaf_count).count()
+	{
+		return false;
+	}
+
+	let mut index = proof.leaf_index - first_leaf;
+	let mut node = leaf;
+	for sibling in &proof.siblings {
+		node = if index & 1 == 0 { merge::<H>(&node, sibling) } else { merge::<H>(sibling, &node) };
+		index >>= 1;
+	}
+	let mut peaks = proof.peaks.clone();
+	peaks.insert(peak, node);
+	bag::<H>(&peaks) == *root
+}
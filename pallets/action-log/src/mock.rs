@@ -0,0 +1,57 @@
+use crate as pallet_action_log;
+use core::cell::RefCell;
+use frame_support::{derive_impl, traits::Contains};
+use sp_runtime::BuildStorage;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		ActionLog: pallet_action_log,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+}
+
+thread_local! {
+	static REGISTERED: RefCell<Vec<u64>> = RefCell::new(Vec::new());
+}
+
+/// Accounts below 10, and those registered for the benchmarks.
+pub struct Agents;
+
+impl Contains<u64> for Agents {
+	fn contains(who: &u64) -> bool {
+		*who < 10 || REGISTERED.with(|r| r.borrow().contains(who))
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkHelper<u64> for Agents {
+	fn register_agent(who: u64) {
+		REGISTERED.with(|r| r.borrow_mut().push(who));
+	}
+}
+
+impl pallet_action_log::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Agents = Agents;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = Agents;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	REGISTERED.with(|r| r.borrow_mut().clear());
+	let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	// Events are not emitted on the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,171 @@
+use crate::{mmr::*, mock::*, *};
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+const AGENT: u64 = 1;
+const OTHER: u64 = 2;
+
+fn content(i: u64) -> H256 {
+	BlakeTwo256::hash_of(&i)
+}
+
+/// Log `count` actions of `agent` and end the block.
+fn log(agent: u64, count: u64) {
+	for i in 0..count {
+		assert_ok!(ActionLog::log_action(RuntimeOrigin::signed(agent), content(i)));
+	}
+	ActionLog::on_finalize(System::block_number());
+}
+
+fn leaf_hash(record: &ActionRecordOf<Test>) -> H256 {
+	BlakeTwo256::hash_of(record)
+}
+
+#[test]
+fn nodes_are_numbered_in_post_order() {
+	assert_eq!((0..5).map(leaf_position).collect::<Vec<_>>(), vec![0, 1, 3, 4, 7]);
+	// Four leaves make a single mountain of seven nodes.
+	assert_eq!(sibling_positions(0, 4), Some((0, vec![1, 5])));
+	assert_eq!(sibling_positions(3, 4), Some((0, vec![3, 2])));
+	// Three leaves make a mountain of two and one of one.
+	assert_eq!(sibling_positions(1, 3), Some((0, vec![0])));
+	assert_eq!(sibling_positions(2, 3), Some((1, vec![])));
+	assert_eq!(sibling_positions(3, 3), None);
+}
+
+#[test]
+fn roots_bag_the_peaks() {
+	let leaves: Vec<H256> = (0..3).map(content).collect();
+	let left = merge::<BlakeTwo256>(&leaves[0], &leaves[1]);
+	assert_eq!(bag::<BlakeTwo256>(&[left, leaves[2]]), merge::<BlakeTwo256>(&left, &leaves[2]));
+	assert_eq!(bag::<BlakeTwo256>(&[leaves[2]]), leaves[2]);
+	assert_eq!(bag::<BlakeTwo256>(&[]), H256::zero());
+
+	new_test_ext().execute_with(|| {
+		for leaf in &leaves {
+			Pallet::<Test>::append(AGENT, ActionKind::Logged(*leaf));
+		}
+		let records: Vec<_> = (0..3).map(|seq| Actions::<Test>::get(AGENT, seq).unwrap()).collect();
+		let hashes: Vec<_> = records.iter().map(leaf_hash).collect();
+		let left = merge::<BlakeTwo256>(&hashes[0], &hashes[1]);
+		assert_eq!(Peaks::<Test>::get().into_inner(), vec![left, hashes[2]]);
+		assert_eq!(Nodes::<Test>::get(2), Some(left));
+		assert_eq!(Nodes::<Test>::get(3), Some(hashes[2]));
+	});
+}
+
+#[test]
+fn actions_are_numbered_per_agent() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ActionLog::log_action(RuntimeOrigin::signed(10), content(0)),
+			Error::<Test>::NotAgent
+		);
+
+		assert_ok!(ActionLog::log_action(RuntimeOrigin::signed(AGENT), content(0)));
+		System::assert_last_event(
+			Event::ActionLogged {
+				agent: AGENT,
+				seq: 0,
+				kind: ActionKind::Logged(content(0)),
+				leaf_index: 0,
+			}
+			.into(),
+		);
+		Pallet::<Test>::append(OTHER, ActionKind::TaskCompleted(7));
+		assert_ok!(ActionLog::log_action(RuntimeOrigin::signed(AGENT), content(1)));
+
+		assert_eq!(ActionCount::<Test>::get(AGENT), 2);
+		assert_eq!(ActionCount::<Test>::get(OTHER), 1);
+		assert_eq!(
+			Actions::<Test>::get(OTHER, 0),
+			Some(ActionRecord {
+				agent: OTHER,
+				seq: 0,
+				kind: ActionKind::TaskCompleted(7),
+				block: 1,
+				leaf_index: 1,
+			})
+		);
+		assert_eq!(Actions::<Test>::get(AGENT, 1).map(|record| record.leaf_index), Some(2));
+		assert_eq!(LeafCount::<Test>::get(), 3);
+	});
+}
+
+#[test]
+fn roots_are_updated_at_the_end_of_blocks_that_logged_actions() {
+	new_test_ext().execute_with(|| {
+		ActionLog::on_finalize(1);
+		assert_eq!(Root::<Test>::get(), H256::zero());
+		assert!(System::events().is_empty());
+
+		assert_ok!(ActionLog::log_action(RuntimeOrigin::signed(AGENT), content(0)));
+		// The root only moves at the end of the block.
+		assert_eq!(Root::<Test>::get(), H256::zero());
+		ActionLog::on_finalize(1);
+		let root = bag::<BlakeTwo256>(&Peaks::<Test>::get());
+		assert_eq!(Root::<Test>::get(), root);
+		System::assert_last_event(Event::RootUpdated { root, leaf_count: 1 }.into());
+		assert!(!Appended::<Test>::get());
+
+		System::reset_events();
+		System::set_block_number(2);
+		ActionLog::on_finalize(2);
+		assert_eq!(Root::<Test>::get(), root);
+		assert!(System::events().is_empty());
+	});
+}
+
+#[test]
+fn every_action_has_a_proof_of_inclusion() {
+	new_test_ext().execute_with(|| {
+		for count in 1..=20u64 {
+			log(AGENT, 1);
+			let root = Root::<Test>::get();
+			for seq in 0..count {
+				let (record, proof) = ActionLog::prove(&AGENT, seq).unwrap();
+				assert_eq!(proof.leaf_count, count);
+				assert!(verify::<BlakeTwo256>(&root, leaf_hash(&record), &proof));
+			}
+		}
+		assert_eq!(ActionLog::prove(&AGENT, 20), None);
+		assert_eq!(ActionLog::prove(&OTHER, 0), None);
+	});
+}
+
+#[test]
+fn forged_proofs_are_rejected() {
+	new_test_ext().execute_with(|| {
+		log(AGENT, 11);
+		let root = Root::<Test>::get();
+		let (record, proof) = ActionLog::prove(&AGENT, 5).unwrap();
+		let leaf = leaf_hash(&record);
+		assert!(verify::<BlakeTwo256>(&root, leaf, &proof));
+
+		let (other, _) = ActionLog::prove(&AGENT, 4).unwrap();
+		assert!(!verify::<BlakeTwo256>(&root, leaf_hash(&other), &proof));
+		assert!(!verify::<BlakeTwo256>(&H256::zero(), leaf, &proof));
+		let mut forged = proof.clone();
+		forged.leaf_index = 4;
+		assert!(!verify::<BlakeTwo256>(&root, leaf, &forged));
+		let mut forged = proof.clone();
+		forged.leaf_count = 12;
+		assert!(!verify::<BlakeTwo256>(&root, leaf, &forged));
+		let mut forged = proof.clone();
+		forged.siblings.push(leaf);
+		assert!(!verify::<BlakeTwo256>(&root, leaf, &forged));
+		let mut forged = proof.clone();
+		forged.peaks.swap(0, 1);
+		assert!(!verify::<BlakeTwo256>(&root, leaf, &forged));
+		let mut forged = proof;
+		forged.leaf_index = 11;
+		assert!(!verify::<BlakeTwo256>(&root, leaf, &forged));
+
+		// Proofs are against the root they were made for.
+		log(AGENT, 1);
+		let (record, proof) = ActionLog::prove(&AGENT, 5).unwrap();
+		assert!(!verify::<BlakeTwo256>(&root, leaf_hash(&record), &proof));
+		assert!(verify::<BlakeTwo256>(&Root::<Test>::get(), leaf_hash(&record), &proof));
+	});
+}
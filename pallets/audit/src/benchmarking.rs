@@ -0,0 +1,131 @@
+//! Benchmarking setup for pallet-audit
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Audit;
+use alloc::vec::Vec;
+use frame_benchmarking::v2::*;
+use frame_support::{
+	traits::{
+		fungible::{Inspect, Mutate, MutateHold},
+		Get,
+	},
+	BoundedVec,
+};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Saturating, Zero};
+
+/// Create `p` encrypted payloads.
+fn payloads<T: Config>(p: u32) -> Vec<T::PayloadId> {
+	let owner: T::AccountId = account("owner", 0, 0);
+	(0..p).map(|i| T::BenchmarkHelper::create_payload(owner.clone(), i)).collect()
+}
+
+/// Create an account able to pay the audit deposit.
+fn auditor<T: Config>(who: T::AccountId) -> T::AccountId {
+	let deposit = T::AuditDeposit::get();
+	T::Currency::set_balance(&who, T::Currency::minimum_balance().saturating_add(deposit));
+	who
+}
+
+/// Insert an audit of `p` payloads being voted on, with a snapshot of the stakes of the agents
+/// and the deposit of its auditor held.
+fn audit<T: Config>(p: u32) -> AuditId {
+	let auditor = auditor::<T>(account("auditor", 0, 0));
+	let deposit = T::AuditDeposit::get();
+	assert!(T::Currency::hold(&HoldReason::AuditDeposit.into(), &auditor, deposit).is_ok());
+	let mut total_stake = T::Balance::zero();
+	for (agent, stake) in T::Agents::stakes().into_iter().take(T::MaxAgents::get() as usize) {
+		VotingStakes::<T>::insert(0, agent, stake);
+		total_stake = total_stake.saturating_add(stake);
+	}
+	Audits::<T>::insert(
+		0,
+		AuditInfo {
+			auditor,
+			deposit,
+			payloads: BoundedVec::truncate_from(payloads::<T>(p)),
+			justification: BoundedVec::truncate_from(
+				alloc::vec![0; T::MaxJustificationLength::get() as usize],
+			),
+			ayes: Zero::zero(),
+			nays: Zero::zero(),
+			total_stake,
+			deadline: frame_system::Pallet::<T>::block_number()
+				.saturating_add(T::VotingPeriod::get()),
+			status: AuditStatus::Voting,
+		},
+	);
+	NextAuditId::<T>::put(1);
+	0
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	// Worst case: the stakes of `MaxAgents` agents are snapshot.
+	#[benchmark]
+	fn request_audit(p: Linear<1, { T::MaxPayloads::get() }>) {
+		let caller = auditor::<T>(whitelisted_caller());
+		T::BenchmarkHelper::register_agent(account("agent", 0, 0));
+		let payloads = BoundedVec::truncate_from(payloads::<T>(p));
+		let justification =
+			BoundedVec::truncate_from(alloc::vec![0; T::MaxJustificationLength::get() as usize]);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), payloads, justification);
+
+		assert!(Audits::<T>::contains_key(0));
+		assert_eq!(VotingStakes::<T>::iter_prefix(0).count() as u32, T::MaxAgents::get());
+	}
+
+	// Worst case: the vote approves the audit and grants access to its `p` payloads. With `p`
+	// zero the vote decides nothing.
+	#[benchmark]
+	fn vote(p: Linear<0, { T::MaxPayloads::get() }>) {
+		let agent: T::AccountId = whitelisted_caller();
+		T::BenchmarkHelper::register_agent(agent.clone());
+		let audit_id = audit::<T>(p.max(1));
+		if p > 0 {
+			let stake = VotingStakes::<T>::get(audit_id, &agent).unwrap_or_default();
+			Audits::<T>::mutate(audit_id, |audit| {
+				if let Some(audit) = audit {
+					let needed = T::ApprovalThreshold::get().mul_ceil(audit.total_stake);
+					audit.ayes = needed.saturating_sub(stake);
+				}
+			});
+		}
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(agent.clone()), audit_id, p > 0);
+
+		assert!(Votes::<T>::contains_key(audit_id, &agent));
+		if p > 0 {
+			let status = Audits::<T>::get(audit_id).map(|audit| audit.status);
+			assert_eq!(status, Some(AuditStatus::Approved));
+		}
+	}
+
+	#[benchmark]
+	fn close() {
+		T::BenchmarkHelper::register_agent(account("agent", 0, 0));
+		let audit_id = audit::<T>(1);
+		frame_system::Pallet::<T>::set_block_number(
+			frame_system::Pallet::<T>::block_number()
+				.saturating_add(T::VotingPeriod::get())
+				.saturating_add(1u32.into()),
+		);
+		let caller: T::AccountId = whitelisted_caller();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), audit_id);
+
+		let status = Audits::<T>::get(audit_id).map(|audit| audit.status);
+		assert_eq!(status, Some(AuditStatus::Rejected));
+		assert_eq!(VotingStakes::<T>::iter_prefix(audit_id).count(), 0);
+	}
+
+	impl_benchmark_test_suite!(Audit, crate::mock::new_test_ext(), crate::mock::Test);
+}
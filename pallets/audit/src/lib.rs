@@ -0,0 +1,536 @@
+//! # Audit Pallet
+//!
+//! Critical audits of encrypted memory need the approval of the agents. An auditor names the
+//! payloads it wants to decrypt and justifies the request, registered agents vote on it with
+//! their stake, and an approved audit authorizes the auditor to decrypt each payload once.
+//!
+//! ## Overview
+//!
+//! - [`Pallet::request_audit`] files an audit naming encrypted payloads and a justification,
+//!   opening a voting period of [`Config::VotingPeriod`] blocks. [`Config::AuditDeposit`] is
+//!   held from the auditor until the audit is decided: it is burnt if the agents reject the
+//!   audit, so that frivolous requests cost more than the agents' time, and released otherwise.
+//! - Agents vote with [`Pallet::vote`]. A vote weighs the stake the agent had when the audit was
+//!   filed, as reported by [`AgentStake`]. The audit is approved as soon as the stake in favour
+//!   reaches [`Config::ApprovalThreshold`] of the total stake at that time, and rejected as soon
+//!   as the stake against makes that impossible.
+//! - On approval the auditor is authorized to request the decryption of every payload through
+//!   [`DecryptionAccess`], which is expected to forward it to the decryption committee.
+//! - [`Pallet::close`] rejects an audit whose voting period ended without a decision, releasing
+//!   the deposit of the auditor.
+//! - The runtime reports every decryption request to [`Pallet::log_access`], which records it in
+//!   [`AccessLog`] together with the audit that granted it, if any. Audits, votes and log
+//!   entries are never removed, so they form a permanent trail of who accessed what and why.
+//!
+//! Stakes are snapshot in [`VotingStakes`] when an audit is filed and kept until it is decided.
+//! Funds moved from one agent to another while an audit is voted on are therefore counted once,
+//! and the total the threshold applies to cannot shift under the voters.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::WeightInfo;
+
+/// Identifier of an audit.
+pub type AuditId = u64;
+
+/// Identifier of an access log entry.
+pub type AccessId = u64;
+
+/// Stake of the agents voting on audits.
+pub trait AgentStake<AccountId, Balance> {
+	/// Every registered agent, with its stake.
+	fn stakes() -> Vec<(AccountId, Balance)>;
+}
+
+/// Access to the decryption of encrypted payloads.
+pub trait DecryptionAccess<PayloadId, AccountId> {
+	/// Whether `payload` exists and is encrypted.
+	fn exists(payload: &PayloadId) -> bool;
+
+	/// Allow `who` to request the decryption of `payload` once.
+	fn authorize(payload: PayloadId, who: AccountId);
+}
+
+/// Creates payloads and agents for the benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<PayloadId, AccountId> {
+	/// Create the encrypted payload with index `i`, owned by `owner`.
+	fn create_payload(owner: AccountId, i: u32) -> PayloadId;
+
+	/// Register `who` as an agent with some stake, along with as many other agents as
+	/// [`Config::MaxAgents`] allows.
+	fn register_agent(who: AccountId);
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{
+			fungible::{Inspect, Mutate, MutateHold},
+			tokens::{Fortitude, Precision},
+		},
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::{
+		traits::{AtLeast32BitUnsigned, Saturating, Zero},
+		Perbill,
+	};
+
+	/// Balance of the currency audit deposits are held in.
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Weight information for the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
+		/// Currency audit deposits are held in.
+		type Currency: MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>
+			+ Mutate<Self::AccountId>;
+		/// Identifier of an encrypted payload.
+		type PayloadId: Parameter + MaxEncodedLen;
+		/// Amount of stake.
+		type Balance: Parameter + MaxEncodedLen + AtLeast32BitUnsigned + Copy + Default;
+		/// Where the stake of the agents is looked up.
+		type Agents: AgentStake<Self::AccountId, Self::Balance>;
+		/// Grants approved auditors access to the payloads.
+		type Decryption: DecryptionAccess<Self::PayloadId, Self::AccountId>;
+		/// Number of blocks during which an audit can be voted on.
+		#[pallet::constant]
+		type VotingPeriod: Get<BlockNumberFor<Self>>;
+		/// Share of the total stake that must vote in favour of an audit to approve it.
+		#[pallet::constant]
+		type ApprovalThreshold: Get<Perbill>;
+		/// Deposit held from an auditor until its audit is decided, burnt if the agents reject
+		/// the audit.
+		#[pallet::constant]
+		type AuditDeposit: Get<BalanceOf<Self>>;
+		/// Maximum number of payloads named by an audit.
+		#[pallet::constant]
+		type MaxPayloads: Get<u32>;
+		/// Maximum length of a justification, in bytes.
+		#[pallet::constant]
+		type MaxJustificationLength: Get<u32>;
+		/// Maximum number of agents voting on an audit. Agents past it are left out of the
+		/// snapshot of the stakes taken when an audit is filed.
+		#[pallet::constant]
+		type MaxAgents: Get<u32>;
+		/// Creates payloads and agents for the benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<Self::PayloadId, Self::AccountId>;
+	}
+
+	/// Reasons for the pallet to hold funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// Deposit of an auditor, held until its audit is decided.
+		AuditDeposit,
+	}
+
+	/// Stage of an audit.
+	#[derive(Clone, Copy, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug)]
+	pub enum AuditStatus {
+		/// Agents are voting on the audit.
+		Voting,
+		/// The agents approved the audit and the auditor was granted access.
+		Approved,
+		/// The agents rejected the audit, or the voting period ended without a decision.
+		Rejected,
+	}
+
+	/// An audit filed by an auditor.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct AuditInfo<T: Config> {
+		/// Account requesting access.
+		pub auditor: T::AccountId,
+		/// Deposit held from the auditor while the audit is voted on.
+		pub deposit: BalanceOf<T>,
+		/// Payloads the auditor wants to decrypt.
+		pub payloads: BoundedVec<T::PayloadId, T::MaxPayloads>,
+		/// Why the auditor needs access.
+		pub justification: BoundedVec<u8, T::MaxJustificationLength>,
+		/// Stake voting in favour.
+		pub ayes: T::Balance,
+		/// Stake voting against.
+		pub nays: T::Balance,
+		/// Stake of all the agents when the audit was filed.
+		pub total_stake: T::Balance,
+		/// Last block accepting votes.
+		pub deadline: BlockNumberFor<T>,
+		/// Stage of the audit.
+		pub status: AuditStatus,
+	}
+
+	/// A vote on an audit.
+	#[derive(Clone, Copy, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug)]
+	pub struct Vote<Balance> {
+		/// Whether the agent approves the audit.
+		pub approve: bool,
+		/// Stake of the agent when it voted.
+		pub stake: Balance,
+	}
+
+	/// How an account came to decrypt a payload.
+	#[derive(Clone, Copy, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug)]
+	pub enum AccessKind {
+		/// The account owns the payload.
+		Owner,
+		/// The account was granted access by an approved audit.
+		Audit(AuditId),
+		/// The account was authorized by other means.
+		Authorized,
+	}
+
+	/// A decryption request, as recorded in the access log.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct AccessRecord<T: Config> {
+		/// Identifier of the decryption request.
+		pub request_id: u64,
+		/// Payload being decrypted.
+		pub payload: T::PayloadId,
+		/// Account that requested the decryption.
+		pub accessor: T::AccountId,
+		/// How the account came to decrypt the payload.
+		pub kind: AccessKind,
+		/// Block of the request.
+		pub block: BlockNumberFor<T>,
+	}
+
+	/// Identifier of the next audit.
+	#[pallet::storage]
+	pub type NextAuditId<T: Config> = StorageValue<_, AuditId, ValueQuery>;
+
+	/// Audits, whatever their stage.
+	#[pallet::storage]
+	pub type Audits<T: Config> = StorageMap<_, Twox64Concat, AuditId, AuditInfo<T>>;
+
+	/// Votes on each audit, by agent.
+	#[pallet::storage]
+	pub type Votes<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AuditId,
+		Blake2_128Concat,
+		T::AccountId,
+		Vote<T::Balance>,
+	>;
+
+	/// Stake each agent votes on an audit with: its stake when the audit was filed. Removed once
+	/// the audit is decided.
+	#[pallet::storage]
+	pub type VotingStakes<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AuditId,
+		Blake2_128Concat,
+		T::AccountId,
+		T::Balance,
+	>;
+
+	/// Audit that granted an account access to a payload, until the access is used. An account
+	/// holds at most one grant per payload.
+	#[pallet::storage]
+	pub type Grants<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::PayloadId,
+		Blake2_128Concat,
+		T::AccountId,
+		AuditId,
+	>;
+
+	/// Identifier of the next access log entry.
+	#[pallet::storage]
+	pub type NextAccessId<T: Config> = StorageValue<_, AccessId, ValueQuery>;
+
+	/// Every decryption request, in the order they were filed.
+	#[pallet::storage]
+	pub type AccessLog<T: Config> = StorageMap<_, Twox64Concat, AccessId, AccessRecord<T>>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An auditor requested access to payloads, against a deposit
+		AuditRequested {
+			audit_id: AuditId,
+			auditor: T::AccountId,
+			payloads: Vec<T::PayloadId>,
+			deposit: BalanceOf<T>,
+		},
+		/// An agent voted on an audit
+		Voted { audit_id: AuditId, agent: T::AccountId, approve: bool, stake: T::Balance },
+		/// The agents approved an audit and the auditor was granted access
+		AuditApproved { audit_id: AuditId },
+		/// An audit was rejected, burning what was slashed from the deposit of the auditor
+		AuditRejected { audit_id: AuditId, slashed: BalanceOf<T> },
+		/// A decryption request was recorded in the access log
+		AccessLogged {
+			access_id: AccessId,
+			payload: T::PayloadId,
+			accessor: T::AccountId,
+			kind: AccessKind,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// An audit must name at least one payload
+		NoPayloads,
+		/// A payload is named twice
+		DuplicatePayload,
+		/// The payload does not exist or is not encrypted
+		PayloadNotFound,
+		/// Audit not found
+		AuditNotFound,
+		/// The audit is no longer being voted on
+		NotVoting,
+		/// The voting period of the audit is over
+		VotingPeriodOver,
+		/// The voting period of the audit is not over yet
+		VotingPeriodOpen,
+		/// The caller is not a registered agent
+		NotAgent,
+		/// The agent has already voted on the audit
+		AlreadyVoted,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Request access to `payloads`, explaining why in `justification`, holding
+		/// [`Config::AuditDeposit`] until the audit is decided.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::request_audit(payloads.len() as u32))]
+		pub fn request_audit(
+			origin: OriginFor<T>,
+			payloads: BoundedVec<T::PayloadId, T::MaxPayloads>,
+			justification: BoundedVec<u8, T::MaxJustificationLength>,
+		) -> DispatchResult {
+			let auditor = ensure_signed(origin)?;
+			ensure!(!payloads.is_empty(), Error::<T>::NoPayloads);
+			for (i, payload) in payloads.iter().enumerate() {
+				ensure!(!payloads[..i].contains(payload), Error::<T>::DuplicatePayload);
+				ensure!(T::Decryption::exists(payload), Error::<T>::PayloadNotFound);
+			}
+			let deposit = T::AuditDeposit::get();
+			T::Currency::hold(&HoldReason::AuditDeposit.into(), &auditor, deposit)?;
+
+			let audit_id = NextAuditId::<T>::mutate(|id| {
+				let current = *id;
+				*id = id.saturating_add(1);
+				current
+			});
+			let mut total_stake = T::Balance::zero();
+			let stakes = T::Agents::stakes().into_iter().take(T::MaxAgents::get() as usize);
+			for (agent, stake) in stakes.filter(|(_, stake)| !stake.is_zero()) {
+				VotingStakes::<T>::insert(audit_id, agent, stake);
+				total_stake = total_stake.saturating_add(stake);
+			}
+			let deadline =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::VotingPeriod::get());
+			Audits::<T>::insert(
+				audit_id,
+				AuditInfo {
+					auditor: auditor.clone(),
+					deposit,
+					payloads: payloads.clone(),
+					justification,
+					ayes: Zero::zero(),
+					nays: Zero::zero(),
+					total_stake,
+					deadline,
+					status: AuditStatus::Voting,
+				},
+			);
+
+			Self::deposit_event(Event::AuditRequested {
+				audit_id,
+				auditor,
+				payloads: payloads.into_inner(),
+				deposit,
+			});
+			Ok(())
+		}
+
+		/// Vote on an audit with the stake the caller had when it was filed.
+		///
+		/// The vote deciding the audit approves or rejects it right away.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::vote(T::MaxPayloads::get()))]
+		pub fn vote(
+			origin: OriginFor<T>,
+			audit_id: AuditId,
+			approve: bool,
+		) -> DispatchResultWithPostInfo {
+			let agent = ensure_signed(origin)?;
+			let mut audit = Audits::<T>::get(audit_id).ok_or(Error::<T>::AuditNotFound)?;
+			ensure!(audit.status == AuditStatus::Voting, Error::<T>::NotVoting);
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= audit.deadline,
+				Error::<T>::VotingPeriodOver
+			);
+			ensure!(!Votes::<T>::contains_key(audit_id, &agent), Error::<T>::AlreadyVoted);
+			let stake = VotingStakes::<T>::get(audit_id, &agent).ok_or(Error::<T>::NotAgent)?;
+
+			Votes::<T>::insert(audit_id, &agent, Vote { approve, stake });
+			if approve {
+				audit.ayes = audit.ayes.saturating_add(stake);
+			} else {
+				audit.nays = audit.nays.saturating_add(stake);
+			}
+			Self::deposit_event(Event::Voted { audit_id, agent, approve, stake });
+
+			let needed = T::ApprovalThreshold::get().mul_ceil(audit.total_stake);
+			let granted = if audit.ayes >= needed {
+				audit.status = AuditStatus::Approved;
+				for payload in &audit.payloads {
+					T::Decryption::authorize(payload.clone(), audit.auditor.clone());
+					Grants::<T>::insert(payload, &audit.auditor, audit_id);
+				}
+				Self::release_stakes(audit_id);
+				Self::settle_deposit(&audit, false)?;
+				Self::deposit_event(Event::AuditApproved { audit_id });
+				audit.payloads.len() as u32
+			} else {
+				if audit.nays > audit.total_stake.saturating_sub(needed) {
+					audit.status = AuditStatus::Rejected;
+					Self::release_stakes(audit_id);
+					let slashed = Self::settle_deposit(&audit, true)?;
+					Self::deposit_event(Event::AuditRejected { audit_id, slashed });
+				}
+				0
+			};
+			Audits::<T>::insert(audit_id, audit);
+
+			Ok(Some(T::WeightInfo::vote(granted)).into())
+		}
+
+		/// Reject an audit whose voting period ended without a decision, releasing the deposit of
+		/// the auditor.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::close())]
+		pub fn close(origin: OriginFor<T>, audit_id: AuditId) -> DispatchResult {
+			ensure_signed(origin)?;
+			let mut audit = Audits::<T>::get(audit_id).ok_or(Error::<T>::AuditNotFound)?;
+			ensure!(audit.status == AuditStatus::Voting, Error::<T>::NotVoting);
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > audit.deadline,
+				Error::<T>::VotingPeriodOpen
+			);
+
+			audit.status = AuditStatus::Rejected;
+			Self::release_stakes(audit_id);
+			Self::settle_deposit(&audit, false)?;
+			Audits::<T>::insert(audit_id, audit);
+			Self::deposit_event(Event::AuditRejected { audit_id, slashed: Zero::zero() });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Drop the stakes snapshot for decided audit `audit_id`.
+		fn release_stakes(audit_id: AuditId) {
+			let _ = VotingStakes::<T>::clear_prefix(audit_id, T::MaxAgents::get(), None);
+		}
+
+		/// Burn the deposit of the auditor of decided `audit` if `slash`, release it otherwise.
+		///
+		/// Returns the amount burnt.
+		fn settle_deposit(
+			audit: &AuditInfo<T>,
+			slash: bool,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let reason = HoldReason::AuditDeposit.into();
+			if slash {
+				T::Currency::burn_held(
+					&reason,
+					&audit.auditor,
+					audit.deposit,
+					Precision::BestEffort,
+					Fortitude::Force,
+				)
+			} else {
+				T::Currency::release(
+					&reason,
+					&audit.auditor,
+					audit.deposit,
+					Precision::BestEffort,
+				)?;
+				Ok(Zero::zero())
+			}
+		}
+
+		/// Record that `accessor` filed decryption request `request_id` for `payload`; `owner`
+		/// tells whether it owns the payload.
+		///
+		/// A non-owner's access is attributed to the audit that granted it, whose grant is used
+		/// up.
+		pub fn log_access(
+			request_id: u64,
+			payload: &T::PayloadId,
+			accessor: &T::AccountId,
+			owner: bool,
+		) {
+			let kind = if owner {
+				AccessKind::Owner
+			} else {
+				Grants::<T>::take(payload, accessor)
+					.map_or(AccessKind::Authorized, AccessKind::Audit)
+			};
+			let access_id = NextAccessId::<T>::mutate(|id| {
+				let current = *id;
+				*id = id.saturating_add(1);
+				current
+			});
+			AccessLog::<T>::insert(
+				access_id,
+				AccessRecord {
+					request_id,
+					payload: payload.clone(),
+					accessor: accessor.clone(),
+					kind,
+					block: frame_system::Pallet::<T>::block_number(),
+				},
+			);
+
+			Self::deposit_event(Event::AccessLogged {
+				access_id,
+				payload: payload.clone(),
+				accessor: accessor.clone(),
+				kind,
+			});
+		}
+	}
+}
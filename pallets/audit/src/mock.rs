@@ -0,0 +1,134 @@
+use crate::{self as pallet_audit, AgentStake, DecryptionAccess};
+use core::cell::RefCell;
+use frame_support::{
+	derive_impl, parameter_types,
+	traits::{ConstU32, ConstU64},
+};
+use sp_core::H256;
+use sp_runtime::{BuildStorage, Perbill};
+use std::collections::{BTreeMap, BTreeSet};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		Balances: pallet_balances,
+		Audit: pallet_audit,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+thread_local! {
+	static STAKES: RefCell<BTreeMap<u64, u64>> = RefCell::new(BTreeMap::new());
+	static PAYLOADS: RefCell<BTreeSet<H256>> = RefCell::new(BTreeSet::new());
+	static AUTHORIZED: RefCell<Vec<(H256, u64)>> = RefCell::new(Vec::new());
+}
+
+/// Agents and their stake, kept in memory.
+pub struct MockAgents;
+
+impl MockAgents {
+	pub fn set_stake(who: u64, stake: u64) {
+		STAKES.with(|s| s.borrow_mut().insert(who, stake));
+	}
+}
+
+impl AgentStake<u64, u64> for MockAgents {
+	fn stakes() -> Vec<(u64, u64)> {
+		STAKES.with(|s| s.borrow().iter().map(|(who, stake)| (*who, *stake)).collect())
+	}
+}
+
+/// Encrypted payloads kept in memory, recording the authorizations granted.
+pub struct MockDecryption;
+
+impl MockDecryption {
+	pub fn insert(payload: H256) {
+		PAYLOADS.with(|p| p.borrow_mut().insert(payload));
+	}
+
+	pub fn authorized() -> Vec<(H256, u64)> {
+		AUTHORIZED.with(|a| a.borrow().clone())
+	}
+}
+
+impl DecryptionAccess<H256, u64> for MockDecryption {
+	fn exists(payload: &H256) -> bool {
+		PAYLOADS.with(|p| p.borrow().contains(payload))
+	}
+
+	fn authorize(payload: H256, who: u64) {
+		AUTHORIZED.with(|a| a.borrow_mut().push((payload, who)));
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkHelper<H256, u64> for MockDecryption {
+	fn create_payload(_owner: u64, i: u32) -> H256 {
+		let payload = H256::from_low_u64_be(i as u64 + 1);
+		Self::insert(payload);
+		payload
+	}
+
+	fn register_agent(who: u64) {
+		for i in 1..4 {
+			MockAgents::set_stake(1_000 + i, 100);
+		}
+		MockAgents::set_stake(who, 100);
+	}
+}
+
+/// Account endowed to file audits.
+pub const AUDITOR: u64 = 1;
+/// Deposit held from auditors.
+pub const AUDIT_DEPOSIT: u64 = 50;
+
+parameter_types! {
+	pub const ApprovalThreshold: Perbill = Perbill::from_percent(60);
+}
+
+impl pallet_audit::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type Currency = Balances;
+	type PayloadId = H256;
+	type Balance = u64;
+	type Agents = MockAgents;
+	type Decryption = MockDecryption;
+	type VotingPeriod = ConstU64<10>;
+	type ApprovalThreshold = ApprovalThreshold;
+	type AuditDeposit = ConstU64<AUDIT_DEPOSIT>;
+	type MaxPayloads = ConstU32<4>;
+	type MaxJustificationLength = ConstU32<64>;
+	type MaxAgents = ConstU32<4>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockDecryption;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	STAKES.with(|s| s.borrow_mut().clear());
+	PAYLOADS.with(|p| p.borrow_mut().clear());
+	AUTHORIZED.with(|a| a.borrow_mut().clear());
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(AUDITOR, 100)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	// Events are not emitted on the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,228 @@
+use crate::{mock::*, *};
+use frame_support::{assert_noop, assert_ok};
+use sp_core::H256;
+use sp_runtime::DispatchResult;
+
+const OWNER: u64 = 2;
+/// Agents and their stake. Approval needs 60% of the total stake of 100.
+const AGENTS: [(u64, u64); 3] = [(10, 30), (11, 30), (12, 40)];
+const PAYLOADS: [H256; 2] = [H256::repeat_byte(1), H256::repeat_byte(2)];
+
+fn setup() {
+	for (agent, stake) in AGENTS {
+		MockAgents::set_stake(agent, stake);
+	}
+	for payload in PAYLOADS {
+		MockDecryption::insert(payload);
+	}
+}
+
+fn request(payloads: &[H256]) -> DispatchResult {
+	Audit::request_audit(
+		RuntimeOrigin::signed(AUDITOR),
+		payloads.to_vec().try_into().unwrap(),
+		b"quarterly compliance review".to_vec().try_into().unwrap(),
+	)
+}
+
+fn vote(agent: u64, audit_id: AuditId, approve: bool) -> DispatchResult {
+	Audit::vote(RuntimeOrigin::signed(agent), audit_id, approve)
+		.map(|_| ())
+		.map_err(|e| e.error)
+}
+
+fn status(audit_id: AuditId) -> AuditStatus {
+	Audits::<Test>::get(audit_id).unwrap().status
+}
+
+#[test]
+fn requests_are_validated() {
+	new_test_ext().execute_with(|| {
+		setup();
+		assert_noop!(request(&[]), Error::<Test>::NoPayloads);
+		assert_noop!(request(&[H256::repeat_byte(9)]), Error::<Test>::PayloadNotFound);
+		assert_noop!(request(&[PAYLOADS[0], PAYLOADS[0]]), Error::<Test>::DuplicatePayload);
+		assert!(Audit::request_audit(
+			RuntimeOrigin::signed(OWNER),
+			PAYLOADS.to_vec().try_into().unwrap(),
+			b"no funds".to_vec().try_into().unwrap(),
+		)
+		.is_err());
+
+		assert_ok!(request(&PAYLOADS));
+		System::assert_last_event(
+			Event::AuditRequested {
+				audit_id: 0,
+				auditor: AUDITOR,
+				payloads: PAYLOADS.to_vec(),
+				deposit: AUDIT_DEPOSIT,
+			}
+			.into(),
+		);
+		assert_eq!(Balances::reserved_balance(AUDITOR), AUDIT_DEPOSIT);
+		let audit = Audits::<Test>::get(0).unwrap();
+		assert_eq!(audit.auditor, AUDITOR);
+		assert_eq!(audit.deposit, AUDIT_DEPOSIT);
+		assert_eq!(audit.justification.to_vec(), b"quarterly compliance review".to_vec());
+		assert_eq!(audit.deadline, 11);
+		assert_eq!(audit.status, AuditStatus::Voting);
+		assert_eq!(NextAuditId::<Test>::get(), 1);
+	});
+}
+
+#[test]
+fn approved_audits_grant_access() {
+	new_test_ext().execute_with(|| {
+		setup();
+		assert_ok!(request(&PAYLOADS));
+
+		assert_ok!(vote(10, 0, true));
+		assert_noop!(vote(10, 0, true), Error::<Test>::AlreadyVoted);
+		assert_eq!(status(0), AuditStatus::Voting);
+		assert!(MockDecryption::authorized().is_empty());
+
+		// 60 of 100 reaches the threshold.
+		assert_ok!(vote(11, 0, true));
+		System::assert_last_event(Event::AuditApproved { audit_id: 0 }.into());
+		let audit = Audits::<Test>::get(0).unwrap();
+		assert_eq!((audit.ayes, audit.nays, audit.status), (60, 0, AuditStatus::Approved));
+		assert_eq!(Votes::<Test>::get(0, 11), Some(Vote { approve: true, stake: 30 }));
+		assert_eq!(
+			MockDecryption::authorized(),
+			vec![(PAYLOADS[0], AUDITOR), (PAYLOADS[1], AUDITOR)]
+		);
+		assert_eq!(Grants::<Test>::get(PAYLOADS[1], AUDITOR), Some(0));
+		assert_eq!(Balances::reserved_balance(AUDITOR), 0);
+		assert_eq!(Balances::free_balance(AUDITOR), 100);
+
+		assert_noop!(vote(12, 0, false), Error::<Test>::NotVoting);
+	});
+}
+
+#[test]
+fn audits_are_rejected_once_approval_is_out_of_reach() {
+	new_test_ext().execute_with(|| {
+		setup();
+		assert_ok!(request(&PAYLOADS));
+
+		// 40 against still leaves exactly 60 that could approve.
+		assert_ok!(vote(12, 0, false));
+		assert_eq!(status(0), AuditStatus::Voting);
+
+		assert_ok!(vote(10, 0, false));
+		System::assert_last_event(
+			Event::AuditRejected { audit_id: 0, slashed: AUDIT_DEPOSIT }.into(),
+		);
+		assert_eq!(status(0), AuditStatus::Rejected);
+		assert_noop!(vote(11, 0, true), Error::<Test>::NotVoting);
+		assert!(MockDecryption::authorized().is_empty());
+		// The deposit is burnt.
+		assert_eq!(Balances::reserved_balance(AUDITOR), 0);
+		assert_eq!(Balances::free_balance(AUDITOR), 100 - AUDIT_DEPOSIT);
+		assert_eq!(Balances::total_issuance(), 100 - AUDIT_DEPOSIT);
+	});
+}
+
+#[test]
+fn only_agents_vote_within_the_voting_period() {
+	new_test_ext().execute_with(|| {
+		setup();
+		assert_noop!(vote(10, 0, true), Error::<Test>::AuditNotFound);
+		assert_ok!(request(&PAYLOADS));
+		assert_noop!(vote(AUDITOR, 0, true), Error::<Test>::NotAgent);
+
+		// Votes weigh the stake snapshot when the audit was filed.
+		MockAgents::set_stake(10, 5);
+		assert_ok!(vote(10, 0, true));
+		assert_eq!(Audits::<Test>::get(0).unwrap().ayes, 30);
+
+		assert_noop!(
+			Audit::close(RuntimeOrigin::signed(OWNER), 0),
+			Error::<Test>::VotingPeriodOpen
+		);
+		System::set_block_number(12);
+		assert_noop!(vote(11, 0, true), Error::<Test>::VotingPeriodOver);
+
+		assert_ok!(Audit::close(RuntimeOrigin::signed(OWNER), 0));
+		System::assert_last_event(Event::AuditRejected { audit_id: 0, slashed: 0 }.into());
+		assert_eq!(status(0), AuditStatus::Rejected);
+		// Audits nobody decided do not cost the auditor its deposit.
+		assert_eq!(Balances::reserved_balance(AUDITOR), 0);
+		assert_eq!(Balances::free_balance(AUDITOR), 100);
+		assert_noop!(Audit::close(RuntimeOrigin::signed(OWNER), 0), Error::<Test>::NotVoting);
+	});
+}
+
+#[test]
+fn stakes_are_snapshot_when_audits_are_filed() {
+	new_test_ext().execute_with(|| {
+		setup();
+		MockAgents::set_stake(13, 0);
+		assert_ok!(request(&PAYLOADS));
+		assert_eq!(Audits::<Test>::get(0).unwrap().total_stake, 100);
+		assert_eq!(VotingStakes::<Test>::iter_prefix(0).count(), 3);
+
+		// Stake moved to another agent, or to a new one, after filing does not vote twice.
+		MockAgents::set_stake(10, 0);
+		MockAgents::set_stake(11, 60);
+		MockAgents::set_stake(13, 100);
+		assert_noop!(vote(13, 0, true), Error::<Test>::NotAgent);
+		assert_ok!(vote(11, 0, true));
+		assert_eq!(status(0), AuditStatus::Voting);
+
+		// Later audits see the new stakes.
+		assert_ok!(request(&PAYLOADS));
+		assert_eq!(Audits::<Test>::get(1).unwrap().total_stake, 200);
+		assert_noop!(vote(10, 1, true), Error::<Test>::NotAgent);
+
+		// The snapshot is dropped once the audit is decided.
+		assert_ok!(vote(10, 0, true));
+		assert_eq!(status(0), AuditStatus::Approved);
+		assert_eq!(VotingStakes::<Test>::iter_prefix(0).count(), 0);
+		assert_eq!(VotingStakes::<Test>::iter_prefix(1).count(), 3);
+	});
+}
+
+#[test]
+fn every_access_is_logged() {
+	new_test_ext().execute_with(|| {
+		setup();
+		assert_ok!(request(&PAYLOADS));
+		assert_ok!(vote(10, 0, true));
+		assert_ok!(vote(12, 0, true));
+
+		System::set_block_number(3);
+		Audit::log_access(7, &PAYLOADS[0], &AUDITOR, false);
+		System::assert_last_event(
+			Event::AccessLogged {
+				access_id: 0,
+				payload: PAYLOADS[0],
+				accessor: AUDITOR,
+				kind: AccessKind::Audit(0),
+			}
+			.into(),
+		);
+		assert_eq!(Grants::<Test>::get(PAYLOADS[0], AUDITOR), None);
+
+		// Once the grant is used up, further access is not attributed to the audit.
+		Audit::log_access(8, &PAYLOADS[0], &AUDITOR, false);
+		Audit::log_access(9, &PAYLOADS[1], &OWNER, true);
+
+		let log: Vec<_> = (0..3)
+			.map(|id| {
+				let record = AccessLog::<Test>::get(id).unwrap();
+				(record.request_id, record.payload, record.accessor, record.kind, record.block)
+			})
+			.collect();
+		assert_eq!(
+			log,
+			vec![
+				(7, PAYLOADS[0], AUDITOR, AccessKind::Audit(0), 3),
+				(8, PAYLOADS[0], AUDITOR, AccessKind::Authorized, 3),
+				(9, PAYLOADS[1], OWNER, AccessKind::Owner, 3),
+			]
+		);
+		assert_eq!(NextAccessId::<Test>::get(), 3);
+		assert_eq!(Grants::<Test>::get(PAYLOADS[1], AUDITOR), Some(0));
+	});
+}
@@ -0,0 +1,117 @@
+//! Weights for pallet_audit
+//!
+//! PLACEHOLDER WEIGHTS, written by hand rather than measured. Storage reads, writes and proof
+//! sizes follow the storage accessed by the worst case of each benchmark in `benchmarking.rs`,
+//! for the runtime configuration (`MaxPayloads = 16`, `MaxJustificationLength = 1024`, an
+//! encryption committee of 16 agents). The ref-time components are guesses. Replace this file
+//! with the output of the node's `benchmark pallet` command, run on reference hardware, before
+//! relying on these weights.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_audit.
+pub trait WeightInfo {
+	fn request_audit(p: u32, ) -> Weight;
+	fn vote(p: u32, ) -> Weight;
+	fn close() -> Weight;
+}
+
+/// Placeholder weights for pallet_audit, until it is benchmarked on reference hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `TemplateModule::Vectors` (r:16 w:0)
+	/// Storage: `TemplateModule::Collections` (r:16 w:0)
+	/// Storage: `Audit::NextAuditId` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Members` (r:1 w:0)
+	/// Storage: `System::Account` (r:17 w:1)
+	/// Storage: `Reputation::Records` (r:16 w:0)
+	/// Storage: `Reputation::StakedSince` (r:1 w:0)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `Audit::Audits` (r:0 w:1)
+	/// Storage: `Audit::VotingStakes` (r:0 w:16)
+	/// The range of component `p` is `[1, 16]`.
+	fn request_audit(p: u32, ) -> Weight {
+		Weight::from_parts(89_700_000, 3_889)
+			.saturating_add(Weight::from_parts(7_450_000, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(37_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes(20_u64))
+			.saturating_add(Weight::from_parts(0, 2_643).saturating_mul(p.into()))
+	}
+	/// Storage: `Audit::Audits` (r:1 w:1)
+	/// Storage: `Audit::Votes` (r:1 w:1)
+	/// Storage: `Audit::VotingStakes` (r:17 w:16)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Authorizations` (r:0 w:16)
+	/// Storage: `Audit::Grants` (r:0 w:16)
+	/// The range of component `p` is `[0, 16]`.
+	fn vote(p: u32, ) -> Weight {
+		Weight::from_parts(75_300_000, 3_593)
+			.saturating_add(Weight::from_parts(9_850_000, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(21_u64))
+			.saturating_add(T::DbWeight::get().writes(20_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(p.into())))
+	}
+	/// Storage: `Audit::Audits` (r:1 w:1)
+	/// Storage: `Audit::VotingStakes` (r:17 w:16)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	fn close() -> Weight {
+		Weight::from_parts(62_800_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(20_u64))
+			.saturating_add(T::DbWeight::get().writes(19_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `TemplateModule::Vectors` (r:16 w:0)
+	/// Storage: `TemplateModule::Collections` (r:16 w:0)
+	/// Storage: `Audit::NextAuditId` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Members` (r:1 w:0)
+	/// Storage: `System::Account` (r:17 w:1)
+	/// Storage: `Reputation::Records` (r:16 w:0)
+	/// Storage: `Reputation::StakedSince` (r:1 w:0)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `Audit::Audits` (r:0 w:1)
+	/// Storage: `Audit::VotingStakes` (r:0 w:16)
+	/// The range of component `p` is `[1, 16]`.
+	fn request_audit(p: u32, ) -> Weight {
+		Weight::from_parts(89_700_000, 3_889)
+			.saturating_add(Weight::from_parts(7_450_000, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(37_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().writes(20_u64))
+			.saturating_add(Weight::from_parts(0, 2_643).saturating_mul(p.into()))
+	}
+	/// Storage: `Audit::Audits` (r:1 w:1)
+	/// Storage: `Audit::Votes` (r:1 w:1)
+	/// Storage: `Audit::VotingStakes` (r:17 w:16)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Authorizations` (r:0 w:16)
+	/// Storage: `Audit::Grants` (r:0 w:16)
+	/// The range of component `p` is `[0, 16]`.
+	fn vote(p: u32, ) -> Weight {
+		Weight::from_parts(75_300_000, 3_593)
+			.saturating_add(Weight::from_parts(9_850_000, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(21_u64))
+			.saturating_add(RocksDbWeight::get().writes(20_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(p.into())))
+	}
+	/// Storage: `Audit::Audits` (r:1 w:1)
+	/// Storage: `Audit::VotingStakes` (r:17 w:16)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	fn close() -> Weight {
+		Weight::from_parts(62_800_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(20_u64))
+			.saturating_add(RocksDbWeight::get().writes(19_u64))
+	}
+}
@@ -0,0 +1,448 @@
+//! # Embedding Pallet
+//!
+//! An offchain worker that turns raw content into memories of the template pallet, so that agents
+//! can hand their node text rather than embeddings computed on their own.
+//!
+//! ## Overview
+//!
+//! - The node operator queues [`RawContent`] submissions in the persistent local storage of the
+//!   node under [`QUEUE_KEY`]: the hash of some text, with the tags and collection to store its
+//!   embedding with.
+//! - Every block, the offchain worker takes up to [`Config::MaxEmbeddingsPerBlock`] submissions
+//!   off the queue. Their text is read from local storage under [`content_key`], or else fetched
+//!   with `GET {endpoint}/0x{hash}` from the endpoint set under [`CONTENT_ENDPOINT_KEY`], and must
+//!   hash to the submitted hash.
+//! - The text is posted as `{"input": text}` to the inference endpoint set under
+//!   [`INFERENCE_ENDPOINT_KEY`], which answers with `{"embedding": [f32, ...]}`. An endpoint
+//!   starting with [`ROOST_PREFIX`] instead has the text embedded by the inference service of the
+//!   node, through the host functions of `roost-primitives`.
+//! - The endpoint embeds with the registered model set under [`MODEL_KEY`], whose dimension the
+//!   embedding must have.
+//! - The embedding is encoded for its collection and stored with a signed `store_weight_data`
+//!   referencing the model, signed with a [`KEY_TYPE`] key of the node's keystore whose account
+//!   becomes the author of the memory.
+//!
+//! Submissions that can never succeed, such as text not matching its hash or an embedding too
+//! long for the pool, are dropped with a warning. Those failing because an endpoint is
+//! unreachable go back to the end of the queue, up to [`MAX_ATTEMPTS`] times.
+//!
+//! Endpoints are stored as raw UTF-8 URLs and texts as raw bytes, so that all of them can be set
+//! with the `offchain_localStorageSet` RPC, while the queue is a SCALE encoded
+//! `Vec<RawContent>` and the model a SCALE encoded `ModelId`. Any HTTP server answering these
+//! requests will do, such as a stub returning fixed embeddings in CI.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::{string::String, vec::Vec};
+use codec::{Decode, Encode};
+use pallet_template::{CollectionId, EmbeddingModels, ModelId};
+use sp_runtime::{KeyTypeId, RuntimeDebug};
+
+/// Key type of the keys that sign the transactions of the offchain worker.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"embd");
+
+/// Persistent local storage key of the queue of [`RawContent`] to embed.
+pub const QUEUE_KEY: &[u8] = b"embedding::queue";
+
+/// Persistent local storage key of the URL of the inference endpoint. The offchain worker is
+/// idle until it is set.
+pub const INFERENCE_ENDPOINT_KEY: &[u8] = b"embedding::inference-endpoint";
+
+/// Persistent local storage key of the id of the registered model the inference endpoint embeds
+/// with. The offchain worker is idle until it is set.
+pub const MODEL_KEY: &[u8] = b"embedding::model";
+
+/// Prefix of the inference endpoints served by the node itself, followed by the name of the model
+/// to embed with, if not the default text embedding model of the node: `roost:` or
+/// `roost:all-minilm`.
+pub const ROOST_PREFIX: &str = "roost:";
+
+/// Persistent local storage key of the URL of the endpoint serving content by hash, if any.
+pub const CONTENT_ENDPOINT_KEY: &[u8] = b"embedding::content-endpoint";
+
+/// Prefix of the persistent local storage keys of content, see [`content_key`].
+pub const CONTENT_PREFIX: &[u8] = b"embedding::content::";
+
+/// Prefix of the persistent local storage keys counting the attempts to embed some content.
+const ATTEMPTS_PREFIX: &[u8] = b"embedding::attempts::";
+
+/// Number of times a submission is tried before it is dropped when an endpoint keeps failing.
+pub const MAX_ATTEMPTS: u32 = 10;
+
+/// Milliseconds an endpoint has to answer.
+const HTTP_TIMEOUT_MS: u64 = 10_000;
+
+const LOG_TARGET: &str = "runtime::embedding";
+
+/// Keys that sign the transactions of the offchain worker.
+pub mod sr25519 {
+	mod app_sr25519 {
+		use sp_runtime::app_crypto::{app_crypto, sr25519};
+		app_crypto!(sr25519, crate::KEY_TYPE);
+	}
+
+	/// Public key of an agent's offchain worker.
+	pub type AuthorityId = app_sr25519::Public;
+
+	/// Signs the offchain worker's transactions with an sr25519 key of type
+	/// [`KEY_TYPE`](crate::KEY_TYPE).
+	pub struct AuthoritySigner;
+
+	impl frame_system::offchain::AppCrypto<sp_runtime::MultiSigner, sp_runtime::MultiSignature>
+		for AuthoritySigner
+	{
+		type RuntimeAppPublic = AuthorityId;
+		type GenericPublic = sp_core::sr25519::Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+	}
+}
+
+/// Content queued for embedding by the node operator.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+pub struct RawContent<Hash> {
+	/// Hash of the text to embed.
+	pub content_hash: Hash,
+	/// Tags to store the embedding with.
+	pub tags: Vec<Vec<u8>>,
+	/// Collection to store the embedding in, or `None` to store it as `f32` components outside
+	/// of any collection.
+	pub collection: Option<CollectionId>,
+}
+
+/// Persistent local storage key of the content hashing to `content_hash`: [`CONTENT_PREFIX`]
+/// followed by the hash.
+pub fn content_key(content_hash: &[u8]) -> Vec<u8> {
+	[CONTENT_PREFIX, content_hash].concat()
+}
+
+/// Why some content could not be embedded.
+#[derive(RuntimeDebug)]
+enum Failure {
+	/// The submission can never succeed.
+	Permanent(&'static str),
+	/// The submission may succeed later, once its endpoint answers.
+	Transient(&'static str),
+}
+
+/// Body of the requests to the inference endpoint.
+#[derive(serde::Serialize)]
+struct InferenceRequest<'a> {
+	input: &'a str,
+}
+
+/// Body of the responses of the inference endpoint.
+#[derive(serde::Deserialize)]
+struct InferenceResponse {
+	embedding: Vec<f32>,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::{
+		offchain::{AppCrypto, CreateSignedTransaction, ForAny, SendSignedTransaction, Signer},
+		pallet_prelude::*,
+	};
+	use pallet_template::VectorEncoding;
+	use sp_runtime::{
+		offchain::{
+			http,
+			storage::{MutateStorageError, StorageRetrievalError, StorageValueRef},
+			Duration, StorageKind,
+		},
+		traits::Hash,
+	};
+
+	/// A submission queued on this runtime.
+	pub type RawContentOf<T> = RawContent<<T as frame_system::Config>::Hash>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config:
+		CreateSignedTransaction<pallet_template::Call<Self>> + pallet_template::Config
+	{
+		/// Keys signing the transactions of the offchain worker.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+		/// Most submissions the offchain worker embeds per block.
+		#[pallet::constant]
+		type MaxEmbeddingsPerBlock: Get<u32>;
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn offchain_worker(_: BlockNumberFor<T>) {
+			if let Err(e) = Self::embed_queued() {
+				log::warn!(target: LOG_TARGET, "Failed to embed queued content: {e}");
+			}
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Embed and store the submissions at the front of the queue.
+		fn embed_queued() -> Result<(), &'static str> {
+			let Some(inference) = endpoint(INFERENCE_ENDPOINT_KEY)? else {
+				return Ok(());
+			};
+			let Some(model) = StorageValueRef::persistent(MODEL_KEY)
+				.get::<ModelId>()
+				.map_err(|_| "the model cannot be decoded")?
+			else {
+				return Ok(());
+			};
+			let dimension = <T as pallet_template::Config>::Models::dimension(model)
+				.ok_or("the model is not registered")?;
+			let content_endpoint = endpoint(CONTENT_ENDPOINT_KEY)?;
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			if !signer.can_sign() {
+				return Err("no signing key in the keystore");
+			}
+
+			let mut retry = Vec::new();
+			for submission in Self::take_queued(T::MaxEmbeddingsPerBlock::get() as usize)? {
+				let result =
+					Self::embed(&submission, &inference, content_endpoint.as_deref(), dimension)
+						.and_then(|data| Self::submit(&signer, &submission, data, model));
+				let attempts = [ATTEMPTS_PREFIX, submission.content_hash.as_ref()].concat();
+				let attempts = StorageValueRef::persistent(&attempts);
+				match result {
+					Ok(()) => attempts.clear(),
+					Err(Failure::Permanent(e)) => {
+						log::warn!(
+							target: LOG_TARGET,
+							"Dropped content {:?}: {e}",
+							submission.content_hash
+						);
+						attempts.clear();
+					},
+					Err(Failure::Transient(e)) => {
+						let made = attempts.get::<u32>().ok().flatten().unwrap_or(0) + 1;
+						if made < MAX_ATTEMPTS {
+							log::debug!(
+								target: LOG_TARGET,
+								"Will retry content {:?}: {e}",
+								submission.content_hash
+							);
+							attempts.set(&made);
+							retry.push(submission);
+						} else {
+							log::warn!(
+								target: LOG_TARGET,
+								"Dropped content {:?} after {made} attempts: {e}",
+								submission.content_hash
+							);
+							attempts.clear();
+						}
+					},
+				}
+			}
+			Self::requeue(retry)
+		}
+
+		/// Take up to `max` submissions off the front of the queue.
+		///
+		/// Nothing is taken if the queue is modified meanwhile, by another offchain worker or the
+		/// node operator. Content queued again while being embedded is stored twice at most, the
+		/// second time being rejected as a duplicate.
+		fn take_queued(max: usize) -> Result<Vec<RawContentOf<T>>, &'static str> {
+			let mut taken = Vec::new();
+			let result = StorageValueRef::persistent(QUEUE_KEY).mutate(
+				|queue: Result<Option<Vec<RawContentOf<T>>>, StorageRetrievalError>| {
+					let mut queue =
+						queue.map_err(|_| "the queue cannot be decoded")?.unwrap_or_default();
+					let rest = queue.split_off(max.min(queue.len()));
+					taken = queue;
+					Ok(rest)
+				},
+			);
+			match result {
+				Ok(_) => Ok(taken),
+				Err(MutateStorageError::ConcurrentModification(_)) => Ok(Vec::new()),
+				Err(MutateStorageError::ValueFunctionFailed(e)) => Err(e),
+			}
+		}
+
+		/// Put `submissions` back at the end of the queue.
+		fn requeue(submissions: Vec<RawContentOf<T>>) -> Result<(), &'static str> {
+			if submissions.is_empty() {
+				return Ok(());
+			}
+			StorageValueRef::persistent(QUEUE_KEY)
+				.mutate(|queue: Result<Option<Vec<RawContentOf<T>>>, StorageRetrievalError>| {
+					let mut queue =
+						queue.map_err(|_| "the queue cannot be decoded")?.unwrap_or_default();
+					queue.extend(submissions);
+					Ok(queue)
+				})
+				.map(|_| ())
+				.map_err(|e| match e {
+					MutateStorageError::ConcurrentModification(_) =>
+						"the queue was modified while submissions were put back",
+					MutateStorageError::ValueFunctionFailed(e) => e,
+				})
+		}
+
+		/// Embed the content of `submission` into `dimension` components, encoded for its
+		/// collection.
+		fn embed(
+			submission: &RawContentOf<T>,
+			inference: &str,
+			content_endpoint: Option<&str>,
+			dimension: u32,
+		) -> Result<Vec<u8>, Failure> {
+			let encoding = match submission.collection {
+				Some(id) =>
+					pallet_template::Collections::<T>::get(id)
+						.ok_or(Failure::Permanent("the collection does not exist"))?
+						.encoding,
+				None => VectorEncoding::default(),
+			};
+			// Tags are checked ahead of the call, rather than paying for a failing one.
+			let tag_length = pallet_template::Pallet::<T>::tag_length() as usize;
+			if submission.tags.len() > pallet_template::Pallet::<T>::tags_per_vector() as usize ||
+				submission.tags.iter().any(|tag| tag.len() > tag_length)
+			{
+				return Err(Failure::Permanent("the tags exceed the limits of the pool"));
+			}
+
+			let content = Self::content(&submission.content_hash, content_endpoint)?;
+			let text = core::str::from_utf8(&content)
+				.map_err(|_| Failure::Permanent("the content is not UTF-8 text"))?;
+			let embedding = infer(inference, text)?;
+			// The endpoint runs another model than the one set; the operator may fix either.
+			if embedding.len() != dimension as usize {
+				return Err(Failure::Transient(
+					"the embedding does not have the dimension of the model",
+				));
+			}
+			let data = encoding
+				.encode(&embedding)
+				.ok_or(Failure::Permanent("the embedding cannot be encoded for the collection"))?;
+			if data.len() > pallet_template::Pallet::<T>::vector_length() as usize {
+				return Err(Failure::Permanent("the embedding is too long for the pool"));
+			}
+			Ok(data)
+		}
+
+		/// The content hashing to `content_hash`, from local storage or else `content_endpoint`.
+		fn content(
+			content_hash: &T::Hash,
+			content_endpoint: Option<&str>,
+		) -> Result<Vec<u8>, Failure> {
+			let content = match sp_io::offchain::local_storage_get(
+				StorageKind::PERSISTENT,
+				&content_key(content_hash.as_ref()),
+			) {
+				Some(content) => content,
+				None => {
+					let endpoint = content_endpoint
+						.ok_or(Failure::Transient("the content is not stored locally"))?;
+					let url = alloc::format!("{endpoint}/0x{}", hex(content_hash.as_ref()));
+					fetch(http::Request::get(&url))?
+				},
+			};
+			if T::Hashing::hash(&content) != *content_hash {
+				return Err(Failure::Permanent("the content does not match its hash"));
+			}
+			Ok(content)
+		}
+
+		/// Store `data` as the embedding of `submission` by `model`, authored by the signing
+		/// account.
+		fn submit(
+			signer: &Signer<T, T::AuthorityId, ForAny>,
+			submission: &RawContentOf<T>,
+			data: Vec<u8>,
+			model: ModelId,
+		) -> Result<(), Failure> {
+			let data: BoundedVec<u8, T::MaxVectorLength> = data
+				.try_into()
+				.map_err(|_| Failure::Permanent("the embedding is too long for the pool"))?;
+			match signer.send_signed_transaction(|account| {
+				pallet_template::Call::store_weight_data {
+					weight_data: (data.clone(), account.id.clone(), Default::default()),
+					tags: submission.tags.clone(),
+					collection: submission.collection,
+					model,
+				}
+			}) {
+				Some((_, Ok(()))) => Ok(()),
+				_ => Err(Failure::Transient("the embedding could not be submitted")),
+			}
+		}
+	}
+
+	/// URL stored under `key` in persistent local storage, without trailing slashes.
+	fn endpoint(key: &[u8]) -> Result<Option<String>, &'static str> {
+		sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, key)
+			.map(|url| {
+				String::from_utf8(url)
+					.map(|url| url.trim_end_matches('/').into())
+					.map_err(|_| "an endpoint is not a UTF-8 URL")
+			})
+			.transpose()
+	}
+
+	/// Embedding of `text` computed by the `inference` endpoint.
+	fn infer(inference: &str, text: &str) -> Result<Vec<f32>, Failure> {
+		if let Some(model) = inference.strip_prefix(ROOST_PREFIX) {
+			// The service logs why it failed; it may have a model to answer with after a restart.
+			return roost_primitives::roost::embed_text(model, text)
+				.filter(|embedding| !embedding.is_empty())
+				.map(|embedding| {
+					embedding
+						.chunks_exact(4)
+						.map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+						.collect()
+				})
+				.ok_or(Failure::Transient("the inference service of the node failed to answer"));
+		}
+		let body = serde_json::to_vec(&InferenceRequest { input: text })
+			.map_err(|_| Failure::Permanent("the content cannot be sent for inference"))?;
+		let response = fetch(
+			http::Request::post(inference, alloc::vec![body])
+				.add_header("Content-Type", "application/json"),
+		)?;
+		// A malformed answer is the endpoint's fault, not the submission's.
+		match serde_json::from_slice::<InferenceResponse>(&response) {
+			Ok(InferenceResponse { embedding }) if !embedding.is_empty() => Ok(embedding),
+			_ => Err(Failure::Transient("the inference endpoint answered with no embedding")),
+		}
+	}
+
+	/// Body of the response to `request`, which must succeed within [`HTTP_TIMEOUT_MS`].
+	fn fetch<I: AsRef<[u8]>, B: IntoIterator<Item = I>>(
+		request: http::Request<'_, B>,
+	) -> Result<Vec<u8>, Failure> {
+		let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(HTTP_TIMEOUT_MS));
+		let response = request
+			.deadline(deadline)
+			.send()
+			.map_err(|_| Failure::Transient("the endpoint cannot be reached"))?
+			.try_wait(deadline)
+			.map_err(|_| Failure::Transient("the endpoint did not answer in time"))?
+			.map_err(|_| Failure::Transient("the endpoint cannot be reached"))?;
+		match response.code {
+			200 => Ok(response.body().collect()),
+			400..=499 => Err(Failure::Permanent("the endpoint rejected the request")),
+			_ => Err(Failure::Transient("the endpoint failed to answer")),
+		}
+	}
+
+	/// Lowercase hexadecimal encoding of `bytes`.
+	fn hex(bytes: &[u8]) -> String {
+		bytes.iter().map(|byte| alloc::format!("{byte:02x}")).collect()
+	}
+}
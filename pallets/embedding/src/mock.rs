@@ -0,0 +1,144 @@
+use crate as pallet_embedding;
+use frame_support::{
+	derive_impl, parameter_types,
+	traits::{ConstU32, ConstU64},
+};
+use frame_system::offchain::{
+	AppCrypto, CreateSignedTransaction, SendTransactionTypes, SigningTypes,
+};
+use sp_runtime::{
+	testing::{TestSignature, TestXt, UintAuthorityId},
+	traits::Extrinsic as ExtrinsicT,
+	BuildStorage, Perbill,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		Balances: pallet_balances,
+		TemplateModule: pallet_template,
+		Embedding: pallet_embedding,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+	type ExistentialDeposit = ConstU64<1>;
+}
+
+parameter_types! {
+	pub const SimilarityThreshold: Option<Perbill> = Some(Perbill::from_percent(95));
+}
+
+impl pallet_template::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type Currency = Balances;
+	type CollectionDeposit = ConstU64<10>;
+	type MaxVectors = ConstU32<8>;
+	type MaxVectorLength = ConstU32<16>;
+	type MaxTagLength = ConstU32<8>;
+	type MaxTagsPerVector = ConstU32<2>;
+	type VectorsPerAuthor = ConstU32<8>;
+	type VectorLength = ConstU32<16>;
+	type TagLength = ConstU32<8>;
+	type TagsPerVector = ConstU32<2>;
+	type SimilarityThreshold = SimilarityThreshold;
+	type MaxSimilaritySamples = ConstU32<8>;
+	type MaxBatchSize = ConstU32<4>;
+	type PruneOrigin = frame_system::EnsureRoot<u64>;
+	type Observer = ();
+	type Models = Models;
+	type EphemeralKeys = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = Models;
+}
+
+/// Models of the mock: model `d` embeds into `d` dimensions, except for model `0`, which is not
+/// registered.
+pub struct Models;
+
+impl pallet_template::EmbeddingModels for Models {
+	fn dimension(model: pallet_template::ModelId) -> Option<u32> {
+		(model != 0).then_some(model)
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_template::BenchmarkHelper for Models {
+	fn register_model(dimension: u32) -> pallet_template::ModelId {
+		dimension
+	}
+}
+
+/// Transactions sent by the offchain worker.
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
+
+impl SigningTypes for Test {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<LocalCall> SendTransactionTypes<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> CreateSignedTransaction<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		_public: UintAuthorityId,
+		account: u64,
+		_nonce: Self::Nonce,
+	) -> Option<(RuntimeCall, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (account, ())))
+	}
+}
+
+/// Signs the offchain worker's transactions with the keys set by
+/// [`UintAuthorityId::set_all_keys`].
+pub struct TestAuthorityId;
+
+impl AppCrypto<UintAuthorityId, TestSignature> for TestAuthorityId {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+}
+
+impl pallet_embedding::Config for Test {
+	type AuthorityId = TestAuthorityId;
+	type MaxEmbeddingsPerBlock = ConstU32<2>;
+}
+
+/// Account of the agent running the offchain worker.
+pub const ALICE: u64 = 1;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	// Enough to create collections.
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(ALICE, 1_000)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	// Events are not emitted on the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,329 @@
+use crate::{mock::*, *};
+use codec::{Decode, Encode};
+use frame_support::{assert_ok, traits::Hooks};
+use pallet_template::{AuthorVectors, ModelId, VectorEncoding, Vectors};
+use parking_lot::RwLock;
+use sp_core::{
+	offchain::{
+		testing::{
+			OffchainState, PendingRequest, PoolState, TestOffchainExt, TestTransactionPoolExt,
+		},
+		OffchainDbExt, OffchainWorkerExt, StorageKind, TransactionPoolExt,
+	},
+	H256,
+};
+use sp_runtime::{
+	testing::UintAuthorityId,
+	traits::{Dispatchable, Hash},
+};
+use std::sync::Arc;
+
+const INFERENCE: &str = "http://localhost:8000/embed";
+
+type Hashing = <Test as frame_system::Config>::Hashing;
+
+/// Externalities running the offchain worker, with its HTTP and transaction pool states.
+fn offchain_ext() -> (sp_io::TestExternalities, Arc<RwLock<OffchainState>>, Arc<RwLock<PoolState>>)
+{
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	UintAuthorityId::set_all_keys(vec![ALICE]);
+	(ext, state, pool_state)
+}
+
+fn set_local(key: &[u8], value: &[u8]) {
+	sp_io::offchain::local_storage_set(StorageKind::PERSISTENT, key, value);
+}
+
+/// Set the inference endpoint, embedding with `model`.
+fn set_inference(endpoint: &str, model: ModelId) {
+	set_local(INFERENCE_ENDPOINT_KEY, endpoint.as_bytes());
+	set_local(MODEL_KEY, &model.encode());
+}
+
+/// Submission of `text`, which is also stored locally.
+fn stored(text: &str, tags: &[&[u8]], collection: Option<u32>) -> RawContentOf<Test> {
+	set_local(&content_key(Hashing::hash(text.as_bytes()).as_ref()), text.as_bytes());
+	submission(text, tags, collection)
+}
+
+fn submission(text: &str, tags: &[&[u8]], collection: Option<u32>) -> RawContentOf<Test> {
+	RawContent {
+		content_hash: Hashing::hash(text.as_bytes()),
+		tags: tags.iter().map(|t| t.to_vec()).collect(),
+		collection,
+	}
+}
+
+fn queue(submissions: Vec<RawContentOf<Test>>) {
+	set_local(QUEUE_KEY, &submissions.encode());
+}
+
+fn queued() -> Vec<RawContentOf<Test>> {
+	sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, QUEUE_KEY)
+		.map(|queue| Decode::decode(&mut &queue[..]).unwrap())
+		.unwrap_or_default()
+}
+
+fn attempts(text: &str) -> Option<u32> {
+	let key = [ATTEMPTS_PREFIX, Hashing::hash(text.as_bytes()).as_ref()].concat();
+	sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, &key)
+		.map(|attempts| Decode::decode(&mut &attempts[..]).unwrap())
+}
+
+/// Expect `text` to be embedded by the inference endpoint, which answers with `response`.
+fn expect_inference(state: &RwLock<OffchainState>, text: &str, response: &str) {
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: INFERENCE.into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: format!(r#"{{"input":"{text}"}}"#).into_bytes(),
+		response: Some(response.as_bytes().to_vec()),
+		sent: true,
+		..Default::default()
+	});
+}
+
+fn f32_vector(components: &[f32]) -> Vec<u8> {
+	components.iter().flat_map(|c| c.to_le_bytes()).collect()
+}
+
+/// Calls sent by the offchain worker, checking that they are signed by [`ALICE`].
+fn sent(pool: &RwLock<PoolState>) -> Vec<RuntimeCall> {
+	pool.write()
+		.transactions
+		.drain(..)
+		.map(|tx| {
+			let tx = Extrinsic::decode(&mut &*tx).unwrap();
+			assert_eq!(tx.signature, Some((ALICE, ())));
+			tx.call
+		})
+		.collect()
+}
+
+fn store_call(
+	data: Vec<u8>,
+	tags: &[&[u8]],
+	collection: Option<u32>,
+	model: ModelId,
+) -> RuntimeCall {
+	RuntimeCall::TemplateModule(pallet_template::Call::store_weight_data {
+		weight_data: (data.try_into().unwrap(), ALICE, Default::default()),
+		tags: tags.iter().map(|t| t.to_vec()).collect(),
+		collection,
+		model,
+	})
+}
+
+#[test]
+fn offchain_worker_stores_embeddings_of_queued_content() {
+	let (mut ext, state, pool) = offchain_ext();
+	ext.execute_with(|| {
+		queue(vec![
+			stored("first", &[b"notes"], None),
+			stored("second", &[], None),
+			stored("third", &[], None),
+		]);
+
+		// Nothing is embedded until the inference endpoint and its model are set.
+		Embedding::offchain_worker(1);
+		set_local(INFERENCE_ENDPOINT_KEY, INFERENCE.as_bytes());
+		Embedding::offchain_worker(1);
+		assert!(pool.read().transactions.is_empty());
+		assert_eq!(queued().len(), 3);
+
+		set_local(MODEL_KEY, &2u32.encode());
+		expect_inference(&state, "first", r#"{"embedding":[1.0,0.0]}"#);
+		expect_inference(&state, "second", r#"{"embedding":[0.0,1.0]}"#);
+		Embedding::offchain_worker(1);
+
+		// Two embeddings per block, the rest waiting for the next one.
+		let calls = sent(&pool);
+		assert_eq!(
+			calls,
+			vec![
+				store_call(f32_vector(&[1.0, 0.0]), &[b"notes"], None, 2),
+				store_call(f32_vector(&[0.0, 1.0]), &[], None, 2),
+			]
+		);
+		assert_eq!(queued(), vec![submission("third", &[], None)]);
+
+		// The memory is authored by the account of the signing key.
+		assert_ok!(calls[0].clone().dispatch(RuntimeOrigin::signed(ALICE)));
+		let vector_id = AuthorVectors::<Test>::get(ALICE).unwrap()[0];
+		assert_eq!(Vectors::<Test>::get(vector_id).unwrap().data.to_vec(), f32_vector(&[1.0, 0.0]));
+	});
+}
+
+#[test]
+fn content_is_fetched_and_checked_against_its_hash() {
+	let (mut ext, state, pool) = offchain_ext();
+	ext.execute_with(|| {
+		set_inference(INFERENCE, 2);
+		set_local(CONTENT_ENDPOINT_KEY, b"http://localhost:8080/content/");
+		queue(vec![submission("remote", &[b"web"], None), submission("expected", &[], None)]);
+
+		let expect_content = |text: &str, response: &[u8]| {
+			let hash: H256 = Hashing::hash(text.as_bytes());
+			state.write().expect_request(PendingRequest {
+				method: "GET".into(),
+				uri: format!("http://localhost:8080/content/{hash:?}"),
+				response: Some(response.to_vec()),
+				sent: true,
+				..Default::default()
+			});
+		};
+		expect_content("remote", b"remote");
+		expect_inference(&state, "remote", r#"{"embedding":[0.5,0.5]}"#);
+		// Content not matching its hash is never embedded.
+		expect_content("expected", b"tampered");
+		Embedding::offchain_worker(1);
+
+		assert_eq!(sent(&pool), vec![store_call(f32_vector(&[0.5, 0.5]), &[b"web"], None, 2)]);
+		assert!(queued().is_empty());
+		assert_eq!(attempts("expected"), None);
+	});
+}
+
+#[test]
+fn submissions_are_retried_until_their_endpoint_answers() {
+	let (mut ext, state, pool) = offchain_ext();
+	ext.execute_with(|| {
+		set_inference(INFERENCE, 2);
+		// The content is neither stored locally nor fetchable yet.
+		queue(vec![submission("later", &[], None)]);
+		Embedding::offchain_worker(1);
+		assert!(pool.read().transactions.is_empty());
+		assert_eq!(queued(), vec![submission("later", &[], None)]);
+		assert_eq!(attempts("later"), Some(1));
+
+		stored("later", &[], None);
+		expect_inference(&state, "later", r#"{"embedding":[1.0,1.0]}"#);
+		Embedding::offchain_worker(2);
+		assert_eq!(sent(&pool), vec![store_call(f32_vector(&[1.0, 1.0]), &[], None, 2)]);
+		assert!(queued().is_empty());
+		assert_eq!(attempts("later"), None);
+
+		// An inference endpoint answering with no embedding is retried a limited number of times.
+		queue(vec![stored("broken", &[], None)]);
+		expect_inference(&state, "broken", r#"{"error":"model not loaded"}"#);
+		Embedding::offchain_worker(3);
+		assert_eq!(attempts("broken"), Some(1));
+		assert_eq!(queued().len(), 1);
+
+		let key = [ATTEMPTS_PREFIX, Hashing::hash(b"broken").as_ref()].concat();
+		set_local(&key, &(MAX_ATTEMPTS - 1).encode());
+		expect_inference(&state, "broken", r#"{"error":"model not loaded"}"#);
+		Embedding::offchain_worker(4);
+		assert!(pool.read().transactions.is_empty());
+		assert!(queued().is_empty());
+		assert_eq!(attempts("broken"), None);
+	});
+}
+
+#[test]
+fn embeddings_are_encoded_for_their_collection() {
+	let (mut ext, state, pool) = offchain_ext();
+	ext.execute_with(|| {
+		assert_ok!(TemplateModule::create_collection(
+			RuntimeOrigin::signed(ALICE),
+			VectorEncoding::Binary
+		));
+		set_inference(INFERENCE, 8);
+		queue(vec![
+			stored("bits", &[], Some(0)),
+			// Eight `f32` components are longer than the 16 bytes vectors may take.
+			stored("long", &[], None),
+			stored("lost", &[], Some(7)),
+			stored("tagged", &[b"much too long"], None),
+		]);
+		expect_inference(&state, "bits", r#"{"embedding":[1,-1,1,-1,1,-1,1,-1]}"#);
+		expect_inference(&state, "long", r#"{"embedding":[1,2,3,4,5,6,7,8]}"#);
+		Embedding::offchain_worker(1);
+		assert_eq!(sent(&pool), vec![store_call(vec![0b1010_1010], &[], Some(0), 8)]);
+
+		// Submissions breaking the limits of the pool are dropped before any request is made.
+		Embedding::offchain_worker(2);
+		assert!(pool.read().transactions.is_empty());
+		assert!(queued().is_empty());
+	});
+}
+
+#[test]
+fn embeddings_must_have_the_dimension_of_the_model() {
+	let (mut ext, state, pool) = offchain_ext();
+	ext.execute_with(|| {
+		queue(vec![stored("flat", &[], None)]);
+
+		// An unregistered model leaves the queue alone.
+		set_inference(INFERENCE, 0);
+		Embedding::offchain_worker(1);
+		assert_eq!(queued(), vec![submission("flat", &[], None)]);
+
+		// The endpoint may be running another model than the one set, until either is fixed.
+		set_inference(INFERENCE, 3);
+		expect_inference(&state, "flat", r#"{"embedding":[1.0,0.0]}"#);
+		Embedding::offchain_worker(2);
+		assert!(pool.read().transactions.is_empty());
+		assert_eq!(queued(), vec![submission("flat", &[], None)]);
+		assert_eq!(attempts("flat"), Some(1));
+
+		expect_inference(&state, "flat", r#"{"embedding":[1.0,0.0,0.0]}"#);
+		Embedding::offchain_worker(3);
+		assert_eq!(sent(&pool), vec![store_call(f32_vector(&[1.0, 0.0, 0.0]), &[], None, 3)]);
+		assert!(queued().is_empty());
+	});
+}
+
+#[test]
+fn nothing_is_taken_without_a_signing_key() {
+	let (mut ext, _, pool) = offchain_ext();
+	UintAuthorityId::set_all_keys(Vec::<u64>::new());
+	ext.execute_with(|| {
+		set_inference(INFERENCE, 2);
+		queue(vec![stored("kept", &[], None)]);
+		Embedding::offchain_worker(1);
+		assert!(pool.read().transactions.is_empty());
+		assert_eq!(queued(), vec![submission("kept", &[], None)]);
+	});
+}
+
+/// Inference service of a node serving a single text embedding model.
+struct Node;
+
+impl roost_primitives::Inference for Node {
+	fn embed_text(&self, model: Option<&str>, text: &str) -> Result<Vec<f32>, String> {
+		match (model, text) {
+			(Some("minilm"), "served") => Ok(vec![0.25, 0.75]),
+			_ => Err("the model failed".into()),
+		}
+	}
+
+	fn embed_image(&self, _: Option<&str>, _: &[u8]) -> Result<Vec<f32>, String> {
+		Err("no image model".into())
+	}
+
+	fn complete(&self, _: Option<&str>, _: &str, _: u32) -> Result<String, String> {
+		Err("no completion model".into())
+	}
+}
+
+#[test]
+fn content_is_embedded_by_the_node_with_a_roost_endpoint() {
+	let (mut ext, _, pool) = offchain_ext();
+	ext.register_extension(roost_primitives::RoostExt::new(Node));
+	ext.execute_with(|| {
+		set_inference("roost:minilm", 2);
+		queue(vec![stored("served", &[], None), stored("failed", &[], None)]);
+		Embedding::offchain_worker(1);
+
+		assert_eq!(sent(&pool), vec![store_call(f32_vector(&[0.25, 0.75]), &[], None, 2)]);
+		// Failures of the service are retried, as those of any endpoint.
+		assert_eq!(queued(), vec![submission("failed", &[], None)]);
+		assert_eq!(attempts("failed"), Some(1));
+	});
+}
@@ -0,0 +1,51 @@
+//! Benchmarking setup for pallet-model-registry
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as ModelRegistry;
+use frame_benchmarking::v2::*;
+use frame_support::{traits::Get, BoundedVec};
+use sp_core::H256;
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn register_model() -> Result<(), BenchmarkError> {
+		let origin =
+			T::RegistrarOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let name = BoundedVec::truncate_from(vec![b'n'; T::MaxNameLength::get() as usize]);
+		let license = BoundedVec::truncate_from(vec![b'l'; T::MaxLicenseLength::get() as usize]);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, name, 384, DistanceMetric::Cosine, H256::zero(), license);
+
+		assert_eq!(Models::<T>::get(0).map(|model| model.dimension), Some(384));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn retire_model() -> Result<(), BenchmarkError> {
+		let origin =
+			T::RegistrarOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let name = BoundedVec::truncate_from(vec![b'n'; T::MaxNameLength::get() as usize]);
+		let license = BoundedVec::truncate_from(vec![b'l'; T::MaxLicenseLength::get() as usize]);
+		let model_id = Pallet::<T>::do_register_model(
+			name,
+			384,
+			DistanceMetric::Cosine,
+			H256::zero(),
+			license,
+		)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, model_id);
+
+		assert!(Pallet::<T>::active_model(model_id).is_none());
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(ModelRegistry, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,226 @@
+//! # Model Registry Pallet
+//!
+//! Embeddings are only comparable when they come from the same model: two models of the same
+//! dimension still embed into unrelated spaces. The registry names the models vectors may be
+//! produced with, so that memory can record which one each vector comes from.
+//!
+//! ## Overview
+//!
+//! - [`Pallet::register_model`] adds a model, with its dimension, the distance its embeddings
+//!   are compared with, the SHA-256 hash of its weights file and its license, once
+//!   [`Config::RegistrarOrigin`] approves it.
+//! - [`Pallet::retire_model`] stops new vectors from referencing a model. Its entry stays in
+//!   [`Models`], so vectors already stored keep describing where they come from.
+//! - [`Pallet::active_model`] is how other pallets resolve the model of a new vector.
+//! - [`migrations::RegisterLegacyModel`] registers the model of the vectors a chain stored before
+//!   it had a registry.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod migrations;
+pub mod weights;
+pub use weights::WeightInfo;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::RuntimeDebug;
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+
+/// Identifier of a registered model.
+pub type ModelId = u32;
+
+/// How the embeddings of a model are compared.
+#[derive(
+	Clone, Copy, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug, Serialize,
+	Deserialize,
+)]
+pub enum DistanceMetric {
+	/// Angle between embeddings, regardless of their length.
+	Cosine,
+	/// Dot product of embeddings, for models trained to use it.
+	DotProduct,
+	/// Straight-line distance between embeddings.
+	Euclidean,
+	/// Number of differing bits, for binary embeddings.
+	Hamming,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use alloc::vec::Vec;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_core::H256;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Weight information for the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+		/// Origin allowed to register and retire models.
+		type RegistrarOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Maximum length of the name of a model, in bytes.
+		#[pallet::constant]
+		type MaxNameLength: Get<u32>;
+		/// Maximum length of the license of a model, in bytes.
+		#[pallet::constant]
+		type MaxLicenseLength: Get<u32>;
+	}
+
+	/// A registered model.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct ModelInfo<T: Config> {
+		/// Name of the model, such as `all-MiniLM-L6-v2`.
+		pub name: BoundedVec<u8, T::MaxNameLength>,
+		/// Number of components of its embeddings.
+		pub dimension: u32,
+		/// How its embeddings are compared.
+		pub metric: DistanceMetric,
+		/// SHA-256 hash of its weights file, so that nodes can check they run the same model.
+		pub weights_hash: H256,
+		/// License of the model, such as an SPDX identifier.
+		pub license: BoundedVec<u8, T::MaxLicenseLength>,
+		/// Whether new vectors may no longer reference the model.
+		pub retired: bool,
+	}
+
+	/// Identifier of the next model.
+	#[pallet::storage]
+	pub type NextModelId<T: Config> = StorageValue<_, ModelId, ValueQuery>;
+
+	/// Registered models, retired ones included.
+	#[pallet::storage]
+	pub type Models<T: Config> = StorageMap<_, Twox64Concat, ModelId, ModelInfo<T>>;
+
+	/// Models the chain starts with.
+	///
+	/// Models are registered in order, so the first entry receives model id `0`.
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// Models to register, as `(name, dimension, metric, weights_hash, license)`.
+		pub models: Vec<(Vec<u8>, u32, DistanceMetric, H256, Vec<u8>)>,
+		#[serde(skip)]
+		pub _config: core::marker::PhantomData<T>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			for (name, dimension, metric, weights_hash, license) in &self.models {
+				let name = BoundedVec::try_from(name.clone())
+					.expect("genesis model name exceeds MaxNameLength");
+				let license = BoundedVec::try_from(license.clone())
+					.expect("genesis model license exceeds MaxLicenseLength");
+				Pallet::<T>::do_register_model(name, *dimension, *metric, *weights_hash, license)
+					.expect("genesis models must be valid");
+			}
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A model was registered
+		ModelRegistered { model_id: ModelId, dimension: u32, metric: DistanceMetric },
+		/// A model was retired
+		ModelRetired { model_id: ModelId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Models must have at least one dimension
+		InvalidDimension,
+		/// Model not found
+		ModelNotFound,
+		/// The model is already retired
+		AlreadyRetired,
+		/// Every model id is taken
+		ModelIdOverflow,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register a model that vectors may reference.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::register_model())]
+		pub fn register_model(
+			origin: OriginFor<T>,
+			name: BoundedVec<u8, T::MaxNameLength>,
+			dimension: u32,
+			metric: DistanceMetric,
+			weights_hash: H256,
+			license: BoundedVec<u8, T::MaxLicenseLength>,
+		) -> DispatchResult {
+			T::RegistrarOrigin::ensure_origin(origin)?;
+			Self::do_register_model(name, dimension, metric, weights_hash, license)?;
+			Ok(())
+		}
+
+		/// Stop new vectors from referencing a model.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::retire_model())]
+		pub fn retire_model(origin: OriginFor<T>, model_id: ModelId) -> DispatchResult {
+			T::RegistrarOrigin::ensure_origin(origin)?;
+			Models::<T>::try_mutate(model_id, |model| -> DispatchResult {
+				let model = model.as_mut().ok_or(Error::<T>::ModelNotFound)?;
+				ensure!(!model.retired, Error::<T>::AlreadyRetired);
+				model.retired = true;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::ModelRetired { model_id });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Register a model, returning its id.
+		///
+		/// Shared by [`Pallet::register_model`] and the genesis build.
+		pub fn do_register_model(
+			name: BoundedVec<u8, T::MaxNameLength>,
+			dimension: u32,
+			metric: DistanceMetric,
+			weights_hash: H256,
+			license: BoundedVec<u8, T::MaxLicenseLength>,
+		) -> Result<ModelId, DispatchError> {
+			ensure!(dimension > 0, Error::<T>::InvalidDimension);
+			let model_id = NextModelId::<T>::get();
+			NextModelId::<T>::put(model_id.checked_add(1).ok_or(Error::<T>::ModelIdOverflow)?);
+			let model =
+				ModelInfo { name, dimension, metric, weights_hash, license, retired: false };
+			Models::<T>::insert(model_id, model);
+
+			Self::deposit_event(Event::ModelRegistered { model_id, dimension, metric });
+			Ok(model_id)
+		}
+
+		/// The model `model_id`, unless it is unknown or retired.
+		pub fn active_model(model_id: ModelId) -> Option<ModelInfo<T>> {
+			Models::<T>::get(model_id).filter(|model| !model.retired)
+		}
+	}
+}
@@ -0,0 +1,65 @@
+//! Upgrades of the model registry.
+
+use crate::{Config, DistanceMetric, NextModelId, Pallet};
+use core::marker::PhantomData;
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Defensive, Get, OnRuntimeUpgrade},
+};
+
+#[cfg(feature = "try-runtime")]
+use crate::ModelId;
+#[cfg(feature = "try-runtime")]
+use alloc::vec::Vec;
+
+/// Registers the model of the vectors stored before the registry existed, with the name `Name`
+/// and `Dimension` dimensions, as model `0`.
+///
+/// The registry is added to a running chain by a runtime upgrade, so governance cannot register
+/// anything ahead of the migration attributing those vectors to a model. The model is only
+/// registered while no model is, so the upgrade does nothing on chains whose registry was filled
+/// at genesis or by an earlier upgrade. Their vectors were never checked against a model, so
+/// neither the metric nor the weights of the model are known: it is recorded as a cosine model
+/// without a weights hash or license.
+pub struct RegisterLegacyModel<T, Name, Dimension>(PhantomData<(T, Name, Dimension)>);
+
+impl<T, Name, Dimension> OnRuntimeUpgrade for RegisterLegacyModel<T, Name, Dimension>
+where
+	T: Config,
+	Name: Get<&'static [u8]>,
+	Dimension: Get<u32>,
+{
+	fn on_runtime_upgrade() -> Weight {
+		if NextModelId::<T>::get() != 0 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let name = BoundedVec::truncate_from(Name::get().to_vec());
+		// Model ids are free and only a zero dimension is rejected.
+		let _ = Pallet::<T>::do_register_model(
+			name,
+			Dimension::get(),
+			DistanceMetric::Cosine,
+			Default::default(),
+			Default::default(),
+		)
+		.defensive_proof("the legacy model has at least one dimension");
+		T::DbWeight::get().reads_writes(1, 2)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, frame_support::sp_runtime::TryRuntimeError> {
+		Ok(NextModelId::<T>::get().encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+		let next_model_id =
+			ModelId::decode(&mut &state[..]).map_err(|_| "cannot decode pre-upgrade state")?;
+		if next_model_id == 0 {
+			let model = Pallet::<T>::active_model(0).ok_or("legacy model was not registered")?;
+			ensure!(model.dimension == Dimension::get(), "legacy model has the wrong dimension");
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,135 @@
+use crate::{mock::*, *};
+use frame_support::{assert_noop, assert_ok};
+use sp_core::H256;
+use sp_runtime::{BuildStorage, DispatchError, DispatchResult};
+
+const WEIGHTS: H256 = H256::repeat_byte(0xab);
+
+fn register(dimension: u32, metric: DistanceMetric) -> DispatchResult {
+	ModelRegistry::register_model(
+		RuntimeOrigin::root(),
+		b"minilm".to_vec().try_into().unwrap(),
+		dimension,
+		metric,
+		WEIGHTS,
+		b"Apache-2.0".to_vec().try_into().unwrap(),
+	)
+}
+
+#[test]
+fn models_are_registered_by_the_registrar() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ModelRegistry::register_model(
+				RuntimeOrigin::signed(1),
+				b"minilm".to_vec().try_into().unwrap(),
+				384,
+				DistanceMetric::Cosine,
+				WEIGHTS,
+				b"Apache-2.0".to_vec().try_into().unwrap(),
+			),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(register(0, DistanceMetric::Cosine), Error::<Test>::InvalidDimension);
+
+		assert_ok!(register(384, DistanceMetric::Cosine));
+		System::assert_last_event(
+			Event::ModelRegistered { model_id: 0, dimension: 384, metric: DistanceMetric::Cosine }
+				.into(),
+		);
+		assert_ok!(register(512, DistanceMetric::Hamming));
+		assert_eq!(NextModelId::<Test>::get(), 2);
+
+		let model = ModelRegistry::active_model(0).unwrap();
+		assert_eq!(model.name.to_vec(), b"minilm".to_vec());
+		assert_eq!(model.dimension, 384);
+		assert_eq!(model.metric, DistanceMetric::Cosine);
+		assert_eq!(model.weights_hash, WEIGHTS);
+		assert_eq!(model.license.to_vec(), b"Apache-2.0".to_vec());
+		assert!(!model.retired);
+		assert_eq!(ModelRegistry::active_model(1).map(|model| model.dimension), Some(512));
+	});
+}
+
+#[test]
+fn model_ids_do_not_wrap() {
+	new_test_ext().execute_with(|| {
+		NextModelId::<Test>::put(ModelId::MAX);
+		assert_noop!(register(384, DistanceMetric::Cosine), Error::<Test>::ModelIdOverflow);
+	});
+}
+
+#[test]
+fn retired_models_are_kept_but_inactive() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(register(384, DistanceMetric::Cosine));
+		assert_noop!(
+			ModelRegistry::retire_model(RuntimeOrigin::signed(1), 0),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			ModelRegistry::retire_model(RuntimeOrigin::root(), 1),
+			Error::<Test>::ModelNotFound
+		);
+
+		assert_ok!(ModelRegistry::retire_model(RuntimeOrigin::root(), 0));
+		System::assert_last_event(Event::ModelRetired { model_id: 0 }.into());
+		assert!(ModelRegistry::active_model(0).is_none());
+		assert!(Models::<Test>::get(0).unwrap().retired);
+		assert_noop!(
+			ModelRegistry::retire_model(RuntimeOrigin::root(), 0),
+			Error::<Test>::AlreadyRetired
+		);
+
+		// Retired ids are not reused.
+		assert_ok!(register(384, DistanceMetric::Cosine));
+		assert_eq!(NextModelId::<Test>::get(), 2);
+	});
+}
+
+#[test]
+fn genesis_registers_models_in_order() {
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	crate::GenesisConfig::<Test> {
+		models: vec![
+			(b"minilm".to_vec(), 384, DistanceMetric::Cosine, WEIGHTS, b"Apache-2.0".to_vec()),
+			(b"binary".to_vec(), 512, DistanceMetric::Hamming, H256::zero(), b"MIT".to_vec()),
+		],
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	sp_io::TestExternalities::new(t).execute_with(|| {
+		assert_eq!(ModelRegistry::active_model(0).map(|model| model.dimension), Some(384));
+		assert_eq!(
+			ModelRegistry::active_model(1).map(|model| model.metric),
+			Some(DistanceMetric::Hamming)
+		);
+		assert_eq!(NextModelId::<Test>::get(), 2);
+	});
+}
+
+#[test]
+fn legacy_model_is_registered_by_the_upgrade_adding_the_registry() {
+	use frame_support::{
+		parameter_types,
+		traits::{ConstU32, OnRuntimeUpgrade},
+	};
+
+	parameter_types! {
+		pub const LegacyName: &'static [u8] = b"legacy";
+	}
+	type Upgrade = migrations::RegisterLegacyModel<Test, LegacyName, ConstU32<384>>;
+
+	new_test_ext().execute_with(|| {
+		Upgrade::on_runtime_upgrade();
+		let model = ModelRegistry::active_model(0).unwrap();
+		assert_eq!((model.name.to_vec(), model.dimension), (b"legacy".to_vec(), 384));
+
+		// Later upgrades leave the registry alone.
+		assert_ok!(register(512, DistanceMetric::Hamming));
+		Upgrade::on_runtime_upgrade();
+		assert_eq!(NextModelId::<Test>::get(), 2);
+	});
+}
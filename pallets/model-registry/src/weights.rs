@@ -0,0 +1,55 @@
+//! Weights for pallet_model_registry
+//!
+//! PLACEHOLDER WEIGHTS, written by hand rather than measured. Storage reads, writes and proof
+//! sizes follow the storage accessed by the worst case of each benchmark in `benchmarking.rs`,
+//! for the runtime configuration (`MaxNameLength = 64`, `MaxLicenseLength = 64`). The ref-time
+//! components are guesses. Replace this file with the output of the node's `benchmark pallet`
+//! command, run on reference hardware, before relying on these weights.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_model_registry.
+pub trait WeightInfo {
+	fn register_model() -> Weight;
+	fn retire_model() -> Weight;
+}
+
+/// Placeholder weights for pallet_model_registry, until it is benchmarked on reference hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `ModelRegistry::NextModelId` (r:1 w:1)
+	/// Storage: `ModelRegistry::Models` (r:0 w:1)
+	fn register_model() -> Weight {
+		Weight::from_parts(12_600_000, 1_489)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `ModelRegistry::Models` (r:1 w:1)
+	fn retire_model() -> Weight {
+		Weight::from_parts(13_100_000, 3_683)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `ModelRegistry::NextModelId` (r:1 w:1)
+	/// Storage: `ModelRegistry::Models` (r:0 w:1)
+	fn register_model() -> Weight {
+		Weight::from_parts(12_600_000, 1_489)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `ModelRegistry::Models` (r:1 w:1)
+	fn retire_model() -> Weight {
+		Weight::from_parts(13_100_000, 3_683)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
@@ -0,0 +1,22 @@
+//! Runtime API of the reputation pallet, for off-chain consumers choosing which agents to
+//! trust with work.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_reputation::AgentRecord;
+use sp_runtime::Perbill;
+
+sp_api::decl_runtime_apis! {
+	pub trait ReputationApi<AccountId, BlockNumber>
+	where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// Score of `who`, as used to weigh its votes and decide its eligibility.
+		fn reputation(who: AccountId) -> Perbill;
+
+		/// History of `who`, with its points decayed to the current block.
+		fn record(who: AccountId) -> Option<AgentRecord<BlockNumber>>;
+	}
+}
@@ -0,0 +1,35 @@
+//! Benchmarking setup for pallet-reputation
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Reputation;
+use frame_benchmarking::v2::*;
+use frame_support::{traits::Get, BoundedVec};
+use frame_system::pallet_prelude::BlockNumberFor;
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	// Worst case: every agent is new, so all of them are looked up among the known ones.
+	#[benchmark]
+	fn sweep(n: Linear<1, { T::MaxAgents::get() }>) {
+		for i in 0..n {
+			T::BenchmarkHelper::register_agent(account("agent", i, 0));
+		}
+		let known: Vec<_> = (0..n)
+			.map(|i| (account("known", i, 0), BlockNumberFor::<T>::default()))
+			.collect();
+		StakedSince::<T>::put(BoundedVec::truncate_from(known));
+
+		#[block]
+		{
+			Reputation::<T>::sweep(1u32.into());
+		}
+
+		assert!(StakedSince::<T>::get().len() >= n as usize);
+	}
+
+	impl_benchmark_test_suite!(Reputation, crate::mock::new_test_ext(), crate::mock::Test);
+}
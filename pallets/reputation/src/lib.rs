@@ -0,0 +1,298 @@
+//! # Reputation Pallet
+//!
+//! Keeps the on-chain history of every agent and condenses it into a score between zero and one,
+//! used to weigh an agent's votes and to decide whether it may take on work.
+//!
+//! ## Overview
+//!
+//! - Other pallets report what agents do through [`Pallet::note_task_completed`],
+//!   [`Pallet::note_dispute_lost`] and [`Pallet::note_memory_pruned`]. Each event is counted in the agent's [`AgentRecord`] and adds
+//!   good or bad points to it, as set by the `*Points` constants of [`Config`].
+//! - Points decay, halving every [`Config::HalfLife`] blocks, so an agent's recent behaviour
+//!   weighs more than its past and a bad streak is eventually forgotten.
+//! - Every [`Config::SweepInterval`] blocks the agents of [`Config::Agents`] are swept, noting
+//!   since when each of them has been staking. Agents are only fully trusted once they have
+//!   staked for [`Config::MaturityPeriod`] blocks.
+//! - [`Pallet::score`] is the share of good points, both sides padded with [`Config::Prior`]
+//!   points so that a short history does not swing it, scaled by stake age from zero for
+//!   accounts never seen staking to its full value for mature agents. It is exposed by the
+//!   runtime API of `pallet-reputation-runtime-api`.
+//! - [`Reputable`] holds the accounts scoring at least [`Config::EligibilityThreshold`], to filter
+//!   out agents that keep losing disputes or having their memories pruned. Unknown accounts
+//!   score zero, so they are only let in once they have staked for a while.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::WeightInfo;
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// Points are kept in thousandths, so that decay does not round them away too early.
+pub const POINT: u64 = 1_000;
+
+/// Agents whose stake age is tracked.
+pub trait AgentRegistry<AccountId> {
+	/// Currently registered agents.
+	fn agents() -> Vec<AccountId>;
+}
+
+/// Registers agents for the benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<AccountId> {
+	/// Register `who` as an agent.
+	fn register_agent(who: AccountId);
+}
+
+/// History of an agent.
+#[derive(
+	Clone, Copy, Default, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug,
+)]
+pub struct AgentRecord<BlockNumber> {
+	/// Tasks the agent completed.
+	pub completed_tasks: u32,
+	/// Disputes the agent lost.
+	pub disputes_lost: u32,
+	/// Memories authored by the agent that were pruned.
+	pub memories_pruned: u32,
+	/// Decayed good points, in thousandths of a point, as of `updated`.
+	pub good: u64,
+	/// Decayed bad points, in thousandths of a point, as of `updated`.
+	pub bad: u64,
+	/// Block the points were last decayed at.
+	pub updated: BlockNumber,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{pallet_prelude::*, traits::Contains};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::{
+		traits::{Saturating, Zero},
+		Perbill, SaturatedConversion,
+	};
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Weight information for the operations of this pallet.
+		type WeightInfo: WeightInfo;
+		/// Agents whose stake age is tracked.
+		type Agents: AgentRegistry<Self::AccountId>;
+		/// Blocks after which points are worth half as much.
+		#[pallet::constant]
+		type HalfLife: Get<BlockNumberFor<Self>>;
+		/// Blocks an agent has to stake for to be fully trusted.
+		#[pallet::constant]
+		type MaturityPeriod: Get<BlockNumberFor<Self>>;
+		/// Blocks between two sweeps of the agents.
+		#[pallet::constant]
+		type SweepInterval: Get<BlockNumberFor<Self>>;
+		/// Good points for completing a task.
+		#[pallet::constant]
+		type TaskCompletedPoints: Get<u32>;
+		/// Bad points for losing a dispute.
+		#[pallet::constant]
+		type DisputeLostPoints: Get<u32>;
+		/// Bad points for having a memory pruned.
+		#[pallet::constant]
+		type PrunePoints: Get<u32>;
+		/// Points added to both sides of the score.
+		#[pallet::constant]
+		type Prior: Get<u32>;
+		/// Score an agent needs to be in [`Reputable`].
+		#[pallet::constant]
+		type EligibilityThreshold: Get<Perbill>;
+		/// Maximum number of agents whose stake age is tracked.
+		#[pallet::constant]
+		type MaxAgents: Get<u32>;
+		/// Registers agents for the benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<Self::AccountId>;
+	}
+
+	/// History of the agents.
+	#[pallet::storage]
+	pub type Records<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, AgentRecord<BlockNumberFor<T>>>;
+
+	/// Agents as of the last sweep, with the block they were first seen staking at.
+	#[pallet::storage]
+	pub type StakedSince<T: Config> =
+		StorageValue<_, BoundedVec<(T::AccountId, BlockNumberFor<T>), T::MaxAgents>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The agents were swept
+		AgentsSwept { agents: u32 },
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			let interval = T::SweepInterval::get();
+			if interval.is_zero() || !(now % interval).is_zero() {
+				return Weight::zero();
+			}
+			let agents = Self::sweep(now);
+			T::WeightInfo::sweep(agents)
+		}
+
+		fn integrity_test() {
+			assert!(!T::HalfLife::get().is_zero(), "points need a half-life");
+			assert!(T::Prior::get() > 0, "a score needs a prior");
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Note that `agent` completed a task.
+		pub fn note_task_completed(agent: &T::AccountId) {
+			Self::note(agent, T::TaskCompletedPoints::get(), 0, |record| {
+				record.completed_tasks.saturating_inc()
+			});
+		}
+
+		/// Note that `agent` lost a dispute.
+		pub fn note_dispute_lost(agent: &T::AccountId) {
+			Self::note(agent, 0, T::DisputeLostPoints::get(), |record| {
+				record.disputes_lost.saturating_inc()
+			});
+		}
+
+		/// Note that a memory authored by `agent` was pruned.
+		pub fn note_memory_pruned(agent: &T::AccountId) {
+			Self::note(agent, 0, T::PrunePoints::get(), |record| {
+				record.memories_pruned.saturating_inc()
+			});
+		}
+
+		/// History of `who`, with its points decayed to the current block.
+		pub fn record(who: &T::AccountId) -> Option<AgentRecord<BlockNumberFor<T>>> {
+			Records::<T>::get(who).map(|record| Self::decayed(record, Self::now()))
+		}
+
+		/// Score of `who`: its share of good points, scaled down for agents that have not staked
+		/// for [`Config::MaturityPeriod`] blocks yet.
+		pub fn score(who: &T::AccountId) -> Perbill {
+			let record = Self::record(who).unwrap_or_default();
+			let prior = u64::from(T::Prior::get()).saturating_mul(POINT);
+			let history = Perbill::from_rational(
+				record.good.saturating_add(prior),
+				record.good.saturating_add(record.bad).saturating_add(prior.saturating_mul(2)),
+			);
+			history.saturating_mul(Self::maturity(who))
+		}
+
+		/// Share of trust given to `who` for its stake age, from zero for agents that were not
+		/// seen staking yet to one once they staked for [`Config::MaturityPeriod`] blocks.
+		fn maturity(who: &T::AccountId) -> Perbill {
+			let period: u64 = T::MaturityPeriod::get().saturated_into();
+			if period == 0 {
+				return Perbill::one();
+			}
+			let age: u64 = StakedSince::<T>::get()
+				.into_iter()
+				.find(|(agent, _)| agent == who)
+				.map(|(_, since)| Self::now().saturating_sub(since).saturated_into())
+				.unwrap_or(0);
+			Perbill::from_rational(age.min(period), period)
+		}
+
+		/// Decay the points of `who`, add `good` and `bad` points and update its counters.
+		fn note(
+			who: &T::AccountId,
+			good: u32,
+			bad: u32,
+			count: impl FnOnce(&mut AgentRecord<BlockNumberFor<T>>),
+		) {
+			let now = Self::now();
+			Records::<T>::mutate(who, |record| {
+				let mut decayed = Self::decayed(record.take().unwrap_or_default(), now);
+				decayed.good.saturating_accrue(u64::from(good).saturating_mul(POINT));
+				decayed.bad.saturating_accrue(u64::from(bad).saturating_mul(POINT));
+				count(&mut decayed);
+				*record = Some(decayed);
+			});
+		}
+
+		/// `record` with its points decayed to `now`.
+		fn decayed(
+			mut record: AgentRecord<BlockNumberFor<T>>,
+			now: BlockNumberFor<T>,
+		) -> AgentRecord<BlockNumberFor<T>> {
+			let elapsed: u64 = now.saturating_sub(record.updated).saturated_into();
+			let half_life: u64 = T::HalfLife::get().saturated_into();
+			record.good = decay(record.good, elapsed, half_life);
+			record.bad = decay(record.bad, elapsed, half_life);
+			record.updated = now;
+			record
+		}
+
+		/// Refresh [`StakedSince`] from the current agents, returning how many there are.
+		pub(crate) fn sweep(now: BlockNumberFor<T>) -> u32 {
+			let previous = StakedSince::<T>::get();
+			let agents: Vec<_> = T::Agents::agents()
+				.into_iter()
+				.map(|agent| {
+					let since = previous
+						.iter()
+						.find(|(known, _)| *known == agent)
+						.map_or(now, |(_, since)| *since);
+					(agent, since)
+				})
+				.collect();
+			let count = agents.len() as u32;
+			StakedSince::<T>::put(BoundedVec::truncate_from(agents));
+			Self::deposit_event(Event::AgentsSwept { agents: count });
+			count
+		}
+
+		fn now() -> BlockNumberFor<T> {
+			frame_system::Pallet::<T>::block_number()
+		}
+	}
+
+	/// Accounts whose [`Pallet::score`] is at least [`Config::EligibilityThreshold`].
+	pub struct Reputable<T>(PhantomData<T>);
+
+	impl<T: Config> Contains<T::AccountId> for Reputable<T> {
+		fn contains(who: &T::AccountId) -> bool {
+			Pallet::<T>::score(who) >= T::EligibilityThreshold::get()
+		}
+	}
+}
+
+/// `points` after `elapsed` blocks, halving every `half_life` blocks and linearly in between.
+pub fn decay(points: u64, elapsed: u64, half_life: u64) -> u64 {
+	if half_life == 0 {
+		return points;
+	}
+	let halved = match u32::try_from(elapsed / half_life) {
+		Ok(halvings) if halvings < u64::BITS => points >> halvings,
+		_ => return 0,
+	};
+	let into = u128::from(elapsed % half_life);
+	let lost = u128::from(halved) * into / (2 * u128::from(half_life));
+	halved - lost as u64
+}
@@ -0,0 +1,79 @@
+use crate::{self as pallet_reputation, AgentRegistry};
+use core::cell::RefCell;
+use frame_support::{
+	derive_impl, parameter_types,
+	traits::{ConstU32, ConstU64},
+};
+use sp_runtime::{BuildStorage, Perbill};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		Reputation: pallet_reputation,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+}
+
+thread_local! {
+	static AGENTS: RefCell<Vec<u64>> = RefCell::new(Vec::new());
+}
+
+/// Agents kept in memory.
+pub struct MockAgents;
+
+impl MockAgents {
+	pub fn set(agents: Vec<u64>) {
+		AGENTS.with(|a| *a.borrow_mut() = agents);
+	}
+}
+
+impl AgentRegistry<u64> for MockAgents {
+	fn agents() -> Vec<u64> {
+		AGENTS.with(|a| a.borrow().clone())
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkHelper<u64> for MockAgents {
+	fn register_agent(who: u64) {
+		AGENTS.with(|a| a.borrow_mut().push(who));
+	}
+}
+
+parameter_types! {
+	pub const EligibilityThreshold: Perbill = Perbill::from_percent(20);
+}
+
+impl pallet_reputation::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Agents = MockAgents;
+	type HalfLife = ConstU64<100>;
+	type MaturityPeriod = ConstU64<50>;
+	type SweepInterval = ConstU64<10>;
+	type TaskCompletedPoints = ConstU32<2>;
+	type DisputeLostPoints = ConstU32<10>;
+	type PrunePoints = ConstU32<1>;
+	type Prior = ConstU32<5>;
+	type EligibilityThreshold = EligibilityThreshold;
+	type MaxAgents = ConstU32<4>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockAgents;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	AGENTS.with(|a| a.borrow_mut().clear());
+	let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	// Events are not emitted on the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,138 @@
+use crate::{mock::*, *};
+use frame_support::traits::{Contains, Hooks};
+use sp_runtime::Perbill;
+
+const AGENT: u64 = 1;
+const OTHER: u64 = 2;
+
+/// Sweep the agents at `block`, registering `agents` first.
+fn sweep_at(block: u64, agents: Vec<u64>) {
+	MockAgents::set(agents);
+	System::set_block_number(block);
+	Reputation::on_initialize(block);
+}
+
+#[test]
+fn points_halve_every_half_life() {
+	assert_eq!(decay(1_000, 0, 100), 1_000);
+	assert_eq!(decay(1_000, 50, 100), 750);
+	assert_eq!(decay(1_000, 100, 100), 500);
+	assert_eq!(decay(1_000, 150, 100), 375);
+	assert_eq!(decay(1_000, 64 * 100, 100), 0);
+	assert_eq!(decay(u64::MAX, u64::MAX, 1), 0);
+	assert_eq!(decay(1_000, 100, 0), 1_000);
+}
+
+#[test]
+fn history_is_recorded_and_decays() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Reputation::record(&AGENT), None);
+		Reputation::note_task_completed(&AGENT);
+		Reputation::note_task_completed(&AGENT);
+		Reputation::note_dispute_lost(&AGENT);
+		Reputation::note_memory_pruned(&AGENT);
+		let record = AgentRecord {
+			completed_tasks: 2,
+			disputes_lost: 1,
+			memories_pruned: 1,
+			good: 4 * POINT,
+			bad: 11 * POINT,
+			updated: 1,
+		};
+		assert_eq!(Reputation::record(&AGENT), Some(record));
+
+		System::set_block_number(101);
+		let decayed = AgentRecord { good: 2_000, bad: 5_500, updated: 101, ..record };
+		assert_eq!(Reputation::record(&AGENT), Some(decayed));
+		// Reading does not write the decay back.
+		assert_eq!(Records::<Test>::get(AGENT), Some(record));
+
+		Reputation::note_task_completed(&AGENT);
+		assert_eq!(
+			Records::<Test>::get(AGENT),
+			Some(AgentRecord { completed_tasks: 3, good: 4_000, ..decayed })
+		);
+	});
+}
+
+#[test]
+fn sweeps_track_since_when_agents_stake() {
+	new_test_ext().execute_with(|| {
+		sweep_at(10, vec![AGENT, OTHER]);
+		assert_eq!(StakedSince::<Test>::get().into_inner(), vec![(AGENT, 10), (OTHER, 10)]);
+		System::assert_last_event(Event::AgentsSwept { agents: 2 }.into());
+
+		// Agents are only swept every `SweepInterval` blocks.
+		sweep_at(15, vec![OTHER, 3]);
+		assert_eq!(StakedSince::<Test>::get().into_inner(), vec![(AGENT, 10), (OTHER, 10)]);
+
+		sweep_at(20, vec![OTHER, 3]);
+		assert_eq!(StakedSince::<Test>::get().into_inner(), vec![(OTHER, 10), (3, 20)]);
+
+		// Agents beyond `MaxAgents` are not tracked.
+		sweep_at(30, (1..=5).collect());
+		assert_eq!(StakedSince::<Test>::get().len(), 4);
+	});
+}
+
+#[test]
+fn scores_rise_with_stake_age() {
+	new_test_ext().execute_with(|| {
+		// Accounts never seen staking are not trusted, whatever their history.
+		assert_eq!(Reputation::score(&AGENT), Perbill::zero());
+		Reputation::note_task_completed(&AGENT);
+		assert_eq!(Reputation::score(&AGENT), Perbill::zero());
+
+		sweep_at(10, vec![AGENT, OTHER]);
+		assert_eq!(Reputation::score(&OTHER), Perbill::zero());
+		System::set_block_number(35);
+		assert_eq!(Reputation::score(&OTHER), Perbill::from_percent(25));
+		System::set_block_number(60);
+		assert_eq!(Reputation::score(&OTHER), Perbill::from_percent(50));
+		System::set_block_number(1_000);
+		assert_eq!(Reputation::score(&OTHER), Perbill::from_percent(50));
+		assert_eq!(Reputation::score(&3), Perbill::zero());
+	});
+}
+
+#[test]
+fn good_history_raises_the_score() {
+	new_test_ext().execute_with(|| {
+		sweep_at(10, vec![AGENT]);
+		System::set_block_number(60);
+		for _ in 0..5 {
+			Reputation::note_task_completed(&AGENT);
+		}
+		// (10 + 5) / (10 + 10)
+		assert_eq!(Reputation::score(&AGENT), Perbill::from_percent(75));
+	});
+}
+
+#[test]
+fn flaky_agents_are_filtered_out_until_their_record_decays() {
+	new_test_ext().execute_with(|| {
+		// Unknown accounts have to stake for a while before they are trusted.
+		assert!(!Reputable::<Test>::contains(&AGENT));
+		sweep_at(10, vec![AGENT, OTHER]);
+		System::set_block_number(29);
+		assert!(!Reputable::<Test>::contains(&AGENT));
+		// 5 / 10, weighed by 20 of 50 blocks of stake age.
+		System::set_block_number(30);
+		assert_eq!(Reputation::score(&AGENT), Perbill::from_percent(20));
+		assert!(Reputable::<Test>::contains(&AGENT));
+		// Young agents cannot afford to lose a dispute.
+		Reputation::note_dispute_lost(&AGENT);
+		assert!(!Reputable::<Test>::contains(&AGENT));
+
+		System::set_block_number(60);
+		// Mature agents can afford a lost dispute, but not two.
+		assert!(Reputable::<Test>::contains(&AGENT));
+		Reputation::note_dispute_lost(&OTHER);
+		Reputation::note_dispute_lost(&OTHER);
+		assert!(!Reputable::<Test>::contains(&OTHER));
+
+		System::set_block_number(160);
+		assert_eq!(Reputation::score(&OTHER), Perbill::from_percent(25));
+		assert!(Reputable::<Test>::contains(&OTHER));
+	});
+}
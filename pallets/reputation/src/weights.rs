@@ -0,0 +1,48 @@
+//! Weights for pallet_reputation
+//!
+//! PLACEHOLDER WEIGHTS, written by hand rather than measured. Storage reads, writes and proof
+//! sizes follow the storage accessed by the worst case of each benchmark in `benchmarking.rs`,
+//! for the runtime configuration (`MaxAgents = 16`, agents being the members of the encryption
+//! committee). The ref-time components are guesses. Replace this file with the output of the
+//! node's `benchmark pallet` command, run on reference hardware, before relying on these weights.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_reputation.
+pub trait WeightInfo {
+	fn sweep(n: u32, ) -> Weight;
+}
+
+/// Placeholder weights for pallet_reputation, until it is benchmarked on reference hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `ThresholdEncryption::Members` (r:1 w:0)
+	/// Storage: `Reputation::StakedSince` (r:1 w:1)
+	/// The range of component `n` is `[1, 16]`.
+	fn sweep(n: u32, ) -> Weight {
+		Weight::from_parts(9_400_000, 2_245)
+			.saturating_add(Weight::from_parts(610_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 80).saturating_mul(n.into()))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `ThresholdEncryption::Members` (r:1 w:0)
+	/// Storage: `Reputation::StakedSince` (r:1 w:1)
+	/// The range of component `n` is `[1, 16]`.
+	fn sweep(n: u32, ) -> Weight {
+		Weight::from_parts(9_400_000, 2_245)
+			.saturating_add(Weight::from_parts(610_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 80).saturating_mul(n.into()))
+	}
+}
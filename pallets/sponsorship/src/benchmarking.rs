@@ -0,0 +1,116 @@
+//! Benchmarking setup for pallet-sponsorship
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Sponsorship;
+use frame_benchmarking::v2::*;
+use frame_support::dispatch::DispatchResult;
+use frame_system::RawOrigin;
+
+/// Sponsor and agent of the benchmarked sponsorship.
+fn parties<T: Config>() -> (T::AccountId, T::AccountId) {
+	(whitelisted_caller(), account("agent", 0, 0))
+}
+
+/// Make `sponsor` the sponsor of `agent`.
+fn sponsorship<T: Config>(sponsor: &T::AccountId, agent: &T::AccountId) -> DispatchResult {
+	Pallet::<T>::sponsor(
+		RawOrigin::Signed(sponsor.clone()).into(),
+		agent.clone(),
+		1_000u32.into(),
+		10,
+	)?;
+	Pallet::<T>::accept(RawOrigin::Signed(agent.clone()).into(), sponsor.clone())
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn sponsor() {
+		let (sponsor, agent) = parties::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(sponsor.clone()), agent.clone(), 1_000u32.into(), 10);
+
+		assert!(Offers::<T>::contains_key(&agent, &sponsor));
+	}
+
+	#[benchmark]
+	fn revoke() -> Result<(), BenchmarkError> {
+		let (sponsor, agent) = parties::<T>();
+		sponsorship::<T>(&sponsor, &agent)?;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(sponsor), agent.clone());
+
+		assert!(!Sponsorships::<T>::contains_key(&agent));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn renounce() -> Result<(), BenchmarkError> {
+		let (sponsor, agent) = parties::<T>();
+		sponsorship::<T>(&sponsor, &agent)?;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(agent.clone()));
+
+		assert!(!Sponsorships::<T>::contains_key(&agent));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn accept() -> Result<(), BenchmarkError> {
+		// The agent replaces its current sponsor.
+		let (sponsor, agent) = parties::<T>();
+		let current: T::AccountId = account("sponsor", 0, 0);
+		sponsorship::<T>(&current, &agent)?;
+		Pallet::<T>::sponsor(
+			RawOrigin::Signed(sponsor.clone()).into(),
+			agent.clone(),
+			1_000u32.into(),
+			10,
+		)?;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(agent.clone()), sponsor.clone());
+
+		assert_eq!(Sponsorships::<T>::get(&agent).map(|s| s.sponsor), Some(sponsor));
+		Ok(())
+	}
+
+	/// Storage accessed by `ChargeSponsoredTransactionPayment` on top of
+	/// `ChargeTransactionPayment`, for an agent accepting an offer while sponsored by another
+	/// account.
+	#[benchmark]
+	fn charge_sponsored() -> Result<(), BenchmarkError> {
+		let (sponsor, agent) = parties::<T>();
+		let current: T::AccountId = account("sponsor", 0, 0);
+		sponsorship::<T>(&current, &agent)?;
+		Pallet::<T>::sponsor(
+			RawOrigin::Signed(sponsor.clone()).into(),
+			agent.clone(),
+			1_000u32.into(),
+			10,
+		)?;
+		let call: <T as Config>::RuntimeCall =
+			Call::<T>::accept { sponsor: sponsor.clone() }.into();
+		let call: <T as frame_system::Config>::RuntimeCall = call.into();
+
+		#[block]
+		{
+			let payer = Pallet::<T>::sponsor_of(&agent, &call, 10u32.into());
+			assert_eq!(payer.as_ref(), Some(&sponsor));
+			Pallet::<T>::charge(&agent, &sponsor, 10u32.into());
+			Pallet::<T>::refund(&agent, &sponsor, 5u32.into());
+		}
+
+		assert_eq!(Offers::<T>::get(&agent, &sponsor).map(|o| o.budget), Some(995u32.into()));
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(Sponsorship, crate::mock::new_test_ext(), crate::mock::Test);
+}
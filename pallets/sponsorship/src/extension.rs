@@ -0,0 +1,184 @@
+//! Transaction extension charging the fees of sponsored agents to their sponsor.
+
+use crate::{weights::WeightInfo, BalanceOf, Config, Event, Pallet, Sponsorships};
+use alloc::{vec, vec::Vec};
+use codec::{Decode, Encode};
+use frame_support::dispatch::{DispatchInfo, PostDispatchInfo};
+use pallet_transaction_payment::{ChargeTransactionPayment, OnChargeTransaction};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{DispatchInfoOf, Dispatchable, PostDispatchInfoOf, Saturating, SignedExtension, Zero},
+	transaction_validity::{TransactionValidity, TransactionValidityError, ValidTransaction},
+	DispatchResult,
+};
+
+/// Drop-in replacement of [`ChargeTransactionPayment`], charging the fee to the sponsor of the
+/// signer when [`Pallet::sponsor_of`] finds one.
+///
+/// It encodes like `ChargeTransactionPayment`, as the tip, and shares its identifier, so that
+/// wallets sign transactions as they did. Tipped transactions are never sponsored: the tip would
+/// let agents spend the budget of their sponsor on priority.
+///
+/// The budget and rate limit are checked against what the sponsor paid on chain, not what is
+/// waiting in the transaction pool. Sponsored transactions of an agent therefore all provide the
+/// same tag until one of them is paid, so that the pool holds one of them at a time.
+///
+/// Looking for a sponsor and charging it accesses the storage of the pallet on top of what
+/// `ChargeTransactionPayment` does. That weight, [`WeightInfo::charge_sponsored`], is added to
+/// the block of every untipped transaction.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct ChargeSponsoredTransactionPayment<T: Config>(#[codec(compact)] BalanceOf<T>);
+
+impl<T: Config> From<BalanceOf<T>> for ChargeSponsoredTransactionPayment<T> {
+	fn from(tip: BalanceOf<T>) -> Self {
+		Self(tip)
+	}
+}
+
+impl<T: Config> core::fmt::Debug for ChargeSponsoredTransactionPayment<T> {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(f, "ChargeSponsoredTransactionPayment<{:?}>", self.0)
+	}
+}
+
+impl<T: Config> ChargeSponsoredTransactionPayment<T>
+where
+	<T as frame_system::Config>::RuntimeCall:
+		Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+	/// The extension charging the signer.
+	fn unsponsored(&self) -> ChargeTransactionPayment<T> {
+		ChargeTransactionPayment::from(self.0)
+	}
+
+	/// Sponsor paying for `who` to dispatch `call`, with the fee it pays.
+	fn sponsor(
+		&self,
+		who: &T::AccountId,
+		call: &<T as frame_system::Config>::RuntimeCall,
+		info: &DispatchInfoOf<<T as frame_system::Config>::RuntimeCall>,
+		len: usize,
+	) -> Option<(T::AccountId, BalanceOf<T>)> {
+		if !self.0.is_zero() {
+			return None;
+		}
+		let fee =
+			pallet_transaction_payment::Pallet::<T>::compute_fee(len as u32, info, Zero::zero());
+		Pallet::<T>::sponsor_of(who, call, fee).map(|sponsor| (sponsor, fee))
+	}
+
+	fn withdraw_fee(
+		sponsor: &T::AccountId,
+		call: &<T as frame_system::Config>::RuntimeCall,
+		info: &DispatchInfoOf<<T as frame_system::Config>::RuntimeCall>,
+		fee: BalanceOf<T>,
+	) -> Result<
+		<T::OnChargeTransaction as OnChargeTransaction<T>>::LiquidityInfo,
+		TransactionValidityError,
+	> {
+		T::OnChargeTransaction::withdraw_fee(sponsor, call, info, fee, Zero::zero())
+	}
+
+	/// Tag provided by the sponsored transactions of `agent`, which changes whenever its sponsor
+	/// pays a fee.
+	fn pool_tag(agent: &T::AccountId) -> Vec<u8> {
+		let usage = Sponsorships::<T>::get(agent).map(|s| (s.sponsor, s.period_start, s.used));
+		(b"sponsored", agent, usage).encode()
+	}
+}
+
+impl<T: Config> SignedExtension for ChargeSponsoredTransactionPayment<T>
+where
+	BalanceOf<T>: Send + Sync,
+	<T as frame_system::Config>::RuntimeCall:
+		Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+	const IDENTIFIER: &'static str = <ChargeTransactionPayment<T> as SignedExtension>::IDENTIFIER;
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::RuntimeCall;
+	type AdditionalSigned = ();
+	type Pre = (
+		<ChargeTransactionPayment<T> as SignedExtension>::Pre,
+		// Agent whose sponsor paid, with the fee taken from the budget.
+		Option<(T::AccountId, BalanceOf<T>)>,
+	);
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		if let Some((sponsor, fee)) = self.sponsor(who, call, info, len) {
+			// As `ChargeTransactionPayment` does, the fee is withdrawn to check that it can be
+			// paid. Validation discards the withdrawal.
+			if Self::withdraw_fee(&sponsor, call, info, fee).is_ok() {
+				let priority =
+					ChargeTransactionPayment::<T>::get_priority(info, len, Zero::zero(), fee);
+				let provides = vec![Self::pool_tag(who)];
+				return Ok(ValidTransaction { priority, provides, ..Default::default() });
+			}
+		}
+		self.unsponsored().validate(who, call, info, len)
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		if self.0.is_zero() {
+			frame_system::Pallet::<T>::register_extra_weight_unchecked(
+				T::WeightInfo::charge_sponsored(),
+				info.class,
+			);
+		}
+		if let Some((sponsor, fee)) = self.sponsor(who, call, info, len) {
+			if let Ok(liquidity) = Self::withdraw_fee(&sponsor, call, info, fee) {
+				Pallet::<T>::charge(who, &sponsor, fee);
+				return Ok(((Zero::zero(), sponsor, liquidity), Some((who.clone(), fee))));
+			}
+		}
+		// The agent pays, if it can, and is told when its sponsorship did not cover the fee.
+		let pre = self.unsponsored().pre_dispatch(who, call, info, len)?;
+		if self.0.is_zero() {
+			Pallet::<T>::note_unsponsored(who, call);
+		}
+		Ok((pre, None))
+	}
+
+	fn post_dispatch(
+		pre: Option<Self::Pre>,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		let Some((pre, sponsored)) = pre else {
+			return ChargeTransactionPayment::<T>::post_dispatch(
+				None, info, post_info, len, result,
+			);
+		};
+		if let Some((agent, charged)) = sponsored {
+			let sponsor = pre.1.clone();
+			let fee = pallet_transaction_payment::Pallet::<T>::compute_actual_fee(
+				len as u32,
+				info,
+				post_info,
+				Zero::zero(),
+			);
+			Pallet::<T>::refund(&agent, &sponsor, charged.saturating_sub(fee));
+			Pallet::<T>::deposit_event(Event::FeeSponsored { sponsor, agent, fee });
+		}
+		// Refunds the unspent part of the fee to whoever paid it.
+		ChargeTransactionPayment::<T>::post_dispatch(Some(pre), info, post_info, len, result)
+	}
+}
@@ -0,0 +1,380 @@
+//! # Sponsorship Pallet
+//!
+//! Agents need tokens to pay the fees of their first transactions, before they can earn
+//! anything. A sponsor, such as the human backing an agent or the owner of a collection it
+//! writes to, can pay those fees instead, within limits of its choosing.
+//!
+//! ## Overview
+//!
+//! - [`Pallet::sponsor`] offers to pay the fees of an agent, up to a budget and a number of
+//!   transactions every [`Config::RatePeriod`] blocks. Once the agent is sponsored, calling it
+//!   again tops up or changes the terms.
+//! - [`Pallet::accept`] lets the agent take up an offer, replacing its current sponsor if it has
+//!   one. Its sponsor is always of its choosing, so no account can lock an agent into a
+//!   sponsorship by offering first.
+//! - [`Pallet::revoke`] lets the sponsor withdraw its offer or sponsorship, and
+//!   [`Pallet::renounce`] lets the agent end its sponsorship.
+//! - [`ChargeSponsoredTransactionPayment`] replaces `ChargeTransactionPayment` in the
+//!   transaction extensions of the runtime. It charges the fee of a [`Config::SponsoredCalls`]
+//!   call signed by a sponsored agent to the sponsor, as long as the budget and rate limit
+//!   allow it, and the fee of [`Pallet::accept`] to the sponsor whose offer the agent accepts,
+//!   out of the offered budget. Any other transaction, or one with a tip, is paid by its signer
+//!   as usual.
+//!
+//! Budgets are not held: a sponsor whose balance cannot cover a fee leaves it to the agent, as
+//! does an exhausted budget or rate limit. The agent pays only if it can, and
+//! [`Event::FeeNotSponsored`] tells it so.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+mod extension;
+pub mod weights;
+pub use extension::ChargeSponsoredTransactionPayment;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{Contains, IsSubType},
+	};
+	use frame_system::pallet_prelude::*;
+	use pallet_transaction_payment::OnChargeTransaction;
+	use sp_runtime::traits::{Saturating, Zero};
+
+	/// Balance fees are paid in.
+	pub type BalanceOf<T> =
+		<<T as pallet_transaction_payment::Config>::OnChargeTransaction as OnChargeTransaction<
+			T,
+		>>::Balance;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Weight information for the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+		/// The overarching call type, to recognize [`Pallet::accept`] among the calls of agents.
+		type RuntimeCall: Parameter
+			+ IsSubType<Call<Self>>
+			+ From<Call<Self>>
+			+ IsType<<Self as frame_system::Config>::RuntimeCall>;
+		/// Calls whose fees sponsors pay.
+		type SponsoredCalls: Contains<<Self as frame_system::Config>::RuntimeCall>;
+		/// Number of blocks the rate limit of a sponsorship applies to.
+		#[pallet::constant]
+		type RatePeriod: Get<BlockNumberFor<Self>>;
+	}
+
+	/// Terms on which a sponsor pays the fees of an agent.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct SponsorshipInfo<T: Config> {
+		/// Account paying the fees.
+		pub sponsor: T::AccountId,
+		/// Fees the sponsor is still willing to pay.
+		pub budget: BalanceOf<T>,
+		/// Transactions paid at most every [`Config::RatePeriod`] blocks.
+		pub per_period: u32,
+		/// First block of the current period.
+		pub period_start: BlockNumberFor<T>,
+		/// Transactions paid during the current period.
+		pub used: u32,
+	}
+
+	/// Terms a sponsor offers an agent.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct Offer<T: Config> {
+		/// Fees the sponsor is willing to pay.
+		pub budget: BalanceOf<T>,
+		/// Transactions paid at most every [`Config::RatePeriod`] blocks.
+		pub per_period: u32,
+	}
+
+	/// Sponsorships, by agent.
+	#[pallet::storage]
+	pub type Sponsorships<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, SponsorshipInfo<T>>;
+
+	/// Offers the agents have not accepted yet, by agent and sponsor.
+	#[pallet::storage]
+	pub type Offers<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		Offer<T>,
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A sponsor offered to pay the fees of an agent, which has yet to accept
+		SponsorshipOffered {
+			sponsor: T::AccountId,
+			agent: T::AccountId,
+			budget: BalanceOf<T>,
+			per_period: u32,
+		},
+		/// A sponsor withdrew an offer the agent had not accepted
+		OfferWithdrawn { sponsor: T::AccountId, agent: T::AccountId },
+		/// A sponsor set the terms on which it pays the fees of an agent
+		SponsorshipSet {
+			sponsor: T::AccountId,
+			agent: T::AccountId,
+			budget: BalanceOf<T>,
+			per_period: u32,
+		},
+		/// A sponsorship was revoked by its sponsor, renounced by its agent or replaced by the
+		/// offer of another sponsor
+		SponsorshipEnded { sponsor: T::AccountId, agent: T::AccountId },
+		/// A sponsor paid the fee of a transaction of an agent
+		FeeSponsored { sponsor: T::AccountId, agent: T::AccountId, fee: BalanceOf<T> },
+		/// A sponsored agent paid the fee of a transaction its sponsorship could not cover
+		FeeNotSponsored { sponsor: T::AccountId, agent: T::AccountId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The budget of a sponsorship cannot be zero
+		ZeroBudget,
+		/// A sponsorship must allow at least one transaction per period
+		ZeroRateLimit,
+		/// Sponsorship not found
+		SponsorshipNotFound,
+		/// The sponsor made no offer to the agent
+		OfferNotFound,
+		/// Only the sponsor can do this
+		NotSponsor,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn integrity_test() {
+			assert!(
+				!T::RatePeriod::get().is_zero(),
+				"rate limits need a period of a block at least"
+			);
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Offer to pay the fees of `agent`, up to `budget` in total and `per_period`
+		/// transactions every [`Config::RatePeriod`] blocks, until it accepts with
+		/// [`Pallet::accept`].
+		///
+		/// If the caller already sponsors `agent`, the terms of the sponsorship are replaced
+		/// instead, without resetting the transactions already paid in the current period.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::sponsor())]
+		pub fn sponsor(
+			origin: OriginFor<T>,
+			agent: T::AccountId,
+			budget: BalanceOf<T>,
+			per_period: u32,
+		) -> DispatchResult {
+			let sponsor = ensure_signed(origin)?;
+			ensure!(!budget.is_zero(), Error::<T>::ZeroBudget);
+			ensure!(per_period > 0, Error::<T>::ZeroRateLimit);
+
+			let updated = Sponsorships::<T>::mutate(&agent, |sponsorship| match sponsorship {
+				Some(existing) if existing.sponsor == sponsor => {
+					existing.budget = budget;
+					existing.per_period = per_period;
+					true
+				},
+				_ => false,
+			});
+			if updated {
+				Self::deposit_event(Event::SponsorshipSet { sponsor, agent, budget, per_period });
+			} else {
+				Offers::<T>::insert(&agent, &sponsor, Offer { budget, per_period });
+				Self::deposit_event(Event::SponsorshipOffered {
+					sponsor,
+					agent,
+					budget,
+					per_period,
+				});
+			}
+			Ok(())
+		}
+
+		/// Stop paying the fees of `agent`, or withdraw the offer to pay them.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::revoke())]
+		pub fn revoke(origin: OriginFor<T>, agent: T::AccountId) -> DispatchResult {
+			let sponsor = ensure_signed(origin)?;
+			if Offers::<T>::take(&agent, &sponsor).is_some() {
+				Self::deposit_event(Event::OfferWithdrawn { sponsor, agent });
+				return Ok(());
+			}
+			let sponsorship =
+				Sponsorships::<T>::get(&agent).ok_or(Error::<T>::SponsorshipNotFound)?;
+			ensure!(sponsorship.sponsor == sponsor, Error::<T>::NotSponsor);
+			Sponsorships::<T>::remove(&agent);
+
+			Self::deposit_event(Event::SponsorshipEnded { sponsor, agent });
+			Ok(())
+		}
+
+		/// Turn down the sponsorship of the caller, which then pays its own fees.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::renounce())]
+		pub fn renounce(origin: OriginFor<T>) -> DispatchResult {
+			let agent = ensure_signed(origin)?;
+			let sponsorship =
+				Sponsorships::<T>::take(&agent).ok_or(Error::<T>::SponsorshipNotFound)?;
+
+			Self::deposit_event(Event::SponsorshipEnded { sponsor: sponsorship.sponsor, agent });
+			Ok(())
+		}
+
+		/// Accept the offer of `sponsor` to pay the fees of the caller, ending its current
+		/// sponsorship if it has one.
+		///
+		/// The fee of the call is paid by `sponsor` out of the offered budget, so that agents
+		/// without funds can accept.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::accept())]
+		pub fn accept(origin: OriginFor<T>, sponsor: T::AccountId) -> DispatchResult {
+			let agent = ensure_signed(origin)?;
+			let Offer { budget, per_period } =
+				Offers::<T>::take(&agent, &sponsor).ok_or(Error::<T>::OfferNotFound)?;
+
+			let sponsorship = SponsorshipInfo {
+				sponsor: sponsor.clone(),
+				budget,
+				per_period,
+				period_start: frame_system::Pallet::<T>::block_number(),
+				used: 0,
+			};
+			if let Some(previous) = Sponsorships::<T>::mutate(&agent, |s| s.replace(sponsorship)) {
+				Self::deposit_event(Event::SponsorshipEnded {
+					sponsor: previous.sponsor,
+					agent: agent.clone(),
+				});
+			}
+
+			Self::deposit_event(Event::SponsorshipSet { sponsor, agent, budget, per_period });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Sponsor that pays `fee` for `agent` to dispatch `call`, if its sponsorship, or for
+		/// [`Pallet::accept`] its offer, covers it.
+		pub fn sponsor_of(
+			agent: &T::AccountId,
+			call: &<T as frame_system::Config>::RuntimeCall,
+			fee: BalanceOf<T>,
+		) -> Option<T::AccountId> {
+			if fee.is_zero() {
+				return None;
+			}
+			if let Some(Call::accept { sponsor }) =
+				<T as Config>::RuntimeCall::from_ref(call).is_sub_type()
+			{
+				let offer = Offers::<T>::get(agent, sponsor)?;
+				return (fee <= offer.budget).then(|| sponsor.clone());
+			}
+			if !T::SponsoredCalls::contains(call) {
+				return None;
+			}
+			let sponsorship = Sponsorships::<T>::get(agent)?;
+			let used = if Self::period_over(&sponsorship) { 0 } else { sponsorship.used };
+			(fee <= sponsorship.budget && used < sponsorship.per_period)
+				.then_some(sponsorship.sponsor)
+		}
+
+		/// Tell `agent` it paid for `call` itself, if its sponsor pays for such calls.
+		pub(crate) fn note_unsponsored(
+			agent: &T::AccountId,
+			call: &<T as frame_system::Config>::RuntimeCall,
+		) {
+			if !T::SponsoredCalls::contains(call) {
+				return;
+			}
+			if let Some(sponsorship) = Sponsorships::<T>::get(agent) {
+				Self::deposit_event(Event::FeeNotSponsored {
+					sponsor: sponsorship.sponsor,
+					agent: agent.clone(),
+				});
+			}
+		}
+
+		/// Count a transaction of `agent` whose fee of at most `fee` `sponsor` pays, taking the
+		/// fee from the offer of `sponsor` if it does not sponsor `agent` yet.
+		pub(crate) fn charge(agent: &T::AccountId, sponsor: &T::AccountId, fee: BalanceOf<T>) {
+			let charged = Sponsorships::<T>::mutate(agent, |sponsorship| match sponsorship {
+				Some(sponsorship) if sponsorship.sponsor == *sponsor => {
+					if Self::period_over(sponsorship) {
+						sponsorship.period_start = frame_system::Pallet::<T>::block_number();
+						sponsorship.used = 0;
+					}
+					sponsorship.used.saturating_inc();
+					sponsorship.budget.saturating_reduce(fee);
+					true
+				},
+				_ => false,
+			});
+			if !charged {
+				Offers::<T>::mutate(agent, sponsor, |offer| {
+					if let Some(offer) = offer {
+						offer.budget.saturating_reduce(fee);
+					}
+				});
+			}
+		}
+
+		/// Return to the budget of the sponsorship of `agent` by `sponsor`, or of its offer, the
+		/// part of a fee that was not spent.
+		pub(crate) fn refund(agent: &T::AccountId, sponsor: &T::AccountId, unspent: BalanceOf<T>) {
+			let refunded = Sponsorships::<T>::mutate(agent, |sponsorship| match sponsorship {
+				Some(sponsorship) if sponsorship.sponsor == *sponsor => {
+					sponsorship.budget.saturating_accrue(unspent);
+					true
+				},
+				_ => false,
+			});
+			if !refunded {
+				Offers::<T>::mutate(agent, sponsor, |offer| {
+					if let Some(offer) = offer {
+						offer.budget.saturating_accrue(unspent);
+					}
+				});
+			}
+		}
+
+		fn period_over(sponsorship: &SponsorshipInfo<T>) -> bool {
+			frame_system::Pallet::<T>::block_number() >=
+				sponsorship.period_start.saturating_add(T::RatePeriod::get())
+		}
+	}
+}
@@ -0,0 +1,270 @@
+use crate::{mock::*, *};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{DispatchInfo, PostDispatchInfo},
+	weights::Weight,
+};
+use sp_runtime::{
+	traits::SignedExtension,
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+};
+
+const LEN: usize = 10;
+
+fn remark() -> RuntimeCall {
+	RuntimeCall::System(frame_system::Call::remark { remark: vec![] })
+}
+
+fn info() -> DispatchInfo {
+	DispatchInfo { weight: Weight::from_parts(1_000, 0), ..Default::default() }
+}
+
+fn fee() -> u64 {
+	TransactionPayment::compute_fee(LEN as u32, &info(), 0)
+}
+
+/// Charge the fee of `call`, signed by `who` with `tip`, and refund what `post_info` leaves
+/// unspent.
+fn charge(
+	who: u64,
+	call: &RuntimeCall,
+	tip: u64,
+	post_info: PostDispatchInfo,
+) -> Result<(), TransactionValidityError> {
+	let extension = ChargeSponsoredTransactionPayment::<Test>::from(tip);
+	extension.validate(&who, call, &info(), LEN)?;
+	let pre = extension.pre_dispatch(&who, call, &info(), LEN)?;
+	ChargeSponsoredTransactionPayment::<Test>::post_dispatch(
+		Some(pre),
+		&info(),
+		&post_info,
+		LEN,
+		&Ok(()),
+	)
+}
+
+fn charge_remark(who: u64) -> Result<(), TransactionValidityError> {
+	charge(who, &remark(), 0, PostDispatchInfo::default())
+}
+
+/// Make `sponsor` the sponsor of `AGENT`, on the given terms.
+fn sponsor(sponsor: u64, budget: u64, per_period: u32) {
+	assert_ok!(Sponsorship::sponsor(RuntimeOrigin::signed(sponsor), AGENT, budget, per_period));
+	assert_ok!(Sponsorship::accept(RuntimeOrigin::signed(AGENT), sponsor));
+}
+
+#[test]
+fn sponsorships_are_managed_by_their_parties() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Sponsorship::sponsor(RuntimeOrigin::signed(SPONSOR), AGENT, 0, 1),
+			Error::<Test>::ZeroBudget
+		);
+		assert_noop!(
+			Sponsorship::sponsor(RuntimeOrigin::signed(SPONSOR), AGENT, 100, 0),
+			Error::<Test>::ZeroRateLimit
+		);
+
+		// The agent has to accept an offer before it is sponsored.
+		assert_ok!(Sponsorship::sponsor(RuntimeOrigin::signed(SPONSOR), AGENT, 100, 5));
+		System::assert_last_event(
+			Event::SponsorshipOffered {
+				sponsor: SPONSOR,
+				agent: AGENT,
+				budget: 100,
+				per_period: 5,
+			}
+			.into(),
+		);
+		assert!(!Sponsorships::<Test>::contains_key(AGENT));
+		assert_noop!(
+			Sponsorship::accept(RuntimeOrigin::signed(AGENT), OTHER_SPONSOR),
+			Error::<Test>::OfferNotFound
+		);
+		assert_ok!(Sponsorship::accept(RuntimeOrigin::signed(AGENT), SPONSOR));
+		System::assert_last_event(
+			Event::SponsorshipSet { sponsor: SPONSOR, agent: AGENT, budget: 100, per_period: 5 }
+				.into(),
+		);
+		assert!(!Offers::<Test>::contains_key(AGENT, SPONSOR));
+
+		// The sponsor changes its terms without the agent accepting them again.
+		assert_ok!(Sponsorship::sponsor(RuntimeOrigin::signed(SPONSOR), AGENT, 200, 1));
+		let sponsorship = Sponsorships::<Test>::get(AGENT).unwrap();
+		assert_eq!((sponsorship.budget, sponsorship.per_period), (200, 1));
+
+		assert_noop!(
+			Sponsorship::revoke(RuntimeOrigin::signed(OTHER_SPONSOR), AGENT),
+			Error::<Test>::NotSponsor
+		);
+		assert_ok!(Sponsorship::revoke(RuntimeOrigin::signed(SPONSOR), AGENT));
+		System::assert_last_event(
+			Event::SponsorshipEnded { sponsor: SPONSOR, agent: AGENT }.into(),
+		);
+		assert_noop!(
+			Sponsorship::revoke(RuntimeOrigin::signed(SPONSOR), AGENT),
+			Error::<Test>::SponsorshipNotFound
+		);
+
+		// A sponsor withdraws an offer the agent has not accepted.
+		assert_ok!(Sponsorship::sponsor(RuntimeOrigin::signed(SPONSOR), AGENT, 100, 5));
+		assert_ok!(Sponsorship::revoke(RuntimeOrigin::signed(SPONSOR), AGENT));
+		System::assert_last_event(Event::OfferWithdrawn { sponsor: SPONSOR, agent: AGENT }.into());
+		assert_noop!(
+			Sponsorship::accept(RuntimeOrigin::signed(AGENT), SPONSOR),
+			Error::<Test>::OfferNotFound
+		);
+
+		// The agent turns down its sponsor.
+		sponsor(SPONSOR, 100, 5);
+		assert_ok!(Sponsorship::renounce(RuntimeOrigin::signed(AGENT)));
+		System::assert_last_event(
+			Event::SponsorshipEnded { sponsor: SPONSOR, agent: AGENT }.into(),
+		);
+		assert_noop!(
+			Sponsorship::renounce(RuntimeOrigin::signed(AGENT)),
+			Error::<Test>::SponsorshipNotFound
+		);
+	});
+}
+
+#[test]
+fn agents_choose_their_sponsor() {
+	new_test_ext().execute_with(|| {
+		// An account sponsoring the agent with a tiny budget does not keep others out.
+		sponsor(POOR_SPONSOR, 1, 1);
+		assert_ok!(Sponsorship::sponsor(RuntimeOrigin::signed(SPONSOR), AGENT, 10 * fee(), 5));
+		assert_ok!(Sponsorship::accept(RuntimeOrigin::signed(AGENT), SPONSOR));
+		System::assert_has_event(
+			Event::SponsorshipEnded { sponsor: POOR_SPONSOR, agent: AGENT }.into(),
+		);
+		assert_eq!(Sponsorships::<Test>::get(AGENT).unwrap().sponsor, SPONSOR);
+
+		// Offers of other accounts do not affect the sponsorship.
+		assert_ok!(Sponsorship::sponsor(RuntimeOrigin::signed(OTHER_SPONSOR), AGENT, 100, 5));
+		assert_eq!(Sponsorships::<Test>::get(AGENT).unwrap().sponsor, SPONSOR);
+		assert_ok!(charge_remark(AGENT));
+		assert_eq!(Balances::free_balance(SPONSOR), BALANCE - fee());
+		assert_eq!(Balances::free_balance(OTHER_SPONSOR), BALANCE);
+	});
+}
+
+#[test]
+fn sponsors_pay_for_their_offer_to_be_accepted() {
+	new_test_ext().execute_with(|| {
+		let accept = |sponsor| RuntimeCall::Sponsorship(Call::accept { sponsor });
+		let payment_error = Err(InvalidTransaction::Payment.into());
+
+		// An offer too small for the fee leaves the agent to pay it.
+		assert_ok!(Sponsorship::sponsor(RuntimeOrigin::signed(OTHER_SPONSOR), AGENT, 1, 5));
+		let accept_other = accept(OTHER_SPONSOR);
+		assert_eq!(charge(AGENT, &accept_other, 0, PostDispatchInfo::default()), payment_error);
+
+		assert_ok!(Sponsorship::sponsor(RuntimeOrigin::signed(SPONSOR), AGENT, 10 * fee(), 5));
+		assert_ok!(charge(AGENT, &accept(SPONSOR), 0, PostDispatchInfo::default()));
+		assert_eq!(Balances::free_balance(SPONSOR), BALANCE - fee());
+		assert_eq!(Offers::<Test>::get(AGENT, SPONSOR).unwrap().budget, 9 * fee());
+		System::assert_has_event(
+			Event::FeeSponsored { sponsor: SPONSOR, agent: AGENT, fee: fee() }.into(),
+		);
+	});
+}
+
+#[test]
+fn sponsors_pay_the_fees_of_their_agents() {
+	new_test_ext().execute_with(|| {
+		let payment_error = Err(InvalidTransaction::Payment.into());
+		assert_eq!(charge_remark(AGENT), payment_error);
+
+		sponsor(SPONSOR, 10 * fee(), 5);
+		assert_ok!(charge_remark(AGENT));
+		assert_eq!(Balances::free_balance(SPONSOR), BALANCE - fee());
+		let sponsorship = Sponsorships::<Test>::get(AGENT).unwrap();
+		assert_eq!((sponsorship.budget, sponsorship.used), (9 * fee(), 1));
+		System::assert_has_event(
+			Event::FeeSponsored { sponsor: SPONSOR, agent: AGENT, fee: fee() }.into(),
+		);
+
+		// Other calls and tipped transactions are paid by the agent.
+		let other = RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![] });
+		assert_eq!(charge(AGENT, &other, 0, PostDispatchInfo::default()), payment_error);
+		assert_eq!(charge(AGENT, &remark(), 1, PostDispatchInfo::default()), payment_error);
+		assert_eq!(Balances::free_balance(SPONSOR), BALANCE - fee());
+	});
+}
+
+#[test]
+fn sponsorships_are_limited_by_budget_and_rate() {
+	new_test_ext().execute_with(|| {
+		let payment_error = Err(InvalidTransaction::Payment.into());
+
+		sponsor(SPONSOR, 3 * fee(), 2);
+		assert_ok!(charge_remark(AGENT));
+		assert_ok!(charge_remark(AGENT));
+		assert_eq!(charge_remark(AGENT), payment_error);
+
+		// A new period starts after `RatePeriod` blocks, with what is left of the budget.
+		System::set_block_number(11);
+		assert_ok!(charge_remark(AGENT));
+		assert_eq!(Sponsorships::<Test>::get(AGENT).unwrap().used, 1);
+		assert_eq!(charge_remark(AGENT), payment_error);
+		assert_eq!(Balances::free_balance(SPONSOR), BALANCE - 3 * fee());
+	});
+}
+
+#[test]
+fn sponsored_transactions_wait_in_the_pool_one_at_a_time() {
+	new_test_ext().execute_with(|| {
+		sponsor(SPONSOR, 10 * fee(), 5);
+		let provides = || {
+			ChargeSponsoredTransactionPayment::<Test>::from(0)
+				.validate(&AGENT, &remark(), &info(), LEN)
+				.unwrap()
+				.provides
+		};
+
+		// A second sponsored transaction would replace the first in the pool, not join it.
+		let tags = provides();
+		assert_eq!(tags.len(), 1);
+		assert_eq!(provides(), tags);
+
+		assert_ok!(charge_remark(AGENT));
+		assert_ne!(provides(), tags);
+	});
+}
+
+#[test]
+fn unspent_fees_return_to_the_budget() {
+	new_test_ext().execute_with(|| {
+		sponsor(SPONSOR, 10 * fee(), 5);
+		let post_info = PostDispatchInfo {
+			actual_weight: Some(Weight::from_parts(400, 0)),
+			..Default::default()
+		};
+		assert_ok!(charge(AGENT, &remark(), 0, post_info));
+
+		let spent = fee() - 600;
+		assert_eq!(Balances::free_balance(SPONSOR), BALANCE - spent);
+		assert_eq!(Sponsorships::<Test>::get(AGENT).unwrap().budget, 10 * fee() - spent);
+		System::assert_has_event(
+			Event::FeeSponsored { sponsor: SPONSOR, agent: AGENT, fee: spent }.into(),
+		);
+	});
+}
+
+#[test]
+fn agents_pay_when_their_sponsor_cannot() {
+	new_test_ext().execute_with(|| {
+		sponsor(POOR_SPONSOR, fee(), 5);
+		assert_eq!(charge_remark(AGENT), Err(InvalidTransaction::Payment.into()));
+		let sponsorship = Sponsorships::<Test>::get(AGENT).unwrap();
+		assert_eq!((sponsorship.budget, sponsorship.used), (fee(), 0));
+
+		assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), AGENT, BALANCE));
+		assert_ok!(charge_remark(AGENT));
+		assert_eq!(Balances::free_balance(AGENT), BALANCE - fee());
+		System::assert_has_event(
+			Event::FeeNotSponsored { sponsor: POOR_SPONSOR, agent: AGENT }.into(),
+		);
+	});
+}
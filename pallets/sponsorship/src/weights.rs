@@ -0,0 +1,100 @@
+//! Weights for pallet_sponsorship
+//!
+//! PLACEHOLDER WEIGHTS, written by hand rather than measured. Storage reads, writes and proof
+//! sizes follow the storage accessed by the worst case of each benchmark in `benchmarking.rs`,
+//! for the runtime configuration. The ref-time components are guesses. Replace this file with
+//! the output of the node's `benchmark pallet` command, run on reference hardware, before
+//! relying on these weights.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_sponsorship.
+pub trait WeightInfo {
+	fn sponsor() -> Weight;
+	fn revoke() -> Weight;
+	fn renounce() -> Weight;
+	fn accept() -> Weight;
+	fn charge_sponsored() -> Weight;
+}
+
+/// Placeholder weights for pallet_sponsorship, until it is benchmarked on reference hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `Sponsorship::Sponsorships` (r:1 w:0)
+	/// Storage: `Sponsorship::Offers` (r:0 w:1)
+	fn sponsor() -> Weight {
+		Weight::from_parts(15_100_000, 3_573)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Sponsorship::Offers` (r:1 w:1)
+	/// Storage: `Sponsorship::Sponsorships` (r:1 w:1)
+	fn revoke() -> Weight {
+		Weight::from_parts(17_300_000, 3_621)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Sponsorship::Sponsorships` (r:1 w:1)
+	fn renounce() -> Weight {
+		Weight::from_parts(12_900_000, 3_573)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Sponsorship::Offers` (r:1 w:1)
+	/// Storage: `Sponsorship::Sponsorships` (r:1 w:1)
+	fn accept() -> Weight {
+		Weight::from_parts(18_600_000, 3_621)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Sponsorship::Offers` (r:1 w:1)
+	/// Storage: `Sponsorship::Sponsorships` (r:1 w:1)
+	fn charge_sponsored() -> Weight {
+		Weight::from_parts(21_800_000, 3_621)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `Sponsorship::Sponsorships` (r:1 w:0)
+	/// Storage: `Sponsorship::Offers` (r:0 w:1)
+	fn sponsor() -> Weight {
+		Weight::from_parts(15_100_000, 3_573)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Sponsorship::Offers` (r:1 w:1)
+	/// Storage: `Sponsorship::Sponsorships` (r:1 w:1)
+	fn revoke() -> Weight {
+		Weight::from_parts(17_300_000, 3_621)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Sponsorship::Sponsorships` (r:1 w:1)
+	fn renounce() -> Weight {
+		Weight::from_parts(12_900_000, 3_573)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Sponsorship::Offers` (r:1 w:1)
+	/// Storage: `Sponsorship::Sponsorships` (r:1 w:1)
+	fn accept() -> Weight {
+		Weight::from_parts(18_600_000, 3_621)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Sponsorship::Offers` (r:1 w:1)
+	/// Storage: `Sponsorship::Sponsorships` (r:1 w:1)
+	fn charge_sponsored() -> Weight {
+		Weight::from_parts(21_800_000, 3_621)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+}
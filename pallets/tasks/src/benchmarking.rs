@@ -0,0 +1,231 @@
+//! Benchmarking setup for pallet-tasks
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Tasks;
+use frame_benchmarking::v2::*;
+use frame_support::{
+	traits::{
+		fungible::{Inspect, Mutate},
+		Get, Hooks,
+	},
+	BoundedVec,
+};
+use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
+use sp_runtime::traits::{Saturating, Zero};
+
+fn reward<T: Config>() -> BalanceOf<T> {
+	T::Currency::minimum_balance().saturating_mul(100u32.into())
+}
+
+/// An account that can afford several rewards, stakes and bonds.
+fn funded<T: Config>(name: &'static str) -> T::AccountId {
+	let who = account(name, 0, 0);
+	let amount = reward::<T>()
+		.saturating_add(T::TaskStake::get())
+		.saturating_add(T::DisputeBond::get())
+		.saturating_mul(10u32.into());
+	T::Currency::set_balance(&who, amount);
+	who
+}
+
+/// An agent eligible to accept tasks, that can afford their stake.
+fn agent<T: Config>() -> T::AccountId {
+	let agent = funded::<T>("agent");
+	T::BenchmarkHelper::register_agent(agent.clone());
+	agent
+}
+
+fn status<T: Config>() -> Option<TaskStatus<BlockNumberFor<T>>> {
+	pallet::Tasks::<T>::get(0).map(|task| task.status)
+}
+
+/// Post task 0, returning its funder.
+fn created<T: Config>() -> Result<T::AccountId, BenchmarkError> {
+	let funder = funded::<T>("funder");
+	Tasks::<T>::create_task(
+		RawOrigin::Signed(funder.clone()).into(),
+		reward::<T>(),
+		T::Hash::default(),
+	)?;
+	Ok(funder)
+}
+
+/// Post task 0 and submit a result for it, returning its funder.
+fn submitted<T: Config>() -> Result<T::AccountId, BenchmarkError> {
+	let funder = created::<T>()?;
+	let agent = agent::<T>();
+	Tasks::<T>::accept_task(RawOrigin::Signed(agent.clone()).into(), 0)?;
+	Tasks::<T>::submit_result(RawOrigin::Signed(agent).into(), 0, T::Hash::default())?;
+	Ok(funder)
+}
+
+/// Move to the next block, drawing the juries of the disputes of the current one.
+fn next_block<T: Config>() {
+	let next = frame_system::Pallet::<T>::block_number().saturating_add(1u32.into());
+	frame_system::Pallet::<T>::set_block_number(next);
+	Tasks::<T>::on_initialize(next);
+}
+
+/// Have the funder of task 0 dispute its result, drawing a full jury of which all but the last
+/// juror voted against the dispute. The last juror holds half the jury's stake, so its vote
+/// against decides the dispute.
+fn disputed<T: Config>() -> Result<(T::AccountId, T::AccountId), BenchmarkError> {
+	let funder = submitted::<T>()?;
+	for i in 0..T::JurySize::get() {
+		T::BenchmarkHelper::register_agent(account("juror", i, 0));
+	}
+	Tasks::<T>::dispute(RawOrigin::Signed(funder.clone()).into(), 0).map_err(|e| e.error)?;
+	next_block::<T>();
+
+	let mut dispute = Disputes::<T>::get(0).ok_or(BenchmarkError::Stop("no dispute"))?;
+	let mut jury = dispute.jury.into_inner();
+	let (last, others) = jury.split_last_mut().ok_or(BenchmarkError::Stop("no jury"))?;
+	let mut stake = BalanceOf::<T>::zero();
+	for (juror, juror_stake) in others {
+		JuryVotes::<T>::insert(0, &*juror, false);
+		stake.saturating_accrue(*juror_stake);
+	}
+	last.1 = stake;
+	let last = last.0.clone();
+	dispute.jury = BoundedVec::truncate_from(jury);
+	Disputes::<T>::insert(0, dispute);
+	Ok((funder, last))
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn create_task() {
+		let caller = funded::<T>("funder");
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), reward::<T>(), T::Hash::default());
+
+		assert_eq!(status::<T>(), Some(TaskStatus::Open));
+	}
+
+	#[benchmark]
+	fn cancel_task() -> Result<(), BenchmarkError> {
+		let funder = created::<T>()?;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(funder), 0);
+
+		assert_eq!(status::<T>(), None);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn accept_task() -> Result<(), BenchmarkError> {
+		created::<T>()?;
+		let agent = agent::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(agent), 0);
+
+		assert_eq!(status::<T>(), Some(TaskStatus::Assigned));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn submit_result() -> Result<(), BenchmarkError> {
+		created::<T>()?;
+		let agent = agent::<T>();
+		Tasks::<T>::accept_task(RawOrigin::Signed(agent.clone()).into(), 0)?;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(agent), 0, T::Hash::default());
+
+		assert!(matches!(status::<T>(), Some(TaskStatus::Submitted { .. })));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn dispute(
+		c: Linear<{ T::JurySize::get() }, { T::MaxJurorCandidates::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let funder = submitted::<T>()?;
+		for i in 0..c {
+			T::BenchmarkHelper::register_agent(account("juror", i, 0));
+		}
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(funder), 0);
+
+		assert_eq!(status::<T>(), Some(TaskStatus::Disputed));
+		Ok(())
+	}
+
+	// The jury of one dispute, drawn when the next block starts.
+	#[benchmark]
+	fn draw_jury(
+		c: Linear<{ T::JurySize::get() }, { T::MaxJurorCandidates::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let funder = submitted::<T>()?;
+		for i in 0..c {
+			T::BenchmarkHelper::register_agent(account("juror", i, 0));
+		}
+		Tasks::<T>::dispute(RawOrigin::Signed(funder).into(), 0).map_err(|e| e.error)?;
+		let next = frame_system::Pallet::<T>::block_number().saturating_add(1u32.into());
+		frame_system::Pallet::<T>::set_block_number(next);
+
+		#[block]
+		{
+			Tasks::<T>::on_initialize(next);
+		}
+
+		let jury = Disputes::<T>::get(0).map(|dispute| dispute.jury.len() as u32);
+		assert_eq!(jury, Some(T::JurySize::get()));
+		Ok(())
+	}
+
+	// Worst case: the vote decides the dispute against the disputer and completes the task.
+	#[benchmark]
+	fn vote() -> Result<(), BenchmarkError> {
+		let (_, juror) = disputed::<T>()?;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(juror), 0, false);
+
+		assert_eq!(status::<T>(), Some(TaskStatus::Completed));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn resolve_dispute() -> Result<(), BenchmarkError> {
+		let (funder, _) = disputed::<T>()?;
+		frame_system::Pallet::<T>::set_block_number(
+			frame_system::Pallet::<T>::block_number()
+				.saturating_add(T::JuryPeriod::get())
+				.saturating_add(1u32.into()),
+		);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(funder), 0);
+
+		assert_eq!(status::<T>(), Some(TaskStatus::Completed));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn finalize_task() -> Result<(), BenchmarkError> {
+		let funder = submitted::<T>()?;
+		frame_system::Pallet::<T>::set_block_number(
+			frame_system::Pallet::<T>::block_number()
+				.saturating_add(T::ChallengePeriod::get())
+				.saturating_add(1u32.into()),
+		);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(funder), 0);
+
+		assert_eq!(status::<T>(), Some(TaskStatus::Completed));
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(Tasks, crate::mock::new_test_ext(), crate::mock::Test);
+}
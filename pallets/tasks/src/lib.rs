@@ -0,0 +1,719 @@
+//! # Tasks Pallet
+//!
+//! Funders post tasks with a reward and agents accept them against a stake. A submitted result is
+//! not paid out right away: it can be challenged for a while, and a challenged result is judged
+//! by a jury of agents drawn at random, weighted by stake. Whoever loses the dispute is slashed.
+//!
+//! ## Overview
+//!
+//! - [`Pallet::create_task`] posts a task, described by the hash of its specification, and holds
+//!   its reward from the funder. [`Pallet::cancel_task`] withdraws it while it waits for an agent.
+//! - [`Pallet::accept_task`] assigns an open task to an account of [`Config::EligibleAgents`],
+//!   holding [`Config::TaskStake`].
+//! - [`Pallet::submit_result`] records the hash of the result and opens a challenge period of
+//!   [`Config::ChallengePeriod`] blocks. Once it ends unchallenged, [`Pallet::finalize_task`]
+//!   pays the reward and releases the stake.
+//! - During the challenge period the funder or any agent with stake, as reported by
+//!   [`AgentPool`], can [`Pallet::dispute`] the result against a bond of [`Config::DisputeBond`].
+//!   At the start of the next block, up to [`Config::JurySize`] jurors are drawn from the other
+//!   agents, each with a chance proportional to its stake. If none is left by then, the dispute
+//!   is dropped and the bond released.
+//! - Jurors [`Pallet::vote`] on whether to uphold the dispute, weighing the stake they were drawn
+//!   with. The dispute is decided as soon as one side holds a majority of the jury's stake, or by
+//!   [`Pallet::resolve_dispute`] among the votes cast once [`Config::JuryPeriod`] blocks have
+//!   passed. Ties go to the agent.
+//! - An upheld dispute slashes the agent's stake to the disputer, returns the bond and reopens
+//!   the task. A rejected dispute slashes the bond to the agent and completes the task.
+//! - [`Config::Observer`] learns of every completed task and lost dispute, so the history of the
+//!   agents can be kept elsewhere.
+//!
+//! Juries are drawn with [`Config::Randomness`], so they are only as unpredictable as that
+//! source. Randomness derived from past blocks can be influenced by block authors. Drawing the
+//! jury in the block after the dispute keeps the disputer from picking a block whose randomness
+//! it already knows, as long as the source includes the block the dispute is in by then.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::WeightInfo;
+
+use alloc::vec::Vec;
+
+/// Identifier of a task.
+pub type TaskId = u64;
+
+/// Agents that dispute results and sit on juries.
+pub trait AgentPool<AccountId, Balance> {
+	/// Stake of `who`, zero unless it is a registered agent.
+	fn stake_of(who: &AccountId) -> Balance;
+
+	/// Registered agents with their stake.
+	fn agents() -> Vec<(AccountId, Balance)>;
+}
+
+/// Notified of the outcome of tasks and disputes.
+pub trait TaskObserver<AccountId> {
+	/// `agent` completed task `task_id` and was paid its reward.
+	fn on_task_completed(task_id: TaskId, agent: &AccountId);
+
+	/// `loser` lost the dispute of the result of task `task_id` and was slashed.
+	fn on_dispute_lost(task_id: TaskId, loser: &AccountId);
+}
+
+impl<AccountId> TaskObserver<AccountId> for () {
+	fn on_task_completed(_: TaskId, _: &AccountId) {}
+	fn on_dispute_lost(_: TaskId, _: &AccountId) {}
+}
+
+/// Registers agents for the benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<AccountId> {
+	/// Register `who` as an agent with some stake, eligible to accept tasks.
+	fn register_agent(who: AccountId);
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{
+			fungible::{Inspect, Mutate, MutateHold},
+			tokens::{Fortitude, Precision, Restriction},
+			Contains, Randomness,
+		},
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::{
+		traits::{One, Saturating, Zero},
+		SaturatedConversion,
+	};
+
+	/// Balance of the currency rewards, stakes and bonds are paid in.
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Weight information for the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
+		/// Currency rewards, stakes and bonds are held and paid in.
+		type Currency: MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>
+			+ Mutate<Self::AccountId>;
+		/// Agents that dispute results and sit on juries.
+		type Agents: AgentPool<Self::AccountId, BalanceOf<Self>>;
+		/// Accounts allowed to accept tasks.
+		type EligibleAgents: Contains<Self::AccountId>;
+		/// Notified of completed tasks and lost disputes.
+		type Observer: TaskObserver<Self::AccountId>;
+		/// Source of randomness to draw juries with.
+		type Randomness: Randomness<Self::Hash, BlockNumberFor<Self>>;
+		/// Stake an agent puts up to accept a task.
+		#[pallet::constant]
+		type TaskStake: Get<BalanceOf<Self>>;
+		/// Bond put up to dispute a result.
+		#[pallet::constant]
+		type DisputeBond: Get<BalanceOf<Self>>;
+		/// Number of blocks during which a result can be disputed.
+		#[pallet::constant]
+		type ChallengePeriod: Get<BlockNumberFor<Self>>;
+		/// Number of blocks during which a jury votes on a dispute.
+		#[pallet::constant]
+		type JuryPeriod: Get<BlockNumberFor<Self>>;
+		/// Maximum number of jurors of a dispute.
+		#[pallet::constant]
+		type JurySize: Get<u32>;
+		/// Maximum number of agents a jury is drawn from.
+		#[pallet::constant]
+		type MaxJurorCandidates: Get<u32>;
+		/// Registers agents for the benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<Self::AccountId>;
+	}
+
+	/// Reasons for the pallet to hold funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// Reward of a task, held from its funder until the task completes.
+		TaskReward,
+		/// Stake of the agent a task is assigned to.
+		TaskStake,
+		/// Bond of the disputer of a result.
+		DisputeBond,
+	}
+
+	/// Progress of a task.
+	#[derive(Clone, Copy, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug)]
+	pub enum TaskStatus<BlockNumber> {
+		/// Waiting for an agent.
+		Open,
+		/// Being worked on by its agent.
+		Assigned,
+		/// A result was submitted and can be disputed up to `challenge_deadline`, inclusive.
+		Submitted { challenge_deadline: BlockNumber },
+		/// The result is being judged by a jury.
+		Disputed,
+		/// The reward was paid to the agent.
+		Completed,
+	}
+
+	/// A task posted by a funder.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct TaskInfo<T: Config> {
+		/// Account that posted the task and pays its reward.
+		pub funder: T::AccountId,
+		/// Reward held from the funder.
+		pub reward: BalanceOf<T>,
+		/// Hash of the specification of the task.
+		pub spec: T::Hash,
+		/// Agent the task is assigned to, with the stake it put up.
+		pub assignee: Option<(T::AccountId, BalanceOf<T>)>,
+		/// Hash of the result submitted by the agent.
+		pub result: Option<T::Hash>,
+		/// Progress of the task.
+		pub status: TaskStatus<BlockNumberFor<T>>,
+	}
+
+	/// A dispute of the result of a task.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct DisputeInfo<T: Config> {
+		/// Account that disputed the result.
+		pub disputer: T::AccountId,
+		/// Bond held from the disputer.
+		pub bond: BalanceOf<T>,
+		/// Jurors, with the stake each votes with. Empty until the jury is drawn.
+		pub jury: BoundedVec<(T::AccountId, BalanceOf<T>), T::JurySize>,
+		/// Stake voting to uphold the dispute.
+		pub uphold: BalanceOf<T>,
+		/// Stake voting to reject the dispute.
+		pub reject: BalanceOf<T>,
+		/// Last block the jury can vote in.
+		pub deadline: BlockNumberFor<T>,
+	}
+
+	/// Identifier of the next task.
+	#[pallet::storage]
+	pub type NextTaskId<T: Config> = StorageValue<_, TaskId, ValueQuery>;
+
+	/// Tasks. Completed tasks are kept as a record of the work done.
+	#[pallet::storage]
+	pub type Tasks<T: Config> = StorageMap<_, Twox64Concat, TaskId, TaskInfo<T>>;
+
+	/// Ongoing disputes, by task.
+	#[pallet::storage]
+	pub type Disputes<T: Config> = StorageMap<_, Twox64Concat, TaskId, DisputeInfo<T>>;
+
+	/// Disputes whose jury is drawn at the start of a block, by block.
+	#[pallet::storage]
+	pub type PendingJuries<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, BlockNumberFor<T>, Twox64Concat, TaskId, ()>;
+
+	/// Votes of the jurors of ongoing disputes, `true` to uphold the dispute.
+	#[pallet::storage]
+	pub type JuryVotes<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, TaskId, Blake2_128Concat, T::AccountId, bool>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A task was posted
+		TaskCreated { task_id: TaskId, funder: T::AccountId, reward: BalanceOf<T>, spec: T::Hash },
+		/// A task was withdrawn by its funder
+		TaskCancelled { task_id: TaskId },
+		/// An agent accepted a task
+		TaskAccepted { task_id: TaskId, agent: T::AccountId },
+		/// The result of a task was submitted and can be disputed
+		ResultSubmitted { task_id: TaskId, result: T::Hash, challenge_deadline: BlockNumberFor<T> },
+		/// The result of a task was disputed, and its jury is drawn in the next block
+		ResultDisputed { task_id: TaskId, disputer: T::AccountId },
+		/// The jury of a dispute was drawn
+		JuryDrawn { task_id: TaskId, jury: Vec<T::AccountId> },
+		/// No agent was left to judge a dispute, so its bond was released and the result can be
+		/// finalized
+		DisputeDropped { task_id: TaskId },
+		/// A juror voted on a dispute
+		JurorVoted { task_id: TaskId, juror: T::AccountId, uphold: bool },
+		/// A dispute was upheld, the agent slashed and the task reopened
+		DisputeUpheld { task_id: TaskId, agent: T::AccountId, slashed: BalanceOf<T> },
+		/// A dispute was rejected and the disputer slashed
+		DisputeRejected { task_id: TaskId, disputer: T::AccountId, slashed: BalanceOf<T> },
+		/// A task was completed and its reward paid
+		TaskCompleted { task_id: TaskId, agent: T::AccountId, reward: BalanceOf<T> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The reward of a task cannot be zero
+		ZeroReward,
+		/// Task not found
+		TaskNotFound,
+		/// Only the funder of the task can do this
+		NotFunder,
+		/// The task is not open
+		NotOpen,
+		/// The caller is not allowed to accept tasks
+		NotEligible,
+		/// The task is not being worked on by the caller
+		NotAssignee,
+		/// The task has no result waiting to be finalized or disputed
+		NotSubmitted,
+		/// The challenge period of the result is over
+		ChallengePeriodOver,
+		/// The challenge period of the result is not over yet
+		ChallengePeriodOpen,
+		/// Only the funder or an agent other than the assignee can dispute a result
+		CannotDispute,
+		/// No agent is eligible to sit on the jury
+		NoJurors,
+		/// The task is not disputed
+		NotDisputed,
+		/// The caller is not on the jury of the dispute
+		NotJuror,
+		/// The juror already voted
+		AlreadyVoted,
+		/// The voting period of the jury is over
+		JuryPeriodOver,
+		/// The voting period of the jury is not over yet
+		JuryPeriodOpen,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn integrity_test() {
+			assert!(T::JurySize::get() > 0, "a jury needs at least one juror");
+			assert!(
+				T::MaxJurorCandidates::get() >= T::JurySize::get(),
+				"a full jury must be drawable from the candidates"
+			);
+		}
+
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			for (task_id, ()) in PendingJuries::<T>::drain_prefix(now) {
+				let candidates = Self::seat_jury(task_id, now);
+				weight.saturating_accrue(T::WeightInfo::draw_jury(candidates));
+			}
+			weight
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Post a task with the hash of its specification, holding `reward` until it completes.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::create_task())]
+		pub fn create_task(
+			origin: OriginFor<T>,
+			reward: BalanceOf<T>,
+			spec: T::Hash,
+		) -> DispatchResult {
+			let funder = ensure_signed(origin)?;
+			ensure!(!reward.is_zero(), Error::<T>::ZeroReward);
+
+			T::Currency::hold(&HoldReason::TaskReward.into(), &funder, reward)?;
+			let task_id = NextTaskId::<T>::mutate(|id| {
+				let current = *id;
+				*id = id.saturating_add(1);
+				current
+			});
+			Tasks::<T>::insert(
+				task_id,
+				TaskInfo {
+					funder: funder.clone(),
+					reward,
+					spec,
+					assignee: None,
+					result: None,
+					status: TaskStatus::Open,
+				},
+			);
+
+			Self::deposit_event(Event::TaskCreated { task_id, funder, reward, spec });
+			Ok(())
+		}
+
+		/// Withdraw an open task and release its reward.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::cancel_task())]
+		pub fn cancel_task(origin: OriginFor<T>, task_id: TaskId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
+			ensure!(task.funder == who, Error::<T>::NotFunder);
+			ensure!(task.status == TaskStatus::Open, Error::<T>::NotOpen);
+
+			T::Currency::release(
+				&HoldReason::TaskReward.into(),
+				&who,
+				task.reward,
+				Precision::BestEffort,
+			)?;
+			Tasks::<T>::remove(task_id);
+
+			Self::deposit_event(Event::TaskCancelled { task_id });
+			Ok(())
+		}
+
+		/// Accept an open task, holding [`Config::TaskStake`] until it completes.
+		///
+		/// Only [`Config::EligibleAgents`] can accept tasks.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::accept_task())]
+		pub fn accept_task(origin: OriginFor<T>, task_id: TaskId) -> DispatchResult {
+			let agent = ensure_signed(origin)?;
+			Tasks::<T>::try_mutate(task_id, |task| -> DispatchResult {
+				let task = task.as_mut().ok_or(Error::<T>::TaskNotFound)?;
+				ensure!(task.status == TaskStatus::Open, Error::<T>::NotOpen);
+				ensure!(T::EligibleAgents::contains(&agent), Error::<T>::NotEligible);
+
+				let stake = T::TaskStake::get();
+				T::Currency::hold(&HoldReason::TaskStake.into(), &agent, stake)?;
+				task.assignee = Some((agent.clone(), stake));
+				task.status = TaskStatus::Assigned;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::TaskAccepted { task_id, agent });
+			Ok(())
+		}
+
+		/// Submit the hash of the result of a task assigned to the caller, opening its challenge
+		/// period.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::submit_result())]
+		pub fn submit_result(
+			origin: OriginFor<T>,
+			task_id: TaskId,
+			result: T::Hash,
+		) -> DispatchResult {
+			let agent = ensure_signed(origin)?;
+			let challenge_deadline =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::ChallengePeriod::get());
+			Tasks::<T>::try_mutate(task_id, |task| -> DispatchResult {
+				let task = task.as_mut().ok_or(Error::<T>::TaskNotFound)?;
+				ensure!(
+					task.status == TaskStatus::Assigned &&
+						matches!(&task.assignee, Some((assignee, _)) if *assignee == agent),
+					Error::<T>::NotAssignee
+				);
+
+				task.result = Some(result);
+				task.status = TaskStatus::Submitted { challenge_deadline };
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::ResultSubmitted { task_id, result, challenge_deadline });
+			Ok(())
+		}
+
+		/// Dispute the result of a task during its challenge period, holding
+		/// [`Config::DisputeBond`].
+		///
+		/// The jury is drawn at the start of the next block, with randomness the disputer cannot
+		/// know when disputing.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::dispute(T::MaxJurorCandidates::get()))]
+		pub fn dispute(origin: OriginFor<T>, task_id: TaskId) -> DispatchResultWithPostInfo {
+			let disputer = ensure_signed(origin)?;
+			let mut task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
+			let (TaskStatus::Submitted { challenge_deadline }, Some((agent, _))) =
+				(task.status, &task.assignee)
+			else {
+				return Err(Error::<T>::NotSubmitted.into());
+			};
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(now <= challenge_deadline, Error::<T>::ChallengePeriodOver);
+			ensure!(
+				disputer != *agent &&
+					(disputer == task.funder || !T::Agents::stake_of(&disputer).is_zero()),
+				Error::<T>::CannotDispute
+			);
+
+			let candidates = Self::juror_candidates(&[&disputer, agent, &task.funder]).len() as u32;
+			ensure!(candidates > 0, Error::<T>::NoJurors);
+			let bond = T::DisputeBond::get();
+			T::Currency::hold(&HoldReason::DisputeBond.into(), &disputer, bond)?;
+
+			task.status = TaskStatus::Disputed;
+			Tasks::<T>::insert(task_id, task);
+			let draw_at = now.saturating_add(One::one());
+			Disputes::<T>::insert(
+				task_id,
+				DisputeInfo {
+					disputer: disputer.clone(),
+					bond,
+					jury: BoundedVec::new(),
+					uphold: Zero::zero(),
+					reject: Zero::zero(),
+					deadline: draw_at.saturating_add(T::JuryPeriod::get()),
+				},
+			);
+			PendingJuries::<T>::insert(draw_at, task_id, ());
+
+			Self::deposit_event(Event::ResultDisputed { task_id, disputer });
+			Ok(Some(T::WeightInfo::dispute(candidates)).into())
+		}
+
+		/// Vote as a juror on the dispute of a task, `uphold` to side with the disputer.
+		///
+		/// Decides the dispute once one side holds a majority of the jury's stake.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::vote())]
+		pub fn vote(origin: OriginFor<T>, task_id: TaskId, uphold: bool) -> DispatchResult {
+			let juror = ensure_signed(origin)?;
+			let mut dispute = Disputes::<T>::get(task_id).ok_or(Error::<T>::NotDisputed)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= dispute.deadline,
+				Error::<T>::JuryPeriodOver
+			);
+			let stake = dispute
+				.jury
+				.iter()
+				.find(|(member, _)| *member == juror)
+				.map(|(_, stake)| *stake)
+				.ok_or(Error::<T>::NotJuror)?;
+			ensure!(!JuryVotes::<T>::contains_key(task_id, &juror), Error::<T>::AlreadyVoted);
+
+			JuryVotes::<T>::insert(task_id, &juror, uphold);
+			if uphold {
+				dispute.uphold.saturating_accrue(stake);
+			} else {
+				dispute.reject.saturating_accrue(stake);
+			}
+			Self::deposit_event(Event::JurorVoted { task_id, juror, uphold });
+
+			let total = dispute
+				.jury
+				.iter()
+				.fold(BalanceOf::<T>::zero(), |total, (_, stake)| total.saturating_add(*stake));
+			if dispute.uphold.saturating_add(dispute.uphold) > total {
+				Self::conclude(task_id, dispute, true)
+			} else if dispute.reject.saturating_add(dispute.reject) >= total {
+				Self::conclude(task_id, dispute, false)
+			} else {
+				Disputes::<T>::insert(task_id, dispute);
+				Ok(())
+			}
+		}
+
+		/// Decide a dispute by the votes cast once its voting period is over.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::resolve_dispute())]
+		pub fn resolve_dispute(origin: OriginFor<T>, task_id: TaskId) -> DispatchResult {
+			ensure_signed(origin)?;
+			let dispute = Disputes::<T>::get(task_id).ok_or(Error::<T>::NotDisputed)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > dispute.deadline,
+				Error::<T>::JuryPeriodOpen
+			);
+
+			let upheld = dispute.uphold > dispute.reject;
+			Self::conclude(task_id, dispute, upheld)
+		}
+
+		/// Complete a task whose result went undisputed through its challenge period, paying the
+		/// reward and releasing the stake of the agent.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::finalize_task())]
+		pub fn finalize_task(origin: OriginFor<T>, task_id: TaskId) -> DispatchResult {
+			ensure_signed(origin)?;
+			let task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
+			let TaskStatus::Submitted { challenge_deadline } = task.status else {
+				return Err(Error::<T>::NotSubmitted.into());
+			};
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > challenge_deadline,
+				Error::<T>::ChallengePeriodOpen
+			);
+
+			Self::complete(task_id, task)
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Agents with stake other than `parties`, which juries are drawn from.
+		fn juror_candidates(parties: &[&T::AccountId]) -> Vec<(T::AccountId, BalanceOf<T>)> {
+			T::Agents::agents()
+				.into_iter()
+				.filter(|(who, stake)| !stake.is_zero() && !parties.contains(&who))
+				.take(T::MaxJurorCandidates::get() as usize)
+				.collect()
+		}
+
+		/// Draw the jury of the dispute of `task_id`, or drop the dispute if no agent is left to
+		/// sit on it.
+		///
+		/// Returns the number of candidates the jury was drawn from.
+		fn seat_jury(task_id: TaskId, now: BlockNumberFor<T>) -> u32 {
+			let (Some(mut dispute), Some(mut task)) =
+				(Disputes::<T>::get(task_id), Tasks::<T>::get(task_id))
+			else {
+				return 0;
+			};
+			let Some((agent, _)) = &task.assignee else {
+				return 0;
+			};
+			let candidates = Self::juror_candidates(&[&dispute.disputer, agent, &task.funder]);
+			let count = candidates.len() as u32;
+			let jury = Self::draw_jury(task_id, candidates);
+
+			if jury.is_empty() {
+				// Releasing the whole bond cannot fail, as nothing else holds it.
+				let _ = T::Currency::release(
+					&HoldReason::DisputeBond.into(),
+					&dispute.disputer,
+					dispute.bond,
+					Precision::BestEffort,
+				);
+				task.status = TaskStatus::Submitted { challenge_deadline: now };
+				Tasks::<T>::insert(task_id, task);
+				Disputes::<T>::remove(task_id);
+				Self::deposit_event(Event::DisputeDropped { task_id });
+			} else {
+				let jurors = jury.iter().map(|(juror, _)| juror.clone()).collect();
+				dispute.jury = BoundedVec::truncate_from(jury);
+				Disputes::<T>::insert(task_id, dispute);
+				Self::deposit_event(Event::JuryDrawn { task_id, jury: jurors });
+			}
+			count
+		}
+
+		/// Draw up to [`Config::JurySize`] jurors for the dispute of `task_id` among
+		/// `candidates`, each with a chance proportional to its stake.
+		fn draw_jury(
+			task_id: TaskId,
+			mut candidates: Vec<(T::AccountId, BalanceOf<T>)>,
+		) -> Vec<(T::AccountId, BalanceOf<T>)> {
+			let (seed, _) = T::Randomness::random(&(b"tasks/jury", task_id).encode());
+
+			let mut total = candidates.iter().fold(0u128, |total, (_, stake)| {
+				total.saturating_add((*stake).saturated_into::<u128>())
+			});
+			let mut jury = Vec::new();
+			for seat in 0..T::JurySize::get() {
+				if candidates.is_empty() || total == 0 {
+					break;
+				}
+				let draw = u128::from_le_bytes(sp_io::hashing::blake2_128(&(seed, seat).encode()));
+				let draw = draw % total;
+				let mut cumulative = 0u128;
+				let index = candidates
+					.iter()
+					.position(|(_, stake)| {
+						cumulative = cumulative.saturating_add((*stake).saturated_into::<u128>());
+						draw < cumulative
+					})
+					.unwrap_or(candidates.len() - 1);
+				let juror = candidates.swap_remove(index);
+				total = total.saturating_sub(juror.1.saturated_into::<u128>());
+				jury.push(juror);
+			}
+			jury
+		}
+
+		/// Settle the dispute of `task_id`, slashing whoever lost it.
+		fn conclude(task_id: TaskId, dispute: DisputeInfo<T>, upheld: bool) -> DispatchResult {
+			let mut task = Tasks::<T>::get(task_id).ok_or(Error::<T>::TaskNotFound)?;
+			let (agent, stake) = task.assignee.clone().ok_or(Error::<T>::NotDisputed)?;
+			Disputes::<T>::remove(task_id);
+			let _ = JuryVotes::<T>::clear_prefix(task_id, T::JurySize::get(), None);
+
+			if upheld {
+				let slashed = T::Currency::transfer_on_hold(
+					&HoldReason::TaskStake.into(),
+					&agent,
+					&dispute.disputer,
+					stake,
+					Precision::BestEffort,
+					Restriction::Free,
+					Fortitude::Force,
+				)?;
+				T::Currency::release(
+					&HoldReason::DisputeBond.into(),
+					&dispute.disputer,
+					dispute.bond,
+					Precision::BestEffort,
+				)?;
+				task.assignee = None;
+				task.result = None;
+				task.status = TaskStatus::Open;
+				Tasks::<T>::insert(task_id, task);
+
+				T::Observer::on_dispute_lost(task_id, &agent);
+				Self::deposit_event(Event::DisputeUpheld { task_id, agent, slashed });
+				Ok(())
+			} else {
+				let slashed = T::Currency::transfer_on_hold(
+					&HoldReason::DisputeBond.into(),
+					&dispute.disputer,
+					&agent,
+					dispute.bond,
+					Precision::BestEffort,
+					Restriction::Free,
+					Fortitude::Force,
+				)?;
+				T::Observer::on_dispute_lost(task_id, &dispute.disputer);
+				Self::deposit_event(Event::DisputeRejected {
+					task_id,
+					disputer: dispute.disputer,
+					slashed,
+				});
+				Self::complete(task_id, task)
+			}
+		}
+
+		/// Pay the reward of `task_id` to its agent and release the agent's stake.
+		fn complete(task_id: TaskId, mut task: TaskInfo<T>) -> DispatchResult {
+			let (agent, stake) = task.assignee.clone().ok_or(Error::<T>::NotSubmitted)?;
+			let reward = T::Currency::transfer_on_hold(
+				&HoldReason::TaskReward.into(),
+				&task.funder,
+				&agent,
+				task.reward,
+				Precision::BestEffort,
+				Restriction::Free,
+				Fortitude::Polite,
+			)?;
+			T::Currency::release(
+				&HoldReason::TaskStake.into(),
+				&agent,
+				stake,
+				Precision::BestEffort,
+			)?;
+			task.status = TaskStatus::Completed;
+			Tasks::<T>::insert(task_id, task);
+
+			T::Observer::on_task_completed(task_id, &agent);
+			Self::deposit_event(Event::TaskCompleted { task_id, agent, reward });
+			Ok(())
+		}
+	}
+}
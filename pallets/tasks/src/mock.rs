@@ -0,0 +1,151 @@
+use crate::{self as pallet_tasks, AgentPool, TaskId, TaskObserver};
+use core::cell::RefCell;
+use frame_support::{
+	derive_impl,
+	traits::{ConstU32, ConstU64, Contains, Randomness},
+};
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, Hash},
+	BuildStorage,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		Balances: pallet_balances,
+		Tasks: pallet_tasks,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+thread_local! {
+	static STAKES: RefCell<BTreeMap<u64, u64>> = RefCell::new(BTreeMap::new());
+	static INELIGIBLE: RefCell<BTreeSet<u64>> = RefCell::new(BTreeSet::new());
+	static OUTCOMES: RefCell<Vec<Outcome>> = RefCell::new(Vec::new());
+}
+
+/// Agents and their stake, kept in memory.
+pub struct MockAgents;
+
+impl MockAgents {
+	pub fn set_stake(who: u64, stake: u64) {
+		STAKES.with(|s| s.borrow_mut().insert(who, stake));
+	}
+}
+
+impl AgentPool<u64, u64> for MockAgents {
+	fn stake_of(who: &u64) -> u64 {
+		STAKES.with(|s| s.borrow().get(who).copied().unwrap_or_default())
+	}
+
+	fn agents() -> Vec<(u64, u64)> {
+		STAKES.with(|s| s.borrow().iter().map(|(who, stake)| (*who, *stake)).collect())
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkHelper<u64> for MockAgents {
+	fn register_agent(who: u64) {
+		Self::set_stake(who, 100);
+	}
+}
+
+/// Every account but those excluded with [`EligibleAgents::exclude`].
+pub struct EligibleAgents;
+
+impl EligibleAgents {
+	pub fn exclude(who: u64) {
+		INELIGIBLE.with(|i| i.borrow_mut().insert(who));
+	}
+}
+
+impl Contains<u64> for EligibleAgents {
+	fn contains(who: &u64) -> bool {
+		INELIGIBLE.with(|i| !i.borrow().contains(who))
+	}
+}
+
+/// An outcome reported to [`MockObserver`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+	Completed(TaskId, u64),
+	DisputeLost(TaskId, u64),
+}
+
+/// Records the outcomes it is notified of.
+pub struct MockObserver;
+
+impl MockObserver {
+	pub fn outcomes() -> Vec<Outcome> {
+		OUTCOMES.with(|o| o.borrow().clone())
+	}
+}
+
+impl TaskObserver<u64> for MockObserver {
+	fn on_task_completed(task_id: TaskId, agent: &u64) {
+		OUTCOMES.with(|o| o.borrow_mut().push(Outcome::Completed(task_id, *agent)));
+	}
+
+	fn on_dispute_lost(task_id: TaskId, loser: &u64) {
+		OUTCOMES.with(|o| o.borrow_mut().push(Outcome::DisputeLost(task_id, *loser)));
+	}
+}
+
+/// Randomness that is the hash of its subject and the current block number.
+pub struct MockRandomness;
+
+impl Randomness<H256, u64> for MockRandomness {
+	fn random(subject: &[u8]) -> (H256, u64) {
+		let block = System::block_number();
+		(BlakeTwo256::hash_of(&(subject, block)), block)
+	}
+}
+
+impl pallet_tasks::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type Currency = Balances;
+	type Agents = MockAgents;
+	type EligibleAgents = EligibleAgents;
+	type Observer = MockObserver;
+	type Randomness = MockRandomness;
+	type TaskStake = ConstU64<50>;
+	type DisputeBond = ConstU64<20>;
+	type ChallengePeriod = ConstU64<10>;
+	type JuryPeriod = ConstU64<5>;
+	type JurySize = ConstU32<3>;
+	type MaxJurorCandidates = ConstU32<8>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockAgents;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	STAKES.with(|s| s.borrow_mut().clear());
+	INELIGIBLE.with(|i| i.borrow_mut().clear());
+	OUTCOMES.with(|o| o.borrow_mut().clear());
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: (1..=20).map(|who| (who, 1_000)).collect() }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	// Events are not emitted on the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
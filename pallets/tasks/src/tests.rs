@@ -0,0 +1,372 @@
+use crate::{
+	mock::{Tasks, *},
+	*,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{fungible::InspectHold, Hooks},
+};
+use sp_core::H256;
+use sp_runtime::{DispatchError, TokenError};
+
+const FUNDER: u64 = 1;
+const AGENT: u64 = 2;
+const OUTSIDER: u64 = 3;
+/// Agents with stake. Any jury of three of them weighs 30, so two votes decide a dispute.
+const AGENTS: [u64; 4] = [10, 11, 12, 13];
+const REWARD: u64 = 100;
+const SPEC: H256 = H256::repeat_byte(1);
+const RESULT: H256 = H256::repeat_byte(2);
+
+fn register_agents() {
+	for agent in AGENTS {
+		MockAgents::set_stake(agent, 10);
+	}
+}
+
+/// Post a task and submit a result for it as [`AGENT`].
+fn submitted_task() -> TaskId {
+	let task_id = NextTaskId::<Test>::get();
+	assert_ok!(Tasks::create_task(RuntimeOrigin::signed(FUNDER), REWARD, SPEC));
+	assert_ok!(Tasks::accept_task(RuntimeOrigin::signed(AGENT), task_id));
+	assert_ok!(Tasks::submit_result(RuntimeOrigin::signed(AGENT), task_id, RESULT));
+	task_id
+}
+
+/// Move to the next block, drawing the juries of the disputes of the current one.
+fn next_block() {
+	let next = System::block_number() + 1;
+	System::set_block_number(next);
+	Tasks::on_initialize(next);
+}
+
+/// Dispute `task_id` as `disputer` and draw its jury.
+fn disputed(disputer: u64, task_id: TaskId) {
+	assert_ok!(Tasks::dispute(RuntimeOrigin::signed(disputer), task_id));
+	next_block();
+}
+
+fn jury(task_id: TaskId) -> Vec<u64> {
+	Disputes::<Test>::get(task_id)
+		.unwrap()
+		.jury
+		.into_iter()
+		.map(|(juror, _)| juror)
+		.collect()
+}
+
+fn status(task_id: TaskId) -> TaskStatus<u64> {
+	pallet::Tasks::<Test>::get(task_id).unwrap().status
+}
+
+fn held(who: u64) -> u64 {
+	Balances::total_balance_on_hold(&who)
+}
+
+#[test]
+fn undisputed_results_are_paid_after_the_challenge_period() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Tasks::create_task(RuntimeOrigin::signed(FUNDER), 0, SPEC),
+			Error::<Test>::ZeroReward
+		);
+		assert_noop!(
+			Tasks::create_task(RuntimeOrigin::signed(FUNDER), 2_000, SPEC),
+			TokenError::FundsUnavailable
+		);
+		assert_ok!(Tasks::create_task(RuntimeOrigin::signed(FUNDER), REWARD, SPEC));
+		System::assert_last_event(
+			Event::TaskCreated { task_id: 0, funder: FUNDER, reward: REWARD, spec: SPEC }.into(),
+		);
+		assert_eq!(held(FUNDER), REWARD);
+
+		assert_noop!(
+			Tasks::submit_result(RuntimeOrigin::signed(AGENT), 0, RESULT),
+			Error::<Test>::NotAssignee
+		);
+		EligibleAgents::exclude(OUTSIDER);
+		assert_noop!(
+			Tasks::accept_task(RuntimeOrigin::signed(OUTSIDER), 0),
+			Error::<Test>::NotEligible
+		);
+		assert_ok!(Tasks::accept_task(RuntimeOrigin::signed(AGENT), 0));
+		System::assert_last_event(Event::TaskAccepted { task_id: 0, agent: AGENT }.into());
+		assert_eq!(held(AGENT), 50);
+		assert_noop!(
+			Tasks::accept_task(RuntimeOrigin::signed(OUTSIDER), 0),
+			Error::<Test>::NotOpen
+		);
+		assert_noop!(Tasks::cancel_task(RuntimeOrigin::signed(FUNDER), 0), Error::<Test>::NotOpen);
+		assert_noop!(
+			Tasks::submit_result(RuntimeOrigin::signed(OUTSIDER), 0, RESULT),
+			Error::<Test>::NotAssignee
+		);
+		assert_noop!(
+			Tasks::finalize_task(RuntimeOrigin::signed(OUTSIDER), 0),
+			Error::<Test>::NotSubmitted
+		);
+
+		assert_ok!(Tasks::submit_result(RuntimeOrigin::signed(AGENT), 0, RESULT));
+		System::assert_last_event(
+			Event::ResultSubmitted { task_id: 0, result: RESULT, challenge_deadline: 11 }.into(),
+		);
+		assert_noop!(
+			Tasks::submit_result(RuntimeOrigin::signed(AGENT), 0, RESULT),
+			Error::<Test>::NotAssignee
+		);
+
+		System::set_block_number(11);
+		assert_noop!(
+			Tasks::finalize_task(RuntimeOrigin::signed(OUTSIDER), 0),
+			Error::<Test>::ChallengePeriodOpen
+		);
+		System::set_block_number(12);
+		assert_ok!(Tasks::finalize_task(RuntimeOrigin::signed(OUTSIDER), 0));
+		System::assert_last_event(
+			Event::TaskCompleted { task_id: 0, agent: AGENT, reward: REWARD }.into(),
+		);
+		let task = pallet::Tasks::<Test>::get(0).unwrap();
+		assert_eq!(task.assignee, Some((AGENT, 50)));
+		assert_eq!(task.result, Some(RESULT));
+		assert_eq!(task.status, TaskStatus::Completed);
+		assert_eq!((Balances::free_balance(FUNDER), held(FUNDER)), (900, 0));
+		assert_eq!((Balances::free_balance(AGENT), held(AGENT)), (1_100, 0));
+		assert_eq!(MockObserver::outcomes(), vec![Outcome::Completed(0, AGENT)]);
+	});
+}
+
+#[test]
+fn open_tasks_can_be_cancelled_by_their_funder() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Tasks::cancel_task(RuntimeOrigin::signed(FUNDER), 0),
+			Error::<Test>::TaskNotFound
+		);
+		assert_ok!(Tasks::create_task(RuntimeOrigin::signed(FUNDER), REWARD, SPEC));
+		assert_noop!(Tasks::cancel_task(RuntimeOrigin::signed(AGENT), 0), Error::<Test>::NotFunder);
+
+		assert_ok!(Tasks::cancel_task(RuntimeOrigin::signed(FUNDER), 0));
+		System::assert_last_event(Event::TaskCancelled { task_id: 0 }.into());
+		assert!(!pallet::Tasks::<Test>::contains_key(0));
+		assert_eq!((Balances::free_balance(FUNDER), held(FUNDER)), (1_000, 0));
+	});
+}
+
+#[test]
+fn only_the_funder_and_agents_dispute_within_the_challenge_period() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Tasks::create_task(RuntimeOrigin::signed(FUNDER), REWARD, SPEC));
+		assert_noop!(Tasks::dispute(RuntimeOrigin::signed(FUNDER), 0), Error::<Test>::NotSubmitted);
+		let task_id = submitted_task();
+
+		// Without other agents there is nobody to judge the result.
+		assert_noop!(
+			Tasks::dispute(RuntimeOrigin::signed(FUNDER), task_id),
+			Error::<Test>::NoJurors
+		);
+		register_agents();
+		MockAgents::set_stake(AGENT, 10);
+		assert_noop!(
+			Tasks::dispute(RuntimeOrigin::signed(AGENT), task_id),
+			Error::<Test>::CannotDispute
+		);
+		assert_noop!(
+			Tasks::dispute(RuntimeOrigin::signed(OUTSIDER), task_id),
+			Error::<Test>::CannotDispute
+		);
+
+		System::set_block_number(12);
+		assert_noop!(
+			Tasks::dispute(RuntimeOrigin::signed(FUNDER), task_id),
+			Error::<Test>::ChallengePeriodOver
+		);
+		System::set_block_number(11);
+		assert_ok!(Tasks::dispute(RuntimeOrigin::signed(AGENTS[0]), task_id));
+		assert_eq!(status(task_id), TaskStatus::Disputed);
+		assert_eq!(held(AGENTS[0]), 20);
+		assert_noop!(
+			Tasks::dispute(RuntimeOrigin::signed(FUNDER), task_id),
+			Error::<Test>::NotSubmitted
+		);
+		assert_noop!(
+			Tasks::finalize_task(RuntimeOrigin::signed(FUNDER), task_id),
+			Error::<Test>::NotSubmitted
+		);
+	});
+}
+
+#[test]
+fn juries_are_drawn_from_other_agents_by_stake() {
+	new_test_ext().execute_with(|| {
+		register_agents();
+		// Agents without stake are never drawn.
+		MockAgents::set_stake(14, 0);
+
+		let task_id = submitted_task();
+		assert_ok!(Tasks::dispute(RuntimeOrigin::signed(AGENTS[0]), task_id));
+		System::assert_last_event(Event::ResultDisputed { task_id, disputer: AGENTS[0] }.into());
+		// The jury is only drawn in the next block, with randomness unknown to the disputer.
+		assert!(jury(task_id).is_empty());
+		assert_noop!(
+			Tasks::vote(RuntimeOrigin::signed(AGENTS[1]), task_id, true),
+			Error::<Test>::NotJuror
+		);
+		next_block();
+		let mut drawn = jury(task_id);
+		System::assert_last_event(Event::JuryDrawn { task_id, jury: drawn.clone() }.into());
+		drawn.sort();
+		assert_eq!(drawn, AGENTS[1..].to_vec());
+		let dispute = Disputes::<Test>::get(task_id).unwrap();
+		assert!(dispute.jury.iter().all(|(_, stake)| *stake == 10));
+		assert_eq!((dispute.disputer, dispute.bond, dispute.deadline), (AGENTS[0], 20, 7));
+		assert_eq!(PendingJuries::<Test>::iter().count(), 0);
+
+		// An agent holding nearly all the stake is all but certain to be drawn.
+		MockAgents::set_stake(AGENTS[3], 1_000_000);
+		for _ in 0..5 {
+			let task_id = submitted_task();
+			disputed(FUNDER, task_id);
+			assert_eq!(jury(task_id).len(), 3);
+			assert!(jury(task_id).contains(&AGENTS[3]));
+			assert!(!jury(task_id).contains(&14));
+		}
+	});
+}
+
+#[test]
+fn upheld_disputes_slash_the_agent_and_reopen_the_task() {
+	new_test_ext().execute_with(|| {
+		register_agents();
+		let task_id = submitted_task();
+		disputed(FUNDER, task_id);
+		let jurors = jury(task_id);
+		let outsider = AGENTS.into_iter().find(|agent| !jurors.contains(agent)).unwrap();
+
+		assert_noop!(
+			Tasks::vote(RuntimeOrigin::signed(outsider), task_id, true),
+			Error::<Test>::NotJuror
+		);
+		assert_ok!(Tasks::vote(RuntimeOrigin::signed(jurors[0]), task_id, true));
+		System::assert_last_event(
+			Event::JurorVoted { task_id, juror: jurors[0], uphold: true }.into(),
+		);
+		assert_noop!(
+			Tasks::vote(RuntimeOrigin::signed(jurors[0]), task_id, true),
+			Error::<Test>::AlreadyVoted
+		);
+		assert_ok!(Tasks::vote(RuntimeOrigin::signed(jurors[1]), task_id, false));
+		assert_eq!(status(task_id), TaskStatus::Disputed);
+
+		assert_ok!(Tasks::vote(RuntimeOrigin::signed(jurors[2]), task_id, true));
+		System::assert_last_event(
+			Event::DisputeUpheld { task_id, agent: AGENT, slashed: 50 }.into(),
+		);
+		let task = pallet::Tasks::<Test>::get(task_id).unwrap();
+		assert_eq!((task.assignee, task.result, task.status), (None, None, TaskStatus::Open));
+		assert!(!Disputes::<Test>::contains_key(task_id));
+		assert_eq!(JuryVotes::<Test>::iter_prefix(task_id).count(), 0);
+		assert_eq!((Balances::free_balance(AGENT), held(AGENT)), (950, 0));
+		assert_eq!((Balances::free_balance(FUNDER), held(FUNDER)), (950, REWARD));
+		assert_eq!(MockObserver::outcomes(), vec![Outcome::DisputeLost(task_id, AGENT)]);
+
+		// Another agent can take the task up.
+		assert_ok!(Tasks::accept_task(RuntimeOrigin::signed(OUTSIDER), task_id));
+	});
+}
+
+#[test]
+fn rejected_disputes_slash_the_disputer_and_complete_the_task() {
+	new_test_ext().execute_with(|| {
+		register_agents();
+		let task_id = submitted_task();
+		disputed(AGENTS[0], task_id);
+		let jurors = jury(task_id);
+
+		assert_ok!(Tasks::vote(RuntimeOrigin::signed(jurors[0]), task_id, false));
+		assert_ok!(Tasks::vote(RuntimeOrigin::signed(jurors[1]), task_id, false));
+		System::assert_has_event(
+			Event::DisputeRejected { task_id, disputer: AGENTS[0], slashed: 20 }.into(),
+		);
+		System::assert_last_event(
+			Event::TaskCompleted { task_id, agent: AGENT, reward: REWARD }.into(),
+		);
+		assert_eq!(status(task_id), TaskStatus::Completed);
+		assert_noop!(
+			Tasks::vote(RuntimeOrigin::signed(jurors[2]), task_id, true),
+			Error::<Test>::NotDisputed
+		);
+		assert_eq!((Balances::free_balance(AGENTS[0]), held(AGENTS[0])), (980, 0));
+		assert_eq!((Balances::free_balance(AGENT), held(AGENT)), (1_120, 0));
+		assert_eq!((Balances::free_balance(FUNDER), held(FUNDER)), (900, 0));
+		assert_eq!(
+			MockObserver::outcomes(),
+			vec![Outcome::DisputeLost(task_id, AGENTS[0]), Outcome::Completed(task_id, AGENT)]
+		);
+	});
+}
+
+#[test]
+fn disputes_are_resolved_by_the_votes_cast_after_the_jury_period() {
+	new_test_ext().execute_with(|| {
+		register_agents();
+		let upheld = submitted_task();
+		let rejected = submitted_task();
+		assert_ok!(Tasks::dispute(RuntimeOrigin::signed(FUNDER), upheld));
+		assert_ok!(Tasks::dispute(RuntimeOrigin::signed(FUNDER), rejected));
+		next_block();
+		assert_ok!(Tasks::vote(RuntimeOrigin::signed(jury(upheld)[0]), upheld, true));
+
+		assert_noop!(
+			Tasks::resolve_dispute(RuntimeOrigin::signed(OUTSIDER), upheld),
+			Error::<Test>::JuryPeriodOpen
+		);
+		System::set_block_number(8);
+		assert_noop!(
+			Tasks::vote(RuntimeOrigin::signed(jury(upheld)[1]), upheld, false),
+			Error::<Test>::JuryPeriodOver
+		);
+		assert_noop!(
+			Tasks::resolve_dispute(RuntimeOrigin::none(), upheld),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(Tasks::resolve_dispute(RuntimeOrigin::signed(OUTSIDER), upheld));
+		System::assert_last_event(
+			Event::DisputeUpheld { task_id: upheld, agent: AGENT, slashed: 50 }.into(),
+		);
+		assert_eq!(status(upheld), TaskStatus::Open);
+
+		// Without a single vote, the result stands.
+		assert_ok!(Tasks::resolve_dispute(RuntimeOrigin::signed(OUTSIDER), rejected));
+		assert_eq!(status(rejected), TaskStatus::Completed);
+		assert_noop!(
+			Tasks::resolve_dispute(RuntimeOrigin::signed(OUTSIDER), rejected),
+			Error::<Test>::NotDisputed
+		);
+	});
+}
+
+#[test]
+fn disputes_without_jurors_by_the_draw_are_dropped() {
+	new_test_ext().execute_with(|| {
+		register_agents();
+		let task_id = submitted_task();
+		assert_ok!(Tasks::dispute(RuntimeOrigin::signed(FUNDER), task_id));
+		assert_eq!(held(FUNDER), REWARD + 20);
+
+		// Every other agent leaves before the jury is drawn.
+		for agent in AGENTS {
+			MockAgents::set_stake(agent, 0);
+		}
+		next_block();
+		System::assert_last_event(Event::DisputeDropped { task_id }.into());
+		assert!(!Disputes::<Test>::contains_key(task_id));
+		assert_eq!(held(FUNDER), REWARD);
+		assert_eq!(status(task_id), TaskStatus::Submitted { challenge_deadline: 2 });
+		assert!(MockObserver::outcomes().is_empty());
+
+		next_block();
+		assert_ok!(Tasks::finalize_task(RuntimeOrigin::signed(FUNDER), task_id));
+		assert_eq!(status(task_id), TaskStatus::Completed);
+	});
+}
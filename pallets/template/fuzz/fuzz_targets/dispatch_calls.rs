@@ -0,0 +1,29 @@
+//! Dispatch arbitrary SCALE-encoded pallet calls and check the storage indexes after each one.
+//!
+//! Run with `cargo fuzz run dispatch_calls` from `pallets/template`.
+
+#![no_main]
+
+use codec::DecodeLimit;
+use frame_support::traits::UnfilteredDispatchable;
+use libfuzzer_sys::fuzz_target;
+use pallet_template::{
+	mock::{check_indexes, new_test_ext, RuntimeOrigin, Test, ALICE, BOB},
+	Call,
+};
+
+/// Calls paired with whether they are signed by Bob instead of Alice.
+type Input = Vec<(bool, Call<Test>)>;
+
+fuzz_target!(|data: &[u8]| {
+	let Ok(calls) = Input::decode_all_with_depth_limit(8, &mut &data[..]) else { return };
+
+	new_test_ext().execute_with(|| {
+		for (by_bob, call) in calls {
+			let signer = if by_bob { BOB } else { ALICE };
+			// Failing calls are expected; only their effect on storage is checked.
+			let _ = call.dispatch_bypass_filter(RuntimeOrigin::signed(signer));
+			check_indexes();
+		}
+	});
+});
@@ -0,0 +1,106 @@
+//! # Vector Proofs
+//!
+//! Verification of vectors read from the state of the chain, for light clients and services that
+//! do not trust the node they query. Usable in `no_std`.
+//!
+//! A node answers `vector_getWithProof` with the header of a finalized block and the trie nodes on
+//! the path to the vector in the state of that block:
+//!
+//! - [`state_root`] checks the header against the hash of a block the client knows to be final,
+//!   e.g. from a GRANDPA justification, and returns the state root it commits to.
+//! - [`verify_vector`] reads the vector out of the proof against that state root. A valid proof
+//!   can also show that there is no vector with the given id.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use codec::{Compact, Decode, Encode};
+use sp_core::{
+	hashing::{blake2_128, blake2_256, twox_128},
+	H256,
+};
+use sp_runtime::traits::BlakeTwo256;
+use sp_trie::{read_trie_value, LayoutV1, StorageProof};
+
+/// Name of the template pallet in the runtime of this repository.
+pub const PALLET_NAME: &[u8] = b"TemplateModule";
+
+/// A vector as stored by the template pallet, with its bounded collections decoded as vectors.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct VectorRecord<AccountId> {
+	/// Encoded vector components.
+	pub data: Vec<u8>,
+	/// Account that stored the vector.
+	pub author: AccountId,
+	/// Ids of the tags attached to the vector.
+	pub tags: Vec<H256>,
+	/// Collection the vector belongs to.
+	pub collection: Option<u32>,
+	/// Registered model the vector was embedded with.
+	pub model: u32,
+}
+
+/// Why a proof was rejected.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProofError {
+	/// The header does not hash to the expected block hash.
+	HeaderMismatch,
+	/// The header could not be decoded.
+	InvalidHeader,
+	/// The proof lacks nodes on the path to the key, or does not match the state root.
+	InvalidProof,
+	/// The proven value is not a vector.
+	InvalidValue,
+}
+
+/// Storage key of vector `vector_id` in the state, with the template pallet named `pallet_name`.
+pub fn vector_key(pallet_name: &[u8], vector_id: &H256) -> Vec<u8> {
+	let mut key = Vec::with_capacity(16 + 16 + 16 + 32);
+	key.extend_from_slice(&twox_128(pallet_name));
+	key.extend_from_slice(&twox_128(b"Vectors"));
+	key.extend_from_slice(&blake2_128(vector_id.as_bytes()));
+	key.extend_from_slice(vector_id.as_bytes());
+	key
+}
+
+/// State root committed to by the SCALE-encoded `header`, provided it is the header of the block
+/// with hash `block_hash`.
+pub fn state_root(header: &[u8], block_hash: &H256) -> Result<H256, ProofError> {
+	if blake2_256(header) != block_hash.0 {
+		return Err(ProofError::HeaderMismatch);
+	}
+	// Headers start with the parent hash, the compact block number and the state root.
+	let mut input = header;
+	<[u8; 32]>::decode(&mut input).map_err(|_| ProofError::InvalidHeader)?;
+	<Compact<u64>>::decode(&mut input).map_err(|_| ProofError::InvalidHeader)?;
+	H256::decode(&mut input).map_err(|_| ProofError::InvalidHeader)
+}
+
+/// Value at `key` in the state with `state_root`, as shown by the trie nodes of `proof`.
+pub fn read_value(
+	state_root: &H256,
+	key: &[u8],
+	proof: Vec<Vec<u8>>,
+) -> Result<Option<Vec<u8>>, ProofError> {
+	let db = StorageProof::new(proof).into_memory_db::<BlakeTwo256>();
+	read_trie_value::<LayoutV1<BlakeTwo256>, _>(&db, state_root, key, None, None)
+		.map_err(|_| ProofError::InvalidProof)
+}
+
+/// Vector `vector_id` of the state with `state_root`, as shown by the trie nodes of `proof`.
+/// `None` if the proof shows there is no such vector.
+pub fn verify_vector<AccountId: Decode>(
+	state_root: &H256,
+	pallet_name: &[u8],
+	vector_id: &H256,
+	proof: Vec<Vec<u8>>,
+) -> Result<Option<VectorRecord<AccountId>>, ProofError> {
+	read_value(state_root, &vector_key(pallet_name, vector_id), proof)?
+		.map(|value| VectorRecord::decode(&mut &value[..]).map_err(|_| ProofError::InvalidValue))
+		.transpose()
+}
@@ -0,0 +1,87 @@
+use crate::*;
+use sp_runtime::{
+	generic::{Digest, Header},
+	traits::Header as _,
+};
+use sp_trie::{MemoryDB, TrieDBMutBuilder, TrieMut};
+
+const ID: H256 = H256::repeat_byte(1);
+
+fn record() -> VectorRecord<u64> {
+	VectorRecord {
+		data: vec![1, 2, 3, 4],
+		author: 7,
+		tags: vec![H256::repeat_byte(9)],
+		collection: None,
+		model: 1,
+	}
+}
+
+/// A state holding `entries`, with a proof made of all of its nodes.
+fn state(entries: &[(Vec<u8>, Vec<u8>)]) -> (H256, Vec<Vec<u8>>) {
+	let mut db = MemoryDB::<BlakeTwo256>::default();
+	let mut root = H256::zero();
+	{
+		let mut trie = TrieDBMutBuilder::<LayoutV1<BlakeTwo256>>::new(&mut db, &mut root).build();
+		for (key, value) in entries {
+			trie.insert(key, value).unwrap();
+		}
+	}
+	let nodes = db
+		.drain()
+		.into_values()
+		.filter(|(_, rc)| *rc > 0)
+		.map(|(node, _)| node)
+		.collect();
+	(root, nodes)
+}
+
+#[test]
+fn vectors_are_read_from_proofs() {
+	let other = H256::repeat_byte(2);
+	let (root, proof) = state(&[
+		(vector_key(PALLET_NAME, &ID), record().encode()),
+		(vector_key(PALLET_NAME, &other), vec![0xff]),
+		(b"unrelated".to_vec(), vec![0]),
+	]);
+
+	assert_eq!(verify_vector::<u64>(&root, PALLET_NAME, &ID, proof.clone()), Ok(Some(record())));
+	// A proof can show a vector is missing.
+	let missing = H256::repeat_byte(3);
+	assert_eq!(verify_vector::<u64>(&root, PALLET_NAME, &missing, proof.clone()), Ok(None));
+	assert_eq!(
+		verify_vector::<u64>(&root, PALLET_NAME, &other, proof.clone()),
+		Err(ProofError::InvalidValue)
+	);
+
+	// Proofs only hold against their state root.
+	assert_eq!(
+		verify_vector::<u64>(&H256::repeat_byte(4), PALLET_NAME, &ID, proof),
+		Err(ProofError::InvalidProof)
+	);
+	assert_eq!(
+		verify_vector::<u64>(&root, PALLET_NAME, &ID, vec![]),
+		Err(ProofError::InvalidProof)
+	);
+}
+
+#[test]
+fn headers_are_checked_against_the_block_hash() {
+	let state_root = H256::repeat_byte(5);
+	let header = Header::<u32, BlakeTwo256>::new(
+		42,
+		H256::repeat_byte(6),
+		state_root,
+		H256::repeat_byte(7),
+		Digest::default(),
+	);
+	let encoded = header.encode();
+
+	assert_eq!(crate::state_root(&encoded, &header.hash()), Ok(state_root));
+	assert_eq!(crate::state_root(&encoded, &H256::zero()), Err(ProofError::HeaderMismatch));
+	let truncated = &encoded[..40];
+	assert_eq!(
+		crate::state_root(truncated, &H256(blake2_256(truncated))),
+		Err(ProofError::InvalidHeader)
+	);
+}
@@ -0,0 +1,129 @@
+//! RPC methods serving the vectors of the template pallet.
+//!
+//! `vector_getWithProof` returns a vector together with the proof of its storage in the state of
+//! a finalized block, so that clients can check it with `pallet-template-proof` instead of
+//! trusting the node.
+
+#![warn(missing_docs)]
+
+use codec::Encode;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{ErrorObject, ErrorObjectOwned},
+};
+use sc_client_api::ProofProvider;
+use serde::{Deserialize, Serialize};
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H256};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{marker::PhantomData, sync::Arc};
+
+/// A vector with the proof of its storage.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VectorWithProof<Hash> {
+	/// Hash of the finalized block the vector was read at.
+	pub at: Hash,
+	/// SCALE-encoded header of the block, committing to its state root.
+	pub header: Bytes,
+	/// SCALE-encoded vector, `None` if there is no vector with the requested id.
+	pub value: Option<Bytes>,
+	/// Trie nodes proving `value` against the state root of `header`.
+	pub proof: Vec<Bytes>,
+}
+
+/// RPC methods serving vectors.
+#[rpc(client, server)]
+pub trait VectorApi<BlockHash> {
+	/// Vector `vector_id` with the proof of its storage in the state of finalized block `at`, by
+	/// default the last finalized block.
+	#[method(name = "vector_getWithProof")]
+	fn get_with_proof(
+		&self,
+		vector_id: H256,
+		at: Option<BlockHash>,
+	) -> RpcResult<VectorWithProof<BlockHash>>;
+}
+
+/// Error codes of the vector RPC methods.
+pub enum Error {
+	/// The block is unknown or not finalized yet.
+	NotFinalized,
+	/// The client failed to read the block or its state.
+	ClientError,
+	/// The proof produced by the client does not hold against the state root.
+	InvalidProof,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::NotFinalized => 1,
+			Error::ClientError => 2,
+			Error::InvalidProof => 3,
+		}
+	}
+}
+
+fn error(code: Error, message: impl ToString) -> ErrorObjectOwned {
+	ErrorObject::owned(code.into(), message.to_string(), None::<()>)
+}
+
+/// Serves the vectors of the template pallet named `pallet_name` in the runtime.
+pub struct Vectors<C, Block> {
+	client: Arc<C>,
+	pallet_name: &'static [u8],
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> Vectors<C, Block> {
+	/// Serve the vectors of the template pallet named `pallet_name` from the state of `client`.
+	pub fn new(client: Arc<C>, pallet_name: &'static [u8]) -> Self {
+		Self { client, pallet_name, _marker: Default::default() }
+	}
+}
+
+impl<C, Block> VectorApiServer<Block::Hash> for Vectors<C, Block>
+where
+	Block: BlockT<Hash = H256>,
+	C: HeaderBackend<Block> + ProofProvider<Block> + Send + Sync + 'static,
+{
+	fn get_with_proof(
+		&self,
+		vector_id: H256,
+		at: Option<Block::Hash>,
+	) -> RpcResult<VectorWithProof<Block::Hash>> {
+		let info = self.client.info();
+		let at = at.unwrap_or(info.finalized_hash);
+		let header = self
+			.client
+			.header(at)
+			.map_err(|e| error(Error::ClientError, e))?
+			.ok_or_else(|| error(Error::NotFinalized, "Unknown block"))?;
+		// Blocks past the last finalized one or off its chain could still be reverted.
+		let canonical =
+			self.client.hash(*header.number()).map_err(|e| error(Error::ClientError, e))?;
+		if *header.number() > info.finalized_number || canonical != Some(at) {
+			return Err(error(Error::NotFinalized, "Block is not finalized"));
+		}
+
+		let key = pallet_template_proof::vector_key(self.pallet_name, &vector_id);
+		let proof: Vec<Vec<u8>> = self
+			.client
+			.read_proof(at, &mut core::iter::once(key.as_slice()))
+			.map_err(|e| error(Error::ClientError, e))?
+			.into_iter_nodes()
+			.collect();
+		// The value is read back from the proof, so it is exactly what the proof shows.
+		let value = pallet_template_proof::read_value(header.state_root(), &key, proof.clone())
+			.map_err(|e| error(Error::InvalidProof, format!("{e:?}")))?;
+
+		Ok(VectorWithProof {
+			at,
+			header: header.encode().into(),
+			value: value.map(Into::into),
+			proof: proof.into_iter().map(Into::into).collect(),
+		})
+	}
+}
@@ -0,0 +1,258 @@
+//! Benchmarking setup for pallet-template
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Template;
+use crate::migrations::v1::{v0, Cursor, LazyMigrationV1};
+use frame_benchmarking::v2::*;
+use frame_support::{
+	migrations::SteppedMigration,
+	sp_runtime::Saturating,
+	traits::{
+		fungible::{Inspect, InspectHold, Mutate},
+		ConstU32, StorageVersion,
+	},
+	weights::WeightMeter,
+};
+use frame_system::RawOrigin;
+
+/// Vector data of `len` bytes whose content is unique for `seed`.
+fn vector_data<T: Config>(seed: u32, len: u32) -> BoundedVec<u8, T::MaxVectorLength> {
+	let seed = seed.to_le_bytes();
+	let data: Vec<u8> = (0..len).map(|i| seed[i as usize % 4] ^ (i as u8)).collect();
+	data.try_into().expect("len is within MaxVectorLength")
+}
+
+/// Tag data of maximum length that is unique for `seed`.
+fn tag_data<T: Config>(seed: u32) -> Vec<u8> {
+	let mut tag = vec![b'x'; T::MaxTagLength::get() as usize];
+	tag[..4].copy_from_slice(&seed.to_le_bytes());
+	tag
+}
+
+/// Store a vector carrying the maximum number of tags, those of seeds `first_tag` onwards,
+/// directly, bypassing every check.
+fn insert_vector<T: Config>(
+	seed: u32,
+	author: &T::AccountId,
+	collection: Option<CollectionId>,
+	model: ModelId,
+	first_tag: u32,
+	data: BoundedVec<u8, T::MaxVectorLength>,
+) -> T::Hash {
+	let tags: BoundedVec<T::Hash, T::MaxTagsPerVector> = (0..T::MaxTagsPerVector::get())
+		.map(|i| T::Hashing::hash_of(&tag_data::<T>(first_tag + i)))
+		.collect::<Vec<_>>()
+		.try_into()
+		.expect("exactly MaxTagsPerVector tags");
+	let vector_id = T::Hashing::hash_of(&(b"benchmark", seed));
+	for tag_id in tags.iter() {
+		TagVectors::<T>::insert(tag_id, vector_id, ());
+	}
+	ContentIndex::<T>::insert(T::Hashing::hash_of(&(b"content", seed)), vector_id);
+	Vectors::<T>::insert(
+		vector_id,
+		VectorRecord { data, author: author.clone(), tags, collection, model },
+	);
+	vector_id
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	// Worst case: vectors use the binary encoding, which decompresses to eight components per
+	// byte, every near-duplicate sample is compared in full and the author's vector list is one
+	// entry away from being full.
+	#[benchmark]
+	fn store_weight_data(
+		v: Linear<1, { T::MaxVectorLength::get() }>,
+		t: Linear<1, { T::MaxTagsPerVector::get() }>,
+	) {
+		let caller: T::AccountId = whitelisted_caller();
+		let collection =
+			Pallet::<T>::do_create_collection(caller.clone(), VectorEncoding::Binary, Zero::zero())
+				.expect("collection ids are available");
+		let model = T::BenchmarkHelper::register_model(v * 8);
+
+		let data = vector_data::<T>(u32::MAX, v);
+		// Every sample is the complement of the submission, so it is compared but never
+		// considered a near-duplicate.
+		let complement: BoundedVec<u8, T::MaxVectorLength> =
+			data.iter().map(|b| !b).collect::<Vec<_>>().try_into().expect("same length");
+		for i in 0..T::MaxSimilaritySamples::get() {
+			insert_vector::<T>(i, &caller, Some(collection), model, 0, complement.clone());
+		}
+		let existing = vec![T::Hash::default(); T::MaxVectors::get().saturating_sub(1) as usize];
+		AuthorVectors::<T>::insert(&caller, BoundedVec::truncate_from(existing));
+
+		let tags: Vec<Vec<u8>> = (0..t).map(tag_data::<T>).collect();
+		let weight_data = (data, caller.clone(), BoundedVec::default());
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), weight_data, tags, Some(collection), model);
+
+		assert_eq!(
+			AuthorVectors::<T>::get(&caller).map(|v| v.len() as u32),
+			Some(T::MaxVectors::get())
+		);
+	}
+
+	#[benchmark]
+	fn get_weights_by_tag() {
+		let caller: T::AccountId = whitelisted_caller();
+		let tag = tag_data::<T>(0);
+		Tags::<T>::insert(
+			T::Hashing::hash_of(&tag),
+			BoundedVec::<u8, T::MaxTagLength>::truncate_from(tag.clone()),
+		);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), tag);
+	}
+
+	#[benchmark]
+	fn get_weights_by_author() {
+		let caller: T::AccountId = whitelisted_caller();
+		let existing = vec![T::Hash::default(); T::MaxVectors::get() as usize];
+		AuthorVectors::<T>::insert(&caller, BoundedVec::truncate_from(existing));
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), caller.clone());
+	}
+
+	// Worst case: every pruned vector carries the maximum number of tags.
+	#[benchmark]
+	fn prune_weight_data(n: Linear<1, { T::MaxVectors::get() }>) {
+		let caller: T::AccountId = whitelisted_caller();
+		let ids: Vec<T::Hash> = (0..n)
+			.map(|i| {
+				let data = vector_data::<T>(i, T::MaxVectorLength::get());
+				insert_vector::<T>(i, &caller, None, 0, 0, data)
+			})
+			.collect();
+		AuthorVectors::<T>::insert(&caller, BoundedVec::truncate_from(ids.clone()));
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), ids);
+
+		assert_eq!(Vectors::<T>::iter_keys().count(), 0);
+		assert!(!AuthorVectors::<T>::contains_key(&caller));
+	}
+
+	#[benchmark]
+	fn create_collection() {
+		let caller: T::AccountId = whitelisted_caller();
+		let deposit = T::CollectionDeposit::get();
+		T::Currency::set_balance(
+			&caller,
+			T::Currency::minimum_balance().saturating_add(deposit.saturating_mul(2u32.into())),
+		);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), VectorEncoding::Int8 { scale: 1 << 16, offset: 0 });
+
+		assert_eq!(NextCollectionId::<T>::get(), 1);
+		assert_eq!(
+			T::Currency::balance_on_hold(&HoldReason::CollectionDeposit.into(), &caller),
+			deposit
+		);
+	}
+
+	// Worst case: every item is the worst case of `store_weight_data`, with tags of its own so
+	// that items are only compared with the samples planted for them. The `b` bytes are spread
+	// evenly over the `n` items.
+	#[benchmark]
+	fn store_batch(
+		n: Linear<1, { T::MaxBatchSize::get() }>,
+		b: Linear<1, { T::MaxBatchSize::get() * T::MaxVectorLength::get() }>,
+	) {
+		let caller: T::AccountId = whitelisted_caller();
+		let collection =
+			Pallet::<T>::do_create_collection(caller.clone(), VectorEncoding::Binary, Zero::zero())
+				.expect("collection ids are available");
+		let len = (b / n).clamp(1, T::MaxVectorLength::get());
+		let model = T::BenchmarkHelper::register_model(len * 8);
+
+		let samples = T::MaxSimilaritySamples::get();
+		let tags_per_item = T::MaxTagsPerVector::get();
+		let items: Vec<BatchItem<T>> = (0..n)
+			.map(|i| {
+				let data = vector_data::<T>(u32::MAX - i, len);
+				let complement: BoundedVec<u8, T::MaxVectorLength> =
+					data.iter().map(|b| !b).collect::<Vec<_>>().try_into().expect("same length");
+				let first_tag = i * tags_per_item;
+				for s in 0..samples {
+					let seed = i * samples + s;
+					let sample = complement.clone();
+					insert_vector::<T>(seed, &caller, Some(collection), model, first_tag, sample);
+				}
+				let tags = (first_tag..first_tag + tags_per_item).map(tag_data::<T>).collect();
+				BatchItem { data, tags, collection: Some(collection), model }
+			})
+			.collect();
+		let existing = vec![T::Hash::default(); T::MaxVectors::get().saturating_sub(n) as usize];
+		AuthorVectors::<T>::insert(&caller, BoundedVec::truncate_from(existing));
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Signed(caller.clone()),
+			BoundedVec::truncate_from(items),
+			BatchMode::AllOrNothing,
+		);
+
+		assert_eq!(
+			AuthorVectors::<T>::get(&caller).map(|v| v.len() as u32),
+			Some(T::MaxVectors::get())
+		);
+	}
+
+	#[benchmark]
+	fn migration_step() {
+		let caller: T::AccountId = whitelisted_caller();
+		let key = T::Hashing::hash_of(&0u32);
+		let tags: BoundedVec<T::Hash, T::MaxTagsPerVector> =
+			BoundedVec::truncate_from(vec![T::Hash::default(); T::MaxTagsPerVector::get() as usize]);
+		v0::Vectors::<T>::insert(
+			key,
+			(vector_data::<T>(0, T::MaxVectorLength::get()), caller, tags),
+		);
+		StorageVersion::new(0).put::<Pallet<T>>();
+		let mut meter = WeightMeter::new();
+
+		#[block]
+		{
+			LazyMigrationV1::<T, weights::SubstrateWeight<T>, ConstU32<7>>::step(None, &mut meter)
+				.unwrap();
+		}
+
+		assert_eq!(Vectors::<T>::get(key).map(|r| r.model), Some(7));
+		assert!(TagVectors::<T>::contains_key(T::Hash::default(), key));
+	}
+
+	// Worst case: the author lists the maximum number of vectors, which were all pruned.
+	#[benchmark]
+	fn migration_author_step() {
+		let caller: T::AccountId = whitelisted_caller();
+		let vector_ids: BoundedVec<T::Hash, T::MaxVectors> = BoundedVec::truncate_from(
+			(0..T::MaxVectors::get()).map(|i| T::Hashing::hash_of(&i)).collect(),
+		);
+		AuthorVectors::<T>::insert(&caller, vector_ids);
+		StorageVersion::new(0).put::<Pallet<T>>();
+		let mut meter = WeightMeter::new();
+
+		#[block]
+		{
+			LazyMigrationV1::<T, weights::SubstrateWeight<T>, ConstU32<7>>::step(
+				Some(Cursor::Authors(None)),
+				&mut meter,
+			)
+			.unwrap();
+		}
+
+		assert!(!AuthorVectors::<T>::contains_key(&caller));
+	}
+
+	impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -44,37 +44,117 @@ pub use pallet::*;
 
 // FRAME pallets require their own "mock runtimes" to be able to run unit tests. This module
 // contains a mock runtime specific for testing this pallet's functionality.
-#[cfg(test)]
-mod mock;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod mock;
 
 // This module contains the unit tests for this pallet.
 // Learn about pallet unit testing here: https://docs.substrate.io/test/unit-testing/
 #[cfg(test)]
 mod tests;
 
+// Property-based tests checking the pallet against a reference model of its storage.
+#[cfg(test)]
+mod model_tests;
+
 // Every callable function or "dispatchable" a pallet exposes must have weight values that correctly
 // estimate a dispatchable's execution time. The benchmarking module is used to calculate weights
 // for each dispatchable and generates this pallet's weight.rs file. Learn more about benchmarking here: https://docs.substrate.io/test/benchmark/
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
+pub mod quantization;
+pub mod similarity;
 pub mod weights;
+pub use quantization::{VectorEncoding, EPHEMERAL_KEY_LEN, KEY_PROOF_LEN};
 use weights::WeightInfo;
-use frame_support::sp_runtime::traits::Hash;
+use frame_support::sp_runtime::traits::{Hash, Zero};
+use frame_support::sp_runtime::Perbill;
 use frame_support::BoundedVec;
 use scale_info::prelude::vec;
 use crate::vec::Vec;
+
+/// Notified when governance prunes memories, e.g. to keep track of their authors' record.
+/// Authors pruning their own memories are not reported.
+pub trait MemoryObserver<AccountId> {
+	/// A vector authored by `author` was pruned by [`Config::PruneOrigin`].
+	fn on_memory_pruned(author: &AccountId);
+}
+
+impl<AccountId> MemoryObserver<AccountId> for () {
+	fn on_memory_pruned(_: &AccountId) {}
+}
+
+/// Identifier of an embedding model.
+pub type ModelId = u32;
+
+/// Checks that whoever stores an encrypted vector encrypted it, e.g. against the scheme of a
+/// threshold encryption committee. Otherwise anyone could store a copy of a ciphertext, or one
+/// under a related ephemeral key, and have it decrypted as its owner.
+///
+/// Encrypted vectors skip the near-duplicate check, which costs more than verifying a proof, so
+/// the weight of storing a vector covers it.
+pub trait EphemeralKeyProof<AccountId> {
+	/// Whether `proof` proves that `author` knows the secret of `ephemeral_key` and encrypted
+	/// the vector for `collection`.
+	fn verify(
+		author: &AccountId,
+		collection: CollectionId,
+		ephemeral_key: &[u8; EPHEMERAL_KEY_LEN],
+		proof: &[u8; KEY_PROOF_LEN],
+	) -> bool;
+}
+
+/// Rejects every proof, for chains that cannot decrypt vectors.
+impl<AccountId> EphemeralKeyProof<AccountId> for () {
+	fn verify(
+		_: &AccountId,
+		_: CollectionId,
+		_: &[u8; EPHEMERAL_KEY_LEN],
+		_: &[u8; KEY_PROOF_LEN],
+	) -> bool {
+		false
+	}
+}
+
+/// Embedding models vectors may reference, e.g. the models approved by governance in a registry.
+pub trait EmbeddingModels {
+	/// Number of dimensions of the embeddings of `model`, or `None` if new vectors may not
+	/// reference it.
+	fn dimension(model: ModelId) -> Option<u32>;
+}
+
+/// Registers the models referenced by the vectors of the benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper {
+	/// Register a model embedding into `dimension` dimensions and return its id.
+	fn register_model(dimension: u32) -> ModelId;
+}
 // All pallet logic is defined in its own module and must be annotated by the `pallet` attribute.
 #[frame_support::pallet]
 pub mod pallet {
 	// Import various useful types required by all FRAME pallets.
 	use super::*;
-	use frame_support::pallet_prelude::*;
+	use frame_support::{
+		pallet_prelude::*,
+		storage::with_storage_layer,
+		traits::fungible::{Inspect, Mutate, MutateHold},
+	};
 	use frame_system::pallet_prelude::*;
-	
+
+	/// Balance of the currency collection deposits are held in.
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// The in-code storage version.
+	///
+	/// Bump this whenever the layout of stored data changes and add a migration to
+	/// [`crate::migrations`].
+	pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
 	// The `Pallet` struct serves as a placeholder to implement traits, methods and dispatchables
 	// (`Call`s) in this pallet.
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// The pallet's configuration trait.
@@ -88,19 +168,77 @@ pub mod pallet {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		/// A type representing the weights required by the dispatchables of this pallet.
 		type WeightInfo: WeightInfo;
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
+		/// Currency collection deposits are held in.
+		type Currency: MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>
+			+ Mutate<Self::AccountId>;
+		/// Deposit held from the owner of each collection created with
+		/// [`Pallet::create_collection`], so that collection ids cannot be exhausted for free.
+		#[pallet::constant]
+		type CollectionDeposit: Get<BalanceOf<Self>>;
 		/// The maximum number of vectors that can be stored in the pallet.
+		///
+		/// This bounds storage and caps [`Config::VectorsPerAuthor`].
 		#[pallet::constant]
 		type MaxVectors: Get<u32>;
 		/// Maximum length for vector data
+		///
+		/// This bounds storage and caps [`Config::VectorLength`].
 		#[pallet::constant]
 		type MaxVectorLength: Get<u32>;
 		/// Maximum length for tag data
+		///
+		/// This bounds storage and caps [`Config::TagLength`].
 		#[pallet::constant]
 		type MaxTagLength: Get<u32>;
 		/// Maximum number of tags per vector 
+		///
+		/// This bounds storage and caps [`Config::TagsPerVector`].
 		#[pallet::constant]
 		type MaxTagsPerVector: Get<u32>;
+		/// Number of vectors an author may hold. Unlike [`Config::MaxVectors`], it can be
+		/// tuned without a runtime upgrade.
+		type VectorsPerAuthor: Get<u32>;
+		/// Length of the longest vector data accepted, in bytes. Unlike
+		/// [`Config::MaxVectorLength`], it can be tuned without a runtime upgrade.
+		type VectorLength: Get<u32>;
+		/// Length of the longest tag accepted, in bytes. Unlike [`Config::MaxTagLength`], it can
+		/// be tuned without a runtime upgrade.
+		type TagLength: Get<u32>;
+		/// Number of tags a vector may carry. Unlike [`Config::MaxTagsPerVector`], it can be
+		/// tuned without a runtime upgrade.
+		type TagsPerVector: Get<u32>;
+		/// Cosine similarity at or above which a submission is rejected as a near-duplicate of a
+		/// vector sharing one of its tags. `None` disables near-duplicate detection.
+		#[pallet::constant]
+		type SimilarityThreshold: Get<Option<Perbill>>;
+		/// Maximum number of existing vectors compared against a submission when checking for
+		/// near-duplicates.
+		#[pallet::constant]
+		type MaxSimilaritySamples: Get<u32>;
+		/// Maximum number of vectors submitted at once with [`Pallet::store_batch`].
+		#[pallet::constant]
+		type MaxBatchSize: Get<u32>;
+		/// Origin allowed to prune the vectors of any author. Other accounts can only prune their
+		/// own.
+		type PruneOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Notified of vectors pruned by [`Config::PruneOrigin`].
+		type Observer: MemoryObserver<<Self as frame_system::Config>::AccountId>;
+		/// Models the vectors are embedded with.
+		type Models: EmbeddingModels;
+		/// Verifies the proof of knowledge of the ephemeral secret of encrypted vectors.
+		type EphemeralKeys: EphemeralKeyProof<<Self as frame_system::Config>::AccountId>;
+		/// Registers the models referenced by the vectors of the benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper;
+	}
 
+	/// Reasons for the pallet to hold funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// Deposit of a collection, held from its owner.
+		CollectionDeposit,
 	}
 
 	/// Events that functions in this pallet can emit.
@@ -126,6 +264,68 @@ pub mod pallet {
 		VectorsPruned {
 			count: u32,
 		},
+		/// Collection created
+		CollectionCreated {
+			collection_id: CollectionId,
+			owner: <T as frame_system::Config>::AccountId,
+			encoding: VectorEncoding,
+			deposit: BalanceOf<T>,
+		},
+		/// Vector pruned, emitted for each vector before the `VectorsPruned` summary
+		VectorPruned {
+			vector_id: T::Hash,
+			author: <T as frame_system::Config>::AccountId,
+		},
+		/// Batch stored, emitted after the `VectorStored` of each of its vectors with the id of
+		/// every item, or `None` for the items a best-effort batch skipped
+		BatchStored {
+			author: <T as frame_system::Config>::AccountId,
+			vector_ids: BoundedVec<Option<T::Hash>, T::MaxBatchSize>,
+		},
+	}
+
+	/// Memory the chain starts with.
+	///
+	/// Collections are created in order, so the first entry receives collection id `0`, and
+	/// without a deposit. Vectors
+	/// are stored exactly as if they had been submitted through [`Pallet::store_weight_data`], so
+	/// their models must be registered by the genesis config of the pallet providing
+	/// [`Config::Models`].
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// Collections to create, as `(owner, encoding)`.
+		pub collections: Vec<(<T as frame_system::Config>::AccountId, VectorEncoding)>,
+		/// Vectors to store, as `(author, collection, model, data, tags)`.
+		pub vectors: Vec<(
+			<T as frame_system::Config>::AccountId,
+			Option<CollectionId>,
+			ModelId,
+			Vec<u8>,
+			Vec<Vec<u8>>,
+		)>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			for (owner, encoding) in &self.collections {
+				Pallet::<T>::do_create_collection(owner.clone(), *encoding, Zero::zero())
+					.expect("genesis collections must be valid");
+			}
+			for (author, collection, model, data, tags) in &self.vectors {
+				let data = BoundedVec::try_from(data.clone())
+					.expect("genesis vector exceeds MaxVectorLength");
+				Pallet::<T>::do_store_vector(
+					author.clone(),
+					data,
+					tags.clone(),
+					*collection,
+					*model,
+				)
+				.expect("genesis vectors must be valid");
+			}
+		}
 	}
 
 	/// Errors that can be returned by this pallet.
@@ -150,6 +350,38 @@ pub mod pallet {
 		MaxVectorsReached,
 		/// Invalid vector data
 		InvalidVectorData,
+		/// More tags than `MaxTagsPerVector` were supplied
+		TooManyTags,
+		/// A vector with identical data is already stored
+		DuplicateVector,
+		/// The vector is too similar to an existing vector sharing one of its tags
+		NearDuplicateVector,
+		/// Collection not found
+		CollectionNotFound,
+		/// No more collection ids are available
+		CollectionIdOverflow,
+		/// The model is not registered, or retired
+		ModelNotFound,
+		/// The vector does not have the dimension of its model
+		DimensionMismatch,
+		/// The batch has no items
+		EmptyBatch,
+		/// The parameters of the encoding cannot represent vectors
+		InvalidEncoding,
+		/// Only the author of a vector can prune it
+		NotAuthor,
+		/// The author of an encrypted vector did not prove knowledge of its ephemeral secret
+		InvalidKeyProof,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		#[cfg(feature = "try-runtime")]
+		fn try_state(
+			_n: BlockNumberFor<T>,
+		) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
 	}
 
 	/// The pallet's dispatchable functions ([`Call`]s).
@@ -176,6 +408,8 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			weight_data: WeightData<T>,
 			tags: Vec<Vec<u8>>,
+			collection: Option<CollectionId>,
+			model: ModelId,
 		) -> DispatchResult {
 			let author = ensure_signed(origin)?;
 			
@@ -185,47 +419,12 @@ pub mod pallet {
 				Error::<T>::InvalidVectorData
 			);
 
-			// Generate vector ID
-			let vector_id = T::Hashing::hash_of(&weight_data);
-			
-			// Process tags
-			let mut tag_refs: BoundedVec<T::Hash, T::MaxTagsPerVector> = 
-				BoundedVec::try_from(Vec::new())
-				.expect("Empty vec should always fit bounds");
-
-			for tag_data in tags {
-				let tag_id = T::Hashing::hash_of(&tag_data);
-				let bounded_tag = BoundedVec::<u8, T::MaxTagLength>::try_from(tag_data)
-					.map_err(|_| Error::<T>::TagTooLong)?;
-				Tags::<T>::insert(tag_id, bounded_tag);
-				tag_refs.try_push(tag_id)
-					.map_err(|_| Error::<T>::TagTooLong)?;
-			}
-			
-			// Store vector
-			Vectors::<T>::insert(vector_id, weight_data);
-			
-			// Update author's vector list
-			AuthorVectors::<T>::try_mutate(author.clone(), |vectors| -> Result<(), DispatchError> {
-				match vectors {
-					Some(v) => {
-						v.try_push(vector_id)
-							.map_err(|_| Error::<T>::VectorNotFound)?;
-					},
-					None => {
-						*vectors = Some(BoundedVec::try_from(vec![vector_id])
-							.map_err(|_| Error::<T>::VectorNotFound)?);
-					},
-				}
-				Ok(())
-			})?;
-
-			Self::deposit_event(Event::VectorStored { vector_id, author });
+			Self::do_store_vector(author, weight_data.0, tags, collection, model)?;
 			Ok(())
 		}
 
 		#[pallet::call_index(1)]
-		#[pallet::weight(T::WeightInfo::get_weights_by_tag(1))]
+		#[pallet::weight(T::WeightInfo::get_weights_by_tag())]
 		pub fn get_weights_by_tag(
 			origin: OriginFor<T>,
 			tag_data: Vec<u8>,
@@ -239,7 +438,7 @@ pub mod pallet {
 		}
 
 		#[pallet::call_index(2)]
-		#[pallet::weight(T::WeightInfo::get_weights_by_author(1))]
+		#[pallet::weight(T::WeightInfo::get_weights_by_author())]
 		pub fn get_weights_by_author(
 			origin: OriginFor<T>,
 			author: <T as frame_system::Config>::AccountId,
@@ -252,18 +451,44 @@ pub mod pallet {
 		}
 
 		#[pallet::call_index(3)]
-		#[pallet::weight(T::WeightInfo::prune_weight_data(1))]
+		#[pallet::weight(T::WeightInfo::prune_weight_data(vector_ids.len() as u32))]
 		pub fn prune_weight_data(
 			origin: OriginFor<T>,
 			vector_ids: Vec<T::Hash>,
 		) -> DispatchResult {
-			let _caller = ensure_signed(origin)?;
+			// `None` when governance prunes, whatever the author.
+			let caller = match T::PruneOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
 			
 			ensure!(!vector_ids.is_empty(), Error::<T>::NothingToPrune);
 			
 			let mut pruned = 0;
 			for id in vector_ids {
-				if Vectors::<T>::take(id).is_some() {
+				if let Some(record) = Vectors::<T>::take(id) {
+					if let Some(caller) = &caller {
+						ensure!(record.author == *caller, Error::<T>::NotAuthor);
+					}
+					ContentIndex::<T>::remove(Self::content_key(record.collection, &record.data));
+					for tag_id in record.tags.iter() {
+						TagVectors::<T>::remove(tag_id, id);
+					}
+					AuthorVectors::<T>::mutate_exists(&record.author, |vectors| {
+						if let Some(v) = vectors {
+							v.retain(|vector_id| vector_id != &id);
+							if v.is_empty() {
+								*vectors = None;
+							}
+						}
+					});
+					if caller.is_none() {
+						T::Observer::on_memory_pruned(&record.author);
+					}
+					Self::deposit_event(Event::VectorPruned {
+						vector_id: id,
+						author: record.author,
+					});
 					pruned += 1;
 				}
 			}
@@ -271,6 +496,399 @@ pub mod pallet {
 			Self::deposit_event(Event::VectorsPruned { count: pruned });
 			Ok(())
 		}
+
+		/// Create a collection whose vectors are stored using `encoding`.
+		///
+		/// Quantized encodings let a collection fit several times more dimensions into
+		/// [`Config::MaxVectorLength`] bytes than plain `f32` components.
+		///
+		/// [`Config::CollectionDeposit`] is held from the owner for as long as the collection
+		/// exists.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::create_collection())]
+		pub fn create_collection(
+			origin: OriginFor<T>,
+			encoding: VectorEncoding,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+
+			Self::do_create_collection(owner, encoding, T::CollectionDeposit::get())?;
+			Ok(())
+		}
+
+		/// Store several vectors at once, each as [`Pallet::store_weight_data`] would.
+		///
+		/// With [`BatchMode::AllOrNothing`], the first item that cannot be stored fails the whole
+		/// batch. With [`BatchMode::BestEffort`], such items are skipped and the others stored.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::store_batch(
+			items.len() as u32,
+			items.iter().map(|item| item.data.len() as u32).sum()
+		))]
+		pub fn store_batch(
+			origin: OriginFor<T>,
+			items: BoundedVec<BatchItem<T>, T::MaxBatchSize>,
+			mode: BatchMode,
+		) -> DispatchResult {
+			let author = ensure_signed(origin)?;
+
+			ensure!(!items.is_empty(), Error::<T>::EmptyBatch);
+
+			let vector_ids = items
+				.into_iter()
+				.map(|BatchItem { data, tags, collection, model }| {
+					let store =
+						|| Self::do_store_vector(author.clone(), data, tags, collection, model);
+					match mode {
+						BatchMode::AllOrNothing => store().map(Some),
+						// A storage layer of its own lets a skipped item leave nothing behind,
+						// such as the tags it introduced.
+						BatchMode::BestEffort => Ok(with_storage_layer(store).ok()),
+					}
+				})
+				.collect::<Result<Vec<_>, DispatchError>>()?;
+
+			Self::deposit_event(Event::BatchStored {
+				author,
+				vector_ids: BoundedVec::truncate_from(vector_ids),
+			});
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Store `data`, an embedding by `model`, on behalf of `author`, maintaining every index.
+		///
+		/// Shared by [`Pallet::store_weight_data`] and the genesis build.
+		pub fn do_store_vector(
+			author: <T as frame_system::Config>::AccountId,
+			data: BoundedVec<u8, T::MaxVectorLength>,
+			tags: Vec<Vec<u8>>,
+			collection: Option<CollectionId>,
+			model: ModelId,
+		) -> Result<T::Hash, DispatchError> {
+			// Vectors outside of a collection are stored as plain `f32` components
+			let encoding = match collection {
+				Some(id) => {
+					Collections::<T>::get(id).ok_or(Error::<T>::CollectionNotFound)?.encoding
+				},
+				None => VectorEncoding::default(),
+			};
+			ensure!(encoding.is_valid(&data), Error::<T>::InvalidVectorData);
+			ensure!(data.len() as u32 <= Self::vector_length(), Error::<T>::InvalidVectorData);
+			if let (Some((key, proof)), Some(id)) = (encoding.ephemeral_key(&data), collection) {
+				ensure!(
+					T::EphemeralKeys::verify(&author, id, &key, &proof),
+					Error::<T>::InvalidKeyProof
+				);
+			}
+
+			// Embeddings of different models are never mixed. Ciphertexts hide their dimension,
+			// so encrypted vectors are taken at their word.
+			let dimension = T::Models::dimension(model).ok_or(Error::<T>::ModelNotFound)?;
+			ensure!(
+				encoding.dimension(&data).map_or(true, |d| d == dimension),
+				Error::<T>::DimensionMismatch
+			);
+
+			// Reject exact duplicates of data already stored in the collection
+			let content_key = Self::content_key(collection, &data);
+			ensure!(!ContentIndex::<T>::contains_key(content_key), Error::<T>::DuplicateVector);
+
+			// Process tags
+			let mut tag_refs: BoundedVec<T::Hash, T::MaxTagsPerVector> = 
+				BoundedVec::try_from(Vec::new())
+				.expect("Empty vec should always fit bounds");
+
+			ensure!(tags.len() as u32 <= Self::tags_per_vector(), Error::<T>::TooManyTags);
+			for tag_data in tags {
+				ensure!(tag_data.len() as u32 <= Self::tag_length(), Error::<T>::TagTooLong);
+				let tag_id = T::Hashing::hash_of(&tag_data);
+				let bounded_tag = BoundedVec::<u8, T::MaxTagLength>::try_from(tag_data)
+					.map_err(|_| Error::<T>::TagTooLong)?;
+				Tags::<T>::insert(tag_id, bounded_tag);
+				tag_refs.try_push(tag_id)
+					.map_err(|_| Error::<T>::TooManyTags)?;
+			}
+
+			Self::ensure_not_near_duplicate(&data, &tag_refs, collection, model, encoding)?;
+
+			// Store vector together with the signer and the resolved tag ids, so that the
+			// record always agrees with the tag index. The same data may be stored in several
+			// collections, so the collection is part of the id.
+			let vector_id = T::Hashing::hash_of(&(&data, &author, &tag_refs, collection));
+			for tag_id in tag_refs.iter() {
+				TagVectors::<T>::insert(tag_id, vector_id, ());
+			}
+			let record =
+				VectorRecord { data, author: author.clone(), tags: tag_refs, collection, model };
+			ContentIndex::<T>::insert(content_key, vector_id);
+			Vectors::<T>::insert(vector_id, record);
+		
+			// Update author's vector list
+			AuthorVectors::<T>::try_mutate(author.clone(), |vectors| -> Result<(), DispatchError> {
+				let vectors = vectors.get_or_insert_with(Default::default);
+				ensure!(
+					(vectors.len() as u32) < Self::vectors_per_author(),
+					Error::<T>::MaxVectorsReached
+				);
+				vectors.try_push(vector_id)
+					.map_err(|_| Error::<T>::MaxVectorsReached)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::VectorStored { vector_id, author });
+			Ok(vector_id)
+		}
+
+		/// Number of vectors an author may hold: [`Config::VectorsPerAuthor`], capped by
+		/// [`Config::MaxVectors`].
+		pub fn vectors_per_author() -> u32 {
+			T::VectorsPerAuthor::get().min(T::MaxVectors::get())
+		}
+
+		/// Length of the longest vector data accepted: [`Config::VectorLength`], capped by
+		/// [`Config::MaxVectorLength`].
+		pub fn vector_length() -> u32 {
+			T::VectorLength::get().min(T::MaxVectorLength::get())
+		}
+
+		/// Length of the longest tag accepted: [`Config::TagLength`], capped by
+		/// [`Config::MaxTagLength`].
+		pub fn tag_length() -> u32 {
+			T::TagLength::get().min(T::MaxTagLength::get())
+		}
+
+		/// Number of tags a vector may carry: [`Config::TagsPerVector`], capped by
+		/// [`Config::MaxTagsPerVector`].
+		pub fn tags_per_vector() -> u32 {
+			T::TagsPerVector::get().min(T::MaxTagsPerVector::get())
+		}
+
+		/// Owner and ephemeral public key of an encrypted vector.
+		///
+		/// Returns `None` unless `vector_id` is stored in a collection using
+		/// [`VectorEncoding::Encrypted`].
+		pub fn encrypted_vector(
+			vector_id: &T::Hash,
+		) -> Option<(<T as frame_system::Config>::AccountId, [u8; EPHEMERAL_KEY_LEN])> {
+			let record = Vectors::<T>::get(vector_id)?;
+			let info = Collections::<T>::get(record.collection?)?;
+			if info.encoding != VectorEncoding::Encrypted {
+				return None;
+			}
+			let key = record.data.get(..EPHEMERAL_KEY_LEN)?.try_into().ok()?;
+			Some((record.author, key))
+		}
+
+		/// Ids of every vector stored in an encrypted collection.
+		///
+		/// Iterates over all vectors, so it is only meant to be called off chain.
+		pub fn encrypted_vectors() -> Vec<T::Hash> {
+			let encrypted: Vec<CollectionId> = Collections::<T>::iter()
+				.filter(|(_, info)| info.encoding == VectorEncoding::Encrypted)
+				.map(|(collection_id, _)| collection_id)
+				.collect();
+			Vectors::<T>::iter()
+				.filter(|(_, record)| record.collection.is_some_and(|c| encrypted.contains(&c)))
+				.map(|(vector_id, _)| vector_id)
+				.collect()
+		}
+
+		/// Create a collection owned by `owner`, holding `deposit` from them, and return its id.
+		pub fn do_create_collection(
+			owner: <T as frame_system::Config>::AccountId,
+			encoding: VectorEncoding,
+			deposit: BalanceOf<T>,
+		) -> Result<CollectionId, DispatchError> {
+			ensure!(encoding.is_well_formed(), Error::<T>::InvalidEncoding);
+			let collection_id = NextCollectionId::<T>::get();
+			let next = collection_id.checked_add(1).ok_or(Error::<T>::CollectionIdOverflow)?;
+			if !deposit.is_zero() {
+				T::Currency::hold(&HoldReason::CollectionDeposit.into(), &owner, deposit)?;
+			}
+			NextCollectionId::<T>::put(next);
+			Collections::<T>::insert(
+				collection_id,
+				CollectionInfo { owner: owner.clone(), encoding, deposit },
+			);
+
+			Self::deposit_event(Event::CollectionCreated {
+				collection_id,
+				owner,
+				encoding,
+				deposit,
+			});
+			Ok(collection_id)
+		}
+
+		/// Key of `data` in [`ContentIndex`] when stored in `collection`.
+		///
+		/// Duplicates are only rejected within a collection, so that a collection owner cannot
+		/// keep data out of every other collection by storing it first.
+		pub fn content_key(collection: Option<CollectionId>, data: &[u8]) -> T::Hash {
+			T::Hashing::hash_of(&(collection, data))
+		}
+
+		/// Reject `data` if it is too similar to a vector of the same collection and model sharing
+		/// one of `tag_refs`.
+		///
+		/// At most [`Config::MaxSimilaritySamples`] existing vectors are compared, so the cost of
+		/// the check stays bounded no matter how crowded a tag is. Quantized vectors are
+		/// decompressed before being compared.
+		fn ensure_not_near_duplicate(
+			data: &[u8],
+			tag_refs: &[T::Hash],
+			collection: Option<CollectionId>,
+			model: ModelId,
+			encoding: VectorEncoding,
+		) -> DispatchResult {
+			let Some(threshold) = T::SimilarityThreshold::get() else { return Ok(()) };
+
+			// Only encrypted vectors cannot be decoded once validated.
+			let Some(candidate) = encoding.decode(data) else { return Ok(()) };
+			let mut remaining = T::MaxSimilaritySamples::get();
+
+			for tag_id in tag_refs {
+				for other_id in TagVectors::<T>::iter_key_prefix(tag_id) {
+					if remaining == 0 {
+						return Ok(());
+					}
+					remaining -= 1;
+
+					let Some(other) = Vectors::<T>::get(other_id) else { continue };
+					if other.collection != collection || other.model != model {
+						continue;
+					}
+					let Some(other) = encoding.decode(&other.data) else { continue };
+					if similarity::cosine_similarity(&candidate, &other)
+						.map_or(false, |s| s >= threshold)
+					{
+						return Err(Error::<T>::NearDuplicateVector.into());
+					}
+				}
+			}
+
+			Ok(())
+		}
+
+		/// Check that every index agrees with `Vectors`.
+		///
+		/// * every id listed in `AuthorVectors` is a vector of that author, and no list is empty
+		///   or longer than [`Config::MaxVectors`];
+		/// * every vector is listed for its author, its tags exist in `Tags` and index it in
+		///   `TagVectors`, and `ContentIndex` maps its collection and data back to it;
+		/// * every `TagVectors` and `ContentIndex` entry points to an existing vector;
+		/// * every collection owner has exactly the deposits of their collections on hold.
+		#[cfg(any(feature = "try-runtime", feature = "fuzzing", test))]
+		pub fn do_try_state() -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+			use frame_support::{sp_runtime::Saturating, traits::fungible::InspectHold};
+			use scale_info::prelude::collections::BTreeMap;
+
+			let mut listed = 0usize;
+			for (author, vector_ids) in AuthorVectors::<T>::iter() {
+				ensure!(!vector_ids.is_empty(), "empty author list left behind");
+				ensure!(
+					vector_ids.len() as u32 <= T::MaxVectors::get(),
+					"author holds more than MaxVectors vectors"
+				);
+				for vector_id in vector_ids.iter() {
+					let record =
+						Vectors::<T>::get(vector_id).ok_or("author list points to a missing vector")?;
+					ensure!(record.author == author, "vector listed under the wrong author");
+				}
+				listed = listed.saturating_add(vector_ids.len());
+			}
+
+			let mut stored = 0usize;
+			for (vector_id, record) in Vectors::<T>::iter() {
+				ensure!(
+					AuthorVectors::<T>::get(&record.author)
+						.map_or(false, |ids| ids.contains(&vector_id)),
+					"vector missing from its author's list"
+				);
+				for tag_id in record.tags.iter() {
+					ensure!(Tags::<T>::contains_key(tag_id), "vector references an unknown tag");
+					ensure!(
+						TagVectors::<T>::contains_key(tag_id, vector_id),
+						"vector missing from the tag index"
+					);
+				}
+				ensure!(
+					ContentIndex::<T>::get(Self::content_key(record.collection, &record.data)) ==
+						Some(vector_id),
+					"vector missing from the content index"
+				);
+				stored = stored.saturating_add(1);
+			}
+			ensure!(listed == stored, "author lists disagree with the stored vectors");
+
+			for (tag_id, vector_id, ()) in TagVectors::<T>::iter() {
+				let record =
+					Vectors::<T>::get(vector_id).ok_or("tag index points to a missing vector")?;
+				ensure!(record.tags.contains(&tag_id), "tag index disagrees with the vector record");
+			}
+			for vector_id in ContentIndex::<T>::iter_values() {
+				ensure!(
+					Vectors::<T>::contains_key(vector_id),
+					"content index points to a missing vector"
+				);
+			}
+			let reason: T::RuntimeHoldReason = HoldReason::CollectionDeposit.into();
+			let mut deposits = BTreeMap::<_, BalanceOf<T>>::new();
+			for (_, info) in Collections::<T>::iter() {
+				let total = deposits.entry(info.owner).or_default();
+				*total = total.saturating_add(info.deposit);
+			}
+			for (owner, total) in deposits {
+				ensure!(
+					<T::Currency as InspectHold<_>>::balance_on_hold(&reason, &owner) == total,
+					"collection deposits disagree with the held balance"
+				);
+			}
+
+			Ok(())
+		}
+	}
+
+	/// Identifier of a collection of vectors.
+	pub type CollectionId = u32;
+
+	/// Metadata of a collection.
+	#[derive(Clone, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug)]
+	pub struct CollectionInfo<AccountId, Balance> {
+		/// Account that created the collection.
+		pub owner: AccountId,
+		/// Encoding used by every vector of the collection.
+		pub encoding: VectorEncoding,
+		/// Deposit held from the owner.
+		pub deposit: Balance,
+	}
+
+	/// A vector submitted with [`Pallet::store_batch`], made of the arguments
+	/// [`Pallet::store_weight_data`] takes.
+	#[derive(
+		CloneNoBound, Encode, Decode, TypeInfo, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	pub struct BatchItem<T: Config> {
+		/// Encoded vector components.
+		pub data: BoundedVec<u8, T::MaxVectorLength>,
+		/// Tags to attach to the vector.
+		pub tags: Vec<Vec<u8>>,
+		/// Collection to store the vector in, if any.
+		pub collection: Option<CollectionId>,
+		/// Model the vector is an embedding by.
+		pub model: ModelId,
+	}
+
+	/// How a batch treats the items that cannot be stored.
+	#[derive(Clone, Copy, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug)]
+	pub enum BatchMode {
+		/// Store every item or none of them.
+		AllOrNothing,
+		/// Store the items that can be stored and skip the others.
+		BestEffort,
 	}
 
 	// Define a type alias for the vector data structure
@@ -280,12 +898,33 @@ pub mod pallet {
 		BoundedVec<<T as frame_system::Config>::Hash, <T as Config>::MaxTagsPerVector>
 	);
 
+	/// A stored vector together with the data needed to keep the indexes consistent.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct VectorRecord<T: Config> {
+		/// Encoded vector components.
+		pub data: BoundedVec<u8, T::MaxVectorLength>,
+		/// Account that stored the vector.
+		pub author: <T as frame_system::Config>::AccountId,
+		/// Ids of the tags attached to the vector.
+		pub tags: BoundedVec<T::Hash, T::MaxTagsPerVector>,
+		/// Collection the vector belongs to. Vectors outside of a collection are plain `f32`
+		/// vectors.
+		pub collection: Option<CollectionId>,
+		/// Model the vector is an embedding by.
+		pub model: ModelId,
+	}
+
 	#[pallet::storage]
 	pub type Vectors<T: Config> = StorageMap<
 		_,
 		Blake2_128Concat,
 		T::Hash,
-		WeightData<T>,
+		VectorRecord<T>,
 	>;
 
 	#[pallet::storage]
@@ -296,6 +935,40 @@ pub mod pallet {
 		BoundedVec<u8, T::MaxTagLength>,
 	>;
 
+	/// Index of the vectors carrying each tag, used to scope near-duplicate checks.
+	#[pallet::storage]
+	pub type TagVectors<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,  // Tag ID
+		Blake2_128Concat,
+		T::Hash,  // Vector ID
+		(),
+	>;
+
+	/// Maps the hash of the collection and raw data of each vector to the vector, used to reject
+	/// exact duplicates within a collection. See [`Pallet::content_key`].
+	#[pallet::storage]
+	pub type ContentIndex<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,  // Content key
+		T::Hash,  // Vector ID
+	>;
+
+	/// Collections by id.
+	#[pallet::storage]
+	pub type Collections<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		CollectionId,
+		CollectionInfo<<T as frame_system::Config>::AccountId, BalanceOf<T>>,
+	>;
+
+	/// Id assigned to the next created collection.
+	#[pallet::storage]
+	pub type NextCollectionId<T: Config> = StorageValue<_, CollectionId, ValueQuery>;
+
 	#[pallet::storage]
 	pub type AuthorVectors<T: Config> = StorageMap<
 		_,
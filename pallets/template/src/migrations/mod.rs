@@ -0,0 +1,10 @@
+//! Storage migrations of the template pallet.
+//!
+//! Migrations that touch every stored vector are written as multi-block migrations (see
+//! [`frame_support::migrations::SteppedMigration`]) and executed by `pallet-migrations`, so that
+//! a large memory pool never has to be rewritten within a single block.
+
+/// Identifier prefix shared by all multi-block migrations of this pallet.
+pub const PALLET_MIGRATIONS_ID: &[u8; 15] = b"pallet-template";
+
+pub mod v1;
@@ -0,0 +1,221 @@
+//! Migration from storage version 0 to 1.
+//!
+//! Version 0, the layout of the first release, stored every vector as a `(data, author, tags)`
+//! tuple. Version 1 stores a [`VectorRecord`], which also records the collection and embedding
+//! model of the vector. Vectors stored before were never checked against a model, so they are
+//! all attributed to `LegacyModel`, and none of them belongs to a collection. Vectors are
+//! re-encoded one by one in [`LazyMigrationV1`], which resumes from the last migrated key
+//! whenever a block runs out of weight.
+//!
+//! Version 0 had neither a content nor a tag index, and did not remove pruned vectors from the
+//! list of their author. The migration indexes every vector it re-encodes, then goes through the
+//! author lists to drop the vectors that no longer exist.
+
+use super::PALLET_MIGRATIONS_ID;
+use crate::{
+	weights::WeightInfo, AuthorVectors, Config, ContentIndex, ModelId, Pallet, TagVectors,
+	VectorRecord, Vectors,
+};
+use frame_support::{
+	migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+	pallet_prelude::*,
+	weights::WeightMeter,
+};
+
+#[cfg(feature = "try-runtime")]
+use scale_info::prelude::vec::Vec;
+
+/// Storage layout of version 0.
+pub mod v0 {
+	use super::*;
+	use frame_support::storage_alias;
+
+	/// Vectors as `(data, author, tags)` tuples.
+	#[storage_alias]
+	pub type Vectors<T: Config> = StorageMap<
+		Pallet<T>,
+		Blake2_128Concat,
+		<T as frame_system::Config>::Hash,
+		(
+			BoundedVec<u8, <T as Config>::MaxVectorLength>,
+			<T as frame_system::Config>::AccountId,
+			BoundedVec<<T as frame_system::Config>::Hash, <T as Config>::MaxTagsPerVector>,
+		),
+	>;
+}
+
+/// Progress of [`LazyMigrationV1`].
+#[derive(Encode, Decode, MaxEncodedLen, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum Cursor<Hash, AccountId> {
+	/// Key of the last migrated vector.
+	Vectors(Hash),
+	/// Last author whose list was cleaned up, `None` before the first one.
+	Authors(Option<AccountId>),
+}
+
+/// Re-encodes and indexes every vector into a [`VectorRecord`] attributed to `LegacyModel`, one
+/// vector per step, then removes pruned vectors from the lists of their authors, one author per
+/// step.
+///
+/// Once every author list has been cleaned up the storage version is set to 1.
+pub struct LazyMigrationV1<T: Config, W: WeightInfo, LegacyModel: Get<ModelId>>(
+	PhantomData<(T, W, LegacyModel)>,
+);
+
+impl<T: Config, W: WeightInfo, LegacyModel: Get<ModelId>> LazyMigrationV1<T, W, LegacyModel> {
+	/// Re-encode and index the vector following `last_key`, returning where to continue.
+	fn migrate_vector(last_key: Option<T::Hash>) -> Cursor<T::Hash, T::AccountId> {
+		let mut iter = if let Some(last_key) = last_key {
+			v0::Vectors::<T>::iter_from(v0::Vectors::<T>::hashed_key_for(last_key))
+		} else {
+			v0::Vectors::<T>::iter()
+		};
+
+		let Some((key, (data, author, tags))) = iter.next() else {
+			return Cursor::Authors(None);
+		};
+		// Exact duplicates stored before version 1 stay indexed under the first one.
+		ContentIndex::<T>::mutate(Pallet::<T>::content_key(None, &data), |vector_id| {
+			vector_id.get_or_insert(key);
+		});
+		for tag_id in tags.iter() {
+			TagVectors::<T>::insert(tag_id, key, ());
+		}
+		let model = LegacyModel::get();
+		Vectors::<T>::insert(key, VectorRecord { data, author, tags, collection: None, model });
+		Cursor::Vectors(key)
+	}
+
+	/// Drop the pruned vectors from the list of the author following `last_author`, returning
+	/// that author, or `None` once every list has been cleaned up.
+	fn clean_up_author(last_author: Option<T::AccountId>) -> Option<T::AccountId> {
+		let mut iter = if let Some(last_author) = last_author {
+			AuthorVectors::<T>::iter_from(AuthorVectors::<T>::hashed_key_for(last_author))
+		} else {
+			AuthorVectors::<T>::iter()
+		};
+
+		let (author, mut vector_ids) = iter.next()?;
+		vector_ids.retain(|vector_id| Vectors::<T>::contains_key(vector_id));
+		if vector_ids.is_empty() {
+			AuthorVectors::<T>::remove(&author);
+		} else {
+			AuthorVectors::<T>::insert(&author, vector_ids);
+		}
+		Some(author)
+	}
+}
+
+impl<T: Config, W: WeightInfo, LegacyModel: Get<ModelId>> SteppedMigration
+	for LazyMigrationV1<T, W, LegacyModel>
+{
+	type Cursor = Cursor<T::Hash, T::AccountId>;
+	type Identifier = MigrationId<15>;
+
+	fn id() -> Self::Identifier {
+		MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 0, version_to: 1 }
+	}
+
+	fn step(
+		mut cursor: Option<Self::Cursor>,
+		meter: &mut WeightMeter,
+	) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+		if Pallet::<T>::on_chain_storage_version() != Self::id().version_from as u16 {
+			return Ok(None);
+		}
+
+		let required = W::migration_step().max(W::migration_author_step());
+		// We must be able to migrate at least one vector or author per block, otherwise the
+		// migration would never finish.
+		if meter.remaining().any_lt(required) {
+			return Err(SteppedMigrationError::InsufficientWeight { required });
+		}
+
+		loop {
+			let next = match &cursor {
+				Some(Cursor::Authors(last_author)) => {
+					if meter.try_consume(W::migration_author_step()).is_err() {
+						break;
+					}
+					let Some(author) = Self::clean_up_author(last_author.clone()) else {
+						StorageVersion::new(Self::id().version_to as u16).put::<Pallet<T>>();
+						return Ok(None);
+					};
+					Cursor::Authors(Some(author))
+				},
+				vectors => {
+					if meter.try_consume(W::migration_step()).is_err() {
+						break;
+					}
+					let last_key = match vectors {
+						Some(Cursor::Vectors(last_key)) => Some(*last_key),
+						_ => None,
+					};
+					Self::migrate_vector(last_key)
+				},
+			};
+			cursor = Some(next);
+		}
+
+		Ok(cursor)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, frame_support::sp_runtime::TryRuntimeError> {
+		Ok((v0::Vectors::<T>::iter_keys().count() as u32).encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+		let vectors =
+			u32::decode(&mut &state[..]).map_err(|_| "cannot decode pre-upgrade state")?;
+
+		ensure!(
+			Pallet::<T>::on_chain_storage_version() == Self::id().version_to as u16,
+			"storage version was not bumped"
+		);
+		ensure!(
+			Vectors::<T>::iter_values().count() as u32 == vectors,
+			"number of vectors changed during the migration"
+		);
+		ensure!(
+			Vectors::<T>::iter_values()
+				.all(|r| r.collection.is_none() && r.model == LegacyModel::get()),
+			"a vector was not attributed to the legacy model"
+		);
+		for (tag_id, vector_id, ()) in TagVectors::<T>::iter() {
+			let record = Vectors::<T>::get(vector_id).ok_or("tag index points to a missing vector")?;
+			ensure!(record.tags.contains(&tag_id), "tag index disagrees with the vector record");
+		}
+
+		// Exact duplicates share an entry of the content index, which `Pallet::do_try_state`
+		// would reject, so the indexes are checked here instead.
+		for (vector_id, record) in Vectors::<T>::iter() {
+			ensure!(
+				AuthorVectors::<T>::get(&record.author).is_some_and(|ids| ids.contains(&vector_id)),
+				"vector missing from its author's list"
+			);
+			ensure!(
+				ContentIndex::<T>::contains_key(Pallet::<T>::content_key(
+					record.collection,
+					&record.data
+				)),
+				"vector missing from the content index"
+			);
+			for tag_id in record.tags.iter() {
+				ensure!(
+					TagVectors::<T>::contains_key(tag_id, vector_id),
+					"vector missing from the tag index"
+				);
+			}
+		}
+		for vector_ids in AuthorVectors::<T>::iter_values() {
+			ensure!(
+				vector_ids.iter().all(|vector_id| Vectors::<T>::contains_key(vector_id)),
+				"author list points to a pruned vector"
+			);
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,139 @@
+use crate as pallet_template;
+use codec::Encode;
+use frame_support::{
+	derive_impl, parameter_types,
+	traits::{ConstU32, ConstU64},
+};
+use sp_runtime::{BuildStorage, Perbill};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		Balances: pallet_balances,
+		TemplateModule: pallet_template,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+	type ExistentialDeposit = ConstU64<1>;
+}
+
+parameter_types! {
+	pub const SimilarityThreshold: Option<Perbill> = Some(Perbill::from_percent(95));
+	// Tunable limits, starting at the ceilings.
+	pub static VectorsPerAuthor: u32 = 3;
+	pub static VectorLength: u32 = 128;
+	pub static TagLength: u32 = 8;
+	pub static TagsPerVector: u32 = 2;
+}
+
+/// Models of the mock: a model embeds into as many dimensions as the low 16 bits of its id, and
+/// is not registered if they are zero. Models `3` and `0x1_0003` are two models of three
+/// dimensions.
+pub struct Models;
+
+impl pallet_template::EmbeddingModels for Models {
+	fn dimension(model: pallet_template::ModelId) -> Option<u32> {
+		Some(model & 0xffff).filter(|dimension| *dimension != 0)
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_template::BenchmarkHelper for Models {
+	fn register_model(dimension: u32) -> pallet_template::ModelId {
+		dimension
+	}
+}
+
+/// Stand-in for a proof of knowledge of the secret of the ephemeral key `key`, binding it to
+/// `author` and `collection` like the proof of a real scheme.
+pub fn key_proof(
+	author: u64,
+	collection: pallet_template::CollectionId,
+	key: &[u8; pallet_template::EPHEMERAL_KEY_LEN],
+) -> [u8; pallet_template::KEY_PROOF_LEN] {
+	let mut proof = [0; pallet_template::KEY_PROOF_LEN];
+	proof[..32].copy_from_slice(&sp_io::hashing::blake2_256(&(author, collection, key).encode()));
+	proof
+}
+
+/// Accepts the proofs made by [`key_proof`].
+pub struct EphemeralKeys;
+
+impl pallet_template::EphemeralKeyProof<u64> for EphemeralKeys {
+	fn verify(
+		author: &u64,
+		collection: pallet_template::CollectionId,
+		ephemeral_key: &[u8; pallet_template::EPHEMERAL_KEY_LEN],
+		proof: &[u8; pallet_template::KEY_PROOF_LEN],
+	) -> bool {
+		*proof == key_proof(*author, collection, ephemeral_key)
+	}
+}
+
+impl pallet_template::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type Currency = Balances;
+	type CollectionDeposit = ConstU64<COLLECTION_DEPOSIT>;
+	type MaxVectors = ConstU32<3>;
+	type MaxVectorLength = ConstU32<128>;
+	type MaxTagLength = ConstU32<8>;
+	type MaxTagsPerVector = ConstU32<2>;
+	type VectorsPerAuthor = VectorsPerAuthor;
+	type VectorLength = VectorLength;
+	type TagLength = TagLength;
+	type TagsPerVector = TagsPerVector;
+	type SimilarityThreshold = SimilarityThreshold;
+	// Large enough to compare a submission with every vector the model tests can store.
+	type MaxSimilaritySamples = ConstU32<16>;
+	type MaxBatchSize = ConstU32<3>;
+	type PruneOrigin = frame_system::EnsureRoot<u64>;
+	type Observer = ();
+	type Models = Models;
+	type EphemeralKeys = EphemeralKeys;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = Models;
+}
+
+/// Deposit held for each collection.
+pub const COLLECTION_DEPOSIT: u64 = 10;
+
+/// Accounts endowed at genesis.
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(ALICE, 1_000), (BOB, 1_000)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	VectorsPerAuthor::set(3);
+	VectorLength::set(64);
+	TagLength::set(8);
+	TagsPerVector::set(2);
+
+	let mut ext: sp_io::TestExternalities = t.into();
+	// Events are not emitted on the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Assert that every storage index of the pallet agrees with `Vectors`.
+pub fn check_indexes() {
+	TemplateModule::do_try_state().expect("storage indexes are consistent");
+}
@@ -0,0 +1,211 @@
+//! Model-based tests for the memory pallet.
+//!
+//! Random sequences of calls are applied both to the pallet and to [`Model`], a plain in-memory
+//! description of what the pallet should store. After every call the two must agree and every
+//! storage index must be consistent with `Vectors` (see [`check_indexes`]).
+//!
+//! Generated vectors are one-hot vectors along one of [`DIMENSIONS`] axes with a magnitude of one
+//! or two. Vectors along the same axis have a cosine similarity of one and vectors along
+//! different axes a similarity of zero, so the model can predict near-duplicates exactly.
+
+use crate::{mock::*, *};
+use frame_support::traits::Get;
+use proptest::prelude::*;
+use sp_core::H256;
+use sp_runtime::{traits::Hash, DispatchError};
+use std::collections::BTreeMap;
+
+const DIMENSIONS: usize = 8;
+const TAGS: [&[u8]; 3] = [b"a", b"b", b"c"];
+/// The float32 collection created before any call is applied.
+const COLLECTION: CollectionId = 0;
+
+type Hashing = <Test as frame_system::Config>::Hashing;
+
+#[derive(Clone, Debug)]
+enum Op {
+	Store { author: u64, axis: usize, magnitude: u8, tags: Vec<usize>, in_collection: bool },
+	/// Prune the vectors at the given positions among the stored vectors. Positions past the
+	/// end stand for an unknown id. Without an author, governance prunes them.
+	Prune { author: Option<u64>, picks: Vec<usize> },
+}
+
+fn author() -> impl Strategy<Value = u64> {
+	prop::sample::select(vec![ALICE, BOB])
+}
+
+fn op() -> impl Strategy<Value = Op> {
+	prop_oneof![
+		3 => (
+			author(),
+			0..DIMENSIONS,
+			1..=2u8,
+			prop::sample::subsequence(vec![0, 1, 2], 0..=2),
+			any::<bool>(),
+		)
+			.prop_map(|(author, axis, magnitude, tags, in_collection)| Op::Store {
+				author,
+				axis,
+				magnitude,
+				tags,
+				in_collection,
+			}),
+		1 => (prop::option::of(author()), prop::collection::vec(any::<usize>(), 0..4))
+			.prop_map(|(author, picks)| Op::Prune { author, picks }),
+	]
+}
+
+#[derive(Debug)]
+struct Entry {
+	data: Vec<u8>,
+	author: u64,
+	tags: Vec<H256>,
+	collection: Option<CollectionId>,
+	axis: usize,
+}
+
+/// What the pallet is expected to store.
+#[derive(Default)]
+struct Model {
+	vectors: BTreeMap<H256, Entry>,
+	/// Vector ids of every author, in submission order.
+	authors: BTreeMap<u64, Vec<H256>>,
+}
+
+impl Model {
+	fn store(&mut self, entry: Entry) -> Result<H256, Error<Test>> {
+		if self.vectors.values().any(|v| v.collection == entry.collection && v.data == entry.data) {
+			return Err(Error::DuplicateVector);
+		}
+		if self.vectors.values().any(|v| {
+			v.collection == entry.collection &&
+				v.axis == entry.axis &&
+				v.tags.iter().any(|t| entry.tags.contains(t))
+		}) {
+			return Err(Error::NearDuplicateVector);
+		}
+		let ids = self.authors.entry(entry.author).or_default();
+		if ids.len() >= <Test as Config>::MaxVectors::get() as usize {
+			return Err(Error::MaxVectorsReached);
+		}
+
+		let vector_id =
+			Hashing::hash_of(&(&entry.data, &entry.author, &entry.tags, entry.collection));
+		ids.push(vector_id);
+		self.vectors.insert(vector_id, entry);
+		Ok(vector_id)
+	}
+
+	fn prune(&mut self, caller: Option<u64>, vector_ids: &[H256]) -> Result<u32, Error<Test>> {
+		if vector_ids.is_empty() {
+			return Err(Error::NothingToPrune);
+		}
+		let foreign = |id: &H256| self.vectors.get(id).is_some_and(|e| Some(e.author) != caller);
+		if caller.is_some() && vector_ids.iter().any(foreign) {
+			return Err(Error::NotAuthor);
+		}
+		let mut count = 0;
+		for vector_id in vector_ids {
+			if let Some(entry) = self.vectors.remove(vector_id) {
+				let ids = self.authors.get_mut(&entry.author).expect("every vector has an author");
+				ids.retain(|id| id != vector_id);
+				if ids.is_empty() {
+					self.authors.remove(&entry.author);
+				}
+				count += 1;
+			}
+		}
+		Ok(count)
+	}
+}
+
+fn apply(model: &mut Model, op: Op) {
+	match op {
+		Op::Store { author, axis, magnitude, tags, in_collection } => {
+			let mut components = [0f32; DIMENSIONS];
+			components[axis] = magnitude as f32;
+			let data: Vec<u8> = components.iter().flat_map(|c| c.to_le_bytes()).collect();
+			let tag_data: Vec<Vec<u8>> = tags.iter().map(|&t| TAGS[t].to_vec()).collect();
+			let collection = in_collection.then_some(COLLECTION);
+
+			let result = TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(author),
+				(data.clone().try_into().unwrap(), author, BoundedVec::default()),
+				tag_data.clone(),
+				collection,
+				DIMENSIONS as ModelId,
+			);
+			let expected = model.store(Entry {
+				data,
+				author,
+				tags: tag_data.iter().map(Hashing::hash_of).collect(),
+				collection,
+				axis,
+			});
+
+			match expected {
+				Ok(vector_id) => {
+					assert_eq!(result, Ok(()));
+					System::assert_last_event(Event::VectorStored { vector_id, author }.into());
+				},
+				Err(e) => assert_eq!(result, Err(DispatchError::from(e))),
+			}
+		},
+		Op::Prune { author, picks } => {
+			let known: Vec<H256> = model.vectors.keys().copied().collect();
+			let vector_ids: Vec<H256> = picks
+				.iter()
+				.map(|p| known.get(p % (known.len() + 1)).copied().unwrap_or(H256::repeat_byte(0xff)))
+				.collect();
+
+			let origin = author.map_or_else(RuntimeOrigin::root, RuntimeOrigin::signed);
+			let result = TemplateModule::prune_weight_data(origin, vector_ids.clone());
+
+			match model.prune(author, &vector_ids) {
+				Ok(count) => {
+					assert_eq!(result, Ok(()));
+					System::assert_last_event(Event::VectorsPruned { count }.into());
+				},
+				Err(e) => assert_eq!(result, Err(DispatchError::from(e))),
+			}
+		},
+	}
+}
+
+fn assert_agrees(model: &Model) {
+	let stored: BTreeMap<H256, VectorRecord<Test>> = Vectors::<Test>::iter().collect();
+	assert_eq!(stored.keys().collect::<Vec<_>>(), model.vectors.keys().collect::<Vec<_>>());
+	for (vector_id, entry) in &model.vectors {
+		let record = &stored[vector_id];
+		assert_eq!(record.data.to_vec(), entry.data);
+		assert_eq!(record.author, entry.author);
+		assert_eq!(record.tags.to_vec(), entry.tags);
+		assert_eq!(record.collection, entry.collection);
+		assert_eq!(record.model, DIMENSIONS as ModelId);
+	}
+
+	let authors: BTreeMap<u64, Vec<H256>> =
+		AuthorVectors::<Test>::iter().map(|(author, ids)| (author, ids.into_inner())).collect();
+	assert_eq!(authors, model.authors);
+
+	check_indexes();
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(64))]
+
+	#[test]
+	fn pallet_agrees_with_model(ops in prop::collection::vec(op(), 1..40)) {
+		new_test_ext().execute_with(|| {
+			assert_eq!(
+				TemplateModule::do_create_collection(ALICE, VectorEncoding::Float32, 0),
+				Ok(COLLECTION)
+			);
+			let mut model = Model::default();
+			for op in ops {
+				apply(&mut model, op);
+				assert_agrees(&model);
+			}
+		});
+	}
+}
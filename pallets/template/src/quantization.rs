@@ -0,0 +1,154 @@
+//! Storage encodings for vector data.
+//!
+//! Every collection chooses how the components of its vectors are laid out in storage. Quantized
+//! encodings trade precision for state size; they are decompressed into the fixed-point
+//! representation of [`crate::similarity`] whenever vectors need to be compared on chain.
+
+use crate::similarity::{self, FRACTIONAL_BITS};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::RuntimeDebug;
+use scale_info::{prelude::vec::Vec, TypeInfo};
+use serde::{Deserialize, Serialize};
+
+/// Fixed-point `1.0` in the representation used by [`crate::similarity`].
+const ONE: i64 = 1 << FRACTIONAL_BITS;
+
+/// Length of the ephemeral public key that prefixes every encrypted vector.
+pub const EPHEMERAL_KEY_LEN: usize = 32;
+
+/// Length of the proof of knowledge of the ephemeral secret that follows the ephemeral public key
+/// of an encrypted vector.
+pub const KEY_PROOF_LEN: usize = 64;
+
+/// How the components of stored vectors are encoded.
+#[derive(
+	Clone, Copy, Default, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug,
+	Serialize, Deserialize,
+)]
+pub enum VectorEncoding {
+	/// Little-endian `f32` components, four bytes per dimension.
+	#[default]
+	Float32,
+	/// Scalar quantization to one signed byte per dimension. A component `q` decodes to
+	/// `offset + scale * q`, where `scale` and `offset` are 16.16 fixed-point numbers. The scale
+	/// must be positive.
+	Int8 { scale: i32, offset: i32 },
+	/// One bit per dimension, most significant bit first. Set bits decode to `1.0` and cleared
+	/// bits to `-1.0`.
+	Binary,
+	/// Ciphertext under the key of the threshold encryption committee: a compressed ristretto
+	/// ephemeral public key of [`EPHEMERAL_KEY_LEN`] bytes, a proof of knowledge of its secret of
+	/// [`KEY_PROOF_LEN`] bytes, then the encrypted vector. Encrypted vectors cannot be compared,
+	/// so they are exempt from near-duplicate detection.
+	Encrypted,
+}
+
+impl VectorEncoding {
+	/// Whether the parameters of this encoding can represent vectors.
+	///
+	/// A quantized scale of zero would decode every component to the offset, and a negative one
+	/// would flip the sign of every component. Fixed-point offsets are always finite.
+	pub fn is_well_formed(&self) -> bool {
+		match self {
+			VectorEncoding::Int8 { scale, .. } => *scale > 0,
+			_ => true,
+		}
+	}
+
+	/// Whether `data` is a valid vector in this encoding.
+	pub fn is_valid(&self, data: &[u8]) -> bool {
+		if !self.is_well_formed() {
+			return false;
+		}
+		match self {
+			VectorEncoding::Encrypted => data.len() > EPHEMERAL_KEY_LEN + KEY_PROOF_LEN,
+			_ => self.decode(data).is_some(),
+		}
+	}
+
+	/// Number of components of `data`, a valid vector in this encoding, or `None` if it is
+	/// encrypted.
+	pub fn dimension(&self, data: &[u8]) -> Option<u32> {
+		let components = match self {
+			VectorEncoding::Float32 => data.len() / 4,
+			VectorEncoding::Int8 { .. } => data.len(),
+			VectorEncoding::Binary => data.len().saturating_mul(8),
+			VectorEncoding::Encrypted => return None,
+		};
+		Some(components as u32)
+	}
+
+	/// Ephemeral public key of `data` and the proof of knowledge of its secret, or `None` unless
+	/// `data` is a valid encrypted vector.
+	pub fn ephemeral_key(
+		&self,
+		data: &[u8],
+	) -> Option<([u8; EPHEMERAL_KEY_LEN], [u8; KEY_PROOF_LEN])> {
+		if *self != VectorEncoding::Encrypted || !self.is_valid(data) {
+			return None;
+		}
+		let (key, rest) = data.split_at(EPHEMERAL_KEY_LEN);
+		Some((key.try_into().ok()?, rest.get(..KEY_PROOF_LEN)?.try_into().ok()?))
+	}
+
+	/// Decompress `data` into fixed-point components.
+	///
+	/// Returns `None` if `data` is not a valid encoding of a vector or is encrypted.
+	pub fn decode(&self, data: &[u8]) -> Option<Vec<i64>> {
+		match self {
+			VectorEncoding::Float32 => similarity::decode_f32(data),
+			VectorEncoding::Int8 { scale, offset } => Some(
+				data.iter()
+					.map(|q| (*offset as i64).saturating_add((*scale as i64) * (*q as i8) as i64))
+					.collect(),
+			),
+			VectorEncoding::Binary => Some(
+				data.iter()
+					.flat_map(|byte| (0..8).rev().map(move |bit| (byte >> bit) & 1))
+					.map(|bit| if bit == 1 { ONE } else { -ONE })
+					.collect(),
+			),
+			VectorEncoding::Encrypted => None,
+		}
+	}
+
+	/// Compress `components` into this encoding, rounding to the nearest representable value.
+	///
+	/// Returns `None` if a component is not finite, if the encoding is encrypted, or if it is
+	/// binary and the number of components is not a multiple of eight.
+	pub fn encode(&self, components: &[f32]) -> Option<Vec<u8>> {
+		match self {
+			VectorEncoding::Float32 => {
+				components.iter().all(|c| c.is_finite()).then_some(())?;
+				Some(components.iter().flat_map(|c| c.to_le_bytes()).collect())
+			},
+			VectorEncoding::Int8 { scale, offset } => {
+				let scale = (*scale as i64).max(1);
+				components
+					.iter()
+					.map(|c| {
+						let value = similarity::f32_bits_to_fixed(c.to_bits())?;
+						let shifted = value.saturating_sub(*offset as i64);
+						// Round half away from zero.
+						let q = (shifted.saturating_add(shifted.signum() * scale / 2)) / scale;
+						Some(q.clamp(i8::MIN as i64, i8::MAX as i64) as i8 as u8)
+					})
+					.collect()
+			},
+			VectorEncoding::Binary => {
+				if components.len() % 8 != 0 || !components.iter().all(|c| c.is_finite()) {
+					return None;
+				}
+				Some(
+					components
+						.chunks_exact(8)
+						.map(|chunk| {
+							chunk.iter().fold(0u8, |byte, c| (byte << 1) | (*c > 0.0) as u8)
+						})
+						.collect(),
+				)
+			},
+			VectorEncoding::Encrypted => None,
+		}
+	}
+}
@@ -0,0 +1,85 @@
+//! Deterministic similarity helpers for stored vectors.
+//!
+//! Vector data is interpreted as a sequence of little-endian IEEE-754 `f32` components. Floating
+//! point arithmetic is avoided in the runtime, so every component is converted into a signed
+//! fixed-point integer with [`FRACTIONAL_BITS`] fractional bits before any computation happens.
+
+use frame_support::sp_runtime::{traits::IntegerSquareRoot, Perbill};
+use scale_info::prelude::vec::Vec;
+
+/// Number of fractional bits used by the fixed-point representation of a component.
+pub const FRACTIONAL_BITS: u32 = 16;
+
+/// Largest magnitude a component may have once converted (`2^24` in real terms). Larger values
+/// are saturated so that dot products can never overflow an `i128`.
+const MAX_MAGNITUDE: i64 = 1 << 40;
+
+/// Convert the raw bits of an `f32` into a fixed-point integer.
+///
+/// Returns `None` for NaN and infinities. Subnormal values are flushed to zero.
+pub fn f32_bits_to_fixed(bits: u32) -> Option<i64> {
+	let exponent = ((bits >> 23) & 0xff) as i32;
+	let mantissa = (bits & 0x7f_ffff) as i64;
+
+	if exponent == 0xff {
+		return None;
+	}
+	if exponent == 0 {
+		return Some(0);
+	}
+
+	// value = significand * 2^(exponent - 127 - 23), scaled up by 2^FRACTIONAL_BITS.
+	let significand = mantissa | (1 << 23);
+	let shift = exponent - 150 + FRACTIONAL_BITS as i32;
+	let magnitude = if shift > 16 {
+		MAX_MAGNITUDE
+	} else if shift >= 0 {
+		significand << shift
+	} else {
+		significand.checked_shr(shift.unsigned_abs()).unwrap_or(0)
+	};
+
+	Some(if bits >> 31 == 1 { -magnitude } else { magnitude })
+}
+
+/// Decode raw vector bytes as little-endian `f32` components.
+///
+/// Returns `None` if the length is not a multiple of four or any component is not finite.
+pub fn decode_f32(data: &[u8]) -> Option<Vec<i64>> {
+	if data.len() % 4 != 0 {
+		return None;
+	}
+	data.chunks_exact(4)
+		.map(|c| f32_bits_to_fixed(u32::from_le_bytes([c[0], c[1], c[2], c[3]])))
+		.collect()
+}
+
+/// Cosine similarity between two decoded vectors.
+///
+/// Negative similarities are reported as zero since only near-duplicates are of interest.
+/// Returns `None` if the dimensions differ or either vector has a zero norm.
+pub fn cosine_similarity(a: &[i64], b: &[i64]) -> Option<Perbill> {
+	if a.len() != b.len() {
+		return None;
+	}
+
+	let mut dot: i128 = 0;
+	let mut norm_a: u128 = 0;
+	let mut norm_b: u128 = 0;
+	for (x, y) in a.iter().zip(b.iter()) {
+		let (x, y) = (*x as i128, *y as i128);
+		dot = dot.saturating_add(x * y);
+		norm_a = norm_a.saturating_add((x * x) as u128);
+		norm_b = norm_b.saturating_add((y * y) as u128);
+	}
+
+	let denominator = norm_a.integer_sqrt().saturating_mul(norm_b.integer_sqrt());
+	if denominator == 0 {
+		return None;
+	}
+	if dot <= 0 {
+		return Some(Perbill::zero());
+	}
+
+	Some(Perbill::from_rational(dot as u128, denominator))
+}
@@ -0,0 +1,762 @@
+use crate::{mock::*, *};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_err, assert_noop, assert_ok,
+	traits::{
+		fungible::{InspectHold, Mutate},
+		Get,
+	},
+};
+use sp_core::H256;
+use sp_runtime::{traits::Hash, BuildStorage, DispatchResult, TokenError};
+
+type Hashing = <Test as frame_system::Config>::Hashing;
+
+/// Little-endian `f32` encoding of `components`.
+fn f32_vector(components: &[f32]) -> Vec<u8> {
+	components.iter().flat_map(|c| c.to_le_bytes()).collect()
+}
+
+/// Model of the mock embedding into as many dimensions as `data` has `f32` components.
+fn f32_model(data: &[u8]) -> ModelId {
+	data.len() as u32 / 4
+}
+
+/// Call argument carrying `data`; the author and tag ids are resolved by the pallet.
+fn weight_data(data: Vec<u8>) -> WeightData<Test> {
+	(data.try_into().unwrap(), ALICE, BoundedVec::default())
+}
+
+fn tags(tags: &[&[u8]]) -> Vec<Vec<u8>> {
+	tags.iter().map(|t| t.to_vec()).collect()
+}
+
+/// Store `data` for `author` and return the id of the new vector.
+fn store(author: u64, data: Vec<u8>, tag_data: &[&[u8]]) -> H256 {
+	let model = f32_model(&data);
+	store_in(author, data, tag_data, None, model)
+}
+
+fn store_in(
+	author: u64,
+	data: Vec<u8>,
+	tag_data: &[&[u8]],
+	collection: Option<CollectionId>,
+	model: ModelId,
+) -> H256 {
+	assert_ok!(TemplateModule::store_weight_data(
+		RuntimeOrigin::signed(author),
+		weight_data(data),
+		tags(tag_data),
+		collection,
+		model,
+	));
+	AuthorVectors::<Test>::get(author).unwrap().last().copied().unwrap()
+}
+
+#[test]
+fn store_weight_data_records_vector_and_indexes() {
+	new_test_ext().execute_with(|| {
+		let data = f32_vector(&[1.0, 0.0, 0.0]);
+		let vector_id = store(BOB, data.clone(), &[b"facts"]);
+
+		let tag_id = Hashing::hash_of(&b"facts".to_vec());
+		let record = Vectors::<Test>::get(vector_id).unwrap();
+		assert_eq!(record.data.to_vec(), data);
+		// The record is attributed to the signer, not to the author in the call arguments.
+		assert_eq!(record.author, BOB);
+		assert_eq!(record.tags.to_vec(), vec![tag_id]);
+		assert_eq!(record.collection, None);
+		assert_eq!(record.model, 3);
+
+		assert_eq!(Tags::<Test>::get(tag_id).unwrap().to_vec(), b"facts".to_vec());
+		assert!(TagVectors::<Test>::contains_key(tag_id, vector_id));
+		assert_eq!(
+			ContentIndex::<Test>::get(TemplateModule::content_key(None, &record.data)),
+			Some(vector_id)
+		);
+		assert_eq!(AuthorVectors::<Test>::get(BOB).unwrap().to_vec(), vec![vector_id]);
+		System::assert_last_event(Event::VectorStored { vector_id, author: BOB }.into());
+	});
+}
+
+#[test]
+fn vector_length_is_bounded() {
+	new_test_ext().execute_with(|| {
+		let max = <Test as Config>::MaxVectorLength::get() as usize;
+		// Oversized vectors cannot even be decoded as call arguments.
+		let oversized = (vec![0u8; max + 1], ALICE, Vec::<H256>::new()).encode();
+		assert!(WeightData::<Test>::decode(&mut &oversized[..]).is_err());
+
+		let data = f32_vector(&vec![1.0; max / 4]);
+		assert_eq!(data.len(), max);
+		store(ALICE, data, &[]);
+	});
+}
+
+#[test]
+fn invalid_float_data_is_rejected() {
+	new_test_ext().execute_with(|| {
+		// Not a whole number of `f32` components.
+		assert_noop!(
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(ALICE),
+				weight_data(vec![0u8; 3]),
+				vec![],
+				None,
+				1,
+			),
+			Error::<Test>::InvalidVectorData
+		);
+		// NaN component.
+		assert_noop!(
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(ALICE),
+				weight_data(f32_vector(&[f32::NAN])),
+				vec![],
+				None,
+				1,
+			),
+			Error::<Test>::InvalidVectorData
+		);
+	});
+}
+
+#[test]
+fn tags_are_bounded() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(ALICE),
+				weight_data(f32_vector(&[1.0])),
+				tags(&[b"much-too-long"]),
+				None,
+				1,
+			),
+			Error::<Test>::TagTooLong
+		);
+		assert_noop!(
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(ALICE),
+				weight_data(f32_vector(&[1.0])),
+				tags(&[b"a", b"b", b"c"]),
+				None,
+				1,
+			),
+			Error::<Test>::TooManyTags
+		);
+	});
+}
+
+#[test]
+fn exact_duplicates_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let data = f32_vector(&[1.0, 2.0]);
+		store(ALICE, data.clone(), &[b"a"]);
+
+		// Identical data is rejected regardless of author and tags.
+		assert_noop!(
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(BOB),
+				weight_data(data),
+				tags(&[b"b"]),
+				None,
+				2,
+			),
+			Error::<Test>::DuplicateVector
+		);
+	});
+}
+
+#[test]
+fn near_duplicates_sharing_a_tag_are_rejected() {
+	new_test_ext().execute_with(|| {
+		store(ALICE, f32_vector(&[1.0, 2.0, 3.0]), &[b"a"]);
+
+		// Same direction, different magnitude: cosine similarity of 1.
+		assert_noop!(
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(BOB),
+				weight_data(f32_vector(&[2.0, 4.0, 6.0])),
+				tags(&[b"b", b"a"]),
+				None,
+				3,
+			),
+			Error::<Test>::NearDuplicateVector
+		);
+
+		// Without a shared tag the vectors are never compared.
+		store(BOB, f32_vector(&[2.0, 4.0, 6.0]), &[b"b"]);
+		// Dissimilar vectors sharing a tag are accepted.
+		store(BOB, f32_vector(&[3.0, -1.0, 0.0]), &[b"a"]);
+	});
+}
+
+#[test]
+fn author_vectors_are_bounded() {
+	new_test_ext().execute_with(|| {
+		let max = <Test as Config>::MaxVectors::get();
+		for i in 0..max {
+			store(ALICE, f32_vector(&[i as f32 + 1.0]), &[]);
+		}
+
+		assert_noop!(
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(ALICE),
+				weight_data(f32_vector(&[100.0])),
+				vec![],
+				None,
+				1,
+			),
+			Error::<Test>::MaxVectorsReached
+		);
+		// Other authors are unaffected.
+		store(BOB, f32_vector(&[100.0]), &[]);
+	});
+}
+
+#[test]
+fn limits_are_tunable_below_their_ceilings() {
+	new_test_ext().execute_with(|| {
+		let store_call = |author, data: Vec<u8>, tag_data: &[&[u8]]| {
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(author),
+				weight_data(data.clone()),
+				tags(tag_data),
+				None,
+				f32_model(&data),
+			)
+		};
+
+		VectorLength::set(8);
+		assert_noop!(
+			store_call(ALICE, f32_vector(&[1.0, 2.0, 3.0]), &[]),
+			Error::<Test>::InvalidVectorData
+		);
+		TagLength::set(2);
+		assert_noop!(store_call(ALICE, f32_vector(&[1.0]), &[b"abc"]), Error::<Test>::TagTooLong);
+		TagsPerVector::set(1);
+		assert_noop!(
+			store_call(ALICE, f32_vector(&[1.0]), &[b"a", b"b"]),
+			Error::<Test>::TooManyTags
+		);
+		VectorsPerAuthor::set(1);
+		store(ALICE, f32_vector(&[1.0, 2.0]), &[b"a"]);
+		assert_noop!(store_call(ALICE, f32_vector(&[3.0]), &[]), Error::<Test>::MaxVectorsReached);
+
+		// Limits above their ceilings are capped.
+		TagsPerVector::set(5);
+		assert_noop!(
+			store_call(BOB, f32_vector(&[1.0]), &[b"a", b"b", b"c"]),
+			Error::<Test>::TooManyTags
+		);
+		VectorsPerAuthor::set(10);
+		store(ALICE, f32_vector(&[3.0]), &[]);
+		store(ALICE, f32_vector(&[4.0]), &[]);
+		assert_noop!(store_call(ALICE, f32_vector(&[5.0]), &[]), Error::<Test>::MaxVectorsReached);
+	});
+}
+
+#[test]
+fn lookups_fail_for_unknown_entries() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::get_weights_by_tag(RuntimeOrigin::signed(ALICE), b"a".to_vec()),
+			Error::<Test>::TagNotFound
+		);
+		assert_noop!(
+			TemplateModule::get_weights_by_author(RuntimeOrigin::signed(ALICE), BOB),
+			Error::<Test>::VectorNotFound
+		);
+
+		store(BOB, f32_vector(&[1.0]), &[b"a"]);
+		assert_ok!(TemplateModule::get_weights_by_tag(RuntimeOrigin::signed(ALICE), b"a".to_vec()));
+		assert_ok!(TemplateModule::get_weights_by_author(RuntimeOrigin::signed(ALICE), BOB));
+	});
+}
+
+#[test]
+fn prune_removes_vectors_and_indexes() {
+	new_test_ext().execute_with(|| {
+		let data = f32_vector(&[1.0, 0.0]);
+		let pruned = store(ALICE, data.clone(), &[b"a"]);
+		let kept = store(ALICE, f32_vector(&[0.0, 1.0]), &[b"a"]);
+		let tag_id = Hashing::hash_of(&b"a".to_vec());
+
+		assert_ok!(TemplateModule::prune_weight_data(
+			RuntimeOrigin::signed(ALICE),
+			vec![pruned, H256::repeat_byte(1)],
+		));
+		// Unknown ids are skipped.
+		System::assert_has_event(Event::VectorPruned { vector_id: pruned, author: ALICE }.into());
+		System::assert_last_event(Event::VectorsPruned { count: 1 }.into());
+
+		assert!(!Vectors::<Test>::contains_key(pruned));
+		assert!(!TagVectors::<Test>::contains_key(tag_id, pruned));
+		assert!(TagVectors::<Test>::contains_key(tag_id, kept));
+		assert_eq!(AuthorVectors::<Test>::get(ALICE).unwrap().to_vec(), vec![kept]);
+
+		// The content index no longer blocks the pruned data.
+		store(BOB, data, &[]);
+
+		assert_ok!(TemplateModule::prune_weight_data(RuntimeOrigin::signed(ALICE), vec![kept]));
+		assert!(!AuthorVectors::<Test>::contains_key(ALICE));
+	});
+}
+
+#[test]
+fn only_authors_and_governance_prune() {
+	new_test_ext().execute_with(|| {
+		let alices = store(ALICE, f32_vector(&[1.0, 0.0]), &[b"a"]);
+		let bobs = store(BOB, f32_vector(&[0.0, 1.0]), &[b"a"]);
+
+		assert_noop!(
+			TemplateModule::prune_weight_data(RuntimeOrigin::signed(BOB), vec![alices]),
+			Error::<Test>::NotAuthor
+		);
+		// The vectors of the caller are not pruned either.
+		assert_noop!(
+			TemplateModule::prune_weight_data(RuntimeOrigin::signed(BOB), vec![bobs, alices]),
+			Error::<Test>::NotAuthor
+		);
+
+		assert_ok!(TemplateModule::prune_weight_data(RuntimeOrigin::root(), vec![alices, bobs]));
+		System::assert_last_event(Event::VectorsPruned { count: 2 }.into());
+		assert_eq!(Vectors::<Test>::iter_keys().count(), 0);
+	});
+}
+
+#[test]
+fn prune_requires_ids() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::prune_weight_data(RuntimeOrigin::signed(ALICE), vec![]),
+			Error::<Test>::NothingToPrune
+		);
+	});
+}
+
+#[test]
+fn collections_use_their_encoding() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::create_collection(
+			RuntimeOrigin::signed(ALICE),
+			VectorEncoding::Binary
+		));
+		System::assert_last_event(
+			Event::CollectionCreated {
+				collection_id: 0,
+				owner: ALICE,
+				encoding: VectorEncoding::Binary,
+				deposit: COLLECTION_DEPOSIT,
+			}
+			.into(),
+		);
+		assert_eq!(NextCollectionId::<Test>::get(), 1);
+		assert_eq!(
+			Balances::balance_on_hold(&HoldReason::CollectionDeposit.into(), &ALICE),
+			COLLECTION_DEPOSIT
+		);
+
+		// Six bytes are not a valid `f32` vector but are a valid binary one.
+		assert_ok!(TemplateModule::store_weight_data(
+			RuntimeOrigin::signed(ALICE),
+			weight_data(vec![0b1010_1010, 0xff, 0x00, 0x0f, 0xf0, 0x55]),
+			tags(&[b"a"]),
+			Some(0),
+			48,
+		));
+		let vector_id = AuthorVectors::<Test>::get(ALICE).unwrap()[0];
+		assert_eq!(Vectors::<Test>::get(vector_id).unwrap().collection, Some(0));
+
+		// Near-duplicates are detected on the decompressed components: a single flipped bit
+		// out of 48 keeps the cosine similarity above 95%.
+		assert_noop!(
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(BOB),
+				weight_data(vec![0b1010_1010, 0xff, 0x01, 0x0f, 0xf0, 0x55]),
+				tags(&[b"a"]),
+				Some(0),
+				48,
+			),
+			Error::<Test>::NearDuplicateVector
+		);
+	});
+}
+
+#[test]
+fn encrypted_collections_store_opaque_ciphertext() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::create_collection(
+			RuntimeOrigin::signed(ALICE),
+			VectorEncoding::Encrypted
+		));
+		assert_ok!(TemplateModule::create_collection(
+			RuntimeOrigin::signed(BOB),
+			VectorEncoding::Encrypted
+		));
+		let key = [7; EPHEMERAL_KEY_LEN];
+		let encrypted = |author, collection, ciphertext: &[u8]| {
+			let mut data = key.to_vec();
+			data.extend_from_slice(&key_proof(author, collection, &key));
+			data.extend_from_slice(ciphertext);
+			data
+		};
+		let store_call = |author, data: Vec<u8>, collection| {
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(author),
+				weight_data(data),
+				tags(&[b"a"]),
+				Some(collection),
+				1,
+			)
+		};
+
+		// An ephemeral key and its proof without a ciphertext are not a valid encrypted vector.
+		assert_noop!(
+			store_call(ALICE, encrypted(ALICE, 0, &[]), 0),
+			Error::<Test>::InvalidVectorData
+		);
+
+		let data = encrypted(ALICE, 0, &[1, 2, 3]);
+		assert_ok!(store_call(ALICE, data.clone(), 0));
+		let vector_id = AuthorVectors::<Test>::get(ALICE).unwrap()[0];
+		assert_eq!(TemplateModule::encrypted_vector(&vector_id), Some((ALICE, key)));
+
+		// The proof binds the ciphertext to its author and collection, so that nobody else can
+		// have it decrypted as its owner.
+		let mut copy = data.clone();
+		copy.push(4);
+		assert_noop!(store_call(BOB, copy.clone(), 0), Error::<Test>::InvalidKeyProof);
+		assert_noop!(store_call(ALICE, copy, 1), Error::<Test>::InvalidKeyProof);
+
+		// Ciphertexts are never compared, only exact duplicates are rejected.
+		store_in(BOB, encrypted(BOB, 0, &[1, 2, 3]), &[b"a"], Some(0), 1);
+
+		let plain = store(BOB, f32_vector(&[1.0]), &[]);
+		assert_eq!(TemplateModule::encrypted_vector(&plain), None);
+	});
+}
+
+#[test]
+fn storing_into_unknown_collection_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(ALICE),
+				weight_data(f32_vector(&[1.0])),
+				vec![],
+				Some(7),
+				1,
+			),
+			Error::<Test>::CollectionNotFound
+		);
+	});
+}
+
+#[test]
+fn vectors_must_match_their_model() {
+	new_test_ext().execute_with(|| {
+		let store_call = |data: Vec<u8>, collection, model| {
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(ALICE),
+				weight_data(data),
+				vec![],
+				collection,
+				model,
+			)
+		};
+
+		assert_noop!(store_call(f32_vector(&[1.0]), None, 0), Error::<Test>::ModelNotFound);
+		assert_noop!(
+			store_call(f32_vector(&[1.0, 2.0, 3.0]), None, 2),
+			Error::<Test>::DimensionMismatch
+		);
+
+		// Quantized vectors have the dimension of their decompressed components.
+		assert_ok!(TemplateModule::create_collection(
+			RuntimeOrigin::signed(ALICE),
+			VectorEncoding::Binary
+		));
+		assert_noop!(store_call(vec![0x0f], Some(0), 1), Error::<Test>::DimensionMismatch);
+		let vector_id = store_in(ALICE, vec![0x0f], &[], Some(0), 8);
+		assert_eq!(Vectors::<Test>::get(vector_id).unwrap().model, 8);
+	});
+}
+
+#[test]
+fn near_duplicates_of_other_models_are_not_compared() {
+	new_test_ext().execute_with(|| {
+		store_in(ALICE, f32_vector(&[1.0, 2.0, 3.0]), &[b"a"], None, 3);
+
+		// Another model of the same dimension embeds into another space.
+		store_in(BOB, f32_vector(&[2.0, 4.0, 6.0]), &[b"a"], None, 0x1_0003);
+		assert_noop!(
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(BOB),
+				weight_data(f32_vector(&[3.0, 6.0, 9.0])),
+				tags(&[b"a"]),
+				None,
+				3,
+			),
+			Error::<Test>::NearDuplicateVector
+		);
+	});
+}
+
+/// Batch item storing the `f32` vector `data` outside of any collection.
+fn item(data: Vec<u8>, tag_data: &[&[u8]]) -> BatchItem<Test> {
+	let model = f32_model(&data);
+	BatchItem { data: data.try_into().unwrap(), tags: tags(tag_data), collection: None, model }
+}
+
+fn batch(items: Vec<BatchItem<Test>>, mode: BatchMode) -> DispatchResult {
+	TemplateModule::store_batch(RuntimeOrigin::signed(ALICE), items.try_into().unwrap(), mode)
+}
+
+#[test]
+fn batches_store_every_item_or_none() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(batch(vec![], BatchMode::AllOrNothing), Error::<Test>::EmptyBatch);
+
+		// The second item duplicates the first one, which is then not stored either.
+		let first = item(f32_vector(&[1.0, 0.0]), &[b"a"]);
+		assert_noop!(
+			batch(vec![first.clone(), first.clone()], BatchMode::AllOrNothing),
+			Error::<Test>::DuplicateVector
+		);
+
+		let second = item(f32_vector(&[0.0, 1.0, 0.0]), &[b"b"]);
+		assert_ok!(batch(vec![first, second], BatchMode::AllOrNothing));
+		let ids = AuthorVectors::<Test>::get(ALICE).unwrap().to_vec();
+		assert_eq!(ids.len(), 2);
+		assert_eq!(Vectors::<Test>::get(ids[1]).unwrap().model, 3);
+		System::assert_has_event(Event::VectorStored { vector_id: ids[0], author: ALICE }.into());
+		System::assert_last_event(
+			Event::BatchStored {
+				author: ALICE,
+				vector_ids: BoundedVec::truncate_from(vec![Some(ids[0]), Some(ids[1])]),
+			}
+			.into(),
+		);
+		check_indexes();
+	});
+}
+
+#[test]
+fn best_effort_batches_skip_failing_items() {
+	new_test_ext().execute_with(|| {
+		let kept = store(ALICE, f32_vector(&[1.0, 2.0]), &[b"a"]);
+
+		assert_ok!(batch(
+			vec![
+				item(f32_vector(&[0.0, 1.0]), &[b"b"]),
+				// A near-duplicate of `kept`, whose new tag must not be left behind.
+				item(f32_vector(&[2.0, 4.0]), &[b"new", b"a"]),
+				BatchItem { model: 0, ..item(f32_vector(&[5.0]), &[]) },
+			],
+			BatchMode::BestEffort
+		));
+
+		let ids = AuthorVectors::<Test>::get(ALICE).unwrap().to_vec();
+		assert_eq!(ids.len(), 2);
+		assert_eq!(ids[0], kept);
+		assert!(!Tags::<Test>::contains_key(Hashing::hash_of(&b"new".to_vec())));
+		System::assert_last_event(
+			Event::BatchStored {
+				author: ALICE,
+				vector_ids: BoundedVec::truncate_from(vec![Some(ids[1]), None, None]),
+			}
+			.into(),
+		);
+		check_indexes();
+	});
+}
+
+#[test]
+fn encodings_compress_components() {
+	let components = [1.0, -0.5, 0.25, 0.0, -1.0, 2.0, 0.5, -0.25];
+	assert_eq!(VectorEncoding::Float32.encode(&components), Some(f32_vector(&components)));
+	assert_eq!(VectorEncoding::Binary.encode(&components), Some(vec![0b1010_0110]));
+	// Eight components per byte.
+	assert_eq!(VectorEncoding::Binary.encode(&components[..4]), None);
+
+	// Quarters, offset by a half.
+	let int8 = VectorEncoding::Int8 { scale: 1 << 14, offset: 1 << 15 };
+	let encoded = int8.encode(&components).unwrap();
+	assert_eq!(encoded, vec![2, 252, 255, 254, 250, 6, 0, 253]);
+	assert_eq!(
+		int8.decode(&encoded).unwrap(),
+		components.iter().map(|c| (*c * 65536.0) as i64).collect::<Vec<_>>()
+	);
+	// Components out of range saturate.
+	assert_eq!(int8.encode(&[1000.0]), Some(vec![127]));
+
+	assert_eq!(VectorEncoding::Float32.encode(&[f32::NAN]), None);
+	assert_eq!(VectorEncoding::Encrypted.encode(&components), None);
+}
+
+#[test]
+fn quantized_scales_must_be_positive() {
+	new_test_ext().execute_with(|| {
+		for scale in [0, -(1 << 16)] {
+			let encoding = VectorEncoding::Int8 { scale, offset: 1 << 15 };
+			assert!(!encoding.is_well_formed());
+			assert!(!encoding.is_valid(&[1, 2, 3]));
+			assert_noop!(
+				TemplateModule::create_collection(RuntimeOrigin::signed(ALICE), encoding),
+				Error::<Test>::InvalidEncoding
+			);
+		}
+		assert!(VectorEncoding::Int8 { scale: 1, offset: i32::MIN }.is_well_formed());
+	});
+}
+
+#[test]
+fn collections_cannot_be_created_without_the_deposit() {
+	new_test_ext().execute_with(|| {
+		const CAROL: u64 = 3;
+		assert_ok!(Balances::mint_into(&CAROL, COLLECTION_DEPOSIT));
+
+		// The deposit would reap the account.
+		assert_noop!(
+			TemplateModule::create_collection(RuntimeOrigin::signed(CAROL), VectorEncoding::Binary),
+			TokenError::FundsUnavailable
+		);
+	});
+}
+
+#[test]
+fn duplicates_are_only_rejected_within_a_collection() {
+	new_test_ext().execute_with(|| {
+		for owner in [ALICE, BOB] {
+			assert_ok!(TemplateModule::create_collection(
+				RuntimeOrigin::signed(owner),
+				VectorEncoding::Float32
+			));
+		}
+		let data = f32_vector(&[1.0, 0.0]);
+		let vector_id = store_in(ALICE, data.clone(), &[b"a"], Some(0), 2);
+		assert_noop!(
+			TemplateModule::store_weight_data(
+				RuntimeOrigin::signed(BOB),
+				weight_data(data.clone()),
+				tags(&[b"b"]),
+				Some(0),
+				2,
+			),
+			Error::<Test>::DuplicateVector
+		);
+
+		// Another collection, or none at all, may hold the same data, even under the same tags.
+		let other = store_in(ALICE, data.clone(), &[b"a"], Some(1), 2);
+		let plain = store_in(ALICE, data.clone(), &[b"a"], None, 2);
+		assert_ne!(vector_id, other);
+		assert_ne!(vector_id, plain);
+		assert_eq!(
+			ContentIndex::<Test>::get(TemplateModule::content_key(Some(1), &data)),
+			Some(other)
+		);
+		check_indexes();
+	});
+}
+
+#[test]
+fn genesis_config_seeds_memory() {
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	crate::GenesisConfig::<Test> {
+		collections: vec![(ALICE, VectorEncoding::Int8 { scale: 1 << 16, offset: 0 })],
+		vectors: vec![
+			(ALICE, Some(0), 3, vec![1, 2, 3], vec![b"a".to_vec()]),
+			(BOB, None, 1, f32_vector(&[1.0]), vec![]),
+		],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	sp_io::TestExternalities::new(t).execute_with(|| {
+		assert!(Collections::<Test>::contains_key(0));
+		assert_eq!(Vectors::<Test>::iter_keys().count(), 2);
+		assert_eq!(AuthorVectors::<Test>::get(ALICE).unwrap().len(), 1);
+		assert_eq!(AuthorVectors::<Test>::get(BOB).unwrap().len(), 1);
+	});
+}
+
+#[test]
+fn try_state_holds_after_storing_and_pruning() {
+	new_test_ext().execute_with(|| {
+		let pruned = store(ALICE, f32_vector(&[1.0, 0.0]), &[b"a", b"b"]);
+		store(ALICE, f32_vector(&[0.0, 1.0]), &[b"a"]);
+		store(BOB, f32_vector(&[1.0, 1.0]), &[]);
+		assert_ok!(TemplateModule::do_try_state());
+
+		assert_ok!(TemplateModule::prune_weight_data(RuntimeOrigin::root(), vec![pruned]));
+		assert_ok!(TemplateModule::do_try_state());
+	});
+}
+
+#[test]
+fn try_state_detects_dangling_author_entries() {
+	new_test_ext().execute_with(|| {
+		let vector_id = store(ALICE, f32_vector(&[1.0]), &[]);
+		Vectors::<Test>::remove(vector_id);
+
+		assert_err!(TemplateModule::do_try_state(), "author list points to a missing vector");
+	});
+}
+
+#[test]
+fn try_state_detects_unknown_tags() {
+	new_test_ext().execute_with(|| {
+		store(ALICE, f32_vector(&[1.0]), &[b"a"]);
+		Tags::<Test>::remove(Hashing::hash_of(&b"a".to_vec()));
+
+		assert_err!(TemplateModule::do_try_state(), "vector references an unknown tag");
+	});
+}
+
+#[test]
+fn migration_from_v0_rebuilds_indexes() {
+	use crate::migrations::v1::{v0, LazyMigrationV1};
+	use frame_support::{
+		migrations::SteppedMigration,
+		traits::{ConstU32, StorageVersion},
+		weights::WeightMeter,
+	};
+
+	new_test_ext().execute_with(|| {
+		let tag_id = Hashing::hash_of(&b"a".to_vec());
+		Tags::<Test>::insert(tag_id, BoundedVec::truncate_from(b"a".to_vec()));
+		let (kept, pruned) = (H256::repeat_byte(1), H256::repeat_byte(2));
+		let data = f32_vector(&[1.0]);
+		let tag_ids = BoundedVec::truncate_from(vec![tag_id]);
+		v0::Vectors::<Test>::insert(kept, (data.clone().try_into().unwrap(), ALICE, tag_ids));
+		// Version 0 left pruned vectors in the list of their author.
+		AuthorVectors::<Test>::insert(ALICE, BoundedVec::truncate_from(vec![kept, pruned]));
+		AuthorVectors::<Test>::insert(BOB, BoundedVec::truncate_from(vec![pruned]));
+		StorageVersion::new(0).put::<TemplateModule>();
+
+		// Resume from the cursor until the migration completes, a single author per step.
+		let limit = <() as WeightInfo>::migration_author_step();
+		let mut cursor = None;
+		loop {
+			cursor = LazyMigrationV1::<Test, (), ConstU32<1>>::step(
+				cursor,
+				&mut WeightMeter::with_limit(limit),
+			)
+			.unwrap();
+			if cursor.is_none() {
+				break;
+			}
+		}
+
+		assert_eq!(StorageVersion::get::<TemplateModule>(), StorageVersion::new(1));
+		assert_eq!(ContentIndex::<Test>::get(TemplateModule::content_key(None, &data)), Some(kept));
+		assert!(TagVectors::<Test>::contains_key(tag_id, kept));
+		assert_eq!(AuthorVectors::<Test>::get(ALICE).unwrap().to_vec(), vec![kept]);
+		assert!(!AuthorVectors::<Test>::contains_key(BOB));
+		let record = Vectors::<Test>::get(kept).unwrap();
+		assert_eq!((record.author, record.collection, record.model), (ALICE, None, 1));
+		assert_ok!(TemplateModule::do_try_state());
+	});
+}
@@ -1,127 +1,204 @@
-
-//! Autogenerated weights for pallet_template
+//! Weights for pallet_template
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2023-04-06, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! WORST CASE MAP SIZE: `1000000`
-//! HOSTNAME: `Alexs-MacBook-Pro-2.local`, CPU: `<UNKNOWN>`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
-
-// Executed Command:
-// ../../target/release/node-template
-// benchmark
-// pallet
-// --chain
-// dev
-// --pallet
-// pallet_template
-// --extrinsic
-// *
-// --steps=50
-// --repeat=20
-// --wasm-execution=compiled
-// --output
-// pallets/template/src/weights.rs
-// --template
-// ../../.maintain/frame-weight-template.hbs
+//! PLACEHOLDER WEIGHTS, written by hand rather than measured. Storage reads, writes and proof
+//! sizes follow the storage accessed by the worst case of each benchmark in `benchmarking.rs`,
+//! for the runtime configuration (`MaxVectors = 1000`, `MaxVectorLength = 1000`,
+//! `MaxTagsPerVector = 10`, `MaxSimilaritySamples = 32`, `MaxBatchSize = 32`). The ref-time
+//! components are guesses. Replace this file with the output of the node's `benchmark pallet`
+//! command, run on reference hardware, before relying on these weights.
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
 #![allow(unused_imports)]
 
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
 use core::marker::PhantomData;
 
-use frame_support::{dispatch::DispatchResult, traits::Get, weights::{constants::RocksDbWeight, Weight}, Blake2_128Concat, StorageMap};
-use frame_support::sp_runtime::BoundedVec;
-use frame_support::pallet_prelude::{OptionQuery, IsType};
-use frame_system::{ensure_signed, Account, Event};
-
-
+/// Weight functions needed for pallet_template.
 pub trait WeightInfo {
-	/// Weight for storing embedding data including vector, description, and tags
-	fn store_weight_data(vector_size: u32, tag_count: u32) -> Weight;
-	/// Weight for pruning old weight data
-	fn prune_weight_data(removal_count: u32) -> Weight;
-	/// Weight for retrieving weights by tag
-	fn get_weights_by_tag(result_count: u32) -> Weight;
-	/// Weight for retrieving weights by author
-	fn get_weights_by_author(result_count: u32) -> Weight;
-	/// Weight for handling weight-related errors
-	fn weight_error() -> Weight;
+	fn store_weight_data(v: u32, t: u32, ) -> Weight;
+	fn get_weights_by_tag() -> Weight;
+	fn get_weights_by_author() -> Weight;
+	fn prune_weight_data(n: u32, ) -> Weight;
+	fn create_collection() -> Weight;
+	fn migration_step() -> Weight;
+	fn migration_author_step() -> Weight;
+	fn store_batch(n: u32, b: u32, ) -> Weight;
 }
 
-
-
-/// Weights for pallet_template using the Substrate node and recommended hardware.
+/// Placeholder weights for pallet_template, until it is benchmarked on reference hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	fn store_weight_data(vector_size: u32, tag_count: u32) -> Weight {
-		// Base weight for processing
-		let mut weight = Weight::from_parts(9_000_000, 0);
-		
-		// Add weight for vector size processing
-		weight = weight.saturating_add(Weight::from_parts(50_000, 0).saturating_mul(vector_size.into()));
-		
-		// Add weight for each tag
-		weight = weight.saturating_add(Weight::from_parts(100_000, 0).saturating_mul(tag_count.into()));
-		
-		// Storage operations
-		weight.saturating_add(T::DbWeight::get().writes(2_u64)) // One for vector, one for metadata
-			.saturating_add(T::DbWeight::get().writes(tag_count.into())) // One write per tag
+	/// Storage: `TemplateModule::Collections` (r:1 w:0)
+	/// Storage: `ModelRegistry::Models` (r:1 w:0)
+	/// Storage: `TemplateModule::ContentIndex` (r:1 w:1)
+	/// Storage: `TemplateModule::Tags` (r:0 w:10)
+	/// Storage: `TemplateModule::TagVectors` (r:33 w:10)
+	/// Storage: `TemplateModule::Vectors` (r:32 w:1)
+	/// Storage: `TemplateModule::AuthorVectors` (r:1 w:1)
+	/// Storage: `Parameters::Parameters` (r:5 w:0)
+	/// The range of component `v` is `[1, 1000]`.
+	/// The range of component `t` is `[1, 10]`.
+	fn store_weight_data(v: u32, t: u32, ) -> Weight {
+		Weight::from_parts(219_840_000, 86_263)
+			.saturating_add(Weight::from_parts(1_318_400, 0).saturating_mul(v.into()))
+			.saturating_add(Weight::from_parts(6_412_000, 0).saturating_mul(t.into()))
+			.saturating_add(T::DbWeight::get().reads(74_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(t.into())))
+			.saturating_add(Weight::from_parts(0, 32).saturating_mul(v.into()))
 	}
-
-	fn prune_weight_data(removal_count: u32) -> Weight {
-		Weight::from_parts(5_000_000, 0)
-			.saturating_add(Weight::from_parts(500_000, 0).saturating_mul(removal_count.into()))
-			.saturating_add(T::DbWeight::get().reads(removal_count.into()))
-			.saturating_add(T::DbWeight::get().writes(removal_count.into()))
+	/// Storage: `TemplateModule::Tags` (r:1 w:0)
+	fn get_weights_by_tag() -> Weight {
+		Weight::from_parts(9_420_000, 3_557)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
 	}
-
-	fn get_weights_by_tag(result_count: u32) -> Weight {
-		Weight::from_parts(5_000_000, 0)
-			.saturating_add(Weight::from_parts(100_000, 0).saturating_mul(result_count.into()))
-			.saturating_add(T::DbWeight::get().reads(result_count.saturating_add(1).into()))
+	/// Storage: `TemplateModule::AuthorVectors` (r:1 w:0)
+	fn get_weights_by_author() -> Weight {
+		Weight::from_parts(11_630_000, 35_555)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
 	}
-
-	fn get_weights_by_author(result_count: u32) -> Weight {
-		Weight::from_parts(5_000_000, 0)
-			.saturating_add(Weight::from_parts(100_000, 0).saturating_mul(result_count.into()))
-			.saturating_add(T::DbWeight::get().reads(result_count.saturating_add(1).into()))
+	/// Storage: `TemplateModule::Vectors` (r:1000 w:1000)
+	/// Storage: `TemplateModule::ContentIndex` (r:0 w:1000)
+	/// Storage: `TemplateModule::TagVectors` (r:0 w:10000)
+	/// Storage: `TemplateModule::AuthorVectors` (r:1000 w:1000)
+	/// Storage: `Reputation::Records` (r:1000 w:1000)
+	/// The range of component `n` is `[1, 1000]`.
+	fn prune_weight_data(n: u32, ) -> Weight {
+		Weight::from_parts(12_100_000, 990)
+			.saturating_add(Weight::from_parts(41_520_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((14_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 35_530).saturating_mul(n.into()))
 	}
-
-	fn weight_error() -> Weight {
-		Weight::from_parts(6_000_000, 1489)
-			.saturating_add(T::DbWeight::get().reads(1_u64))
+	/// Storage: `TemplateModule::NextCollectionId` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `TemplateModule::Collections` (r:0 w:1)
+	fn create_collection() -> Weight {
+		Weight::from_parts(41_240_000, 4_764)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: `TemplateModule::Vectors` (r:2 w:1)
+	/// Storage: `TemplateModule::ContentIndex` (r:1 w:1)
+	/// Storage: `TemplateModule::TagVectors` (r:0 w:10)
+	fn migration_step() -> Weight {
+		Weight::from_parts(25_410_000, 6_020)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(12_u64))
+	}
+	/// Storage: `TemplateModule::AuthorVectors` (r:2 w:1)
+	/// Storage: `TemplateModule::Vectors` (r:1000 w:0)
+	fn migration_author_step() -> Weight {
+		Weight::from_parts(4_120_000_000, 2_531_020)
+			.saturating_add(T::DbWeight::get().reads(1002_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: `TemplateModule::Collections` (r:32 w:0)
+	/// Storage: `ModelRegistry::Models` (r:32 w:0)
+	/// Storage: `TemplateModule::ContentIndex` (r:32 w:32)
+	/// Storage: `TemplateModule::Tags` (r:0 w:320)
+	/// Storage: `TemplateModule::TagVectors` (r:1056 w:320)
+	/// Storage: `TemplateModule::Vectors` (r:1024 w:32)
+	/// Storage: `TemplateModule::AuthorVectors` (r:32 w:32)
+	/// Storage: `Parameters::Parameters` (r:160 w:0)
+	/// The range of component `n` is `[1, 32]`.
+	/// The range of component `b` is `[1, 32000]`.
+	fn store_batch(n: u32, b: u32, ) -> Weight {
+		Weight::from_parts(11_260_000, 0)
+			.saturating_add(Weight::from_parts(284_130_000, 86_263).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(1_318_400, 0).saturating_mul(b.into()))
+			.saturating_add(T::DbWeight::get().reads((74_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((23_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 32).saturating_mul(b.into()))
+	}
 }
 
-
-// For backwards compatibility and tests
+// For backwards compatibility and tests.
 impl WeightInfo for () {
-	fn store_weight_data(_vector_size: u32, _tag_count: u32) -> Weight {
-		Weight::from_parts(9_000_000, 0)
-			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	/// Storage: `TemplateModule::Collections` (r:1 w:0)
+	/// Storage: `ModelRegistry::Models` (r:1 w:0)
+	/// Storage: `TemplateModule::ContentIndex` (r:1 w:1)
+	/// Storage: `TemplateModule::Tags` (r:0 w:10)
+	/// Storage: `TemplateModule::TagVectors` (r:33 w:10)
+	/// Storage: `TemplateModule::Vectors` (r:32 w:1)
+	/// Storage: `TemplateModule::AuthorVectors` (r:1 w:1)
+	/// Storage: `Parameters::Parameters` (r:5 w:0)
+	/// The range of component `v` is `[1, 1000]`.
+	/// The range of component `t` is `[1, 10]`.
+	fn store_weight_data(v: u32, t: u32, ) -> Weight {
+		Weight::from_parts(219_840_000, 86_263)
+			.saturating_add(Weight::from_parts(1_318_400, 0).saturating_mul(v.into()))
+			.saturating_add(Weight::from_parts(6_412_000, 0).saturating_mul(t.into()))
+			.saturating_add(RocksDbWeight::get().reads(74_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(t.into())))
+			.saturating_add(Weight::from_parts(0, 32).saturating_mul(v.into()))
 	}
-
-	fn prune_weight_data(_removal_count: u32) -> Weight {
-		Weight::from_parts(5_000_000, 0)
-			.saturating_add(RocksDbWeight::get().writes(1_u64))
-	}
-
-	fn get_weights_by_tag(_result_count: u32) -> Weight {
-		Weight::from_parts(5_000_000, 0)
+	/// Storage: `TemplateModule::Tags` (r:1 w:0)
+	fn get_weights_by_tag() -> Weight {
+		Weight::from_parts(9_420_000, 3_557)
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 	}
-
-	fn get_weights_by_author(_result_count: u32) -> Weight {
-		Weight::from_parts(5_000_000, 0)
+	/// Storage: `TemplateModule::AuthorVectors` (r:1 w:0)
+	fn get_weights_by_author() -> Weight {
+		Weight::from_parts(11_630_000, 35_555)
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 	}
-
-	fn weight_error() -> Weight {
-		Weight::from_parts(6_000_000, 1489)
-			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	/// Storage: `TemplateModule::Vectors` (r:1000 w:1000)
+	/// Storage: `TemplateModule::ContentIndex` (r:0 w:1000)
+	/// Storage: `TemplateModule::TagVectors` (r:0 w:10000)
+	/// Storage: `TemplateModule::AuthorVectors` (r:1000 w:1000)
+	/// Storage: `Reputation::Records` (r:1000 w:1000)
+	/// The range of component `n` is `[1, 1000]`.
+	fn prune_weight_data(n: u32, ) -> Weight {
+		Weight::from_parts(12_100_000, 990)
+			.saturating_add(Weight::from_parts(41_520_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((14_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 35_530).saturating_mul(n.into()))
+	}
+	/// Storage: `TemplateModule::NextCollectionId` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `TemplateModule::Collections` (r:0 w:1)
+	fn create_collection() -> Weight {
+		Weight::from_parts(41_240_000, 4_764)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: `TemplateModule::Vectors` (r:2 w:1)
+	/// Storage: `TemplateModule::ContentIndex` (r:1 w:1)
+	/// Storage: `TemplateModule::TagVectors` (r:0 w:10)
+	fn migration_step() -> Weight {
+		Weight::from_parts(25_410_000, 6_020)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(12_u64))
+	}
+	/// Storage: `TemplateModule::AuthorVectors` (r:2 w:1)
+	/// Storage: `TemplateModule::Vectors` (r:1000 w:0)
+	fn migration_author_step() -> Weight {
+		Weight::from_parts(4_120_000_000, 2_531_020)
+			.saturating_add(RocksDbWeight::get().reads(1002_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: `TemplateModule::Collections` (r:32 w:0)
+	/// Storage: `ModelRegistry::Models` (r:32 w:0)
+	/// Storage: `TemplateModule::ContentIndex` (r:32 w:32)
+	/// Storage: `TemplateModule::Tags` (r:0 w:320)
+	/// Storage: `TemplateModule::TagVectors` (r:1056 w:320)
+	/// Storage: `TemplateModule::Vectors` (r:1024 w:32)
+	/// Storage: `TemplateModule::AuthorVectors` (r:32 w:32)
+	/// Storage: `Parameters::Parameters` (r:160 w:0)
+	/// The range of component `n` is `[1, 32]`.
+	/// The range of component `b` is `[1, 32000]`.
+	fn store_batch(n: u32, b: u32, ) -> Weight {
+		Weight::from_parts(11_260_000, 0)
+			.saturating_add(Weight::from_parts(284_130_000, 86_263).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(1_318_400, 0).saturating_mul(b.into()))
+			.saturating_add(RocksDbWeight::get().reads((74_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((23_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 32).saturating_mul(b.into()))
+	}
 }
@@ -0,0 +1,430 @@
+//! Benchmarking setup for pallet-threshold-encryption
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as ThresholdEncryption;
+use crate::crypto::{RistrettoPoint, Scalar};
+use alloc::vec::Vec;
+use codec::Encode;
+use frame_benchmarking::v2::*;
+use frame_support::{traits::Get, BoundedVec};
+use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
+use sp_runtime::traits::{One, Saturating};
+
+fn generator() -> RistrettoPoint {
+	curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT
+}
+
+/// Install a committee of `m` members directly and return them.
+fn committee<T: Config>(m: u32) -> Vec<T::AccountId> {
+	let members: Vec<T::AccountId> = (0..m).map(|i| account("member", i, 0)).collect();
+	Members::<T>::put(BoundedVec::<_, T::MaxMembers>::truncate_from(members.clone()));
+	Threshold::<T>::put((2 * m).div_ceil(3));
+	members
+}
+
+/// Coefficients of the committee's sharing polynomial.
+fn coefficients<T: Config>() -> Vec<Scalar> {
+	(0..Threshold::<T>::get()).map(|k| Scalar::from(k as u64 + 1)).collect()
+}
+
+/// Install the commitments of [`coefficients`] as the committee key.
+fn install_key<T: Config>() {
+	let commitments: Vec<_> =
+		coefficients::<T>().iter().map(|a| crypto::encode_point(&(generator() * a))).collect();
+	Commitments::<T>::put(BoundedVec::truncate_from(commitments));
+}
+
+fn evaluate(coefficients: &[Scalar], index: u32) -> Scalar {
+	coefficients.iter().rev().fold(Scalar::ZERO, |acc, a| acc * Scalar::from(index as u64) + a)
+}
+
+/// Secret of the encryption key of the incoming member at `position`.
+fn encryption_secret(position: u32) -> Scalar {
+	Scalar::from(position as u64 + 1)
+}
+
+/// Start a rotation to `m` incoming members directly and return them.
+fn start<T: Config>(m: u32) -> Vec<T::AccountId> {
+	let members: Vec<T::AccountId> = (0..m).map(|i| account("incoming", i, 0)).collect();
+	let keys = members
+		.iter()
+		.enumerate()
+		.map(|(i, member)| {
+			let key = crypto::encode_point(&crypto::public_key(&encryption_secret(i as u32)));
+			(member.clone(), key)
+		})
+		.collect::<Vec<_>>();
+	let dealing_deadline =
+		frame_system::Pallet::<T>::block_number().saturating_add(T::DealingPeriod::get());
+	Rotation::<T>::put(RotationInfo {
+		epoch: 1,
+		members: BoundedVec::truncate_from(keys),
+		threshold: (2 * m).div_ceil(3),
+		dealing_deadline,
+		complaint_deadline: dealing_deadline.saturating_add(T::ComplaintPeriod::get()),
+	});
+	members
+}
+
+/// Dealing of `coefficients` to the `m` incoming members.
+fn encrypted_dealing<T: Config>(coefficients: &[Scalar], m: u32) -> EncryptedDealing<T> {
+	let ephemeral_secret = Scalar::from(7u64);
+	let shares = (0..m)
+		.map(|position| {
+			let recipient = crypto::public_key(&encryption_secret(position));
+			let share = evaluate(coefficients, position + 1);
+			crypto::encrypt_share(&ephemeral_secret, &recipient, position + 1, &share)
+		})
+		.collect::<Vec<_>>();
+	let commitments = coefficients
+		.iter()
+		.map(|a| crypto::encode_point(&crypto::public_key(a)))
+		.collect::<Vec<_>>();
+	EncryptedDealing {
+		commitments: BoundedVec::truncate_from(commitments),
+		ephemeral_key: crypto::encode_point(&crypto::public_key(&ephemeral_secret)),
+		shares: BoundedVec::truncate_from(shares),
+	}
+}
+
+/// Coefficients of a fresh polynomial with `threshold` coefficients.
+fn fresh_coefficients(threshold: u32) -> Vec<Scalar> {
+	(0..threshold).map(|k| Scalar::from(k as u64 + 3)).collect()
+}
+
+/// Move past the deadline `deadline`.
+fn pass<T: Config>(deadline: BlockNumberFor<T>) {
+	frame_system::Pallet::<T>::set_block_number(deadline.saturating_add(One::one()));
+}
+
+/// Insert a reshare and a fresh dealing for each of `m` incoming members directly.
+fn insert_dealings<T: Config>(m: u32) {
+	let dealing = encrypted_dealing::<T>(&fresh_coefficients((2 * m).div_ceil(3)), m);
+	for i in 0..m {
+		let outgoing: T::AccountId = account("member", i, 0);
+		let incoming: T::AccountId = account("incoming", i, 0);
+		RotationDealings::<T>::insert(DealingKind::Reshare, outgoing, &dealing);
+		RotationDealings::<T>::insert(DealingKind::Fresh, incoming, &dealing);
+	}
+}
+
+/// Finalize a rotation to `m` incoming members directly, with [`fresh_coefficients`] as the
+/// rekey polynomial, and return the incoming members.
+fn pending_key<T: Config>(m: u32) -> Vec<T::AccountId> {
+	let members: Vec<T::AccountId> = (0..m).map(|i| account("incoming", i, 0)).collect();
+	let threshold = (2 * m).div_ceil(3);
+	let commitments = fresh_coefficients(threshold)
+		.iter()
+		.map(|a| crypto::encode_point(&crypto::public_key(a)))
+		.collect::<Vec<_>>();
+	PendingKey::<T>::put(PendingKeyInfo {
+		epoch: 1,
+		members: BoundedVec::truncate_from(members.clone()),
+		threshold,
+		commitments: BoundedVec::truncate_from(commitments.clone()),
+		rekey_commitments: BoundedVec::truncate_from(commitments),
+		resharers: BoundedVec::default(),
+		fresh_dealers: BoundedVec::truncate_from((1..=m).collect::<Vec<_>>()),
+	});
+	LatestEpoch::<T>::put(1);
+	members
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	// Worst case: a full committee's worth of dealings of restarted key generation is cleared.
+	#[benchmark]
+	fn set_committee(m: Linear<1, { T::MaxMembers::get() }>) -> Result<(), BenchmarkError> {
+		let origin =
+			T::CommitteeOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let stale_dealing = encrypted_dealing::<T>(&fresh_coefficients(1), 1);
+		for i in 0..T::MaxMembers::get() {
+			let stale: T::AccountId = account("stale", i, 0);
+			RotationDealings::<T>::insert(DealingKind::Fresh, stale, &stale_dealing);
+		}
+		let members = (0..m)
+			.map(|i| {
+				let key = crypto::encode_point(&crypto::public_key(&encryption_secret(i)));
+				(account("member", i, 0), key)
+			})
+			.collect::<Vec<_>>();
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, BoundedVec::truncate_from(members));
+
+		assert_eq!(Rotation::<T>::get().map(|r| r.members.len() as u32), Some(m));
+		assert_eq!(RotationDealings::<T>::iter_keys().count(), 0);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn submit_dealing(m: Linear<1, { T::MaxMembers::get() }>) {
+		// Key generation is a rotation of epoch zero, before any committee key exists.
+		let members = start::<T>(m);
+		Rotation::<T>::mutate(|rotation| {
+			if let Some(rotation) = rotation {
+				rotation.epoch = 0;
+			}
+		});
+		let coefficients = fresh_coefficients((2 * m).div_ceil(3));
+		let dealing = encrypted_dealing::<T>(&coefficients, m);
+		let context = (0 as EpochIndex, &members[0]).encode();
+		let proof = crypto::prove_schnorr(&context, &coefficients[0], &Scalar::from(11u64));
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(members[0].clone()), dealing, proof);
+
+		assert!(RotationDealings::<T>::contains_key(DealingKind::Fresh, &members[0]));
+	}
+
+	// Worst case: the caller does not own the payload and consumes an authorization.
+	#[benchmark]
+	fn request_decryption() {
+		committee::<T>(T::MaxMembers::get());
+		install_key::<T>();
+		let owner: T::AccountId = account("owner", 0, 0);
+		let caller: T::AccountId = whitelisted_caller();
+		let ephemeral_key = generator() * Scalar::from(7u64);
+		let payload = T::BenchmarkHelper::create_payload(owner, Scalar::from(7u64).to_bytes());
+		Authorizations::<T>::insert(&payload, &caller, ());
+
+		let blinding = Scalar::from(9u64);
+		let blinded_key = crypto::encode_point(&(ephemeral_key + generator() * blinding));
+		let context = (&caller, &payload, &blinded_key).encode();
+		let proof = crypto::prove_schnorr(&context, &blinding, &Scalar::from(11u64));
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), payload, blinded_key, proof);
+
+		assert!(Requests::<T>::contains_key(0));
+	}
+
+	// Worst case: the share completes the threshold and every share is combined.
+	#[benchmark]
+	fn submit_decryption_share(m: Linear<1, { T::MaxMembers::get() }>) {
+		let members = committee::<T>(m);
+		install_key::<T>();
+		let threshold = Threshold::<T>::get();
+		let owner: T::AccountId = account("owner", 0, 0);
+		let blinded = generator() * Scalar::from(7u64);
+		let payload =
+			T::BenchmarkHelper::create_payload(owner.clone(), Scalar::from(7u64).to_bytes());
+		let filler = crypto::encode_point(&generator());
+		Requests::<T>::insert(
+			0,
+			DecryptionRequest {
+				requester: owner,
+				payload,
+				blinded_key: crypto::encode_point(&blinded),
+				epoch: 0,
+				shares: BoundedVec::truncate_from(
+					(1..threshold).map(|index| (index, filler)).collect::<Vec<_>>(),
+				),
+				released: None,
+			},
+		);
+
+		// The caller holds the key share of index `threshold`.
+		let index = threshold;
+		let key_share = coefficients::<T>()
+			.iter()
+			.rev()
+			.fold(Scalar::ZERO, |acc, a| acc * Scalar::from(index as u64) + a);
+		let context = (0 as RequestId, index).encode();
+		let (share, proof) =
+			crypto::prove_dleq(&context, &key_share, &blinded, &Scalar::from(13u64));
+		let caller = members[index as usize - 1].clone();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), 0, crypto::encode_point(&share), proof);
+
+		assert!(Requests::<T>::get(0).and_then(|r| r.released).is_some());
+	}
+
+	#[benchmark]
+	fn authorize_decryption() -> Result<(), BenchmarkError> {
+		let origin =
+			T::AuthorizeOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let owner: T::AccountId = account("owner", 0, 0);
+		let payload = T::BenchmarkHelper::create_payload(owner, Scalar::ONE.to_bytes());
+		let auditor: T::AccountId = account("auditor", 0, 0);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, payload.clone(), auditor.clone());
+
+		assert!(Authorizations::<T>::contains_key(&payload, &auditor));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn start_rotation(m: Linear<1, { T::MaxMembers::get() }>) -> Result<(), BenchmarkError> {
+		let origin =
+			T::CommitteeOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		committee::<T>(T::MaxMembers::get());
+		install_key::<T>();
+		let members = (0..m)
+			.map(|i| {
+				let key = crypto::encode_point(&crypto::public_key(&encryption_secret(i)));
+				(account("incoming", i, 0), key)
+			})
+			.collect::<Vec<_>>();
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, BoundedVec::truncate_from(members));
+
+		assert_eq!(Rotation::<T>::get().map(|r| r.members.len() as u32), Some(m));
+		Ok(())
+	}
+
+	// Worst case: the outgoing committee is full, so its public share is the most expensive.
+	#[benchmark]
+	fn submit_reshare(m: Linear<1, { T::MaxMembers::get() }>) {
+		let members = committee::<T>(T::MaxMembers::get());
+		install_key::<T>();
+		start::<T>(m);
+		let key_share = evaluate(&coefficients::<T>(), 1);
+		let mut reshare = fresh_coefficients((2 * m).div_ceil(3));
+		reshare[0] = key_share;
+		let dealing = encrypted_dealing::<T>(&reshare, m);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(members[0].clone()), dealing);
+
+		assert!(RotationDealings::<T>::contains_key(DealingKind::Reshare, &members[0]));
+	}
+
+	#[benchmark]
+	fn submit_fresh_dealing(m: Linear<1, { T::MaxMembers::get() }>) {
+		let incoming = start::<T>(m);
+		let coefficients = fresh_coefficients((2 * m).div_ceil(3));
+		let dealing = encrypted_dealing::<T>(&coefficients, m);
+		let context = (1 as EpochIndex, &incoming[0]).encode();
+		let proof = crypto::prove_schnorr(&context, &coefficients[0], &Scalar::from(11u64));
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(incoming[0].clone()), dealing, proof);
+
+		assert!(RotationDealings::<T>::contains_key(DealingKind::Fresh, &incoming[0]));
+	}
+
+	// Worst case: the complaint is justified and the dealing is discarded.
+	#[benchmark]
+	fn complain(m: Linear<1, { T::MaxMembers::get() }>) {
+		let incoming = start::<T>(m);
+		let dealer: T::AccountId = account("member", 0, 0);
+		let mut dealing = encrypted_dealing::<T>(&fresh_coefficients((2 * m).div_ceil(3)), m);
+		dealing.shares[0] = [0; 32];
+		let ephemeral_key = crypto::decode_point(&dealing.ephemeral_key).expect("valid point");
+		RotationDealings::<T>::insert(DealingKind::Reshare, &dealer, dealing);
+		pass::<T>(Rotation::<T>::get().expect("rotation started").dealing_deadline);
+
+		let context = (1 as EpochIndex, &incoming[0], &dealer, DealingKind::Reshare).encode();
+		let (shared, proof) = crypto::prove_dleq(
+			&context,
+			&encryption_secret(0),
+			&ephemeral_key,
+			&Scalar::from(13u64),
+		);
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Signed(incoming[0].clone()),
+			DealingKind::Reshare,
+			dealer.clone(),
+			crypto::encode_point(&shared),
+			proof,
+		);
+
+		assert!(!RotationDealings::<T>::contains_key(DealingKind::Reshare, &dealer));
+	}
+
+	// Worst case: every outgoing and every incoming member dealt. Completing key generation skips
+	// the interpolation of the reshares, which outweighs the few more writes it makes.
+	#[benchmark]
+	fn finalize_rotation(m: Linear<1, { T::MaxMembers::get() }>) {
+		committee::<T>(T::MaxMembers::get());
+		install_key::<T>();
+		start::<T>(m);
+		let dealing = encrypted_dealing::<T>(&fresh_coefficients((2 * m).div_ceil(3)), m);
+		for i in 0..T::MaxMembers::get() {
+			let dealer: T::AccountId = account("member", i, 0);
+			RotationDealings::<T>::insert(DealingKind::Reshare, dealer, &dealing);
+		}
+		for i in 0..m {
+			let dealer: T::AccountId = account("incoming", i, 0);
+			RotationDealings::<T>::insert(DealingKind::Fresh, dealer, &dealing);
+		}
+		pass::<T>(Rotation::<T>::get().expect("rotation started").complaint_deadline);
+		let caller: T::AccountId = whitelisted_caller();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller));
+
+		assert!(PendingKey::<T>::exists());
+	}
+
+	#[benchmark]
+	fn cancel_rotation(m: Linear<1, { T::MaxMembers::get() }>) -> Result<(), BenchmarkError> {
+		let origin =
+			T::CommitteeOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		start::<T>(m);
+		insert_dealings::<T>(m);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin);
+
+		assert!(!Rotation::<T>::exists());
+		assert_eq!(RotationDealings::<T>::iter_keys().count(), 0);
+		Ok(())
+	}
+
+	// Worst case: the share completes the threshold on top of an earlier offset.
+	#[benchmark]
+	fn submit_rekey_share(m: Linear<1, { T::MaxMembers::get() }>) {
+		let members = pending_key::<T>(m);
+		let threshold = (2 * m).div_ceil(3);
+		let owner: T::AccountId = account("owner", 0, 0);
+		let base = generator() * Scalar::from(7u64);
+		let payload = T::BenchmarkHelper::create_payload(owner, Scalar::from(7u64).to_bytes());
+		Offsets::<T>::insert(0, &payload, crypto::encode_point(&generator()));
+		let filler = crypto::encode_point(&generator());
+		RekeyShares::<T>::insert(
+			1,
+			&payload,
+			BoundedVec::truncate_from((1..threshold).map(|i| (i, filler)).collect::<Vec<_>>()),
+		);
+
+		// The caller holds the rekey share of index `threshold`.
+		let index = threshold;
+		let secret = evaluate(&fresh_coefficients(threshold), index);
+		let context = (1 as EpochIndex, &payload, index).encode();
+		let (share, proof) = crypto::prove_dleq(&context, &secret, &base, &Scalar::from(13u64));
+		let caller = members[index as usize - 1].clone();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), payload.clone(), crypto::encode_point(&share), proof);
+
+		assert!(Offsets::<T>::contains_key(1, &payload));
+	}
+
+	#[benchmark]
+	fn retire_key(m: Linear<1, { T::MaxMembers::get() }>) -> Result<(), BenchmarkError> {
+		let origin =
+			T::CommitteeOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		pending_key::<T>(m);
+		insert_dealings::<T>(m);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin);
+
+		assert_eq!(Epoch::<T>::get(), 1);
+		assert_eq!(Members::<T>::get().len() as u32, m);
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(ThresholdEncryption, crate::mock::new_test_ext(), crate::mock::Test);
+}
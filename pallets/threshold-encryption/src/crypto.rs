@@ -0,0 +1,313 @@
+//! Threshold ElGamal over the ristretto255 group.
+//!
+//! The committee shares a secret key `x` using Shamir's scheme of degree `threshold - 1`. Each
+//! member `i` (indexed from one) holds `x_i` and the Feldman commitments `C_k = a_k * G` to the
+//! coefficients of the sharing polynomial are public, so that `C_0` is the aggregate public key
+//! and `X_i = sum(C_k * i^k)` is the public share of member `i`.
+//!
+//! A client encrypts a payload by picking an ephemeral secret `r`, publishing `U = r * G` next to
+//! the ciphertext and deriving the symmetric key from `K = r * C_0` with [`symmetric_key`]. To
+//! recover `K` without revealing it on chain, the requester blinds the ephemeral key as
+//! `U' = U + b * G` and proves knowledge of `b` ([`SchnorrProof`]). Members answer with
+//! `D_i = x_i * U'` and prove it is well formed ([`DleqProof`]). Any `threshold` shares combine
+//! into `x * U' = K + b * C_0`, from which only the requester can remove the blinding. Whoever
+//! stores a ciphertext proves knowledge of `r` with [`prove_ephemeral_key`], bound to itself and
+//! to where the ciphertext is stored. Otherwise anyone could store a copy of `U`, or
+//! `U + s * G` for an `s` of its choosing, and have the committee decrypt it as its owner.
+//!
+//! To rotate the key, every member `i` of the outgoing committee reshares `x_i` with a fresh
+//! polynomial whose commitments `E_k` satisfy `E_0 = X_i`, and the incoming members additionally
+//! deal a fresh key. Shares are posted on chain encrypted to each recipient's encryption key with
+//! [`encrypt_share`]: the dealer publishes `R = p * G` and masks the share of the recipient with
+//! public key `P_j` by a scalar derived from `p * P_j`. A recipient holding a bad share reveals
+//! `sk_j * R` with a [`DleqProof`], which lets anyone decrypt the share and check it against the
+//! commitments. Interpolating the reshares at zero with [`interpolate_commitments`] yields
+//! commitments to the old key, so the incoming members can turn a payload's ephemeral key `U` into
+//! the offset `(x' - x) * U` between the new and the old shared point without anyone learning
+//! either.
+//!
+//! All nonces are chosen by the caller; provers must use fresh, uniformly random scalars.
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode, MaxEncodedLen};
+use curve25519_dalek::{
+	constants::RISTRETTO_BASEPOINT_POINT as G, ristretto::CompressedRistretto, traits::Identity,
+};
+use frame_support::RuntimeDebug;
+use scale_info::TypeInfo;
+
+pub use curve25519_dalek::{RistrettoPoint, Scalar};
+
+/// A compressed ristretto point.
+pub type PointBytes = [u8; 32];
+
+/// Canonical little-endian encoding of a scalar.
+pub type ScalarBytes = [u8; 32];
+
+/// Domain separators of the Fiat-Shamir challenges.
+const SCHNORR_DOMAIN: &[u8] = b"threshold-encryption/schnorr";
+const DLEQ_DOMAIN: &[u8] = b"threshold-encryption/dleq";
+const KEY_DOMAIN: &[u8] = b"threshold-encryption/key";
+const SHARE_DOMAIN: &[u8] = b"threshold-encryption/share";
+const NONCE_DOMAIN: &[u8] = b"threshold-encryption/nonce";
+const EPHEMERAL_DOMAIN: &[u8] = b"threshold-encryption/ephemeral-key";
+
+/// Proof of knowledge of `b` such that `P = b * G`, bound to a context.
+#[derive(Clone, Copy, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug)]
+pub struct SchnorrProof {
+	pub challenge: ScalarBytes,
+	pub response: ScalarBytes,
+}
+
+/// Proof that `log_G(X) == log_U(D)`, bound to a context.
+#[derive(Clone, Copy, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug)]
+pub struct DleqProof {
+	pub challenge: ScalarBytes,
+	pub response: ScalarBytes,
+}
+
+/// Decompress `bytes` into a point.
+pub fn decode_point(bytes: &PointBytes) -> Option<RistrettoPoint> {
+	CompressedRistretto(*bytes).decompress()
+}
+
+/// Compress `point`.
+pub fn encode_point(point: &RistrettoPoint) -> PointBytes {
+	point.compress().to_bytes()
+}
+
+/// Decode a canonically encoded scalar.
+pub fn decode_scalar(bytes: &ScalarBytes) -> Option<Scalar> {
+	Option::from(Scalar::from_canonical_bytes(*bytes))
+}
+
+/// Hash `parts` to a scalar, separated by `domain`.
+fn hash_to_scalar(domain: &[u8], parts: &[&[u8]]) -> Scalar {
+	let mut input = domain.to_vec();
+	for part in parts {
+		input.extend_from_slice(&(part.len() as u32).to_le_bytes());
+		input.extend_from_slice(part);
+	}
+	let mut wide = [0u8; 64];
+	input.push(0);
+	wide[..32].copy_from_slice(&sp_io::hashing::blake2_256(&input));
+	*input.last_mut().expect("just pushed") = 1;
+	wide[32..].copy_from_slice(&sp_io::hashing::blake2_256(&input));
+	Scalar::from_bytes_mod_order_wide(&wide)
+}
+
+/// Public key `secret * G`.
+pub fn public_key(secret: &Scalar) -> RistrettoPoint {
+	G * secret
+}
+
+/// Nonce derived from a secret and a fresh random `seed`, so that a weak seed alone does not
+/// leak the secret.
+pub fn derive_nonce(secret: &Scalar, seed: &[u8]) -> Scalar {
+	hash_to_scalar(NONCE_DOMAIN, &[secret.as_bytes(), seed])
+}
+
+/// Public share `sum(C_k * index^k)` of the member at `index` for the given commitments.
+pub fn public_share(commitments: &[RistrettoPoint], index: u32) -> RistrettoPoint {
+	let x = Scalar::from(index as u64);
+	// Horner's rule, starting from the highest coefficient.
+	commitments.iter().rev().fold(RistrettoPoint::identity(), |acc, c| acc * x + c)
+}
+
+/// Lagrange coefficient at zero of `index` among `indices`.
+///
+/// Returns `None` if the indices contain zero or duplicates.
+pub fn lagrange_coefficient(indices: &[u32], index: u32) -> Option<Scalar> {
+	let i = Scalar::from(index as u64);
+	let mut numerator = Scalar::ONE;
+	let mut denominator = Scalar::ONE;
+	for &other in indices.iter().filter(|&&other| other != index) {
+		let j = Scalar::from(other as u64);
+		numerator *= j;
+		denominator *= j - i;
+	}
+	if index == 0 || denominator == Scalar::ZERO {
+		return None;
+	}
+	Some(numerator * denominator.invert())
+}
+
+/// Combine decryption shares `(index, D_i)` into `x * U'`.
+pub fn combine(shares: &[(u32, RistrettoPoint)]) -> Option<RistrettoPoint> {
+	let indices: Vec<u32> = shares.iter().map(|(index, _)| *index).collect();
+	shares.iter().try_fold(RistrettoPoint::identity(), |acc, (index, share)| {
+		Some(acc + share * lagrange_coefficient(&indices, *index)?)
+	})
+}
+
+/// Commitments to the polynomial interpolating the dealings `(index, E)` of a resharing, whose
+/// constant term commits to the reshared secret.
+///
+/// Returns `None` if the indices contain zero or duplicates.
+pub fn interpolate_commitments(
+	dealings: &[(u32, Vec<RistrettoPoint>)],
+) -> Option<Vec<RistrettoPoint>> {
+	let indices: Vec<u32> = dealings.iter().map(|(index, _)| *index).collect();
+	let degree = dealings.iter().map(|(_, commitments)| commitments.len()).max().unwrap_or(0);
+	let mut sums = alloc::vec![RistrettoPoint::identity(); degree];
+	for (index, commitments) in dealings {
+		let lambda = lagrange_coefficient(&indices, *index)?;
+		for (sum, commitment) in sums.iter_mut().zip(commitments) {
+			*sum += commitment * lambda;
+		}
+	}
+	Some(sums)
+}
+
+/// Encrypt the share of the recipient at `index` with public encryption key `recipient`, using
+/// the secret `ephemeral_secret` of the dealing's ephemeral key `R = ephemeral_secret * G`.
+pub fn encrypt_share(
+	ephemeral_secret: &Scalar,
+	recipient: &RistrettoPoint,
+	index: u32,
+	share: &Scalar,
+) -> ScalarBytes {
+	(share + share_mask(&(recipient * ephemeral_secret), index)).to_bytes()
+}
+
+/// Decrypt the share of the recipient at `index` given `shared = sk * R`.
+///
+/// Returns `None` if the ciphertext is not a canonical scalar.
+pub fn decrypt_share(
+	shared: &RistrettoPoint,
+	index: u32,
+	ciphertext: &ScalarBytes,
+) -> Option<Scalar> {
+	Some(decode_scalar(ciphertext)? - share_mask(shared, index))
+}
+
+fn share_mask(shared: &RistrettoPoint, index: u32) -> Scalar {
+	hash_to_scalar(SHARE_DOMAIN, &[&encode_point(shared), &index.to_le_bytes()])
+}
+
+/// Verify a proof of knowledge of the discrete logarithm of `public`.
+pub fn verify_schnorr(context: &[u8], public: &RistrettoPoint, proof: &SchnorrProof) -> bool {
+	let (Some(c), Some(s)) = (decode_scalar(&proof.challenge), decode_scalar(&proof.response))
+	else {
+		return false;
+	};
+	let commitment = G * s + public * c;
+	c == schnorr_challenge(context, public, &commitment)
+}
+
+/// Prove knowledge of `secret` for `secret * G` using `nonce`.
+pub fn prove_schnorr(context: &[u8], secret: &Scalar, nonce: &Scalar) -> SchnorrProof {
+	let public = G * secret;
+	let c = schnorr_challenge(context, &public, &(G * nonce));
+	SchnorrProof { challenge: c.to_bytes(), response: (nonce - c * secret).to_bytes() }
+}
+
+fn schnorr_challenge(
+	context: &[u8],
+	public: &RistrettoPoint,
+	commitment: &RistrettoPoint,
+) -> Scalar {
+	hash_to_scalar(SCHNORR_DOMAIN, &[context, &encode_point(public), &encode_point(commitment)])
+}
+
+/// Verify that `share = x * base` for the `x` with `public = x * G`.
+pub fn verify_dleq(
+	context: &[u8],
+	public: &RistrettoPoint,
+	base: &RistrettoPoint,
+	share: &RistrettoPoint,
+	proof: &DleqProof,
+) -> bool {
+	let (Some(c), Some(s)) = (decode_scalar(&proof.challenge), decode_scalar(&proof.response))
+	else {
+		return false;
+	};
+	let a1 = G * s + public * c;
+	let a2 = base * s + share * c;
+	c == dleq_challenge(context, public, base, share, &a1, &a2)
+}
+
+/// Compute `secret * base` and prove it is well formed using `nonce`.
+pub fn prove_dleq(
+	context: &[u8],
+	secret: &Scalar,
+	base: &RistrettoPoint,
+	nonce: &Scalar,
+) -> (RistrettoPoint, DleqProof) {
+	let public = G * secret;
+	let share = base * secret;
+	let c = dleq_challenge(context, &public, base, &share, &(G * nonce), &(base * nonce));
+	(share, DleqProof { challenge: c.to_bytes(), response: (nonce - c * secret).to_bytes() })
+}
+
+fn dleq_challenge(
+	context: &[u8],
+	public: &RistrettoPoint,
+	base: &RistrettoPoint,
+	share: &RistrettoPoint,
+	a1: &RistrettoPoint,
+	a2: &RistrettoPoint,
+) -> Scalar {
+	hash_to_scalar(
+		DLEQ_DOMAIN,
+		&[
+			context,
+			&encode_point(public),
+			&encode_point(base),
+			&encode_point(share),
+			&encode_point(a1),
+			&encode_point(a2),
+		],
+	)
+}
+
+/// Prove knowledge of the ephemeral secret `r` of an encryption using `nonce`. `context` binds the
+/// proof to the owner of the ciphertext and where it is stored.
+pub fn prove_ephemeral_key(
+	context: &[u8],
+	ephemeral_secret: &Scalar,
+	nonce: &Scalar,
+) -> SchnorrProof {
+	prove_schnorr(&[EPHEMERAL_DOMAIN, context].concat(), ephemeral_secret, nonce)
+}
+
+/// Verify a proof of knowledge of the ephemeral secret of `ephemeral_key`.
+pub fn verify_ephemeral_key(
+	context: &[u8],
+	ephemeral_key: &RistrettoPoint,
+	proof: &SchnorrProof,
+) -> bool {
+	verify_schnorr(&[EPHEMERAL_DOMAIN, context].concat(), ephemeral_key, proof)
+}
+
+/// Ephemeral public key `U = r * G` and shared point `K = r * aggregate_key` of an encryption.
+pub fn encapsulate(
+	aggregate_key: &RistrettoPoint,
+	ephemeral_secret: &Scalar,
+) -> (RistrettoPoint, RistrettoPoint) {
+	(G * ephemeral_secret, aggregate_key * ephemeral_secret)
+}
+
+/// Remove the blinding `b` from a released `K + b * aggregate_key`.
+pub fn unblind(
+	released: &RistrettoPoint,
+	aggregate_key: &RistrettoPoint,
+	blinding: &Scalar,
+) -> RistrettoPoint {
+	released - aggregate_key * blinding
+}
+
+/// Shared point of a payload encrypted under a retired key, given the shared point under the
+/// current key and the payload's rekeying offset.
+pub fn remove_offset(shared: &RistrettoPoint, offset: &RistrettoPoint) -> RistrettoPoint {
+	shared - offset
+}
+
+/// Symmetric key derived from the shared point `K` of an encryption.
+///
+/// Clients encrypt payloads with an AEAD of their choice under this key.
+pub fn symmetric_key(shared: &RistrettoPoint) -> [u8; 32] {
+	let mut input = KEY_DOMAIN.to_vec();
+	input.extend_from_slice(&encode_point(shared));
+	sp_io::hashing::blake2_256(&input)
+}
@@ -0,0 +1,1243 @@
+//! # Threshold Encryption Pallet
+//!
+//! Sensitive memory is encrypted under a key that no single agent holds. A committee of agents
+//! generates the key together and any two thirds of them can jointly decrypt a payload, while
+//! fewer learn nothing about it.
+//!
+//! ## Overview
+//!
+//! - [`Pallet::set_committee`] names the first committee together with the public keys their
+//!   shares are encrypted to, and opens the dealing period of key generation. The decryption
+//!   threshold is two thirds of its members, rounded up.
+//! - Every member runs a Feldman verifiable secret sharing of a random secret and posts the
+//!   commitments to its polynomial and the encrypted share of every member with
+//!   [`Pallet::submit_dealing`], proving knowledge of its secret so that no member can choose its
+//!   commitments to cancel out the others'.
+//! - Key generation then goes through the complaint period of a rotation (see below), and
+//!   [`Pallet::finalize_rotation`] sums the commitments of the remaining dealings into the
+//!   committee's commitments, whose constant term is the aggregate public key.
+//! - Clients encrypt vectors under the aggregate public key before storing them in a collection
+//!   using the encrypted encoding of the memory pallet, so raw data never touches state. See
+//!   [`crypto`] for the scheme. The store must check with [`Pallet::verify_ephemeral_key`] that
+//!   the owner of a payload encrypted it, as the owner can have it decrypted.
+//! - The owner of a payload, or an account authorized through [`Pallet::authorize_decryption`],
+//!   files a decryption request with a blinded ephemeral key. Members answer with verifiable
+//!   decryption shares and, once the threshold is reached, the blinded shared key is released.
+//!   Only the requester can remove the blinding.
+//! - Other pallets can authorize accounts with [`Pallet::do_authorize_decryption`] and follow
+//!   every request through [`DecryptionObserver`], e.g. to grant access after an approved audit
+//!   and keep a log of it.
+//!
+//! Payloads are not stored by this pallet; they are looked up through [`EncryptedPayloads`].
+//!
+//! ## Key rotation
+//!
+//! The committee key is rotated in epochs, which also replaces the committee.
+//!
+//! - [`Pallet::start_rotation`] names the incoming members together with the public keys their
+//!   shares are encrypted to, and opens the dealing period.
+//! - Every outgoing member reshares its key share with [`Pallet::submit_reshare`] and every
+//!   incoming member deals a fresh key with [`Pallet::submit_fresh_dealing`]. Both post the
+//!   commitments to their polynomial and the encrypted share of every incoming member. The runtime
+//!   checks that a reshare commits to the dealer's public key share.
+//! - During the complaint period an incoming member that received a bad share proves it with
+//!   [`Pallet::complain`] and the dealing is discarded.
+//! - [`Pallet::finalize_rotation`] combines the remaining dealings. It needs reshares from at least
+//!   the outgoing threshold of members and fresh dealings from at least the incoming threshold.
+//!   Each incoming member `j` can then compute its share `y_j` of the old key from the reshares
+//!   and its share `z_j` of the new key from the fresh dealings.
+//! - Ciphertexts are rekeyed rather than rewritten: for every payload with ephemeral key `U`, the
+//!   incoming members publish shares of `(z_j - y_j) * U` with [`Pallet::submit_rekey_share`],
+//!   which combine into the payload's offset between its shared point under the new and the old
+//!   key. The offchain worker of each incoming member does this automatically once its secret
+//!   `z_j - y_j` is stored in the node's persistent local storage under [`REKEY_SECRET_KEY`] and
+//!   its account key of type [`KEY_TYPE`] is in the keystore.
+//! - [`Pallet::retire_key`] installs the new committee and key. Clients encrypt under the new key
+//!   from then on, and the decryption of an older payload releases its offset alongside the
+//!   blinded shared point.
+//!
+//! Payloads that were not rekeyed when the old key is retired can no longer be decrypted, so
+//! retirement should wait for [`Event::PayloadRekeyed`] of every payload. Note that rotation does
+//! not protect old ciphertexts from a threshold of outgoing members who kept their key shares.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod crypto;
+pub mod weights;
+pub use weights::WeightInfo;
+
+use alloc::vec::Vec;
+use crypto::{DleqProof, PointBytes, ScalarBytes, SchnorrProof};
+use sp_core::crypto::KeyTypeId;
+
+/// Identifier of a decryption request.
+pub type RequestId = u64;
+
+/// Index of a committee key. The key generated by the first committee has index zero.
+pub type EpochIndex = u32;
+
+/// Key type of the keys that sign the transactions of the offchain worker.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"tenc");
+
+/// Persistent local storage key of an incoming member's rekeying secret, stored by the node
+/// operator as the SCALE encoded `(EpochIndex, ScalarBytes)` of the rotation's epoch and
+/// `z_j - y_j`.
+pub const REKEY_SECRET_KEY: &[u8] = b"threshold-encryption::rekey-secret";
+
+/// Prefix of the persistent local storage keys recording when a rekey share was last sent.
+const REKEY_SENT_PREFIX: &[u8] = b"threshold-encryption::rekey-sent";
+
+/// Number of blocks after which the offchain worker sends a rekey share again if it has not
+/// been included.
+const REKEY_RETRY_BLOCKS: u32 = 10;
+
+const LOG_TARGET: &str = "runtime::threshold-encryption";
+
+/// Keys that sign the transactions of the offchain worker.
+pub mod sr25519 {
+	mod app_sr25519 {
+		use sp_runtime::app_crypto::{app_crypto, sr25519};
+		app_crypto!(sr25519, crate::KEY_TYPE);
+	}
+
+	/// Public key of a committee member's offchain worker.
+	pub type AuthorityId = app_sr25519::Public;
+
+	/// Signs the offchain worker's transactions with an sr25519 key of type
+	/// [`KEY_TYPE`](crate::KEY_TYPE).
+	pub struct AuthoritySigner;
+
+	impl frame_system::offchain::AppCrypto<sp_runtime::MultiSigner, sp_runtime::MultiSignature>
+		for AuthoritySigner
+	{
+		type RuntimeAppPublic = AuthorityId;
+		type GenericPublic = sp_core::sr25519::Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+	}
+}
+
+/// Source of the payloads that can be decrypted.
+pub trait EncryptedPayloads<PayloadId, AccountId> {
+	/// Owner and ephemeral public key of the payload `id`, if it exists and is encrypted.
+	fn payload(id: &PayloadId) -> Option<(AccountId, PointBytes)>;
+
+	/// Every payload that can be decrypted. Only called by offchain workers.
+	fn payloads() -> Vec<PayloadId>;
+}
+
+/// Notified of every decryption request.
+pub trait DecryptionObserver<PayloadId, AccountId> {
+	/// `requester` filed request `request_id` to decrypt `payload`; `owner` tells whether it owns
+	/// the payload or used an authorization.
+	fn on_decryption_requested(
+		request_id: RequestId,
+		payload: &PayloadId,
+		requester: &AccountId,
+		owner: bool,
+	);
+}
+
+impl<PayloadId, AccountId> DecryptionObserver<PayloadId, AccountId> for () {
+	fn on_decryption_requested(_: RequestId, _: &PayloadId, _: &AccountId, _: bool) {}
+}
+
+/// Creates payloads for the benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<PayloadId, AccountId> {
+	/// Create a payload owned by `owner`, encrypted with the ephemeral secret
+	/// `ephemeral_secret`.
+	fn create_payload(owner: AccountId, ephemeral_secret: ScalarBytes) -> PayloadId;
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use codec::Encode;
+	use crypto::RistrettoPoint;
+	use frame_support::pallet_prelude::*;
+	use frame_system::{
+		offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer},
+		pallet_prelude::*,
+	};
+	use sp_runtime::{
+		offchain::storage::{StorageRetrievalError, StorageValueRef},
+		traits::Saturating,
+	};
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Weight information for the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+		/// Identifier of an encrypted payload.
+		type PayloadId: Parameter + MaxEncodedLen;
+		/// Where encrypted payloads are looked up.
+		type Payloads: EncryptedPayloads<Self::PayloadId, Self::AccountId>;
+		/// Origin allowed to install the committee, rotate its key and retire the old key.
+		type CommitteeOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Origin allowed to authorize accounts to decrypt payloads they do not own.
+		type AuthorizeOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Notified of every decryption request, e.g. to keep an access log.
+		type Observer: DecryptionObserver<Self::PayloadId, Self::AccountId>;
+		/// Key that signs the transactions of the offchain worker.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+		/// Maximum number of committee members.
+		#[pallet::constant]
+		type MaxMembers: Get<u32>;
+		/// Number of blocks during which key generation or a rotation accepts dealings.
+		#[pallet::constant]
+		type DealingPeriod: Get<BlockNumberFor<Self>>;
+		/// Number of blocks after the dealing period during which incoming members can complain
+		/// about their shares.
+		#[pallet::constant]
+		type ComplaintPeriod: Get<BlockNumberFor<Self>>;
+		/// Maximum number of rekey shares the offchain worker sends per block.
+		#[pallet::constant]
+		type MaxRekeysPerBlock: Get<u32>;
+		/// Creates payloads for the benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<Self::PayloadId, Self::AccountId>;
+	}
+
+	/// A request to decrypt a payload.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct DecryptionRequest<T: Config> {
+		/// Account that filed the request.
+		pub requester: T::AccountId,
+		/// Payload to decrypt.
+		pub payload: T::PayloadId,
+		/// Ephemeral public key of the payload, blinded by the requester.
+		pub blinded_key: PointBytes,
+		/// Epoch of the key the request is answered with.
+		pub epoch: EpochIndex,
+		/// Decryption shares received so far, by member index.
+		pub shares: BoundedVec<(u32, PointBytes), T::MaxMembers>,
+		/// Blinded shared key, once enough shares have been received.
+		pub released: Option<PointBytes>,
+	}
+
+	/// Protocol a rotation dealing belongs to.
+	#[derive(Clone, Copy, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq, RuntimeDebug)]
+	pub enum DealingKind {
+		/// An outgoing member's resharing of its key share.
+		Reshare,
+		/// An incoming member's share of the new key.
+		Fresh,
+	}
+
+	/// Commitments and encrypted shares of a rotation dealing.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct EncryptedDealing<T: Config> {
+		/// Feldman commitments to the dealt polynomial, one per coefficient.
+		pub commitments: BoundedVec<PointBytes, T::MaxMembers>,
+		/// Ephemeral public key the shares are encrypted with.
+		pub ephemeral_key: PointBytes,
+		/// Encrypted share of each incoming member, in the order of the incoming committee.
+		pub shares: BoundedVec<ScalarBytes, T::MaxMembers>,
+	}
+
+	/// A rotation collecting dealings.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct RotationInfo<T: Config> {
+		/// Epoch of the key being generated.
+		pub epoch: EpochIndex,
+		/// Incoming members and the public keys their shares are encrypted to. The member at
+		/// position `i` holds the key share of index `i + 1`.
+		pub members: BoundedVec<(T::AccountId, PointBytes), T::MaxMembers>,
+		/// Decryption threshold of the incoming committee.
+		pub threshold: u32,
+		/// Last block accepting dealings.
+		pub dealing_deadline: BlockNumberFor<T>,
+		/// Last block accepting complaints.
+		pub complaint_deadline: BlockNumberFor<T>,
+	}
+
+	/// A generated key waiting for the old key to be retired.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct PendingKeyInfo<T: Config> {
+		/// Epoch of the key.
+		pub epoch: EpochIndex,
+		/// Incoming members.
+		pub members: BoundedVec<T::AccountId, T::MaxMembers>,
+		/// Decryption threshold of the incoming committee.
+		pub threshold: u32,
+		/// Commitments to the sharing of the new key.
+		pub commitments: BoundedVec<PointBytes, T::MaxMembers>,
+		/// Commitments to the sharing of the difference between the new and the old key.
+		pub rekey_commitments: BoundedVec<PointBytes, T::MaxMembers>,
+		/// Indices of the outgoing members whose reshares make up the old key shares.
+		pub resharers: BoundedVec<u32, T::MaxMembers>,
+		/// Indices of the incoming members whose dealings make up the new key shares.
+		pub fresh_dealers: BoundedVec<u32, T::MaxMembers>,
+	}
+
+	/// Committee members. The member at position `i` holds the key share of index `i + 1`.
+	#[pallet::storage]
+	pub type Members<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxMembers>, ValueQuery>;
+
+	/// Number of decryption shares needed to decrypt a payload.
+	#[pallet::storage]
+	pub type Threshold<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Commitments to the committee's key sharing, empty until key generation completes. The
+	/// first commitment is the aggregate public key.
+	#[pallet::storage]
+	pub type Commitments<T: Config> =
+		StorageValue<_, BoundedVec<PointBytes, T::MaxMembers>, ValueQuery>;
+
+	/// Epoch of the committee key.
+	#[pallet::storage]
+	pub type Epoch<T: Config> = StorageValue<_, EpochIndex, ValueQuery>;
+
+	/// Epoch assigned to the latest rotation. Epochs of cancelled rotations are not reused.
+	#[pallet::storage]
+	pub type LatestEpoch<T: Config> = StorageValue<_, EpochIndex, ValueQuery>;
+
+	/// The rotation collecting dealings, if any. Before the committee key exists, this is key
+	/// generation.
+	#[pallet::storage]
+	pub type Rotation<T: Config> = StorageValue<_, RotationInfo<T>>;
+
+	/// Dealings of the current rotation, by dealer.
+	#[pallet::storage]
+	pub type RotationDealings<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		DealingKind,
+		Blake2_128Concat,
+		T::AccountId,
+		EncryptedDealing<T>,
+	>;
+
+	/// The generated key of a finalized rotation, until the old key is retired.
+	#[pallet::storage]
+	pub type PendingKey<T: Config> = StorageValue<_, PendingKeyInfo<T>>;
+
+	/// Rekey shares received so far for a payload, by member index.
+	#[pallet::storage]
+	pub type RekeyShares<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		EpochIndex,
+		Blake2_128Concat,
+		T::PayloadId,
+		BoundedVec<(u32, PointBytes), T::MaxMembers>,
+		ValueQuery,
+	>;
+
+	/// Offset of a payload's shared point under the key of an epoch from its shared point under
+	/// the key it was encrypted with. Payloads encrypted under the key of an epoch have none.
+	#[pallet::storage]
+	pub type Offsets<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		EpochIndex,
+		Blake2_128Concat,
+		T::PayloadId,
+		PointBytes,
+	>;
+
+	/// Accounts allowed to request the decryption of a payload they do not own. Each
+	/// authorization is consumed by a single request.
+	#[pallet::storage]
+	pub type Authorizations<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::PayloadId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+	>;
+
+	/// Identifier of the next decryption request.
+	#[pallet::storage]
+	pub type NextRequestId<T: Config> = StorageValue<_, RequestId, ValueQuery>;
+
+	/// Decryption requests.
+	#[pallet::storage]
+	pub type Requests<T: Config> = StorageMap<_, Twox64Concat, RequestId, DecryptionRequest<T>>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The first committee was named and key generation started
+		CommitteeSet { members: Vec<T::AccountId>, threshold: u32 },
+		/// A member of the first committee dealt its share of the key
+		DealingSubmitted { member: T::AccountId },
+		/// Key generation completed; `dealers` are the indices of the members whose dealings make
+		/// up the key shares
+		KeyGenerated { aggregate_key: PointBytes, dealers: Vec<u32> },
+		/// An account was authorized to decrypt a payload
+		DecryptionAuthorized { payload: T::PayloadId, who: T::AccountId },
+		/// A payload decryption was requested
+		DecryptionRequested {
+			request_id: RequestId,
+			payload: T::PayloadId,
+			requester: T::AccountId,
+		},
+		/// A member submitted a valid decryption share
+		DecryptionShareSubmitted { request_id: RequestId, member: T::AccountId },
+		/// Enough shares were received; `released` is the blinded shared key and `offset` the
+		/// payload's rekeying offset, if it was encrypted under a retired key
+		DecryptionReleased {
+			request_id: RequestId,
+			released: PointBytes,
+			offset: Option<PointBytes>,
+		},
+		/// A key rotation started
+		RotationStarted { epoch: EpochIndex, members: Vec<T::AccountId>, threshold: u32 },
+		/// An outgoing member reshared its key share
+		ReshareSubmitted { epoch: EpochIndex, member: T::AccountId },
+		/// An incoming member dealt its share of the new key
+		FreshDealingSubmitted { epoch: EpochIndex, member: T::AccountId },
+		/// A dealing was discarded after a justified complaint
+		DealerDisqualified {
+			epoch: EpochIndex,
+			kind: DealingKind,
+			dealer: T::AccountId,
+			complainant: T::AccountId,
+		},
+		/// The dealings were combined into a new key
+		RotationFinalized { epoch: EpochIndex, aggregate_key: PointBytes },
+		/// A rotation was abandoned
+		RotationCancelled { epoch: EpochIndex },
+		/// An incoming member submitted a valid rekey share
+		RekeyShareSubmitted { epoch: EpochIndex, payload: T::PayloadId, member: T::AccountId },
+		/// Enough rekey shares were received to decrypt the payload under the key of `epoch`
+		PayloadRekeyed { epoch: EpochIndex, payload: T::PayloadId, offset: PointBytes },
+		/// The old key was retired and the new committee installed
+		KeyRetired { retired: EpochIndex, epoch: EpochIndex, aggregate_key: PointBytes },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The committee has no members
+		EmptyCommittee,
+		/// An account appears twice in the committee
+		DuplicateMember,
+		/// The committee key has already been generated
+		KeyAlreadyGenerated,
+		/// The committee key has not been generated yet
+		NoCommitteeKey,
+		/// The caller is not a committee member
+		NotMember,
+		/// The member has already posted its dealing
+		AlreadyDealt,
+		/// A dealing must commit to exactly `threshold` coefficients, encrypt a share for every
+		/// incoming member and, for a reshare, commit to the dealer's key share
+		InvalidDealing,
+		/// Bytes do not encode a valid group element
+		InvalidPoint,
+		/// A proof does not verify
+		InvalidProof,
+		/// The payload does not exist or is not encrypted
+		PayloadNotFound,
+		/// The caller neither owns the payload nor is authorized to decrypt it
+		NotAuthorized,
+		/// Decryption request not found
+		RequestNotFound,
+		/// The member has already submitted a share for this request
+		AlreadyShared,
+		/// The request has already been decrypted
+		AlreadyReleased,
+		/// The request was filed under a retired key
+		StaleRequest,
+		/// A rotation is already in progress
+		RotationInProgress,
+		/// No rotation is in progress
+		NoRotation,
+		/// The rotation no longer accepts dealings
+		DealingPeriodOver,
+		/// Complaints are only accepted between the dealing and the complaint deadline
+		NotComplaintPeriod,
+		/// The rotation cannot be finalized before the complaint deadline has passed
+		ComplaintPeriodOpen,
+		/// Dealing not found
+		DealingNotFound,
+		/// The complaint is about a valid share
+		ComplaintUnfounded,
+		/// Not enough valid dealings to finalize the rotation
+		NotEnoughDealings,
+		/// No rotation has been finalized
+		NoPendingKey,
+		/// The payload has already been rekeyed
+		AlreadyRekeyed,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn offchain_worker(now: BlockNumberFor<T>) {
+			if let Err(e) = Self::send_rekey_shares(now) {
+				log::warn!(target: LOG_TARGET, "Failed to send rekey shares: {e}");
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Name the first committee, each member given with the public key its shares are
+		/// encrypted to, and start key generation.
+		///
+		/// Restarts key generation if it is in progress. Fails once a committee key exists, since
+		/// ciphertexts under it would become undecryptable. Use [`Pallet::start_rotation`] to
+		/// replace the committee instead.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::set_committee(members.len() as u32))]
+		pub fn set_committee(
+			origin: OriginFor<T>,
+			members: BoundedVec<(T::AccountId, PointBytes), T::MaxMembers>,
+		) -> DispatchResult {
+			T::CommitteeOrigin::ensure_origin(origin)?;
+			ensure!(!members.is_empty(), Error::<T>::EmptyCommittee);
+			ensure!(Commitments::<T>::get().is_empty(), Error::<T>::KeyAlreadyGenerated);
+			for (i, (member, encryption_key)) in members.iter().enumerate() {
+				ensure!(
+					members[..i].iter().all(|(other, _)| other != member),
+					Error::<T>::DuplicateMember
+				);
+				ensure!(crypto::decode_point(encryption_key).is_some(), Error::<T>::InvalidPoint);
+			}
+
+			let threshold = Self::threshold_of(members.len());
+			let dealing_deadline =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::DealingPeriod::get());
+			let complaint_deadline = dealing_deadline.saturating_add(T::ComplaintPeriod::get());
+			let accounts: Vec<_> = members.iter().map(|(member, _)| member.clone()).collect();
+			Self::clear_rotation_dealings();
+			Rotation::<T>::put(RotationInfo {
+				epoch: 0,
+				members,
+				threshold,
+				dealing_deadline,
+				complaint_deadline,
+			});
+
+			Self::deposit_event(Event::CommitteeSet { members: accounts, threshold });
+			Ok(())
+		}
+
+		/// Deal the caller's share of the first committee key.
+		///
+		/// `proof` proves knowledge of the secret committed to by the constant term of the
+		/// dealing. Without it, the last member to deal could commit to its own key minus the sum
+		/// of the others' and control the aggregate key.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::submit_dealing(dealing.shares.len() as u32))]
+		pub fn submit_dealing(
+			origin: OriginFor<T>,
+			dealing: EncryptedDealing<T>,
+			proof: SchnorrProof,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Commitments::<T>::get().is_empty(), Error::<T>::KeyAlreadyGenerated);
+			Self::deal_fresh(&who, dealing, &proof)?;
+
+			Self::deposit_event(Event::DealingSubmitted { member: who });
+			Ok(())
+		}
+
+		/// Request the decryption of `payload`.
+		///
+		/// `blinded_key` is the payload's ephemeral public key plus `b * G` for a secret `b` of
+		/// the caller's choosing, and `proof` proves knowledge of `b`.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::request_decryption())]
+		pub fn request_decryption(
+			origin: OriginFor<T>,
+			payload: T::PayloadId,
+			blinded_key: PointBytes,
+			proof: SchnorrProof,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Commitments::<T>::get().is_empty(), Error::<T>::NoCommitteeKey);
+			let (owner, ephemeral_key) =
+				T::Payloads::payload(&payload).ok_or(Error::<T>::PayloadNotFound)?;
+			let owner = who == owner;
+			if !owner {
+				ensure!(
+					Authorizations::<T>::take(&payload, &who).is_some(),
+					Error::<T>::NotAuthorized
+				);
+			}
+
+			let ephemeral_key =
+				crypto::decode_point(&ephemeral_key).ok_or(Error::<T>::InvalidPoint)?;
+			let blinded = crypto::decode_point(&blinded_key).ok_or(Error::<T>::InvalidPoint)?;
+			let context = (&who, &payload, &blinded_key).encode();
+			ensure!(
+				crypto::verify_schnorr(&context, &(blinded - ephemeral_key), &proof),
+				Error::<T>::InvalidProof
+			);
+
+			let request_id = NextRequestId::<T>::mutate(|id| {
+				let current = *id;
+				*id = id.saturating_add(1);
+				current
+			});
+			Requests::<T>::insert(
+				request_id,
+				DecryptionRequest {
+					requester: who.clone(),
+					payload: payload.clone(),
+					blinded_key,
+					epoch: Epoch::<T>::get(),
+					shares: BoundedVec::default(),
+					released: None,
+				},
+			);
+
+			T::Observer::on_decryption_requested(request_id, &payload, &who, owner);
+			Self::deposit_event(Event::DecryptionRequested { request_id, payload, requester: who });
+			Ok(())
+		}
+
+		/// Submit the caller's decryption share for a request.
+		///
+		/// `share` is the caller's key share times the blinded key of the request and `proof`
+		/// shows it was computed with the key share matching the committee commitments. The
+		/// share completing the threshold releases the blinded shared key.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::submit_decryption_share(T::MaxMembers::get()))]
+		pub fn submit_decryption_share(
+			origin: OriginFor<T>,
+			request_id: RequestId,
+			share: PointBytes,
+			proof: DleqProof,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let index = Self::member_index(&who).ok_or(Error::<T>::NotMember)?;
+			let mut request = Requests::<T>::get(request_id).ok_or(Error::<T>::RequestNotFound)?;
+			ensure!(request.released.is_none(), Error::<T>::AlreadyReleased);
+			ensure!(request.epoch == Epoch::<T>::get(), Error::<T>::StaleRequest);
+			ensure!(request.shares.iter().all(|(i, _)| *i != index), Error::<T>::AlreadyShared);
+
+			let commitments = Self::commitment_points().ok_or(Error::<T>::NoCommitteeKey)?;
+			let public_share = crypto::public_share(&commitments, index);
+			let base =
+				crypto::decode_point(&request.blinded_key).ok_or(Error::<T>::InvalidPoint)?;
+			let share_point = crypto::decode_point(&share).ok_or(Error::<T>::InvalidPoint)?;
+			let context = (request_id, index).encode();
+			ensure!(
+				crypto::verify_dleq(&context, &public_share, &base, &share_point, &proof),
+				Error::<T>::InvalidProof
+			);
+
+			request.shares.try_push((index, share)).map_err(|_| Error::<T>::AlreadyShared)?;
+			Self::deposit_event(Event::DecryptionShareSubmitted { request_id, member: who });
+
+			let shares = request.shares.len() as u32;
+			if shares >= Threshold::<T>::get() {
+				let released =
+					Self::combine_shares(&request.shares).ok_or(Error::<T>::InvalidPoint)?;
+				let offset = Offsets::<T>::get(request.epoch, &request.payload);
+				request.released = Some(released);
+				Self::deposit_event(Event::DecryptionReleased { request_id, released, offset });
+			}
+			Requests::<T>::insert(request_id, request);
+
+			Ok(Some(T::WeightInfo::submit_decryption_share(shares)).into())
+		}
+
+		/// Allow `who` to file one decryption request for `payload`.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::authorize_decryption())]
+		pub fn authorize_decryption(
+			origin: OriginFor<T>,
+			payload: T::PayloadId,
+			who: T::AccountId,
+		) -> DispatchResult {
+			T::AuthorizeOrigin::ensure_origin(origin)?;
+
+			Self::do_authorize_decryption(payload, who);
+			Ok(())
+		}
+
+		/// Start rotating the committee key to `members`, each given with the public key its
+		/// shares are encrypted to.
+		///
+		/// Opens the dealing period for [`Pallet::submit_reshare`] and
+		/// [`Pallet::submit_fresh_dealing`].
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::start_rotation(members.len() as u32))]
+		pub fn start_rotation(
+			origin: OriginFor<T>,
+			members: BoundedVec<(T::AccountId, PointBytes), T::MaxMembers>,
+		) -> DispatchResult {
+			T::CommitteeOrigin::ensure_origin(origin)?;
+			ensure!(!Commitments::<T>::get().is_empty(), Error::<T>::NoCommitteeKey);
+			ensure!(
+				!Rotation::<T>::exists() && !PendingKey::<T>::exists(),
+				Error::<T>::RotationInProgress
+			);
+			ensure!(!members.is_empty(), Error::<T>::EmptyCommittee);
+			for (i, (member, encryption_key)) in members.iter().enumerate() {
+				ensure!(
+					members[..i].iter().all(|(other, _)| other != member),
+					Error::<T>::DuplicateMember
+				);
+				ensure!(crypto::decode_point(encryption_key).is_some(), Error::<T>::InvalidPoint);
+			}
+
+			let epoch = LatestEpoch::<T>::mutate(|epoch| {
+				*epoch = epoch.saturating_add(1);
+				*epoch
+			});
+			let threshold = Self::threshold_of(members.len());
+			let dealing_deadline =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::DealingPeriod::get());
+			let complaint_deadline = dealing_deadline.saturating_add(T::ComplaintPeriod::get());
+			let accounts: Vec<_> = members.iter().map(|(member, _)| member.clone()).collect();
+			Rotation::<T>::put(RotationInfo {
+				epoch,
+				members,
+				threshold,
+				dealing_deadline,
+				complaint_deadline,
+			});
+
+			Self::deposit_event(Event::RotationStarted { epoch, members: accounts, threshold });
+			Ok(())
+		}
+
+		/// Reshare the caller's key share with the incoming committee.
+		///
+		/// The constant term of the dealing must commit to the caller's public key share.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::submit_reshare(dealing.shares.len() as u32))]
+		pub fn submit_reshare(
+			origin: OriginFor<T>,
+			dealing: EncryptedDealing<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let index = Self::member_index(&who).ok_or(Error::<T>::NotMember)?;
+			let rotation = Self::rotation_accepting_dealings()?;
+			ensure!(
+				!RotationDealings::<T>::contains_key(DealingKind::Reshare, &who),
+				Error::<T>::AlreadyDealt
+			);
+			Self::validate_dealing(&rotation, &dealing)?;
+
+			let commitments = Self::commitment_points().ok_or(Error::<T>::NoCommitteeKey)?;
+			let public_share = crypto::public_share(&commitments, index);
+			ensure!(
+				dealing.commitments[0] == crypto::encode_point(&public_share),
+				Error::<T>::InvalidDealing
+			);
+
+			RotationDealings::<T>::insert(DealingKind::Reshare, &who, dealing);
+			Self::deposit_event(Event::ReshareSubmitted { epoch: rotation.epoch, member: who });
+			Ok(())
+		}
+
+		/// Deal the caller's share of the new key to the incoming committee.
+		///
+		/// `proof` proves knowledge of the secret committed to by the constant term of the
+		/// dealing.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::submit_fresh_dealing(dealing.shares.len() as u32))]
+		pub fn submit_fresh_dealing(
+			origin: OriginFor<T>,
+			dealing: EncryptedDealing<T>,
+			proof: SchnorrProof,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Commitments::<T>::get().is_empty(), Error::<T>::NoCommitteeKey);
+			let epoch = Self::deal_fresh(&who, dealing, &proof)?;
+
+			Self::deposit_event(Event::FreshDealingSubmitted { epoch, member: who });
+			Ok(())
+		}
+
+		/// Complain that the share `dealer` encrypted to the caller is invalid.
+		///
+		/// `shared_key` is the caller's encryption secret times the ephemeral key of the dealing
+		/// and `proof` shows it was computed with the secret of the caller's encryption key. The
+		/// runtime decrypts the share and discards the dealing if the share does not match its
+		/// commitments.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::complain(T::MaxMembers::get()))]
+		pub fn complain(
+			origin: OriginFor<T>,
+			kind: DealingKind,
+			dealer: T::AccountId,
+			shared_key: PointBytes,
+			proof: DleqProof,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let rotation = Rotation::<T>::get().ok_or(Error::<T>::NoRotation)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				now > rotation.dealing_deadline && now <= rotation.complaint_deadline,
+				Error::<T>::NotComplaintPeriod
+			);
+			let position = rotation
+				.members
+				.iter()
+				.position(|(member, _)| *member == who)
+				.ok_or(Error::<T>::NotMember)?;
+			let index = position as u32 + 1;
+			let dealing =
+				RotationDealings::<T>::get(kind, &dealer).ok_or(Error::<T>::DealingNotFound)?;
+
+			let (_, encryption_key) = &rotation.members[position];
+			let encryption_key =
+				crypto::decode_point(encryption_key).ok_or(Error::<T>::InvalidPoint)?;
+			let ephemeral_key =
+				crypto::decode_point(&dealing.ephemeral_key).ok_or(Error::<T>::InvalidPoint)?;
+			let shared = crypto::decode_point(&shared_key).ok_or(Error::<T>::InvalidPoint)?;
+			let context = (rotation.epoch, &who, &dealer, kind).encode();
+			ensure!(
+				crypto::verify_dleq(&context, &encryption_key, &ephemeral_key, &shared, &proof),
+				Error::<T>::InvalidProof
+			);
+
+			let commitments = Self::points(&dealing.commitments);
+			let expected = crypto::public_share(&commitments, index);
+			let share = dealing
+				.shares
+				.get(position)
+				.and_then(|ciphertext| crypto::decrypt_share(&shared, index, ciphertext));
+			ensure!(
+				!matches!(share, Some(share) if crypto::public_key(&share) == expected),
+				Error::<T>::ComplaintUnfounded
+			);
+
+			RotationDealings::<T>::remove(kind, &dealer);
+			Self::deposit_event(Event::DealerDisqualified {
+				epoch: rotation.epoch,
+				kind,
+				dealer,
+				complainant: who,
+			});
+			Ok(())
+		}
+
+		/// Combine the remaining dealings into the new key once the complaint period is over.
+		///
+		/// Needs fresh dealings from at least the incoming threshold of members and, unless this
+		/// completes key generation, reshares from at least the outgoing threshold of members.
+		/// Key generation installs the committee and its key right away.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::finalize_rotation(T::MaxMembers::get()))]
+		pub fn finalize_rotation(origin: OriginFor<T>) -> DispatchResult {
+			ensure_signed(origin)?;
+			let rotation = Rotation::<T>::get().ok_or(Error::<T>::NoRotation)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > rotation.complaint_deadline,
+				Error::<T>::ComplaintPeriodOpen
+			);
+
+			let mut fresh_dealers = Vec::new();
+			let mut commitments =
+				alloc::vec![RistrettoPoint::default(); rotation.threshold as usize];
+			for (dealer, dealing) in RotationDealings::<T>::iter_prefix(DealingKind::Fresh) {
+				let Some(position) = rotation.members.iter().position(|(m, _)| *m == dealer) else {
+					continue;
+				};
+				fresh_dealers.push(position as u32 + 1);
+				let points = Self::points(&dealing.commitments);
+				for (sum, commitment) in commitments.iter_mut().zip(points) {
+					*sum += commitment;
+				}
+			}
+			ensure!(
+				fresh_dealers.len() as u32 >= rotation.threshold,
+				Error::<T>::NotEnoughDealings
+			);
+			fresh_dealers.sort();
+
+			let aggregate_key = crypto::encode_point(&commitments[0]);
+			let encode = |points: &[RistrettoPoint]| -> BoundedVec<PointBytes, T::MaxMembers> {
+				let points: Vec<_> = points.iter().map(crypto::encode_point).collect();
+				BoundedVec::truncate_from(points)
+			};
+			let members = BoundedVec::<_, T::MaxMembers>::truncate_from(
+				rotation.members.into_iter().map(|(member, _)| member).collect::<Vec<_>>(),
+			);
+
+			if Commitments::<T>::get().is_empty() {
+				// There is no old key to reshare and no ciphertext to rekey.
+				Members::<T>::put(members);
+				Threshold::<T>::put(rotation.threshold);
+				Commitments::<T>::put(encode(&commitments));
+				Rotation::<T>::kill();
+				Self::clear_rotation_dealings();
+
+				Self::deposit_event(Event::KeyGenerated { aggregate_key, dealers: fresh_dealers });
+				return Ok(());
+			}
+
+			let outgoing = Members::<T>::get();
+			let mut reshares: Vec<_> = RotationDealings::<T>::iter_prefix(DealingKind::Reshare)
+				.filter_map(|(dealer, dealing)| {
+					let position = outgoing.iter().position(|member| *member == dealer)?;
+					Some((position as u32 + 1, Self::points(&dealing.commitments)))
+				})
+				.collect();
+			ensure!(reshares.len() as u32 >= Threshold::<T>::get(), Error::<T>::NotEnoughDealings);
+			reshares.sort_by_key(|(index, _)| *index);
+
+			// Reshares commit to the outgoing key shares, so their interpolation commits to the
+			// old key.
+			let old_commitments =
+				crypto::interpolate_commitments(&reshares).ok_or(Error::<T>::InvalidPoint)?;
+			let rekey_commitments: Vec<_> =
+				commitments.iter().zip(&old_commitments).map(|(new, old)| new - old).collect();
+
+			PendingKey::<T>::put(PendingKeyInfo {
+				epoch: rotation.epoch,
+				members,
+				threshold: rotation.threshold,
+				commitments: encode(&commitments),
+				rekey_commitments: encode(&rekey_commitments),
+				resharers: BoundedVec::truncate_from(
+					reshares.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+				),
+				fresh_dealers: BoundedVec::truncate_from(fresh_dealers),
+			});
+			Rotation::<T>::kill();
+
+			Self::deposit_event(Event::RotationFinalized { epoch: rotation.epoch, aggregate_key });
+			Ok(())
+		}
+
+		/// Abandon the rotation in progress, before or after it was finalized.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::cancel_rotation(T::MaxMembers::get()))]
+		pub fn cancel_rotation(origin: OriginFor<T>) -> DispatchResult {
+			T::CommitteeOrigin::ensure_origin(origin)?;
+			let epoch = Rotation::<T>::take()
+				.map(|rotation| rotation.epoch)
+				.or_else(|| PendingKey::<T>::take().map(|key| key.epoch))
+				.ok_or(Error::<T>::NoRotation)?;
+
+			Self::clear_rotation_dealings();
+			Self::deposit_event(Event::RotationCancelled { epoch });
+			Ok(())
+		}
+
+		/// Submit the caller's rekey share for `payload`.
+		///
+		/// `share` is the caller's share of the difference between the new and the old key times
+		/// the payload's ephemeral key, and `proof` shows it matches the rekey commitments. The
+		/// share completing the incoming threshold records the payload's offset under the new
+		/// key. Valid shares are free of charge.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::submit_rekey_share(T::MaxMembers::get()))]
+		pub fn submit_rekey_share(
+			origin: OriginFor<T>,
+			payload: T::PayloadId,
+			share: PointBytes,
+			proof: DleqProof,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let key = PendingKey::<T>::get().ok_or(Error::<T>::NoPendingKey)?;
+			let index = key
+				.members
+				.iter()
+				.position(|member| *member == who)
+				.map(|position| position as u32 + 1)
+				.ok_or(Error::<T>::NotMember)?;
+			ensure!(!Offsets::<T>::contains_key(key.epoch, &payload), Error::<T>::AlreadyRekeyed);
+			let (_, ephemeral_key) =
+				T::Payloads::payload(&payload).ok_or(Error::<T>::PayloadNotFound)?;
+			let mut shares = RekeyShares::<T>::get(key.epoch, &payload);
+			ensure!(shares.iter().all(|(i, _)| *i != index), Error::<T>::AlreadyShared);
+
+			let public_share = crypto::public_share(&Self::points(&key.rekey_commitments), index);
+			let base = crypto::decode_point(&ephemeral_key).ok_or(Error::<T>::InvalidPoint)?;
+			let share_point = crypto::decode_point(&share).ok_or(Error::<T>::InvalidPoint)?;
+			let context = (key.epoch, &payload, index).encode();
+			ensure!(
+				crypto::verify_dleq(&context, &public_share, &base, &share_point, &proof),
+				Error::<T>::InvalidProof
+			);
+
+			shares.try_push((index, share)).map_err(|_| Error::<T>::AlreadyShared)?;
+			Self::deposit_event(Event::RekeyShareSubmitted {
+				epoch: key.epoch,
+				payload: payload.clone(),
+				member: who,
+			});
+
+			let count = shares.len() as u32;
+			if count >= key.threshold {
+				let difference = Self::combine_shares(&shares)
+					.and_then(|point| crypto::decode_point(&point))
+					.ok_or(Error::<T>::InvalidPoint)?;
+				// Offsets accumulate over rotations.
+				let previous = Offsets::<T>::get(Epoch::<T>::get(), &payload)
+					.and_then(|offset| crypto::decode_point(&offset))
+					.unwrap_or_default();
+				let offset = crypto::encode_point(&(previous + difference));
+				Offsets::<T>::insert(key.epoch, &payload, offset);
+				RekeyShares::<T>::remove(key.epoch, &payload);
+				Self::deposit_event(Event::PayloadRekeyed { epoch: key.epoch, payload, offset });
+			} else {
+				RekeyShares::<T>::insert(key.epoch, &payload, shares);
+			}
+
+			Ok((Some(T::WeightInfo::submit_rekey_share(count)), Pays::No).into())
+		}
+
+		/// Retire the old key and install the committee and key of the finalized rotation.
+		///
+		/// Payloads that have not been rekeyed can no longer be decrypted afterwards.
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::retire_key(T::MaxMembers::get()))]
+		pub fn retire_key(origin: OriginFor<T>) -> DispatchResult {
+			T::CommitteeOrigin::ensure_origin(origin)?;
+			let key = PendingKey::<T>::take().ok_or(Error::<T>::NoPendingKey)?;
+
+			let retired = Epoch::<T>::get();
+			let aggregate_key = key.commitments[0];
+			Members::<T>::put(key.members);
+			Threshold::<T>::put(key.threshold);
+			Commitments::<T>::put(key.commitments);
+			Epoch::<T>::put(key.epoch);
+			Self::clear_rotation_dealings();
+
+			Self::deposit_event(Event::KeyRetired { retired, epoch: key.epoch, aggregate_key });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The aggregate public key, once key generation has completed.
+		pub fn aggregate_key() -> Option<PointBytes> {
+			Commitments::<T>::get().first().copied()
+		}
+
+		/// Whether `proof` proves that `owner` knows the ephemeral secret of `ephemeral_key`,
+		/// for a payload stored at `location`, e.g. the collection holding it.
+		///
+		/// The proof is made with [`crypto::prove_ephemeral_key`] over the SCALE encoded
+		/// `(owner, location)`.
+		pub fn verify_ephemeral_key(
+			owner: &T::AccountId,
+			location: &impl Encode,
+			ephemeral_key: &PointBytes,
+			proof: &SchnorrProof,
+		) -> bool {
+			let Some(ephemeral_key) = crypto::decode_point(ephemeral_key) else {
+				return false;
+			};
+			crypto::verify_ephemeral_key(&(owner, location).encode(), &ephemeral_key, proof)
+		}
+
+		/// Allow `who` to file one decryption request for `payload`.
+		pub fn do_authorize_decryption(payload: T::PayloadId, who: T::AccountId) {
+			Authorizations::<T>::insert(&payload, &who, ());
+			Self::deposit_event(Event::DecryptionAuthorized { payload, who });
+		}
+
+		/// Share index of `who`, if it is a committee member.
+		pub fn member_index(who: &T::AccountId) -> Option<u32> {
+			Members::<T>::get().iter().position(|m| m == who).map(|i| i as u32 + 1)
+		}
+
+		/// Decryption threshold of a committee of `members` members.
+		fn threshold_of(members: usize) -> u32 {
+			(2 * members as u32).div_ceil(3)
+		}
+
+		/// Decompressed committee commitments, `None` before key generation completes.
+		fn commitment_points() -> Option<Vec<RistrettoPoint>> {
+			let commitments = Commitments::<T>::get();
+			if commitments.is_empty() {
+				return None;
+			}
+			commitments.iter().map(crypto::decode_point).collect()
+		}
+
+		/// Decompress points that were validated when submitted.
+		fn points(points: &[PointBytes]) -> Vec<RistrettoPoint> {
+			points.iter().filter_map(crypto::decode_point).collect()
+		}
+
+		/// Combine the shares `(index, share)` of a threshold into `secret * base`.
+		fn combine_shares(shares: &[(u32, PointBytes)]) -> Option<PointBytes> {
+			let points: Vec<_> = shares
+				.iter()
+				.filter_map(|(i, s)| crypto::decode_point(s).map(|p| (*i, p)))
+				.collect();
+			crypto::combine(&points).map(|point| crypto::encode_point(&point))
+		}
+
+		/// Check and record the fresh dealing of `who` for the rotation in progress, returning
+		/// its epoch.
+		fn deal_fresh(
+			who: &T::AccountId,
+			dealing: EncryptedDealing<T>,
+			proof: &SchnorrProof,
+		) -> Result<EpochIndex, DispatchError> {
+			let rotation = Self::rotation_accepting_dealings()?;
+			ensure!(
+				rotation.members.iter().any(|(member, _)| member == who),
+				Error::<T>::NotMember
+			);
+			ensure!(
+				!RotationDealings::<T>::contains_key(DealingKind::Fresh, who),
+				Error::<T>::AlreadyDealt
+			);
+			Self::validate_dealing(&rotation, &dealing)?;
+
+			let secret_commitment =
+				crypto::decode_point(&dealing.commitments[0]).ok_or(Error::<T>::InvalidPoint)?;
+			let context = (rotation.epoch, who).encode();
+			ensure!(
+				crypto::verify_schnorr(&context, &secret_commitment, proof),
+				Error::<T>::InvalidProof
+			);
+
+			RotationDealings::<T>::insert(DealingKind::Fresh, who, dealing);
+			Ok(rotation.epoch)
+		}
+
+		/// The rotation in progress, if it still accepts dealings.
+		fn rotation_accepting_dealings() -> Result<RotationInfo<T>, DispatchError> {
+			let rotation = Rotation::<T>::get().ok_or(Error::<T>::NoRotation)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= rotation.dealing_deadline,
+				Error::<T>::DealingPeriodOver
+			);
+			Ok(rotation)
+		}
+
+		/// Check the shape of a dealing for `rotation`.
+		fn validate_dealing(
+			rotation: &RotationInfo<T>,
+			dealing: &EncryptedDealing<T>,
+		) -> DispatchResult {
+			ensure!(
+				dealing.commitments.len() as u32 == rotation.threshold &&
+					dealing.shares.len() == rotation.members.len(),
+				Error::<T>::InvalidDealing
+			);
+			ensure!(
+				dealing
+					.commitments
+					.iter()
+					.chain([&dealing.ephemeral_key])
+					.all(|point| crypto::decode_point(point).is_some()),
+				Error::<T>::InvalidPoint
+			);
+			Ok(())
+		}
+
+		/// Remove the dealings of the rotation. Dealers are outgoing or incoming members.
+		fn clear_rotation_dealings() {
+			let _ = RotationDealings::<T>::clear(2 * T::MaxMembers::get(), None);
+		}
+
+		/// Send the rekey shares of the local incoming member for payloads that still need
+		/// them.
+		fn send_rekey_shares(now: BlockNumberFor<T>) -> Result<(), &'static str> {
+			let Some(key) = PendingKey::<T>::get() else {
+				return Ok(());
+			};
+			let stored = StorageValueRef::persistent(REKEY_SECRET_KEY)
+				.get::<(EpochIndex, ScalarBytes)>()
+				.map_err(|_| "the rekeying secret cannot be decoded")?;
+			let Some(secret) = stored
+				.filter(|(epoch, _)| *epoch == key.epoch)
+				.and_then(|(_, secret)| crypto::decode_scalar(&secret))
+			else {
+				return Ok(());
+			};
+
+			let rekey_commitments = Self::points(&key.rekey_commitments);
+			let public = crypto::public_key(&secret);
+			let index = (1..=key.members.len() as u32)
+				.find(|index| crypto::public_share(&rekey_commitments, *index) == public)
+				.ok_or("the rekeying secret matches no incoming member")?;
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			if !signer.can_sign() {
+				return Err("no signing key in the keystore");
+			}
+
+			let seed = sp_io::offchain::random_seed();
+			let mut sent = 0;
+			for payload in T::Payloads::payloads() {
+				if sent >= T::MaxRekeysPerBlock::get() {
+					break;
+				}
+				if Offsets::<T>::contains_key(key.epoch, &payload) ||
+					RekeyShares::<T>::get(key.epoch, &payload).iter().any(|(i, _)| *i == index)
+				{
+					continue;
+				}
+				let Some(base) = T::Payloads::payload(&payload)
+					.and_then(|(_, ephemeral_key)| crypto::decode_point(&ephemeral_key))
+				else {
+					continue;
+				};
+
+				// Shares sent recently may still be waiting in the transaction pool.
+				let sent_at = (REKEY_SENT_PREFIX, key.epoch, &payload).encode();
+				let claimed = StorageValueRef::persistent(&sent_at).mutate(
+					|last: Result<Option<BlockNumberFor<T>>, StorageRetrievalError>| match last {
+						Ok(Some(last)) if now < last.saturating_add(REKEY_RETRY_BLOCKS.into()) =>
+							Err(()),
+						_ => Ok(now),
+					},
+				);
+				if claimed.is_err() {
+					continue;
+				}
+
+				let nonce = crypto::derive_nonce(&secret, &(seed, &payload).encode());
+				let context = (key.epoch, &payload, index).encode();
+				let (share, proof) = crypto::prove_dleq(&context, &secret, &base, &nonce);
+				let share = crypto::encode_point(&share);
+				match signer.send_signed_transaction(|_| Call::submit_rekey_share {
+					payload: payload.clone(),
+					share,
+					proof,
+				}) {
+					Some((_, Ok(()))) => sent += 1,
+					_ => return Err("the rekey share could not be submitted"),
+				}
+			}
+			log::debug!(target: LOG_TARGET, "Sent {sent} rekey shares for epoch {}", key.epoch);
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,158 @@
+use crate::{
+	self as pallet_threshold_encryption,
+	crypto::{self, PointBytes, ScalarBytes},
+	DecryptionObserver, EncryptedPayloads, RequestId,
+};
+use core::cell::RefCell;
+use frame_support::{
+	derive_impl,
+	traits::{ConstU32, ConstU64},
+};
+use frame_system::{
+	offchain::{AppCrypto, CreateSignedTransaction, SendTransactionTypes, SigningTypes},
+	EnsureRoot,
+};
+use sp_core::H256;
+use sp_runtime::{
+	testing::{TestSignature, TestXt, UintAuthorityId},
+	traits::Extrinsic as ExtrinsicT,
+	BuildStorage,
+};
+use std::collections::BTreeMap;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		ThresholdEncryption: pallet_threshold_encryption,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+}
+
+/// Transactions sent by the offchain worker.
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
+
+impl SigningTypes for Test {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<LocalCall> SendTransactionTypes<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> CreateSignedTransaction<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		_public: UintAuthorityId,
+		account: u64,
+		_nonce: Self::Nonce,
+	) -> Option<(RuntimeCall, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (account, ())))
+	}
+}
+
+/// Signs the offchain worker's transactions with the keys set by
+/// [`UintAuthorityId::set_all_keys`].
+pub struct TestAuthorityId;
+
+impl AppCrypto<UintAuthorityId, TestSignature> for TestAuthorityId {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+}
+
+thread_local! {
+	static PAYLOADS: RefCell<BTreeMap<H256, (u64, PointBytes)>> = RefCell::new(BTreeMap::new());
+	static REQUESTS: RefCell<Vec<(RequestId, H256, u64, bool)>> = RefCell::new(Vec::new());
+}
+
+/// Encrypted payloads kept in memory.
+pub struct MockPayloads;
+
+impl MockPayloads {
+	pub fn insert(id: H256, owner: u64, ephemeral_key: PointBytes) {
+		PAYLOADS.with(|p| p.borrow_mut().insert(id, (owner, ephemeral_key)));
+	}
+}
+
+impl EncryptedPayloads<H256, u64> for MockPayloads {
+	fn payload(id: &H256) -> Option<(u64, PointBytes)> {
+		PAYLOADS.with(|p| p.borrow().get(id).copied())
+	}
+
+	fn payloads() -> Vec<H256> {
+		PAYLOADS.with(|p| p.borrow().keys().copied().collect())
+	}
+}
+
+/// Records the decryption requests it is notified of.
+pub struct MockObserver;
+
+impl MockObserver {
+	pub fn requests() -> Vec<(RequestId, H256, u64, bool)> {
+		REQUESTS.with(|r| r.borrow().clone())
+	}
+}
+
+impl DecryptionObserver<H256, u64> for MockObserver {
+	fn on_decryption_requested(
+		request_id: RequestId,
+		payload: &H256,
+		requester: &u64,
+		owner: bool,
+	) {
+		REQUESTS.with(|r| r.borrow_mut().push((request_id, *payload, *requester, owner)));
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkHelper<H256, u64> for MockPayloads {
+	fn create_payload(owner: u64, ephemeral_secret: ScalarBytes) -> H256 {
+		let id = H256::repeat_byte(0xbe);
+		let ephemeral_secret = crypto::decode_scalar(&ephemeral_secret).unwrap_or_default();
+		Self::insert(id, owner, crypto::encode_point(&crypto::public_key(&ephemeral_secret)));
+		id
+	}
+}
+
+impl pallet_threshold_encryption::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type PayloadId = H256;
+	type Payloads = MockPayloads;
+	type CommitteeOrigin = EnsureRoot<u64>;
+	type AuthorizeOrigin = EnsureRoot<u64>;
+	type Observer = MockObserver;
+	type AuthorityId = TestAuthorityId;
+	type MaxMembers = ConstU32<16>;
+	type DealingPeriod = ConstU64<10>;
+	type ComplaintPeriod = ConstU64<10>;
+	type MaxRekeysPerBlock = ConstU32<2>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockPayloads;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	PAYLOADS.with(|p| p.borrow_mut().clear());
+	REQUESTS.with(|r| r.borrow_mut().clear());
+	let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	// Events are not emitted on the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
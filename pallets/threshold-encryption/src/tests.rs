@@ -0,0 +1,992 @@
+use crate::{crypto::*, mock::*, *};
+use codec::{Decode, Encode};
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT as G;
+use frame_support::{assert_noop, assert_ok, dispatch::Pays, traits::Hooks, BoundedVec};
+use sp_core::{
+	offchain::{
+		testing::{TestOffchainExt, TestTransactionPoolExt},
+		OffchainDbExt, OffchainWorkerExt, StorageKind, TransactionPoolExt,
+	},
+	H256,
+};
+use sp_runtime::{testing::UintAuthorityId, DispatchError, DispatchResult};
+
+/// Committee members; the member at position `i` holds the key share of index `i + 1`.
+const MEMBERS: [u64; 3] = [10, 11, 12];
+/// Members of the committee the key is rotated to.
+const NEW_MEMBERS: [u64; 4] = [20, 21, 22, 23];
+/// Epoch of the first rotation.
+const EPOCH: EpochIndex = 1;
+const OWNER: u64 = 1;
+const AUDITOR: u64 = 2;
+const PAYLOAD: H256 = H256::repeat_byte(1);
+
+fn scalar(n: u64) -> Scalar {
+	Scalar::from(n)
+}
+
+/// Coefficients of the polynomial dealt by the member at `position`.
+fn polynomial(position: usize) -> Vec<Scalar> {
+	vec![scalar(100 + position as u64), scalar(200 + position as u64)]
+}
+
+fn evaluate(coefficients: &[Scalar], index: u32) -> Scalar {
+	coefficients.iter().rev().fold(Scalar::ZERO, |acc, c| acc * scalar(index as u64) + c)
+}
+
+/// Secret of the encryption key of the member of the first committee at `position`.
+fn committee_secret(position: usize) -> Scalar {
+	scalar(400 + position as u64)
+}
+
+/// First committee and the encryption keys of its members.
+fn committee() -> BoundedVec<(u64, PointBytes), <Test as Config>::MaxMembers> {
+	let members: Vec<_> = MEMBERS
+		.iter()
+		.enumerate()
+		.map(|(position, member)| (*member, encode_point(&public_key(&committee_secret(position)))))
+		.collect();
+	members.try_into().unwrap()
+}
+
+/// Dealing of the member at `position` to the first committee.
+fn dealing(position: usize) -> EncryptedDealing<Test> {
+	let recipients: Vec<_> = (0..MEMBERS.len()).map(committee_secret).collect();
+	deal(&polynomial(position), &scalar(800 + position as u64), &recipients)
+}
+
+/// Proof that `member` knows the secret `secret` of its dealing to the first committee.
+fn dealing_proof(member: u64, secret: &Scalar) -> SchnorrProof {
+	prove_schnorr(&(0 as EpochIndex, member).encode(), secret, &scalar(17))
+}
+
+fn submit_dealing(position: usize, dealing: EncryptedDealing<Test>) -> DispatchResult {
+	let member = MEMBERS[position];
+	let proof = dealing_proof(member, &polynomial(position)[0]);
+	ThresholdEncryption::submit_dealing(RuntimeOrigin::signed(member), dealing, proof)
+}
+
+/// Block after the complaint period of key generation started at block 1.
+const KEY_GENERATION_END: u64 = 22;
+
+/// Run key generation and return the key share of every member.
+fn generate_key() -> Vec<Scalar> {
+	assert_ok!(ThresholdEncryption::set_committee(RuntimeOrigin::root(), committee()));
+	for position in 0..MEMBERS.len() {
+		assert_ok!(submit_dealing(position, dealing(position)));
+	}
+	System::set_block_number(KEY_GENERATION_END);
+	assert_ok!(ThresholdEncryption::finalize_rotation(RuntimeOrigin::signed(OWNER)));
+	(1..=MEMBERS.len() as u32)
+		.map(|index| (0..MEMBERS.len()).map(|p| evaluate(&polynomial(p), index)).sum())
+		.collect()
+}
+
+/// Encrypt a payload under the committee key and return the shared point `K`.
+fn encrypt_payload() -> RistrettoPoint {
+	let aggregate_key = decode_point(&ThresholdEncryption::aggregate_key().unwrap()).unwrap();
+	let (ephemeral_key, shared) = encapsulate(&aggregate_key, &scalar(7));
+	MockPayloads::insert(PAYLOAD, OWNER, encode_point(&ephemeral_key));
+	shared
+}
+
+/// Blinded ephemeral key of `PAYLOAD` and the proof of its blinding, filed by `requester`.
+fn blind(requester: u64, blinding: &Scalar) -> (PointBytes, SchnorrProof) {
+	let (_, ephemeral_key) = MockPayloads::payload(&PAYLOAD).unwrap();
+	let blinded = encode_point(&(decode_point(&ephemeral_key).unwrap() + G * blinding));
+	let context = (requester, PAYLOAD, blinded).encode();
+	(blinded, prove_schnorr(&context, blinding, &scalar(11)))
+}
+
+fn request(requester: u64, blinding: &Scalar) -> DispatchResult {
+	let (blinded, proof) = blind(requester, blinding);
+	let origin = RuntimeOrigin::signed(requester);
+	ThresholdEncryption::request_decryption(origin, PAYLOAD, blinded, proof)
+}
+
+/// Decryption share of the member at `position` for `request_id`.
+fn share(request_id: RequestId, position: usize, key_share: &Scalar) -> (PointBytes, DleqProof) {
+	let request = Requests::<Test>::get(request_id).unwrap();
+	let base = decode_point(&request.blinded_key).unwrap();
+	let context = (request_id, position as u32 + 1).encode();
+	let (share, proof) = prove_dleq(&context, key_share, &base, &scalar(13));
+	(encode_point(&share), proof)
+}
+
+fn submit_share(request_id: RequestId, position: usize, key_share: &Scalar) -> DispatchResult {
+	submit_share_as(MEMBERS[position], request_id, position, key_share)
+}
+
+fn submit_share_as(
+	member: u64,
+	request_id: RequestId,
+	position: usize,
+	key_share: &Scalar,
+) -> DispatchResult {
+	let (share, proof) = share(request_id, position, key_share);
+	ThresholdEncryption::submit_decryption_share(
+		RuntimeOrigin::signed(member),
+		request_id,
+		share,
+		proof,
+	)
+	.map(|_| ())
+	.map_err(|e| e.error)
+}
+
+/// Secret of the encryption key of the incoming member at `position`.
+fn encryption_secret(position: usize) -> Scalar {
+	scalar(500 + position as u64)
+}
+
+/// Incoming members and their encryption keys.
+fn incoming() -> BoundedVec<(u64, PointBytes), <Test as Config>::MaxMembers> {
+	let members: Vec<_> = NEW_MEMBERS
+		.iter()
+		.enumerate()
+		.map(|(position, member)| {
+			(*member, encode_point(&public_key(&encryption_secret(position))))
+		})
+		.collect();
+	members.try_into().unwrap()
+}
+
+/// Dealing of the polynomial with `coefficients` to the members whose encryption keys have the
+/// secrets `recipients`.
+fn deal(
+	coefficients: &[Scalar],
+	ephemeral_secret: &Scalar,
+	recipients: &[Scalar],
+) -> EncryptedDealing<Test> {
+	let commitments: Vec<_> = coefficients.iter().map(|a| encode_point(&public_key(a))).collect();
+	let shares: Vec<_> = recipients
+		.iter()
+		.enumerate()
+		.map(|(position, secret)| {
+			let index = position as u32 + 1;
+			let share = evaluate(coefficients, index);
+			encrypt_share(ephemeral_secret, &public_key(secret), index, &share)
+		})
+		.collect();
+	EncryptedDealing {
+		commitments: commitments.try_into().unwrap(),
+		ephemeral_key: encode_point(&public_key(ephemeral_secret)),
+		shares: shares.try_into().unwrap(),
+	}
+}
+
+/// Dealing of the polynomial with `coefficients` to the incoming committee.
+fn encrypted_dealing(coefficients: &[Scalar], ephemeral_secret: &Scalar) -> EncryptedDealing<Test> {
+	let recipients: Vec<_> = (0..NEW_MEMBERS.len()).map(encryption_secret).collect();
+	deal(coefficients, ephemeral_secret, &recipients)
+}
+
+/// Coefficients of the polynomial resharing the key share of the outgoing member at `position`.
+fn reshare_polynomial(position: usize, key_share: &Scalar) -> Vec<Scalar> {
+	vec![*key_share, scalar(300 + position as u64), scalar(400 + position as u64)]
+}
+
+/// Coefficients of the polynomial dealt by the incoming member at `position`.
+fn fresh_polynomial(position: usize) -> Vec<Scalar> {
+	(0..3).map(|k| scalar(600 + 100 * k + position as u64)).collect()
+}
+
+fn reshare_dealing(position: usize, key_share: &Scalar) -> EncryptedDealing<Test> {
+	encrypted_dealing(&reshare_polynomial(position, key_share), &scalar(900 + position as u64))
+}
+
+fn submit_reshare(position: usize, dealing: EncryptedDealing<Test>) -> DispatchResult {
+	ThresholdEncryption::submit_reshare(RuntimeOrigin::signed(MEMBERS[position]), dealing)
+}
+
+fn submit_fresh(position: usize) -> DispatchResult {
+	let coefficients = fresh_polynomial(position);
+	let context = (EPOCH, NEW_MEMBERS[position]).encode();
+	let proof = prove_schnorr(&context, &coefficients[0], &scalar(17));
+	ThresholdEncryption::submit_fresh_dealing(
+		RuntimeOrigin::signed(NEW_MEMBERS[position]),
+		encrypted_dealing(&coefficients, &scalar(950 + position as u64)),
+		proof,
+	)
+}
+
+/// Block after the dealing period of a rotation started at `KEY_GENERATION_END`.
+const DEALING_END: u64 = 33;
+
+/// Block after the complaint period of a rotation started at `KEY_GENERATION_END`.
+const ROTATION_END: u64 = 43;
+
+/// Rotate the key to `NEW_MEMBERS` with every dealer honest.
+fn rotate(key_shares: &[Scalar]) {
+	assert_ok!(ThresholdEncryption::start_rotation(RuntimeOrigin::root(), incoming()));
+	for (position, key_share) in key_shares.iter().enumerate() {
+		assert_ok!(submit_reshare(position, reshare_dealing(position, key_share)));
+	}
+	for position in 0..NEW_MEMBERS.len() {
+		assert_ok!(submit_fresh(position));
+	}
+	System::set_block_number(ROTATION_END);
+	assert_ok!(ThresholdEncryption::finalize_rotation(RuntimeOrigin::signed(OWNER)));
+}
+
+/// Shares of the old and of the new key of every incoming member, decrypted from the dealings
+/// the pending key is made of.
+fn incoming_shares() -> (Vec<Scalar>, Vec<Scalar>) {
+	let key = PendingKey::<Test>::get().unwrap();
+	let decrypt = |kind: DealingKind, dealer: u64, position: usize| {
+		let dealing = RotationDealings::<Test>::get(kind, dealer).unwrap();
+		let shared = decode_point(&dealing.ephemeral_key).unwrap() * encryption_secret(position);
+		decrypt_share(&shared, position as u32 + 1, &dealing.shares[position]).unwrap()
+	};
+	(0..NEW_MEMBERS.len())
+		.map(|position| {
+			let old: Scalar = key
+				.resharers
+				.iter()
+				.map(|i| {
+					let lambda = lagrange_coefficient(&key.resharers, *i).unwrap();
+					lambda * decrypt(DealingKind::Reshare, MEMBERS[*i as usize - 1], position)
+				})
+				.sum();
+			let new: Scalar = key
+				.fresh_dealers
+				.iter()
+				.map(|i| decrypt(DealingKind::Fresh, NEW_MEMBERS[*i as usize - 1], position))
+				.sum();
+			(old, new)
+		})
+		.unzip()
+}
+
+/// Rekey share of `PAYLOAD` by the incoming member at `position` holding `secret`.
+fn rekey_share(position: usize, secret: &Scalar) -> (PointBytes, DleqProof) {
+	let (_, ephemeral_key) = MockPayloads::payload(&PAYLOAD).unwrap();
+	let context = (EPOCH, PAYLOAD, position as u32 + 1).encode();
+	let base = decode_point(&ephemeral_key).unwrap();
+	let (share, proof) = prove_dleq(&context, secret, &base, &scalar(19));
+	(encode_point(&share), proof)
+}
+
+fn submit_rekey(position: usize, secret: &Scalar) -> DispatchResult {
+	let (share, proof) = rekey_share(position, secret);
+	ThresholdEncryption::submit_rekey_share(
+		RuntimeOrigin::signed(NEW_MEMBERS[position]),
+		PAYLOAD,
+		share,
+		proof,
+	)
+	.map(|_| ())
+	.map_err(|e| e.error)
+}
+
+fn complain(position: usize, kind: DealingKind, dealer: u64, secret: &Scalar) -> DispatchResult {
+	let dealing = RotationDealings::<Test>::get(kind, dealer).unwrap();
+	let ephemeral_key = decode_point(&dealing.ephemeral_key).unwrap();
+	let complainant = NEW_MEMBERS[position];
+	let context = (EPOCH, complainant, dealer, kind).encode();
+	let (shared, proof) = prove_dleq(&context, secret, &ephemeral_key, &scalar(23));
+	ThresholdEncryption::complain(
+		RuntimeOrigin::signed(complainant),
+		kind,
+		dealer,
+		encode_point(&shared),
+		proof,
+	)
+}
+
+#[test]
+fn key_generation_publishes_aggregate_key() {
+	new_test_ext().execute_with(|| {
+		generate_key();
+
+		let secret: Scalar = (0..MEMBERS.len()).map(|p| polynomial(p)[0]).sum();
+		let aggregate_key = encode_point(&(G * secret));
+		assert_eq!(ThresholdEncryption::aggregate_key(), Some(aggregate_key));
+		assert_eq!(Members::<Test>::get().into_inner(), MEMBERS.to_vec());
+		assert_eq!(Threshold::<Test>::get(), 2);
+		assert_eq!(Epoch::<Test>::get(), 0);
+		assert!(Rotation::<Test>::get().is_none());
+		assert_eq!(RotationDealings::<Test>::iter().count(), 0);
+		System::assert_last_event(
+			Event::KeyGenerated { aggregate_key, dealers: vec![1, 2, 3] }.into(),
+		);
+	});
+}
+
+#[test]
+fn committee_must_be_valid() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ThresholdEncryption::set_committee(RuntimeOrigin::signed(OWNER), committee()),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			ThresholdEncryption::set_committee(RuntimeOrigin::root(), BoundedVec::default()),
+			Error::<Test>::EmptyCommittee
+		);
+		let mut duplicate = committee();
+		duplicate[2].0 = MEMBERS[0];
+		assert_noop!(
+			ThresholdEncryption::set_committee(RuntimeOrigin::root(), duplicate),
+			Error::<Test>::DuplicateMember
+		);
+		let mut invalid_key = committee();
+		invalid_key[1].1 = [0xff; 32];
+		assert_noop!(
+			ThresholdEncryption::set_committee(RuntimeOrigin::root(), invalid_key),
+			Error::<Test>::InvalidPoint
+		);
+
+		// Setting the committee again restarts key generation.
+		assert_ok!(ThresholdEncryption::set_committee(RuntimeOrigin::root(), committee()));
+		System::assert_last_event(
+			Event::CommitteeSet { members: MEMBERS.to_vec(), threshold: 2 }.into(),
+		);
+		assert_ok!(submit_dealing(0, dealing(0)));
+		assert_ok!(ThresholdEncryption::set_committee(RuntimeOrigin::root(), committee()));
+		assert_eq!(RotationDealings::<Test>::iter().count(), 0);
+
+		generate_key();
+		assert_noop!(
+			ThresholdEncryption::set_committee(RuntimeOrigin::root(), committee()),
+			Error::<Test>::KeyAlreadyGenerated
+		);
+		assert_noop!(submit_dealing(0, dealing(0)), Error::<Test>::KeyAlreadyGenerated);
+	});
+}
+
+#[test]
+fn dealings_are_validated() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(submit_dealing(0, dealing(0)), Error::<Test>::NoRotation);
+		assert_ok!(ThresholdEncryption::set_committee(RuntimeOrigin::root(), committee()));
+
+		let proof = dealing_proof(OWNER, &polynomial(0)[0]);
+		assert_noop!(
+			ThresholdEncryption::submit_dealing(RuntimeOrigin::signed(OWNER), dealing(0), proof),
+			Error::<Test>::NotMember
+		);
+		let mut too_long = dealing(0);
+		too_long.commitments.try_push(too_long.commitments[0]).unwrap();
+		assert_noop!(submit_dealing(0, too_long), Error::<Test>::InvalidDealing);
+		let mut missing_share = dealing(0);
+		missing_share.shares.pop();
+		assert_noop!(submit_dealing(0, missing_share), Error::<Test>::InvalidDealing);
+		let mut invalid_point = dealing(0);
+		invalid_point.commitments[1] = [0xff; 32];
+		assert_noop!(submit_dealing(0, invalid_point), Error::<Test>::InvalidPoint);
+
+		assert_ok!(submit_dealing(0, dealing(0)));
+		System::assert_last_event(Event::DealingSubmitted { member: MEMBERS[0] }.into());
+		assert_noop!(submit_dealing(0, dealing(0)), Error::<Test>::AlreadyDealt);
+
+		// The last dealer cannot cancel out the others' secrets: it would commit to its own key
+		// minus theirs, whose secret it does not know.
+		let own_key = public_key(&polynomial(1)[0]);
+		let mut rogue = dealing(1);
+		rogue.commitments[0] = encode_point(&(own_key - public_key(&polynomial(0)[0])));
+		assert_noop!(submit_dealing(1, rogue), Error::<Test>::InvalidProof);
+		// Nor can it replay the proof of another member.
+		let proof = dealing_proof(MEMBERS[0], &polynomial(0)[0]);
+		assert_noop!(
+			ThresholdEncryption::submit_dealing(
+				RuntimeOrigin::signed(MEMBERS[1]),
+				dealing(0),
+				proof
+			),
+			Error::<Test>::InvalidProof
+		);
+
+		// Dealings go through the complaint period before the key is generated.
+		assert_noop!(
+			ThresholdEncryption::finalize_rotation(RuntimeOrigin::signed(OWNER)),
+			Error::<Test>::ComplaintPeriodOpen
+		);
+		System::set_block_number(12);
+		assert_noop!(submit_dealing(1, dealing(1)), Error::<Test>::DealingPeriodOver);
+		System::set_block_number(KEY_GENERATION_END);
+		assert_noop!(
+			ThresholdEncryption::finalize_rotation(RuntimeOrigin::signed(OWNER)),
+			Error::<Test>::NotEnoughDealings
+		);
+		assert_eq!(ThresholdEncryption::aggregate_key(), None);
+	});
+}
+
+#[test]
+fn complaints_discard_bad_dealings_of_key_generation() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ThresholdEncryption::set_committee(RuntimeOrigin::root(), committee()));
+
+		// The first member sends a bad share to the second member.
+		let mut bad = dealing(0);
+		let recipient = public_key(&committee_secret(1));
+		bad.shares[1] = encrypt_share(&scalar(800), &recipient, 2, &scalar(1));
+		assert_ok!(submit_dealing(0, bad));
+		for position in 1..MEMBERS.len() {
+			assert_ok!(submit_dealing(position, dealing(position)));
+		}
+
+		System::set_block_number(12);
+		let kind = DealingKind::Fresh;
+		let ephemeral_key = public_key(&scalar(800));
+		let context = (0 as EpochIndex, MEMBERS[1], MEMBERS[0], kind).encode();
+		let (shared, proof) =
+			prove_dleq(&context, &committee_secret(1), &ephemeral_key, &scalar(23));
+		assert_ok!(ThresholdEncryption::complain(
+			RuntimeOrigin::signed(MEMBERS[1]),
+			kind,
+			MEMBERS[0],
+			encode_point(&shared),
+			proof
+		));
+		System::assert_last_event(
+			Event::DealerDisqualified {
+				epoch: 0,
+				kind,
+				dealer: MEMBERS[0],
+				complainant: MEMBERS[1],
+			}
+			.into(),
+		);
+
+		// The key is made of the remaining dealings, which still reach the threshold.
+		System::set_block_number(KEY_GENERATION_END);
+		assert_ok!(ThresholdEncryption::finalize_rotation(RuntimeOrigin::signed(OWNER)));
+		let secret = polynomial(1)[0] + polynomial(2)[0];
+		let aggregate_key = encode_point(&public_key(&secret));
+		assert_eq!(ThresholdEncryption::aggregate_key(), Some(aggregate_key));
+		System::assert_last_event(
+			Event::KeyGenerated { aggregate_key, dealers: vec![2, 3] }.into(),
+		);
+	});
+}
+
+#[test]
+fn threshold_of_shares_releases_blinded_key() {
+	new_test_ext().execute_with(|| {
+		let key_shares = generate_key();
+		let shared = encrypt_payload();
+		let blinding = scalar(9);
+
+		assert_ok!(request(OWNER, &blinding));
+		System::assert_last_event(
+			Event::DecryptionRequested { request_id: 0, payload: PAYLOAD, requester: OWNER }.into(),
+		);
+
+		// One share is not enough.
+		assert_ok!(submit_share(0, 2, &key_shares[2]));
+		assert_eq!(Requests::<Test>::get(0).unwrap().released, None);
+
+		assert_ok!(submit_share(0, 0, &key_shares[0]));
+		let released = Requests::<Test>::get(0).unwrap().released.unwrap();
+		System::assert_last_event(
+			Event::DecryptionReleased { request_id: 0, released, offset: None }.into(),
+		);
+
+		// Only the requester can remove the blinding.
+		let aggregate_key = decode_point(&ThresholdEncryption::aggregate_key().unwrap()).unwrap();
+		let unblinded = unblind(&decode_point(&released).unwrap(), &aggregate_key, &blinding);
+		assert_eq!(unblinded, shared);
+		assert_eq!(symmetric_key(&unblinded), symmetric_key(&shared));
+		assert_ne!(decode_point(&released).unwrap(), shared);
+
+		assert_noop!(submit_share(0, 1, &key_shares[1]), Error::<Test>::AlreadyReleased);
+	});
+}
+
+#[test]
+fn decryption_shares_are_verified() {
+	new_test_ext().execute_with(|| {
+		let key_shares = generate_key();
+		encrypt_payload();
+		assert_ok!(request(OWNER, &scalar(9)));
+
+		// A share computed with another member's key share.
+		assert_noop!(submit_share(0, 0, &key_shares[1]), Error::<Test>::InvalidProof);
+
+		let (share, proof) = share(0, 0, &key_shares[0]);
+		assert_noop!(
+			ThresholdEncryption::submit_decryption_share(
+				RuntimeOrigin::signed(OWNER),
+				0,
+				share,
+				proof
+			),
+			Error::<Test>::NotMember
+		);
+		assert_noop!(
+			ThresholdEncryption::submit_decryption_share(
+				RuntimeOrigin::signed(MEMBERS[0]),
+				1,
+				share,
+				proof
+			),
+			Error::<Test>::RequestNotFound
+		);
+
+		assert_ok!(submit_share(0, 0, &key_shares[0]));
+		assert_noop!(submit_share(0, 0, &key_shares[0]), Error::<Test>::AlreadyShared);
+	});
+}
+
+#[test]
+fn requests_need_ownership_or_authorization() {
+	new_test_ext().execute_with(|| {
+		MockPayloads::insert(PAYLOAD, OWNER, encode_point(&G));
+		assert_noop!(request(OWNER, &scalar(9)), Error::<Test>::NoCommitteeKey);
+
+		generate_key();
+		encrypt_payload();
+		assert_noop!(request(AUDITOR, &scalar(9)), Error::<Test>::NotAuthorized);
+
+		assert_noop!(
+			ThresholdEncryption::authorize_decryption(
+				RuntimeOrigin::signed(OWNER),
+				PAYLOAD,
+				AUDITOR
+			),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(ThresholdEncryption::authorize_decryption(
+			RuntimeOrigin::root(),
+			PAYLOAD,
+			AUDITOR
+		));
+
+		// Proofs are bound to the requester.
+		let (blinded, proof) = blind(OWNER, &scalar(9));
+		assert_noop!(
+			ThresholdEncryption::request_decryption(
+				RuntimeOrigin::signed(AUDITOR),
+				PAYLOAD,
+				blinded,
+				proof
+			),
+			Error::<Test>::InvalidProof
+		);
+
+		// Authorizations are consumed by a single request.
+		assert_ok!(request(AUDITOR, &scalar(9)));
+		assert_noop!(request(AUDITOR, &scalar(9)), Error::<Test>::NotAuthorized);
+
+		// The observer sees every request, whether authorized or filed by the owner.
+		assert_ok!(request(OWNER, &scalar(9)));
+		assert_eq!(
+			MockObserver::requests(),
+			vec![(0, PAYLOAD, AUDITOR, false), (1, PAYLOAD, OWNER, true)]
+		);
+	});
+}
+
+#[test]
+fn unknown_payloads_cannot_be_requested() {
+	new_test_ext().execute_with(|| {
+		generate_key();
+		let (blinded, proof) = (encode_point(&G), prove_schnorr(&[], &scalar(1), &scalar(2)));
+		assert_noop!(
+			ThresholdEncryption::request_decryption(
+				RuntimeOrigin::signed(OWNER),
+				PAYLOAD,
+				blinded,
+				proof
+			),
+			Error::<Test>::PayloadNotFound
+		);
+	});
+}
+
+#[test]
+fn only_the_encrypter_proves_knowledge_of_the_ephemeral_secret() {
+	new_test_ext().execute_with(|| {
+		const COLLECTION: u32 = 0;
+		let ephemeral_key = encode_point(&public_key(&scalar(7)));
+		let context = (OWNER, COLLECTION).encode();
+		let proof = prove_ephemeral_key(&context, &scalar(7), &scalar(11));
+		assert!(ThresholdEncryption::verify_ephemeral_key(
+			&OWNER,
+			&COLLECTION,
+			&ephemeral_key,
+			&proof
+		));
+
+		// The proof binds the ciphertext to its owner and location.
+		assert!(!ThresholdEncryption::verify_ephemeral_key(
+			&AUDITOR,
+			&COLLECTION,
+			&ephemeral_key,
+			&proof
+		));
+		assert!(!ThresholdEncryption::verify_ephemeral_key(&OWNER, &1u32, &ephemeral_key, &proof));
+
+		// A key shifted by a known secret cannot be proven without the original secret, nor can
+		// a proof of knowledge made for another purpose be reused.
+		let shifted = encode_point(&(public_key(&scalar(7)) + G * scalar(3)));
+		let proof = prove_ephemeral_key(&context, &scalar(3), &scalar(11));
+		assert!(!ThresholdEncryption::verify_ephemeral_key(&OWNER, &COLLECTION, &shifted, &proof));
+		let proof = prove_schnorr(&context, &scalar(7), &scalar(11));
+		assert!(!ThresholdEncryption::verify_ephemeral_key(
+			&OWNER,
+			&COLLECTION,
+			&ephemeral_key,
+			&proof
+		));
+	});
+}
+
+#[test]
+fn rotation_rekeys_payloads_and_retires_the_old_key() {
+	new_test_ext().execute_with(|| {
+		let key_shares = generate_key();
+		let shared = encrypt_payload();
+		let old_key = decode_point(&ThresholdEncryption::aggregate_key().unwrap()).unwrap();
+		// A request filed under the old key.
+		assert_ok!(request(OWNER, &scalar(9)));
+
+		rotate(&key_shares);
+		let key = PendingKey::<Test>::get().unwrap();
+		assert_eq!(key.threshold, 3);
+		assert_eq!(key.resharers.into_inner(), vec![1, 2, 3]);
+		assert_eq!(key.fresh_dealers.into_inner(), vec![1, 2, 3, 4]);
+		System::assert_last_event(
+			Event::RotationFinalized { epoch: 1, aggregate_key: key.commitments[0] }.into(),
+		);
+		// The old key is advertised until it is retired.
+		assert_eq!(ThresholdEncryption::aggregate_key(), Some(encode_point(&old_key)));
+
+		// Every incoming member holds a share of the old and of the new key.
+		let (old, new) = incoming_shares();
+		let commitments: Vec<_> =
+			key.commitments.iter().map(|c| decode_point(c).unwrap()).collect();
+		for (position, share) in new.iter().enumerate() {
+			assert_eq!(public_key(share), public_share(&commitments, position as u32 + 1));
+		}
+		let indices = [1, 2, 3];
+		let old_secret: Scalar = indices
+			.iter()
+			.map(|i| lagrange_coefficient(&indices, *i).unwrap() * old[*i as usize - 1])
+			.sum();
+		assert_eq!(public_key(&old_secret), old_key);
+
+		// Rekey the payload with a threshold of rekey shares.
+		let rekey_secrets: Vec<_> = new.iter().zip(&old).map(|(new, old)| new - old).collect();
+		assert_noop!(submit_rekey(0, &rekey_secrets[1]), Error::<Test>::InvalidProof);
+		let (share, proof) = rekey_share(0, &rekey_secrets[0]);
+		let info = ThresholdEncryption::submit_rekey_share(
+			RuntimeOrigin::signed(NEW_MEMBERS[0]),
+			PAYLOAD,
+			share,
+			proof,
+		)
+		.unwrap();
+		assert_eq!(info.pays_fee, Pays::No);
+		assert_noop!(submit_rekey(0, &rekey_secrets[0]), Error::<Test>::AlreadyShared);
+		assert_ok!(submit_rekey(3, &rekey_secrets[3]));
+		assert_ok!(submit_rekey(1, &rekey_secrets[1]));
+		let offset = Offsets::<Test>::get(1, PAYLOAD).unwrap();
+		System::assert_last_event(
+			Event::PayloadRekeyed { epoch: 1, payload: PAYLOAD, offset }.into(),
+		);
+		assert_eq!(RekeyShares::<Test>::get(1, PAYLOAD).len(), 0);
+		assert_noop!(submit_rekey(2, &rekey_secrets[2]), Error::<Test>::AlreadyRekeyed);
+
+		assert_noop!(
+			ThresholdEncryption::retire_key(RuntimeOrigin::signed(OWNER)),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(ThresholdEncryption::retire_key(RuntimeOrigin::root()));
+		System::assert_last_event(
+			Event::KeyRetired { retired: 0, epoch: 1, aggregate_key: key.commitments[0] }.into(),
+		);
+		assert_eq!(Epoch::<Test>::get(), 1);
+		assert_eq!(Members::<Test>::get().into_inner(), NEW_MEMBERS.to_vec());
+		assert_eq!(Threshold::<Test>::get(), 3);
+		assert!(PendingKey::<Test>::get().is_none());
+		assert_eq!(RotationDealings::<Test>::iter().count(), 0);
+
+		// Requests filed under the old key can no longer be answered.
+		assert_noop!(submit_share_as(NEW_MEMBERS[0], 0, 0, &new[0]), Error::<Test>::StaleRequest);
+
+		// The incoming committee decrypts the payload; the offset recovers the old shared point.
+		let blinding = scalar(29);
+		assert_ok!(request(OWNER, &blinding));
+		for position in 0..3 {
+			assert_ok!(submit_share_as(NEW_MEMBERS[position], 1, position, &new[position]));
+		}
+		let released = Requests::<Test>::get(1).unwrap().released.unwrap();
+		System::assert_last_event(
+			Event::DecryptionReleased { request_id: 1, released, offset: Some(offset) }.into(),
+		);
+		let new_key = decode_point(&key.commitments[0]).unwrap();
+		let unblinded = unblind(&decode_point(&released).unwrap(), &new_key, &blinding);
+		assert_eq!(remove_offset(&unblinded, &decode_point(&offset).unwrap()), shared);
+	});
+}
+
+#[test]
+fn rotation_needs_a_key_and_no_rotation_in_progress() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ThresholdEncryption::start_rotation(RuntimeOrigin::root(), incoming()),
+			Error::<Test>::NoCommitteeKey
+		);
+		generate_key();
+		assert_noop!(
+			ThresholdEncryption::start_rotation(RuntimeOrigin::signed(OWNER), incoming()),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			ThresholdEncryption::start_rotation(RuntimeOrigin::root(), BoundedVec::default()),
+			Error::<Test>::EmptyCommittee
+		);
+		let mut duplicate = incoming();
+		duplicate[1].0 = NEW_MEMBERS[0];
+		assert_noop!(
+			ThresholdEncryption::start_rotation(RuntimeOrigin::root(), duplicate),
+			Error::<Test>::DuplicateMember
+		);
+		let mut invalid_key = incoming();
+		invalid_key[1].1 = [0xff; 32];
+		assert_noop!(
+			ThresholdEncryption::start_rotation(RuntimeOrigin::root(), invalid_key),
+			Error::<Test>::InvalidPoint
+		);
+
+		assert_ok!(ThresholdEncryption::start_rotation(RuntimeOrigin::root(), incoming()));
+		System::assert_last_event(
+			Event::RotationStarted { epoch: 1, members: NEW_MEMBERS.to_vec(), threshold: 3 }.into(),
+		);
+		assert_noop!(
+			ThresholdEncryption::start_rotation(RuntimeOrigin::root(), incoming()),
+			Error::<Test>::RotationInProgress
+		);
+	});
+}
+
+#[test]
+fn dealings_are_checked_against_the_committee() {
+	new_test_ext().execute_with(|| {
+		let key_shares = generate_key();
+		assert_noop!(
+			submit_reshare(0, reshare_dealing(0, &key_shares[0])),
+			Error::<Test>::NoRotation
+		);
+		assert_ok!(ThresholdEncryption::start_rotation(RuntimeOrigin::root(), incoming()));
+
+		// A reshare must commit to the dealer's own key share.
+		assert_noop!(
+			submit_reshare(0, reshare_dealing(0, &key_shares[1])),
+			Error::<Test>::InvalidDealing
+		);
+		let mut short = reshare_dealing(0, &key_shares[0]);
+		short.commitments.pop();
+		assert_noop!(submit_reshare(0, short), Error::<Test>::InvalidDealing);
+		let mut missing_share = reshare_dealing(0, &key_shares[0]);
+		missing_share.shares.pop();
+		assert_noop!(submit_reshare(0, missing_share), Error::<Test>::InvalidDealing);
+		let mut invalid_key = reshare_dealing(0, &key_shares[0]);
+		invalid_key.ephemeral_key = [0xff; 32];
+		assert_noop!(submit_reshare(0, invalid_key), Error::<Test>::InvalidPoint);
+		assert_noop!(
+			ThresholdEncryption::submit_reshare(
+				RuntimeOrigin::signed(NEW_MEMBERS[0]),
+				reshare_dealing(0, &key_shares[0])
+			),
+			Error::<Test>::NotMember
+		);
+
+		assert_ok!(submit_reshare(0, reshare_dealing(0, &key_shares[0])));
+		System::assert_last_event(Event::ReshareSubmitted { epoch: 1, member: MEMBERS[0] }.into());
+		assert_noop!(
+			submit_reshare(0, reshare_dealing(0, &key_shares[0])),
+			Error::<Test>::AlreadyDealt
+		);
+
+		// Fresh dealings prove knowledge of their secret.
+		let dealing = encrypted_dealing(&fresh_polynomial(0), &scalar(950));
+		let context = (EPOCH, NEW_MEMBERS[0]).encode();
+		let proof = prove_schnorr(&context, &scalar(1), &scalar(17));
+		assert_noop!(
+			ThresholdEncryption::submit_fresh_dealing(
+				RuntimeOrigin::signed(NEW_MEMBERS[0]),
+				dealing.clone(),
+				proof
+			),
+			Error::<Test>::InvalidProof
+		);
+		assert_noop!(
+			ThresholdEncryption::submit_fresh_dealing(
+				RuntimeOrigin::signed(MEMBERS[0]),
+				dealing,
+				proof
+			),
+			Error::<Test>::NotMember
+		);
+		assert_ok!(submit_fresh(0));
+
+		assert_noop!(
+			ThresholdEncryption::finalize_rotation(RuntimeOrigin::signed(OWNER)),
+			Error::<Test>::ComplaintPeriodOpen
+		);
+		System::set_block_number(DEALING_END);
+		assert_noop!(submit_fresh(1), Error::<Test>::DealingPeriodOver);
+
+		// One reshare and one fresh dealing are below both thresholds.
+		System::set_block_number(ROTATION_END);
+		assert_noop!(
+			ThresholdEncryption::finalize_rotation(RuntimeOrigin::signed(OWNER)),
+			Error::<Test>::NotEnoughDealings
+		);
+	});
+}
+
+#[test]
+fn complaints_discard_bad_dealings() {
+	new_test_ext().execute_with(|| {
+		let key_shares = generate_key();
+		encrypt_payload();
+		assert_ok!(ThresholdEncryption::start_rotation(RuntimeOrigin::root(), incoming()));
+
+		// The first outgoing member sends a bad share to the second incoming member.
+		let mut bad = reshare_dealing(0, &key_shares[0]);
+		let recipient = public_key(&encryption_secret(1));
+		bad.shares[1] = encrypt_share(&scalar(900), &recipient, 2, &scalar(1));
+		assert_ok!(submit_reshare(0, bad));
+		for position in 1..MEMBERS.len() {
+			assert_ok!(submit_reshare(position, reshare_dealing(position, &key_shares[position])));
+		}
+		for position in 0..NEW_MEMBERS.len() {
+			assert_ok!(submit_fresh(position));
+		}
+
+		assert_noop!(
+			complain(1, DealingKind::Reshare, MEMBERS[0], &encryption_secret(1)),
+			Error::<Test>::NotComplaintPeriod
+		);
+		System::set_block_number(DEALING_END);
+		assert_noop!(
+			complain(1, DealingKind::Reshare, MEMBERS[0], &encryption_secret(0)),
+			Error::<Test>::InvalidProof
+		);
+		assert_noop!(
+			complain(1, DealingKind::Reshare, MEMBERS[1], &encryption_secret(1)),
+			Error::<Test>::ComplaintUnfounded
+		);
+		assert_noop!(
+			complain(0, DealingKind::Reshare, MEMBERS[0], &encryption_secret(0)),
+			Error::<Test>::ComplaintUnfounded
+		);
+		assert_ok!(complain(1, DealingKind::Reshare, MEMBERS[0], &encryption_secret(1)));
+		System::assert_last_event(
+			Event::DealerDisqualified {
+				epoch: 1,
+				kind: DealingKind::Reshare,
+				dealer: MEMBERS[0],
+				complainant: NEW_MEMBERS[1],
+			}
+			.into(),
+		);
+		assert!(RotationDealings::<Test>::get(DealingKind::Reshare, MEMBERS[0]).is_none());
+
+		// The remaining reshares still reach the outgoing threshold.
+		System::set_block_number(ROTATION_END);
+		assert_noop!(
+			complain(1, DealingKind::Reshare, MEMBERS[1], &encryption_secret(1)),
+			Error::<Test>::NotComplaintPeriod
+		);
+		assert_ok!(ThresholdEncryption::finalize_rotation(RuntimeOrigin::signed(OWNER)));
+		let key = PendingKey::<Test>::get().unwrap();
+		assert_eq!(key.resharers.into_inner(), vec![2, 3]);
+
+		let (old, new) = incoming_shares();
+		let rekey_secrets: Vec<_> = new.iter().zip(&old).map(|(new, old)| new - old).collect();
+		for position in 0..3 {
+			assert_ok!(submit_rekey(position, &rekey_secrets[position]));
+		}
+		assert!(Offsets::<Test>::contains_key(1, PAYLOAD));
+	});
+}
+
+#[test]
+fn cancelled_rotations_do_not_reuse_epochs() {
+	new_test_ext().execute_with(|| {
+		let key_shares = generate_key();
+		assert_noop!(
+			ThresholdEncryption::cancel_rotation(RuntimeOrigin::root()),
+			Error::<Test>::NoRotation
+		);
+		assert_ok!(ThresholdEncryption::start_rotation(RuntimeOrigin::root(), incoming()));
+		assert_ok!(submit_reshare(0, reshare_dealing(0, &key_shares[0])));
+
+		assert_noop!(
+			ThresholdEncryption::cancel_rotation(RuntimeOrigin::signed(OWNER)),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(ThresholdEncryption::cancel_rotation(RuntimeOrigin::root()));
+		System::assert_last_event(Event::RotationCancelled { epoch: 1 }.into());
+		assert!(Rotation::<Test>::get().is_none());
+		assert_eq!(RotationDealings::<Test>::iter().count(), 0);
+		assert_noop!(
+			ThresholdEncryption::retire_key(RuntimeOrigin::root()),
+			Error::<Test>::NoPendingKey
+		);
+
+		assert_ok!(ThresholdEncryption::start_rotation(RuntimeOrigin::root(), incoming()));
+		assert_eq!(Rotation::<Test>::get().unwrap().epoch, 2);
+		assert_eq!(Epoch::<Test>::get(), 0);
+	});
+}
+
+#[test]
+fn offchain_worker_sends_rekey_shares() {
+	let (offchain, _) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		let key_shares = generate_key();
+		encrypt_payload();
+		rotate(&key_shares);
+		let (old, new) = incoming_shares();
+
+		// Nothing is sent without the member's secret.
+		UintAuthorityId::set_all_keys(vec![NEW_MEMBERS[1]]);
+		ThresholdEncryption::offchain_worker(ROTATION_END);
+		assert!(pool_state.read().transactions.is_empty());
+
+		let secret = (EPOCH, (new[1] - old[1]).to_bytes());
+		sp_io::offchain::local_storage_set(
+			StorageKind::PERSISTENT,
+			REKEY_SECRET_KEY,
+			&secret.encode(),
+		);
+		ThresholdEncryption::offchain_worker(ROTATION_END);
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, Some((NEW_MEMBERS[1], ())));
+		let RuntimeCall::ThresholdEncryption(crate::Call::submit_rekey_share {
+			payload,
+			share,
+			proof,
+		}) = tx.call
+		else {
+			panic!("unexpected call");
+		};
+
+		// The share is not sent again while it may be waiting in the pool.
+		ThresholdEncryption::offchain_worker(ROTATION_END + 1);
+		assert!(pool_state.read().transactions.is_empty());
+
+		assert_ok!(ThresholdEncryption::submit_rekey_share(
+			RuntimeOrigin::signed(NEW_MEMBERS[1]),
+			payload,
+			share,
+			proof
+		));
+		assert_eq!(RekeyShares::<Test>::get(1, PAYLOAD).into_inner(), vec![(2, share)]);
+	});
+}
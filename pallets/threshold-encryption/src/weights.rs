@@ -0,0 +1,344 @@
+//! Weights for pallet_threshold_encryption
+//!
+//! PLACEHOLDER WEIGHTS, written by hand rather than measured. Storage reads, writes and proof
+//! sizes follow the storage accessed by the worst case of each benchmark in `benchmarking.rs`,
+//! for the runtime configuration (`MaxMembers = 16`). The ref-time components are guesses.
+//! Replace this file with the output of the node's `benchmark pallet` command, run on reference
+//! hardware, before relying on these weights.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_threshold_encryption.
+pub trait WeightInfo {
+	fn set_committee(m: u32, ) -> Weight;
+	fn submit_dealing(m: u32, ) -> Weight;
+	fn request_decryption() -> Weight;
+	fn submit_decryption_share(m: u32, ) -> Weight;
+	fn authorize_decryption() -> Weight;
+	fn start_rotation(m: u32, ) -> Weight;
+	fn submit_reshare(m: u32, ) -> Weight;
+	fn submit_fresh_dealing(m: u32, ) -> Weight;
+	fn complain(m: u32, ) -> Weight;
+	fn finalize_rotation(m: u32, ) -> Weight;
+	fn cancel_rotation(m: u32, ) -> Weight;
+	fn submit_rekey_share(m: u32, ) -> Weight;
+	fn retire_key(m: u32, ) -> Weight;
+}
+
+/// Placeholder weights for pallet_threshold_encryption, until it is benchmarked on reference hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:16 w:16)
+	/// Storage: `ThresholdEncryption::Rotation` (r:0 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn set_committee(m: u32, ) -> Weight {
+		Weight::from_parts(19_800_000, 1_527)
+			.saturating_add(Weight::from_parts(24_600_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(17_u64))
+			.saturating_add(T::DbWeight::get().writes(17_u64))
+			.saturating_add(Weight::from_parts(0, 48).saturating_mul(m.into()))
+	}
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:1 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn submit_dealing(m: u32, ) -> Weight {
+		Weight::from_parts(424_900_000, 2_107)
+			.saturating_add(Weight::from_parts(28_100_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 96).saturating_mul(m.into()))
+	}
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `TemplateModule::Vectors` (r:1 w:0)
+	/// Storage: `TemplateModule::Collections` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Authorizations` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::NextRequestId` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Epoch` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Requests` (r:0 w:1)
+	/// Storage: `Audit::Grants` (r:1 w:1)
+	/// Storage: `Audit::NextAccessId` (r:1 w:1)
+	/// Storage: `Audit::AccessLog` (r:0 w:1)
+	fn request_decryption() -> Weight {
+		Weight::from_parts(421_300_000, 4_098)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: `ThresholdEncryption::Members` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Requests` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Epoch` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Threshold` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Offsets` (r:1 w:0)
+	/// The range of component `m` is `[1, 16]`.
+	fn submit_decryption_share(m: u32, ) -> Weight {
+		Weight::from_parts(905_300_000, 3_672)
+			.saturating_add(Weight::from_parts(187_400_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `ThresholdEncryption::Authorizations` (r:0 w:1)
+	fn authorize_decryption() -> Weight {
+		Weight::from_parts(11_300_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::PendingKey` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::LatestEpoch` (r:1 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn start_rotation(m: u32, ) -> Weight {
+		Weight::from_parts(21_400_000, 1_527)
+			.saturating_add(Weight::from_parts(24_600_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 48).saturating_mul(m.into()))
+	}
+	/// Storage: `ThresholdEncryption::Members` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// The range of component `m` is `[1, 16]`.
+	fn submit_reshare(m: u32, ) -> Weight {
+		Weight::from_parts(310_200_000, 4_163)
+			.saturating_add(Weight::from_parts(52_300_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 96).saturating_mul(m.into()))
+	}
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:1 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn submit_fresh_dealing(m: u32, ) -> Weight {
+		Weight::from_parts(424_900_000, 2_107)
+			.saturating_add(Weight::from_parts(28_100_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 96).saturating_mul(m.into()))
+	}
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:1 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn complain(m: u32, ) -> Weight {
+		Weight::from_parts(862_500_000, 3_529)
+			.saturating_add(Weight::from_parts(110_400_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Members` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Threshold` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:32 w:0)
+	/// Storage: `ThresholdEncryption::PendingKey` (r:0 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn finalize_rotation(m: u32, ) -> Weight {
+		Weight::from_parts(155_900_000, 2_107)
+			.saturating_add(Weight::from_parts(1_241_000_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 1_062).saturating_mul(m.into()))
+	}
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::PendingKey` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:0 w:32)
+	/// The range of component `m` is `[1, 16]`.
+	fn cancel_rotation(m: u32, ) -> Weight {
+		Weight::from_parts(16_300_000, 1_993)
+			.saturating_add(Weight::from_parts(3_050_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(m.into())))
+	}
+	/// Storage: `ThresholdEncryption::PendingKey` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Offsets` (r:2 w:1)
+	/// Storage: `TemplateModule::Vectors` (r:1 w:0)
+	/// Storage: `TemplateModule::Collections` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RekeyShares` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Epoch` (r:1 w:0)
+	/// The range of component `m` is `[1, 16]`.
+	fn submit_rekey_share(m: u32, ) -> Weight {
+		Weight::from_parts(914_600_000, 5_233)
+			.saturating_add(Weight::from_parts(190_200_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `ThresholdEncryption::PendingKey` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Epoch` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Members` (r:0 w:1)
+	/// Storage: `ThresholdEncryption::Threshold` (r:0 w:1)
+	/// Storage: `ThresholdEncryption::Commitments` (r:0 w:1)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:0 w:32)
+	/// The range of component `m` is `[1, 16]`.
+	fn retire_key(m: u32, ) -> Weight {
+		Weight::from_parts(24_100_000, 2_107)
+			.saturating_add(Weight::from_parts(3_050_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(m.into())))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:16 w:16)
+	/// Storage: `ThresholdEncryption::Rotation` (r:0 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn set_committee(m: u32, ) -> Weight {
+		Weight::from_parts(19_800_000, 1_527)
+			.saturating_add(Weight::from_parts(24_600_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(17_u64))
+			.saturating_add(RocksDbWeight::get().writes(17_u64))
+			.saturating_add(Weight::from_parts(0, 48).saturating_mul(m.into()))
+	}
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:1 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn submit_dealing(m: u32, ) -> Weight {
+		Weight::from_parts(424_900_000, 2_107)
+			.saturating_add(Weight::from_parts(28_100_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 96).saturating_mul(m.into()))
+	}
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `TemplateModule::Vectors` (r:1 w:0)
+	/// Storage: `TemplateModule::Collections` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Authorizations` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::NextRequestId` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Epoch` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Requests` (r:0 w:1)
+	/// Storage: `Audit::Grants` (r:1 w:1)
+	/// Storage: `Audit::NextAccessId` (r:1 w:1)
+	/// Storage: `Audit::AccessLog` (r:0 w:1)
+	fn request_decryption() -> Weight {
+		Weight::from_parts(421_300_000, 4_098)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: `ThresholdEncryption::Members` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Requests` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Epoch` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Threshold` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Offsets` (r:1 w:0)
+	/// The range of component `m` is `[1, 16]`.
+	fn submit_decryption_share(m: u32, ) -> Weight {
+		Weight::from_parts(905_300_000, 3_672)
+			.saturating_add(Weight::from_parts(187_400_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `ThresholdEncryption::Authorizations` (r:0 w:1)
+	fn authorize_decryption() -> Weight {
+		Weight::from_parts(11_300_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::PendingKey` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::LatestEpoch` (r:1 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn start_rotation(m: u32, ) -> Weight {
+		Weight::from_parts(21_400_000, 1_527)
+			.saturating_add(Weight::from_parts(24_600_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 48).saturating_mul(m.into()))
+	}
+	/// Storage: `ThresholdEncryption::Members` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// The range of component `m` is `[1, 16]`.
+	fn submit_reshare(m: u32, ) -> Weight {
+		Weight::from_parts(310_200_000, 4_163)
+			.saturating_add(Weight::from_parts(52_300_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 96).saturating_mul(m.into()))
+	}
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:1 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn submit_fresh_dealing(m: u32, ) -> Weight {
+		Weight::from_parts(424_900_000, 2_107)
+			.saturating_add(Weight::from_parts(28_100_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 96).saturating_mul(m.into()))
+	}
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:1 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn complain(m: u32, ) -> Weight {
+		Weight::from_parts(862_500_000, 3_529)
+			.saturating_add(Weight::from_parts(110_400_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Commitments` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Members` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Threshold` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:32 w:0)
+	/// Storage: `ThresholdEncryption::PendingKey` (r:0 w:1)
+	/// The range of component `m` is `[1, 16]`.
+	fn finalize_rotation(m: u32, ) -> Weight {
+		Weight::from_parts(155_900_000, 2_107)
+			.saturating_add(Weight::from_parts(1_241_000_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(m.into())))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 1_062).saturating_mul(m.into()))
+	}
+	/// Storage: `ThresholdEncryption::Rotation` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::PendingKey` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:0 w:32)
+	/// The range of component `m` is `[1, 16]`.
+	fn cancel_rotation(m: u32, ) -> Weight {
+		Weight::from_parts(16_300_000, 1_993)
+			.saturating_add(Weight::from_parts(3_050_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(m.into())))
+	}
+	/// Storage: `ThresholdEncryption::PendingKey` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::Offsets` (r:2 w:1)
+	/// Storage: `TemplateModule::Vectors` (r:1 w:0)
+	/// Storage: `TemplateModule::Collections` (r:1 w:0)
+	/// Storage: `ThresholdEncryption::RekeyShares` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Epoch` (r:1 w:0)
+	/// The range of component `m` is `[1, 16]`.
+	fn submit_rekey_share(m: u32, ) -> Weight {
+		Weight::from_parts(914_600_000, 5_233)
+			.saturating_add(Weight::from_parts(190_200_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `ThresholdEncryption::PendingKey` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Epoch` (r:1 w:1)
+	/// Storage: `ThresholdEncryption::Members` (r:0 w:1)
+	/// Storage: `ThresholdEncryption::Threshold` (r:0 w:1)
+	/// Storage: `ThresholdEncryption::Commitments` (r:0 w:1)
+	/// Storage: `ThresholdEncryption::RotationDealings` (r:0 w:32)
+	/// The range of component `m` is `[1, 16]`.
+	fn retire_key(m: u32, ) -> Weight {
+		Weight::from_parts(24_100_000, 2_107)
+			.saturating_add(Weight::from_parts(3_050_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(m.into())))
+	}
+}
@@ -0,0 +1,68 @@
+//! Benchmarking setup for pallet-zk-verifier
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as ZkVerifier;
+use frame_benchmarking::v2::*;
+use frame_support::{traits::Get, BoundedVec};
+use frame_system::RawOrigin;
+
+fn subject<T: Config>() -> T::Hash {
+	T::Hash::default()
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn register_circuit(n: Linear<1, { T::MaxPublicInputs::get() }>) -> Result<(), BenchmarkError> {
+		let origin =
+			T::RegistrarOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let (key, _, _) = T::BenchmarkHelper::proof(subject_input(subject::<T>().as_ref()), n);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, BoundedVec::truncate_from(key));
+
+		assert_eq!(Circuits::<T>::get(0).map(|circuit| circuit.public_inputs), Some(n));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn remove_circuit() -> Result<(), BenchmarkError> {
+		let origin =
+			T::RegistrarOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let (key, _, _) = T::BenchmarkHelper::proof(subject_input(subject::<T>().as_ref()), 1);
+		let circuit = CircuitInfo { key: BoundedVec::truncate_from(key), public_inputs: 1 };
+		Circuits::<T>::insert(0, circuit);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 0);
+
+		assert!(!Circuits::<T>::contains_key(0));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn submit_proof(n: Linear<1, { T::MaxPublicInputs::get() }>) {
+		let subject = subject::<T>();
+		let (key, inputs, proof) = T::BenchmarkHelper::proof(subject_input(subject.as_ref()), n);
+		let circuit = CircuitInfo { key: BoundedVec::truncate_from(key), public_inputs: n };
+		Circuits::<T>::insert(0, circuit);
+		let caller: T::AccountId = whitelisted_caller();
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Signed(caller),
+			0,
+			subject,
+			BoundedVec::truncate_from(inputs),
+			BoundedVec::truncate_from(proof),
+		);
+
+		assert!(Attestations::<T>::contains_key(subject, 0));
+	}
+
+	impl_benchmark_test_suite!(ZkVerifier, crate::mock::new_test_ext(), crate::mock::Test);
+}
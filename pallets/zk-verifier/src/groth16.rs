@@ -0,0 +1,111 @@
+//! Groth16 proofs over the BN254 curve.
+//!
+//! Verification keys, proofs and public inputs use the compressed canonical serialization of
+//! arkworks. Public inputs are scalars of BN254's scalar field and must be canonical, i.e. less
+//! than the field modulus.
+
+use crate::{ProofVerifier, PublicInput};
+use alloc::vec::Vec;
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::{prepare_verifying_key, Groth16, Proof, VerifyingKey};
+use ark_serialize::CanonicalDeserialize;
+
+/// Verifies Groth16 proofs over BN254.
+pub struct Groth16Bn254;
+
+impl ProofVerifier for Groth16Bn254 {
+	fn validate_key(key: &[u8]) -> Option<u32> {
+		let mut reader = key;
+		let key = VerifyingKey::<Bn254>::deserialize_compressed(&mut reader).ok()?;
+		if !reader.is_empty() {
+			return None;
+		}
+		(key.gamma_abc_g1.len() as u32).checked_sub(1)
+	}
+
+	fn verify(key: &[u8], public_inputs: &[PublicInput], proof: &[u8]) -> bool {
+		// The key was validated when the circuit was registered.
+		let Ok(key) = VerifyingKey::<Bn254>::deserialize_compressed_unchecked(key) else {
+			return false;
+		};
+		let mut reader = proof;
+		let Ok(proof) = Proof::<Bn254>::deserialize_compressed(&mut reader) else {
+			return false;
+		};
+		let Some(inputs) = public_inputs
+			.iter()
+			.map(|input| Fr::deserialize_compressed(&input[..]).ok())
+			.collect::<Option<Vec<_>>>()
+		else {
+			return false;
+		};
+		if !reader.is_empty() || key.gamma_abc_g1.len() != inputs.len() + 1 {
+			return false;
+		}
+
+		Groth16::<Bn254>::verify_proof(&prepare_verifying_key(&key), &proof, &inputs)
+			.unwrap_or(false)
+	}
+}
+
+/// A circuit to exercise the verifier with, in tests and benchmarks.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub mod testing {
+	use super::*;
+	use ark_relations::{
+		lc,
+		r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+	};
+	use ark_serialize::CanonicalSerialize;
+
+	/// Proves knowledge of the square roots of all public inputs but the subject.
+	#[derive(Clone)]
+	struct SquareRoots {
+		subject: Fr,
+		roots: Vec<Fr>,
+	}
+
+	impl ConstraintSynthesizer<Fr> for SquareRoots {
+		fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+			// A public input outside of every constraint would not be bound by the proof.
+			let subject = cs.new_input_variable(|| Ok(self.subject))?;
+			cs.enforce_constraint(lc!() + subject, lc!() + Variable::One, lc!() + subject)?;
+			for root in self.roots {
+				let square = cs.new_input_variable(|| Ok(root * root))?;
+				let root = cs.new_witness_variable(|| Ok(root))?;
+				cs.enforce_constraint(lc!() + root, lc!() + root, lc!() + square)?;
+			}
+			Ok(())
+		}
+	}
+
+	fn serialize(value: &impl CanonicalSerialize) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		value.serialize_compressed(&mut bytes).expect("writing to a vector cannot fail");
+		bytes
+	}
+
+	/// Verification key of a circuit with `public_inputs` public inputs, together with the
+	/// public inputs and proof of a statement about `subject`.
+	pub fn prove(subject: PublicInput, public_inputs: u32) -> (Vec<u8>, Vec<PublicInput>, Vec<u8>) {
+		let circuit = SquareRoots {
+			subject: Fr::deserialize_compressed(&subject[..]).expect("subject is canonical"),
+			roots: (1..public_inputs).map(|i| Fr::from(i as u64 + 1)).collect(),
+		};
+		let mut inputs = alloc::vec![subject];
+		inputs.extend(circuit.roots.iter().map(|root| {
+			let mut input = [0; 32];
+			input.copy_from_slice(&serialize(&(*root * root)));
+			input
+		}));
+
+		let mut rng = ark_std::test_rng();
+		let proving_key =
+			Groth16::<Bn254>::generate_random_parameters_with_reduction(circuit.clone(), &mut rng)
+				.expect("the circuit is satisfiable");
+		let proof =
+			Groth16::<Bn254>::create_random_proof_with_reduction(circuit, &proving_key, &mut rng)
+				.expect("the circuit is satisfiable");
+		(serialize(&proving_key.vk), inputs, serialize(&proof))
+	}
+}
@@ -0,0 +1,261 @@
+//! # ZK Verifier Pallet
+//!
+//! Zero-knowledge proofs let agents make verifiable claims about memories and tasks without
+//! exposing them, e.g. that a ciphertext encrypts a vector of norm one whose hash is `H`.
+//!
+//! ## Overview
+//!
+//! - [`Pallet::register_circuit`] adds the verification key of a circuit to the registry once
+//!   [`Config::Verifier`] accepts it, and [`Pallet::remove_circuit`] retires it.
+//! - [`Pallet::submit_proof`] verifies a proof for a registered circuit about a subject, any hash
+//!   such as the id of a vector or a task, and records the attestation in [`Attestations`].
+//! - The first public input of every proof is the subject, as encoded by [`subject_input`], so a
+//!   proof about one subject cannot be replayed for another. Circuits must expose it as a public
+//!   input and constrain it.
+//!
+//! Proof systems plug in through [`ProofVerifier`]. [`groth16::Groth16Bn254`] verifies Groth16
+//! proofs over BN254.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod groth16;
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[cfg(feature = "runtime-benchmarks")]
+use alloc::vec::Vec;
+
+/// Identifier of a registered circuit.
+pub type CircuitId = u32;
+
+/// A public input of a proof: a scalar of the proof system's field, 32 bytes little endian.
+pub type PublicInput = [u8; 32];
+
+/// Verifies zero-knowledge proofs against the verification key of a circuit.
+pub trait ProofVerifier {
+	/// Number of public inputs of the circuit with verification key `key`, `None` if the key is
+	/// malformed.
+	fn validate_key(key: &[u8]) -> Option<u32>;
+
+	/// Whether `proof` is valid for `public_inputs` under the verification key `key`, which
+	/// passed [`ProofVerifier::validate_key`].
+	fn verify(key: &[u8], public_inputs: &[PublicInput], proof: &[u8]) -> bool;
+}
+
+/// Public input binding a proof to `subject`: its first 32 bytes, little endian, with the top
+/// three bits cleared so that it is a canonical scalar of any field of at least 253 bits.
+pub fn subject_input(subject: &[u8]) -> PublicInput {
+	let mut input = [0; 32];
+	let len = subject.len().min(32);
+	input[..len].copy_from_slice(&subject[..len]);
+	input[31] &= 0x1f;
+	input
+}
+
+/// Creates circuits and proofs for the benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper {
+	/// Verification key of a circuit with `public_inputs` public inputs, together with the public
+	/// inputs and proof of a statement about `subject`.
+	fn proof(subject: PublicInput, public_inputs: u32) -> (Vec<u8>, Vec<PublicInput>, Vec<u8>);
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Weight information for the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+		/// Verifies proofs against the registered verification keys.
+		type Verifier: ProofVerifier;
+		/// Origin allowed to register and remove circuits.
+		type RegistrarOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Maximum length of a verification key, in bytes.
+		#[pallet::constant]
+		type MaxKeyLength: Get<u32>;
+		/// Maximum length of a proof, in bytes.
+		#[pallet::constant]
+		type MaxProofLength: Get<u32>;
+		/// Maximum number of public inputs of a circuit.
+		#[pallet::constant]
+		type MaxPublicInputs: Get<u32>;
+		/// Creates circuits and proofs for the benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper;
+	}
+
+	/// A registered circuit.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct CircuitInfo<T: Config> {
+		/// Verification key of the circuit.
+		pub key: BoundedVec<u8, T::MaxKeyLength>,
+		/// Number of public inputs, the subject included.
+		pub public_inputs: u32,
+	}
+
+	/// A verified claim about a subject.
+	#[derive(
+		CloneNoBound, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEqNoBound, EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct Attestation<T: Config> {
+		/// Account that submitted the proof.
+		pub prover: T::AccountId,
+		/// Public inputs of the proof, the subject first.
+		pub public_inputs: BoundedVec<PublicInput, T::MaxPublicInputs>,
+		/// Block the proof was verified in.
+		pub block: BlockNumberFor<T>,
+	}
+
+	/// Identifier of the next circuit.
+	#[pallet::storage]
+	pub type NextCircuitId<T: Config> = StorageValue<_, CircuitId, ValueQuery>;
+
+	/// Registered circuits.
+	#[pallet::storage]
+	pub type Circuits<T: Config> = StorageMap<_, Twox64Concat, CircuitId, CircuitInfo<T>>;
+
+	/// Latest verified proof about a subject, by circuit. Attestations outlive the removal of
+	/// their circuit.
+	#[pallet::storage]
+	pub type Attestations<T: Config> = StorageDoubleMap<
+		_,
+		Identity,
+		T::Hash,
+		Twox64Concat,
+		CircuitId,
+		Attestation<T>,
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A circuit was registered
+		CircuitRegistered { circuit_id: CircuitId, public_inputs: u32 },
+		/// A circuit was removed from the registry
+		CircuitRemoved { circuit_id: CircuitId },
+		/// A proof about a subject was verified
+		ProofVerified { circuit_id: CircuitId, subject: T::Hash, prover: T::AccountId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The verification key is malformed, or its circuit has no public input for the
+		/// subject or too many public inputs
+		InvalidKey,
+		/// Circuit not found
+		CircuitNotFound,
+		/// The number of public inputs does not match the circuit
+		WrongInputCount,
+		/// The first public input is not the subject
+		SubjectMismatch,
+		/// The proof does not verify
+		InvalidProof,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register a circuit with its verification key.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::register_circuit(T::MaxPublicInputs::get()))]
+		pub fn register_circuit(
+			origin: OriginFor<T>,
+			key: BoundedVec<u8, T::MaxKeyLength>,
+		) -> DispatchResultWithPostInfo {
+			T::RegistrarOrigin::ensure_origin(origin)?;
+			let public_inputs = T::Verifier::validate_key(&key)
+				.filter(|n| (1..=T::MaxPublicInputs::get()).contains(n))
+				.ok_or(Error::<T>::InvalidKey)?;
+
+			let circuit_id = NextCircuitId::<T>::mutate(|id| {
+				let current = *id;
+				*id = id.saturating_add(1);
+				current
+			});
+			Circuits::<T>::insert(circuit_id, CircuitInfo { key, public_inputs });
+
+			Self::deposit_event(Event::CircuitRegistered { circuit_id, public_inputs });
+			Ok(Some(T::WeightInfo::register_circuit(public_inputs)).into())
+		}
+
+		/// Remove a circuit from the registry.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::remove_circuit())]
+		pub fn remove_circuit(origin: OriginFor<T>, circuit_id: CircuitId) -> DispatchResult {
+			T::RegistrarOrigin::ensure_origin(origin)?;
+			ensure!(Circuits::<T>::contains_key(circuit_id), Error::<T>::CircuitNotFound);
+
+			Circuits::<T>::remove(circuit_id);
+			Self::deposit_event(Event::CircuitRemoved { circuit_id });
+			Ok(())
+		}
+
+		/// Prove a claim about `subject` with the circuit `circuit_id`.
+		///
+		/// The first public input must be the [`subject_input`] of `subject`.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::submit_proof(public_inputs.len() as u32))]
+		pub fn submit_proof(
+			origin: OriginFor<T>,
+			circuit_id: CircuitId,
+			subject: T::Hash,
+			public_inputs: BoundedVec<PublicInput, T::MaxPublicInputs>,
+			proof: BoundedVec<u8, T::MaxProofLength>,
+		) -> DispatchResult {
+			let prover = ensure_signed(origin)?;
+			let circuit = Circuits::<T>::get(circuit_id).ok_or(Error::<T>::CircuitNotFound)?;
+			ensure!(
+				public_inputs.len() as u32 == circuit.public_inputs,
+				Error::<T>::WrongInputCount
+			);
+			ensure!(
+				public_inputs.first() == Some(&subject_input(subject.as_ref())),
+				Error::<T>::SubjectMismatch
+			);
+			ensure!(
+				T::Verifier::verify(&circuit.key, &public_inputs, &proof),
+				Error::<T>::InvalidProof
+			);
+
+			Attestations::<T>::insert(
+				subject,
+				circuit_id,
+				Attestation {
+					prover: prover.clone(),
+					public_inputs,
+					block: frame_system::Pallet::<T>::block_number(),
+				},
+			);
+			Self::deposit_event(Event::ProofVerified { circuit_id, subject, prover });
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,54 @@
+use crate::{self as pallet_zk_verifier, groth16::Groth16Bn254};
+use frame_support::{derive_impl, traits::ConstU32};
+use frame_system::EnsureRoot;
+use sp_runtime::BuildStorage;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		ZkVerifier: pallet_zk_verifier,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+}
+
+/// Proves statements of the test circuit of [`crate::groth16::testing`].
+#[cfg(feature = "runtime-benchmarks")]
+pub struct TestCircuit;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkHelper for TestCircuit {
+	fn proof(
+		subject: crate::PublicInput,
+		public_inputs: u32,
+	) -> (Vec<u8>, Vec<crate::PublicInput>, Vec<u8>) {
+		crate::groth16::testing::prove(subject, public_inputs)
+	}
+}
+
+impl pallet_zk_verifier::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Verifier = Groth16Bn254;
+	type RegistrarOrigin = EnsureRoot<u64>;
+	type MaxKeyLength = ConstU32<1024>;
+	type MaxProofLength = ConstU32<256>;
+	type MaxPublicInputs = ConstU32<4>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = TestCircuit;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	// Events are not emitted on the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
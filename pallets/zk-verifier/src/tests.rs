@@ -0,0 +1,130 @@
+use crate::{groth16::testing::prove, mock::*, *};
+use frame_support::{assert_noop, assert_ok};
+use sp_core::H256;
+use sp_runtime::{DispatchError, DispatchResult};
+
+const PROVER: u64 = 1;
+const SUBJECT: H256 = H256::repeat_byte(0xab);
+
+fn register(key: Vec<u8>) -> DispatchResult {
+	ZkVerifier::register_circuit(RuntimeOrigin::root(), key.try_into().unwrap())
+		.map(|_| ())
+		.map_err(|e| e.error)
+}
+
+fn submit(
+	circuit_id: CircuitId,
+	subject: H256,
+	public_inputs: Vec<PublicInput>,
+	proof: Vec<u8>,
+) -> DispatchResult {
+	ZkVerifier::submit_proof(
+		RuntimeOrigin::signed(PROVER),
+		circuit_id,
+		subject,
+		public_inputs.try_into().unwrap(),
+		proof.try_into().unwrap(),
+	)
+}
+
+#[test]
+fn subject_inputs_are_canonical() {
+	let input = subject_input(&[0xff; 32]);
+	assert_eq!(input[..31], [0xff; 31]);
+	assert_eq!(input[31], 0x1f);
+	assert_eq!(subject_input(&[1, 2]), {
+		let mut expected = [0; 32];
+		expected[..2].copy_from_slice(&[1, 2]);
+		expected
+	});
+}
+
+#[test]
+fn circuits_need_a_valid_key() {
+	new_test_ext().execute_with(|| {
+		let (key, _, _) = prove(subject_input(SUBJECT.as_ref()), 3);
+		assert_noop!(
+			ZkVerifier::register_circuit(
+				RuntimeOrigin::signed(PROVER),
+				key.clone().try_into().unwrap()
+			),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(register(vec![7; 64]), Error::<Test>::InvalidKey);
+		let mut trailing = key.clone();
+		trailing.push(0);
+		assert_noop!(register(trailing), Error::<Test>::InvalidKey);
+		let (too_many, _, _) = prove(subject_input(SUBJECT.as_ref()), 5);
+		assert_noop!(register(too_many), Error::<Test>::InvalidKey);
+
+		assert_ok!(register(key.clone()));
+		System::assert_last_event(
+			Event::CircuitRegistered { circuit_id: 0, public_inputs: 3 }.into(),
+		);
+		let circuit = Circuits::<Test>::get(0).unwrap();
+		assert_eq!((circuit.key.into_inner(), circuit.public_inputs), (key, 3));
+		assert_eq!(NextCircuitId::<Test>::get(), 1);
+
+		assert_noop!(
+			ZkVerifier::remove_circuit(RuntimeOrigin::root(), 1),
+			Error::<Test>::CircuitNotFound
+		);
+		assert_ok!(ZkVerifier::remove_circuit(RuntimeOrigin::root(), 0));
+		System::assert_last_event(Event::CircuitRemoved { circuit_id: 0 }.into());
+		assert!(!Circuits::<Test>::contains_key(0));
+	});
+}
+
+#[test]
+fn valid_proofs_are_attested() {
+	new_test_ext().execute_with(|| {
+		let (key, inputs, proof) = prove(subject_input(SUBJECT.as_ref()), 3);
+		assert_ok!(register(key));
+
+		System::set_block_number(5);
+		assert_ok!(submit(0, SUBJECT, inputs.clone(), proof));
+		System::assert_last_event(
+			Event::ProofVerified { circuit_id: 0, subject: SUBJECT, prover: PROVER }.into(),
+		);
+		let attestation = Attestations::<Test>::get(SUBJECT, 0).unwrap();
+		assert_eq!(attestation.prover, PROVER);
+		assert_eq!(attestation.public_inputs.into_inner(), inputs);
+		assert_eq!(attestation.block, 5);
+
+		// Attestations outlive their circuit.
+		assert_ok!(ZkVerifier::remove_circuit(RuntimeOrigin::root(), 0));
+		assert!(Attestations::<Test>::contains_key(SUBJECT, 0));
+	});
+}
+
+#[test]
+fn invalid_proofs_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let (key, inputs, proof) = prove(subject_input(SUBJECT.as_ref()), 3);
+		assert_noop!(
+			submit(0, SUBJECT, inputs.clone(), proof.clone()),
+			Error::<Test>::CircuitNotFound
+		);
+		assert_ok!(register(key));
+
+		assert_noop!(
+			submit(0, SUBJECT, inputs[..2].to_vec(), proof.clone()),
+			Error::<Test>::WrongInputCount
+		);
+		// A proof about one subject cannot be claimed for another.
+		assert_noop!(
+			submit(0, H256::repeat_byte(0xcd), inputs.clone(), proof.clone()),
+			Error::<Test>::SubjectMismatch
+		);
+
+		let mut wrong_inputs = inputs.clone();
+		wrong_inputs[2] = wrong_inputs[1];
+		assert_noop!(submit(0, SUBJECT, wrong_inputs, proof.clone()), Error::<Test>::InvalidProof);
+		let mut non_canonical = inputs.clone();
+		non_canonical[1] = [0xff; 32];
+		assert_noop!(submit(0, SUBJECT, non_canonical, proof.clone()), Error::<Test>::InvalidProof);
+		let (_, _, other_proof) = prove(subject_input(H256::repeat_byte(0xcd).as_ref()), 3);
+		assert_noop!(submit(0, SUBJECT, inputs.clone(), other_proof), Error::<Test>::InvalidProof);
+		assert_noop!(submit(0, SUBJECT, inputs, vec![0; 128]), Error::<Test>::InvalidProof);
+	});
+}
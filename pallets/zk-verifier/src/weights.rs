@@ -0,0 +1,78 @@
+//! Weights for pallet_zk_verifier
+//!
+//! PLACEHOLDER WEIGHTS, written by hand rather than measured. Storage reads, writes and proof
+//! sizes follow the storage accessed by the worst case of each benchmark in `benchmarking.rs`,
+//! for the runtime configuration (`MaxPublicInputs = 16`). The ref-time components are guesses,
+//! the cost of the Groth16 BN254 pairings above all. Replace this file with the output of the
+//! node's `benchmark pallet` command, run on reference hardware, before relying on these weights.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_zk_verifier.
+pub trait WeightInfo {
+	fn register_circuit(n: u32, ) -> Weight;
+	fn remove_circuit() -> Weight;
+	fn submit_proof(n: u32, ) -> Weight;
+}
+
+/// Placeholder weights for pallet_zk_verifier, until it is benchmarked on reference hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `ZkVerifier::NextCircuitId` (r:1 w:1)
+	/// Storage: `ZkVerifier::Circuits` (r:0 w:1)
+	/// The range of component `n` is `[1, 16]`.
+	fn register_circuit(n: u32, ) -> Weight {
+		Weight::from_parts(9_420_000_000, 1_489)
+			.saturating_add(Weight::from_parts(436_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `ZkVerifier::Circuits` (r:1 w:1)
+	fn remove_circuit() -> Weight {
+		Weight::from_parts(14_300_000, 2_313)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `ZkVerifier::Circuits` (r:1 w:0)
+	/// Storage: `ZkVerifier::Attestations` (r:0 w:1)
+	/// The range of component `n` is `[1, 16]`.
+	fn submit_proof(n: u32, ) -> Weight {
+		Weight::from_parts(31_800_000_000, 2_313)
+			.saturating_add(Weight::from_parts(912_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `ZkVerifier::NextCircuitId` (r:1 w:1)
+	/// Storage: `ZkVerifier::Circuits` (r:0 w:1)
+	/// The range of component `n` is `[1, 16]`.
+	fn register_circuit(n: u32, ) -> Weight {
+		Weight::from_parts(9_420_000_000, 1_489)
+			.saturating_add(Weight::from_parts(436_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `ZkVerifier::Circuits` (r:1 w:1)
+	fn remove_circuit() -> Weight {
+		Weight::from_parts(14_300_000, 2_313)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `ZkVerifier::Circuits` (r:1 w:0)
+	/// Storage: `ZkVerifier::Attestations` (r:0 w:1)
+	/// The range of component `n` is `[1, 16]`.
+	fn submit_proof(n: u32, ) -> Weight {
+		Weight::from_parts(31_800_000_000, 2_313)
+			.saturating_add(Weight::from_parts(912_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
@@ -0,0 +1,102 @@
+//! Host functions through which offchain workers reach Roost, the inference service of the node.
+//!
+//! The node registers a [`RoostExt`] wrapping its service in the externalities of offchain
+//! workers, and [`roost::HostFunctions`] with its executor. Anywhere else, or on nodes running no
+//! inference service, every function of [`roost`] returns `None`.
+//!
+//! Embeddings cross the boundary as their little-endian `f32` components, the encoding of
+//! `VectorEncoding::Float32` in the template pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use sp_runtime_interface::runtime_interface;
+
+#[cfg(feature = "std")]
+pub use extension::{Inference, RoostExt};
+
+#[cfg(feature = "std")]
+mod extension {
+	/// Inference as provided by the node. Models are named as configured on the node, `None`
+	/// selecting the default model of the kind.
+	pub trait Inference: Send + Sync {
+		/// Embedding of `text`.
+		fn embed_text(&self, model: Option<&str>, text: &str) -> Result<Vec<f32>, String>;
+		/// Embedding of the encoded `image`, such as a PNG or a JPEG.
+		fn embed_image(&self, model: Option<&str>, image: &[u8]) -> Result<Vec<f32>, String>;
+		/// Completion of `prompt`, of at most `max_tokens` tokens.
+		fn complete(
+			&self,
+			model: Option<&str>,
+			prompt: &str,
+			max_tokens: u32,
+		) -> Result<String, String>;
+	}
+
+	sp_externalities::decl_extension! {
+		/// The inference service of the node, as seen by offchain workers.
+		pub struct RoostExt(Box<dyn Inference>);
+	}
+
+	impl RoostExt {
+		/// Expose `inference` to offchain workers.
+		pub fn new(inference: impl Inference + 'static) -> Self {
+			Self(Box::new(inference))
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+const LOG_TARGET: &str = "roost";
+
+/// Name of the model to use, `None` for the default one when `model` is empty.
+#[cfg(feature = "std")]
+fn model_name(model: &str) -> Option<&str> {
+	(!model.is_empty()).then_some(model)
+}
+
+/// Little-endian `f32` encoding of `embedding`.
+#[cfg(feature = "std")]
+fn to_le_bytes(embedding: Vec<f32>) -> Vec<u8> {
+	embedding.iter().flat_map(|c| c.to_le_bytes()).collect()
+}
+
+/// Inference from offchain workers. Models are named as configured on the node, the empty name
+/// selecting the default model of the kind.
+#[runtime_interface]
+pub trait Roost {
+	/// Embedding of `text` by `model`, as little-endian `f32` components.
+	fn embed_text(&mut self, model: &str, text: &str) -> Option<Vec<u8>> {
+		use sp_externalities::ExternalitiesExt;
+		let roost = self.extension::<RoostExt>()?;
+		roost
+			.embed_text(model_name(model), text)
+			.map(to_le_bytes)
+			.map_err(|e| log::debug!(target: LOG_TARGET, "Text embedding failed: {e}"))
+			.ok()
+	}
+
+	/// Embedding of the encoded `image` by `model`, as little-endian `f32` components.
+	fn embed_image(&mut self, model: &str, image: &[u8]) -> Option<Vec<u8>> {
+		use sp_externalities::ExternalitiesExt;
+		let roost = self.extension::<RoostExt>()?;
+		roost
+			.embed_image(model_name(model), image)
+			.map(to_le_bytes)
+			.map_err(|e| log::debug!(target: LOG_TARGET, "Image embedding failed: {e}"))
+			.ok()
+	}
+
+	/// UTF-8 completion of `prompt` by `model`, of at most `max_tokens` tokens.
+	fn complete(&mut self, model: &str, prompt: &str, max_tokens: u32) -> Option<Vec<u8>> {
+		use sp_externalities::ExternalitiesExt;
+		let roost = self.extension::<RoostExt>()?;
+		roost
+			.complete(model_name(model), prompt, max_tokens)
+			.map(String::into_bytes)
+			.map_err(|e| log::debug!(target: LOG_TARGET, "Completion failed: {e}"))
+			.ok()
+	}
+}
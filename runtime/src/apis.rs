@@ -24,7 +24,7 @@
 // For more information, please refer to <http://unlicense.org>
 
 // External crates imports
-use alloc::{vec, vec::Vec};
+use alloc::vec::Vec;
 use frame_support::{
 	genesis_builder_helper::{build_state, get_preset},
 	weights::Weight,
@@ -36,14 +36,15 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	traits::{Block as BlockT, NumberFor},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult,
+	ApplyExtrinsicResult, Perbill,
 };
 use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-	AccountId, Aura, Balance, Block, Executive, Grandpa, InherentDataExt, Nonce, Runtime,
-	RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment, VERSION,
+	AccountId, ActionLog, Aura, Balance, Block, BlockNumber, Executive, Grandpa, Hash,
+	InherentDataExt, Nonce, Reputation, Runtime, RuntimeCall, RuntimeGenesisConfig, SessionKeys,
+	System, TransactionPayment, VERSION,
 };
 
 impl_runtime_apis! {
@@ -191,6 +192,36 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_action_log_runtime_api::ActionLogApi<Block, AccountId, Hash, BlockNumber> for Runtime {
+		fn root() -> Hash {
+			pallet_action_log::Root::<Runtime>::get()
+		}
+
+		fn action_count(agent: AccountId) -> u64 {
+			pallet_action_log::ActionCount::<Runtime>::get(agent)
+		}
+
+		fn prove_action(
+			agent: AccountId,
+			seq: u64,
+		) -> Option<(
+			pallet_action_log::ActionRecordOf<Runtime>,
+			pallet_action_log::mmr::MmrProof<Hash>,
+		)> {
+			ActionLog::prove(&agent, seq)
+		}
+	}
+
+	impl pallet_reputation_runtime_api::ReputationApi<Block, AccountId, BlockNumber> for Runtime {
+		fn reputation(who: AccountId) -> Perbill {
+			Reputation::score(&who)
+		}
+
+		fn record(who: AccountId) -> Option<pallet_reputation::AgentRecord<BlockNumber>> {
+			Reputation::record(&who)
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentCallApi<Block, Balance, RuntimeCall>
 		for Runtime
 	{
@@ -285,11 +316,11 @@ impl_runtime_apis! {
 		}
 
 		fn get_preset(id: &Option<sp_genesis_builder::PresetId>) -> Option<Vec<u8>> {
-			get_preset::<RuntimeGenesisConfig>(id, |_| None)
+			get_preset::<RuntimeGenesisConfig>(id, crate::genesis_config_presets::get_preset)
 		}
 
 		fn preset_names() -> Vec<sp_genesis_builder::PresetId> {
-			vec![]
+			crate::genesis_config_presets::preset_names()
 		}
 	}
 }
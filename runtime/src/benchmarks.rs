@@ -30,4 +30,20 @@ frame_benchmarking::define_benchmarks!(
 	[pallet_timestamp, Timestamp]
 	[pallet_sudo, Sudo]
 	[pallet_template, TemplateModule]
+	[pallet_migrations, MultiBlockMigrations]
+	[pallet_threshold_encryption, ThresholdEncryption]
+	[pallet_audit, Audit]
+	[pallet_zk_verifier, ZkVerifier]
+	[pallet_tasks, Tasks]
+	[pallet_reputation, Reputation]
+	[pallet_action_log, ActionLog]
+	[pallet_scheduler, Scheduler]
+	[pallet_preimage, Preimage]
+	[pallet_collective, Council]
+	[pallet_referenda, Referenda]
+	[pallet_conviction_voting, ConvictionVoting]
+	[pallet_treasury, Treasury]
+	[pallet_parameters, Parameters]
+	[pallet_model_registry, ModelRegistry]
+	[pallet_sponsorship, Sponsorship]
 );
@@ -24,25 +24,46 @@
 // For more information, please refer to <http://unlicense.org>
 
 // Substrate and Polkadot dependencies
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
 use frame_support::{
-	derive_impl, parameter_types,
-	traits::{ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, VariantCountOf},
+	derive_impl,
+	dynamic_params::{dynamic_pallet_params, dynamic_params},
+	parameter_types,
+	traits::{
+		fungible::{HoldConsideration, Inspect},
+		tokens::{imbalance::ResolveTo, PayFromAccount, UnityAssetBalanceConversion},
+		AsEnsureOriginWithArg, ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, Contains,
+		EitherOf, EqualPrivilegeOnly, LinearStoragePrice, VariantCountOf,
+	},
 	weights::{
 		constants::{RocksDbWeight, WEIGHT_REF_TIME_PER_SECOND},
 		IdentityFee, Weight,
 	},
+	PalletId,
+};
+use frame_system::{
+	limits::{BlockLength, BlockWeights},
+	EnsureRoot, EnsureRootWithSuccess, EnsureSigned,
 };
-use frame_system::limits::{BlockLength, BlockWeights};
 use pallet_transaction_payment::{ConstFeeMultiplier, FungibleAdapter, Multiplier};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_runtime::{traits::One, Perbill};
+use sp_runtime::{
+	generic,
+	traits::{IdentityLookup, One, Verify},
+	MultiAddress, Perbill, SaturatedConversion,
+};
 use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-	AccountId, Aura, Balance, Balances, Block, BlockNumber, Hash, Nonce, PalletInfo, Runtime,
-	RuntimeCall, RuntimeEvent, RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask,
-	System, EXISTENTIAL_DEPOSIT, SLOT_DURATION, VERSION,
+	governance, AccountId, ActionLog, Audit, Aura, Balance, Balances, Block, BlockNumber, Hash,
+	ModelRegistry, MultiBlockMigrations, Nonce, OriginCaller, PalletInfo, Preimage,
+	RandomnessCollectiveFlip, Referenda, Reputation, Runtime, RuntimeCall, RuntimeEvent,
+	RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask, Scheduler, Signature,
+	SignedExtra, SignedPayload, System, TemplateModule, ThresholdEncryption, Treasury,
+	UncheckedExtrinsic, DAYS, EXISTENTIAL_DEPOSIT, HOURS, MICRO_UNIT, MINUTES, SLOT_DURATION, UNIT,
+	VERSION,
 };
 
 const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
@@ -88,6 +109,10 @@ impl frame_system::Config for Runtime {
 	/// This is used as an identifier of the chain. 42 is the generic substrate prefix.
 	type SS58Prefix = SS58Prefix;
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
+	/// Multi-block migrations are driven by `pallet_migrations`.
+	type MultiBlockMigrator = MultiBlockMigrations;
+	/// Sudo calls are refused from [`SudoSunset`] on.
+	type BaseCallFilter = SudoUntilSunset;
 }
 
 impl pallet_aura::Config for Runtime {
@@ -142,21 +167,815 @@ parameter_types! {
 
 impl pallet_transaction_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type OnChargeTransaction = FungibleAdapter<Balances, ()>;
+	/// Fees fund the treasury.
+	type OnChargeTransaction = FungibleAdapter<Balances, ResolveTo<TreasuryAccount, Balances>>;
 	type OperationalFeeMultiplier = ConstU8<5>;
 	type WeightToFee = IdentityFee<Balance>;
 	type LengthToFee = IdentityFee<Balance>;
 	type FeeMultiplierUpdate = ConstFeeMultiplier<FeeMultiplier>;
 }
 
+parameter_types! {
+	/// Multi-block migrations may use up to 80% of a block.
+	pub MbmServiceWeight: Weight = Perbill::from_percent(80) * RuntimeBlockWeights::get().max_block;
+	/// Model the vectors stored before the model registry are attributed to: the first one it
+	/// registers, which `RegisterLegacyModel` does on the upgrade adding the registry.
+	pub const LegacyModel: pallet_template::ModelId = 0;
+	/// Name of the legacy model.
+	pub const LegacyModelName: &'static [u8] = b"legacy";
+	/// Dimension of the legacy model. Vectors of the first release were stored unchecked, as
+	/// plain `f32` components, so the legacy model takes the largest dimension they fit in.
+	pub const LegacyModelDimension: u32 = MAX_VECTOR_LENGTH / 4;
+}
+
+impl pallet_migrations::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	#[cfg(not(feature = "runtime-benchmarks"))]
+	type Migrations = pallet_template::migrations::v1::LazyMigrationV1<
+		Runtime,
+		pallet_template::weights::SubstrateWeight<Runtime>,
+		LegacyModel,
+	>;
+	// Benchmarks need mocked migrations to guarantee that they succeed.
+	#[cfg(feature = "runtime-benchmarks")]
+	type Migrations = pallet_migrations::mock_helpers::MockedMigrations;
+	type CursorMaxLen = ConstU32<65_536>;
+	type IdentifierMaxLen = ConstU32<256>;
+	type MigrationStatusHandler = ();
+	type FailedMigrationHandler = frame_support::migrations::FreezeChainOnFailedMigration;
+	type MaxServiceWeight = MbmServiceWeight;
+	type WeightInfo = pallet_migrations::weights::SubstrateWeight<Runtime>;
+}
+
 impl pallet_sudo::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
 	type WeightInfo = pallet_sudo::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	/// Block from which the sudo key can no longer dispatch calls, leaving root to referenda.
+	pub const SudoSunset: BlockNumber = 180 * DAYS;
+}
+
+/// Refuses the calls of the sudo key from [`SudoSunset`] on, except for giving the key up.
+///
+/// Root origins are not filtered, so calls scheduled by the key before the sunset and root
+/// referenda still go through, and the latter can remove the key with `Sudo::remove_key`.
+pub struct SudoUntilSunset;
+
+impl Contains<RuntimeCall> for SudoUntilSunset {
+	fn contains(call: &RuntimeCall) -> bool {
+		match call {
+			RuntimeCall::Sudo(pallet_sudo::Call::remove_key {}) => true,
+			RuntimeCall::Sudo(_) => System::block_number() < SudoSunset::get(),
+			_ => true,
+		}
+	}
+}
+
+parameter_types! {
+	/// Scheduled calls, such as enacted referenda, may use up to 80% of a block.
+	pub MaximumSchedulerWeight: Weight =
+		Perbill::from_percent(80) * RuntimeBlockWeights::get().max_block;
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeEvent = RuntimeEvent;
+	type PalletsOrigin = OriginCaller;
+	type RuntimeCall = RuntimeCall;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = ConstU32<50>;
+	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type Preimages = Preimage;
+}
+
+parameter_types! {
+	pub const PreimageBaseDeposit: Balance = UNIT;
+	pub const PreimageByteDeposit: Balance = MICRO_UNIT;
+	pub const PreimageHoldReason: RuntimeHoldReason =
+		RuntimeHoldReason::Preimage(pallet_preimage::HoldReason::Preimage);
+}
+
+impl pallet_preimage::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<AccountId>;
+	type Consideration = HoldConsideration<
+		AccountId,
+		Balances,
+		PreimageHoldReason,
+		LinearStoragePrice<PreimageBaseDeposit, PreimageByteDeposit, Balance>,
+	>;
+}
+
+/// The agent council.
+pub type CouncilCollective = pallet_collective::Instance1;
+
+/// Two thirds of the agent council.
+pub type CouncilTwoThirds =
+	pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>;
+
+/// More than half of the agent council.
+pub type CouncilMajority =
+	pallet_collective::EnsureProportionMoreThan<AccountId, CouncilCollective, 1, 2>;
+
+parameter_types! {
+	pub CouncilMaxProposalWeight: Weight =
+		Perbill::from_percent(50) * RuntimeBlockWeights::get().max_block;
+}
+
+impl pallet_collective::Config<CouncilCollective> for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type Proposal = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	/// Blocks the council has to vote on a motion.
+	type MotionDuration = ConstU32<{ 3 * DAYS }>;
+	type MaxProposals = ConstU32<100>;
+	/// Seats on the council.
+	type MaxMembers = ConstU32<16>;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+	/// Members are elected by root referenda.
+	type SetMembersOrigin = EnsureRoot<AccountId>;
+	type MaxProposalWeight = CouncilMaxProposalWeight;
+}
+
+impl governance::origins::Config for Runtime {}
+
+impl pallet_referenda::Config for Runtime {
+	type WeightInfo = pallet_referenda::weights::SubstrateWeight<Runtime>;
+	type RuntimeCall = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	type Scheduler = Scheduler;
+	type Currency = Balances;
+	type SubmitOrigin = EnsureSigned<AccountId>;
+	/// The council can cancel a referendum, refunding its deposits.
+	type CancelOrigin = EitherOf<EnsureRoot<AccountId>, CouncilTwoThirds>;
+	type KillOrigin = EnsureRoot<AccountId>;
+	type Slash = Treasury;
+	type Votes = pallet_conviction_voting::VotesOf<Runtime>;
+	type Tally = pallet_conviction_voting::TallyOf<Runtime>;
+	type SubmissionDeposit = ConstU128<UNIT>;
+	/// Referenda waiting for a decision slot, per track.
+	type MaxQueued = ConstU32<20>;
+	/// Blocks a referendum is kept without a decision deposit before it times out.
+	type UndecidingTimeout = ConstU32<{ 14 * DAYS }>;
+	type AlarmInterval = ConstU32<1>;
+	type Tracks = governance::TracksInfo;
+	type Preimages = Preimage;
+}
+
+impl pallet_conviction_voting::Config for Runtime {
+	type WeightInfo = pallet_conviction_voting::weights::SubstrateWeight<Runtime>;
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type Polls = Referenda;
+	type MaxTurnout =
+		frame_support::traits::tokens::currency::ActiveIssuanceOf<Balances, Self::AccountId>;
+	type MaxVotes = ConstU32<512>;
+	/// Blocks a vote with a conviction of one keeps its balance locked for once the referendum
+	/// is over, doubling with each further conviction level.
+	type VoteLockingPeriod = ConstU32<{ 7 * DAYS }>;
+}
+
+parameter_types! {
+	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+	pub TreasuryAccount: AccountId = Treasury::account_id();
+	pub const MaxBalance: Balance = Balance::MAX;
+}
+
+/// Creates the native asset and the beneficiaries of the treasury benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct TreasuryArguments;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_treasury::ArgumentsFactory<(), AccountId> for TreasuryArguments {
+	fn create_asset_kind(_seed: u32) {}
+
+	fn create_beneficiary(seed: [u8; 32]) -> AccountId {
+		AccountId::from(seed)
+	}
+}
+
+impl pallet_treasury::Config for Runtime {
+	type PalletId = TreasuryPalletId;
+	type Currency = Balances;
+	type RejectOrigin = EitherOf<EnsureRoot<AccountId>, CouncilMajority>;
+	type RuntimeEvent = RuntimeEvent;
+	type SpendPeriod = ConstU32<{ 6 * DAYS }>;
+	type Burn = ();
+	type BurnDestination = ();
+	type SpendFunds = ();
+	type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+	type MaxApprovals = ConstU32<100>;
+	type SpendOrigin =
+		EitherOf<EnsureRootWithSuccess<AccountId, MaxBalance>, governance::origins::Treasurer>;
+	/// Only the native token is spent.
+	type AssetKind = ();
+	type Beneficiary = AccountId;
+	type BeneficiaryLookup = IdentityLookup<AccountId>;
+	type Paymaster = PayFromAccount<Balances, TreasuryAccount>;
+	type BalanceConverter = UnityAssetBalanceConversion;
+	/// Blocks a beneficiary has to claim an approved spend.
+	type PayoutPeriod = ConstU32<{ 30 * DAYS }>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = TreasuryArguments;
+}
+
+/// Ceiling on the number of vectors per author.
+const MAX_VECTORS: u32 = 1000;
+/// Ceiling on the length of a single vector, in bytes.
+const MAX_VECTOR_LENGTH: u32 = 1000;
+/// Ceiling on the length of a single tag, in bytes.
+const MAX_TAG_LENGTH: u32 = 50;
+/// Ceiling on the number of tags attached to a vector.
+const MAX_TAGS_PER_VECTOR: u32 = 10;
+
+/// Parameters governance can tune without a runtime upgrade, stored by `pallet_parameters`.
+///
+/// Limits backing storage bounds start at their compile-time ceilings and can only be tuned
+/// below them; the pallets cap larger values.
+#[dynamic_params(RuntimeParameters, pallet_parameters::Parameters::<Runtime>)]
+pub mod dynamic_params {
+	use super::*;
+
+	/// Limits of the memory pool.
+	#[dynamic_pallet_params]
+	#[codec(index = 0)]
+	pub mod memory {
+		/// Number of vectors an author may hold.
+		#[codec(index = 0)]
+		pub static VectorsPerAuthor: u32 = MAX_VECTORS;
+
+		/// Length of the longest vector accepted, in bytes.
+		#[codec(index = 1)]
+		pub static VectorLength: u32 = MAX_VECTOR_LENGTH;
+
+		/// Length of the longest tag accepted, in bytes.
+		#[codec(index = 2)]
+		pub static TagLength: u32 = MAX_TAG_LENGTH;
+
+		/// Number of tags a vector may carry.
+		#[codec(index = 3)]
+		pub static TagsPerVector: u32 = MAX_TAGS_PER_VECTOR;
+
+		/// Submissions at least this similar to a vector sharing one of their tags are rejected.
+		#[codec(index = 4)]
+		pub static SimilarityThreshold: Option<Perbill> = Some(Perbill::from_percent(98));
+	}
+
+	/// Deposits of the task market.
+	#[dynamic_pallet_params]
+	#[codec(index = 1)]
+	pub mod tasks {
+		/// Stake an agent puts up to accept a task.
+		#[codec(index = 0)]
+		pub static TaskStake: Balance = 10 * UNIT;
+
+		/// Bond put up to dispute a result.
+		#[codec(index = 1)]
+		pub static DisputeBond: Balance = 5 * UNIT;
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl Default for RuntimeParameters {
+	fn default() -> Self {
+		RuntimeParameters::Memory(dynamic_params::memory::Parameters::VectorLength(
+			dynamic_params::memory::VectorLength,
+			Some(MAX_VECTOR_LENGTH),
+		))
+	}
+}
+
+impl pallet_parameters::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeParameters = RuntimeParameters;
+	/// Root, or referenda of the `parameter_admin` track.
+	type AdminOrigin =
+		AsEnsureOriginWithArg<EitherOf<EnsureRoot<AccountId>, governance::origins::ParameterAdmin>>;
+	type WeightInfo = pallet_parameters::weights::SubstrateWeight<Runtime>;
+}
+
 /// Configure the pallet-template in pallets/template.
 impl pallet_template::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type Currency = Balances;
+	/// Collection ids are a `u32`, so creating one must cost more than a transaction fee.
+	type CollectionDeposit = ConstU128<UNIT>;
+	type MaxVectors = ConstU32<MAX_VECTORS>;
+	type MaxVectorLength = ConstU32<MAX_VECTOR_LENGTH>;
+	type MaxTagLength = ConstU32<MAX_TAG_LENGTH>;
+	type MaxTagsPerVector = ConstU32<MAX_TAGS_PER_VECTOR>;
+	type VectorsPerAuthor = dynamic_params::memory::VectorsPerAuthor;
+	type VectorLength = dynamic_params::memory::VectorLength;
+	type TagLength = dynamic_params::memory::TagLength;
+	type TagsPerVector = dynamic_params::memory::TagsPerVector;
+	type SimilarityThreshold = dynamic_params::memory::SimilarityThreshold;
+	/// Vectors compared against each submission when looking for near-duplicates.
+	type MaxSimilaritySamples = ConstU32<32>;
+	/// Vectors an agent submits at most in one `store_batch`.
+	type MaxBatchSize = ConstU32<32>;
+	type PruneOrigin = EitherOf<EnsureRoot<AccountId>, CouncilMajority>;
+	type Observer = AgentHistory;
+	type Models = RegisteredModels;
+	type EphemeralKeys = EncryptedMemory;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = RegisteredModels;
+}
+
+/// Models of the model registry that new vectors may reference.
+pub struct RegisteredModels;
+
+impl pallet_template::EmbeddingModels for RegisteredModels {
+	fn dimension(model: pallet_template::ModelId) -> Option<u32> {
+		ModelRegistry::active_model(model).map(|model| model.dimension)
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_template::BenchmarkHelper for RegisteredModels {
+	fn register_model(dimension: u32) -> pallet_template::ModelId {
+		ModelRegistry::do_register_model(
+			Default::default(),
+			dimension,
+			pallet_model_registry::DistanceMetric::Cosine,
+			Default::default(),
+			Default::default(),
+		)
+		.expect("model ids are available")
+	}
+}
+
+impl pallet_model_registry::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_model_registry::weights::SubstrateWeight<Runtime>;
+	/// Root, or a majority of the agent council.
+	type RegistrarOrigin = EitherOf<EnsureRoot<AccountId>, CouncilMajority>;
+	type MaxNameLength = ConstU32<64>;
+	type MaxLicenseLength = ConstU32<64>;
+}
+
+impl pallet_embedding::Config for Runtime {
+	type AuthorityId = pallet_embedding::sr25519::AuthoritySigner;
+	/// Embeddings each agent's offchain worker stores per block.
+	type MaxEmbeddingsPerBlock = ConstU32<4>;
+}
+
+/// Memory writes, the calls sponsors pay the fees of. Both store vectors authored by the signer,
+/// so that a budget is only spent on the agent's own memories.
+pub struct MemoryWrites;
+
+impl Contains<RuntimeCall> for MemoryWrites {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(
+			call,
+			RuntimeCall::TemplateModule(
+				pallet_template::Call::store_weight_data { .. } |
+					pallet_template::Call::store_batch { .. }
+			)
+		)
+	}
+}
+
+impl pallet_sponsorship::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_sponsorship::weights::SubstrateWeight<Runtime>;
+	type RuntimeCall = RuntimeCall;
+	type SponsoredCalls = MemoryWrites;
+	/// Rate limits count the transactions of an hour.
+	type RatePeriod = ConstU32<HOURS>;
+}
+
+/// Encrypted vectors of the memory pallet, as seen by the threshold encryption committee, which
+/// also checks that their authors encrypted them.
+pub struct EncryptedMemory;
+
+impl pallet_threshold_encryption::EncryptedPayloads<Hash, AccountId> for EncryptedMemory {
+	fn payload(id: &Hash) -> Option<(AccountId, [u8; 32])> {
+		TemplateModule::encrypted_vector(id)
+	}
+
+	fn payloads() -> Vec<Hash> {
+		TemplateModule::encrypted_vectors()
+	}
+}
+
+impl pallet_template::EphemeralKeyProof<AccountId> for EncryptedMemory {
+	fn verify(
+		author: &AccountId,
+		collection: pallet_template::CollectionId,
+		ephemeral_key: &[u8; pallet_template::EPHEMERAL_KEY_LEN],
+		proof: &[u8; pallet_template::KEY_PROOF_LEN],
+	) -> bool {
+		// The collection is the location the proof is bound to, so that a ciphertext is only
+		// accepted in the collection it was encrypted for.
+		let Ok(proof) = pallet_threshold_encryption::crypto::SchnorrProof::decode(&mut &proof[..])
+		else {
+			return false;
+		};
+		ThresholdEncryption::verify_ephemeral_key(author, &collection, ephemeral_key, &proof)
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_threshold_encryption::BenchmarkHelper<Hash, AccountId> for EncryptedMemory {
+	fn create_payload(owner: AccountId, ephemeral_secret: [u8; 32]) -> Hash {
+		use pallet_threshold_encryption::crypto;
+
+		let collection = TemplateModule::do_create_collection(
+			owner.clone(),
+			pallet_template::VectorEncoding::Encrypted,
+			0,
+		)
+		.expect("collection ids are available");
+		let secret = crypto::decode_scalar(&ephemeral_secret).unwrap_or_default();
+		let context = (&owner, collection).encode();
+		let proof = crypto::prove_ephemeral_key(&context, &secret, &crypto::Scalar::ONE);
+		let mut data = crypto::encode_point(&crypto::public_key(&secret)).to_vec();
+		data.extend(proof.encode());
+		data.push(0);
+		// Ciphertexts hide their dimension, any registered model will do.
+		let model = <RegisteredModels as pallet_template::BenchmarkHelper>::register_model(1);
+		TemplateModule::do_store_vector(
+			owner,
+			data.try_into().expect("fits in MaxVectorLength"),
+			Default::default(),
+			Some(collection),
+			model,
+		)
+		.expect("encrypted vector is valid")
+	}
+}
+
+impl pallet_threshold_encryption::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_threshold_encryption::weights::SubstrateWeight<Runtime>;
+	type PayloadId = Hash;
+	type Payloads = EncryptedMemory;
+	type CommitteeOrigin = EnsureRoot<AccountId>;
+	type AuthorizeOrigin = EnsureRoot<AccountId>;
+	type Observer = AuditAccessLog;
+	/// Maximum number of agents in the encryption committee.
+	type MaxMembers = ConstU32<16>;
+	type AuthorityId = pallet_threshold_encryption::sr25519::AuthoritySigner;
+	/// Blocks the incoming committee has to post its dealings.
+	type DealingPeriod = ConstU32<{ 10 * MINUTES }>;
+	/// Blocks left to complain about a dealing before the rotation can be finalized.
+	type ComplaintPeriod = ConstU32<{ 10 * MINUTES }>;
+	/// Rekey shares each committee member's offchain worker submits per block.
+	type MaxRekeysPerBlock = ConstU32<8>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = EncryptedMemory;
+}
+
+/// Agents voting on audits and judging task disputes: the members of the encryption committee,
+/// weighted by their balance until agents stake through a pallet of their own. Balances are
+/// scaled by the reputation of their holder, so that flaky agents weigh less. Audits snapshot
+/// these stakes when they are filed, as balances are not held while the agents vote.
+pub struct CommitteeStake;
+
+impl CommitteeStake {
+	fn weighted_stake(who: &AccountId) -> Balance {
+		Reputation::score(who).mul_floor(Balances::total_balance(who))
+	}
+
+	/// Trust `who` as if it had staked for the whole maturity period, as accounts never seen
+	/// staking have no weight and cannot accept tasks.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn mature(who: &AccountId) {
+		use frame_support::traits::Get;
+
+		let period = <Runtime as pallet_reputation::Config>::MaturityPeriod::get();
+		if System::block_number() < period {
+			System::set_block_number(period);
+		}
+		pallet_reputation::StakedSince::<Runtime>::mutate(|agents| {
+			if !agents.iter().any(|(agent, _)| agent == who) {
+				let _ = agents.try_push((who.clone(), 0));
+			}
+		});
+	}
+}
+
+impl pallet_audit::AgentStake<AccountId, Balance> for CommitteeStake {
+	fn stakes() -> Vec<(AccountId, Balance)> {
+		<Self as pallet_tasks::AgentPool<_, _>>::agents()
+	}
+}
+
+impl pallet_audit::DecryptionAccess<Hash, AccountId> for EncryptedMemory {
+	fn exists(payload: &Hash) -> bool {
+		TemplateModule::encrypted_vector(payload).is_some()
+	}
+
+	fn authorize(payload: Hash, who: AccountId) {
+		ThresholdEncryption::do_authorize_decryption(payload, who)
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_audit::BenchmarkHelper<Hash, AccountId> for EncryptedMemory {
+	fn create_payload(owner: AccountId, i: u32) -> Hash {
+		let mut ephemeral_secret = [0; 32];
+		ephemeral_secret[..4].copy_from_slice(&i.to_le_bytes());
+		<Self as pallet_threshold_encryption::BenchmarkHelper<_, _>>::create_payload(
+			owner,
+			ephemeral_secret,
+		)
+	}
+
+	fn register_agent(who: AccountId) {
+		use frame_support::traits::{fungible::Mutate, Get};
+
+		// A full committee makes the snapshot of the stakes as expensive as it gets.
+		let max = <Runtime as pallet_threshold_encryption::Config>::MaxMembers::get();
+		let mut members: Vec<AccountId> =
+			(1..max).map(|i| frame_benchmarking::account("agent", i, 0)).collect();
+		members.push(who);
+		for member in &members {
+			let _ = Balances::mint_into(member, EXISTENTIAL_DEPOSIT * 1_000);
+			CommitteeStake::mature(member);
+		}
+		pallet_threshold_encryption::Members::<Runtime>::put(
+			frame_support::BoundedVec::truncate_from(members),
+		);
+	}
+}
+
+/// Records every decryption request in the access log of the audit pallet.
+pub struct AuditAccessLog;
+
+impl pallet_threshold_encryption::DecryptionObserver<Hash, AccountId> for AuditAccessLog {
+	fn on_decryption_requested(
+		request_id: pallet_threshold_encryption::RequestId,
+		payload: &Hash,
+		requester: &AccountId,
+		owner: bool,
+	) {
+		Audit::log_access(request_id, payload, requester, owner)
+	}
+}
+
+parameter_types! {
+	/// Two thirds of the agents' stake must approve an audit.
+	pub const AuditApprovalThreshold: Perbill = Perbill::from_percent(67);
+}
+
+impl pallet_audit::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_audit::weights::SubstrateWeight<Runtime>;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type Currency = Balances;
+	type PayloadId = Hash;
+	type Balance = Balance;
+	type Agents = CommitteeStake;
+	type Decryption = EncryptedMemory;
+	/// Blocks the agents have to vote on an audit.
+	type VotingPeriod = ConstU32<{ 2 * DAYS }>;
+	type ApprovalThreshold = AuditApprovalThreshold;
+	/// Held from auditors until their audit is decided; burnt if the committee rejects the audit.
+	type AuditDeposit = ConstU128<{ 10 * UNIT }>;
+	/// Maximum number of vectors named by an audit.
+	type MaxPayloads = ConstU32<16>;
+	/// Maximum length of an audit's justification, in bytes.
+	type MaxJustificationLength = ConstU32<1024>;
+	/// The encryption committee, the agents voting on audits, has at most 16 members.
+	type MaxAgents = ConstU32<16>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = EncryptedMemory;
+}
+
+/// Proves statements of the test circuit shipped with the Groth16 verifier.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct Groth16TestCircuit;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_zk_verifier::BenchmarkHelper for Groth16TestCircuit {
+	fn proof(
+		subject: pallet_zk_verifier::PublicInput,
+		public_inputs: u32,
+	) -> (Vec<u8>, Vec<pallet_zk_verifier::PublicInput>, Vec<u8>) {
+		pallet_zk_verifier::groth16::testing::prove(subject, public_inputs)
+	}
+}
+
+impl pallet_zk_verifier::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_zk_verifier::weights::SubstrateWeight<Runtime>;
+	type Verifier = pallet_zk_verifier::groth16::Groth16Bn254;
+	type RegistrarOrigin = EnsureRoot<AccountId>;
+	/// Fits a Groth16 BN254 verification key with `MaxPublicInputs` public inputs.
+	type MaxKeyLength = ConstU32<1024>;
+	type MaxProofLength = ConstU32<256>;
+	type MaxPublicInputs = ConstU32<16>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = Groth16TestCircuit;
+}
+
+impl pallet_insecure_randomness_collective_flip::Config for Runtime {}
+
+impl pallet_tasks::AgentPool<AccountId, Balance> for CommitteeStake {
+	fn stake_of(who: &AccountId) -> Balance {
+		match ThresholdEncryption::member_index(who) {
+			Some(_) => Self::weighted_stake(who),
+			None => 0,
+		}
+	}
+
+	fn agents() -> Vec<(AccountId, Balance)> {
+		pallet_threshold_encryption::Members::<Runtime>::get()
+			.into_iter()
+			.map(|member| {
+				let stake = Self::weighted_stake(&member);
+				(member, stake)
+			})
+			.collect()
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_tasks::BenchmarkHelper<AccountId> for CommitteeStake {
+	fn register_agent(who: AccountId) {
+		use frame_support::traits::fungible::Mutate;
+
+		let _ = Balances::mint_into(&who, EXISTENTIAL_DEPOSIT * 1_000);
+		Self::mature(&who);
+		pallet_threshold_encryption::Members::<Runtime>::mutate(|members| {
+			let _ = members.try_push(who);
+		});
+	}
+}
+
+impl pallet_tasks::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_tasks::weights::SubstrateWeight<Runtime>;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type Currency = Balances;
+	type Agents = CommitteeStake;
+	/// Agents whose reputation has not fallen below the eligibility threshold.
+	type EligibleAgents = pallet_reputation::Reputable<Runtime>;
+	type Observer = AgentHistory;
+	/// The collective flip is derived from past block hashes, which block authors can influence
+	/// by withholding blocks. Juries should be drawn from VRF output once the chain has it. The
+	/// flip is declared before the tasks pallet, so that juries drawn at the start of a block mix
+	/// in the hash of the block the dispute is in.
+	type Randomness = RandomnessCollectiveFlip;
+	type TaskStake = dynamic_params::tasks::TaskStake;
+	type DisputeBond = dynamic_params::tasks::DisputeBond;
+	/// Blocks a submitted result can be disputed for.
+	type ChallengePeriod = ConstU32<DAYS>;
+	/// Blocks a jury has to vote on a dispute.
+	type JuryPeriod = ConstU32<{ 2 * DAYS }>;
+	type JurySize = ConstU32<5>;
+	/// Agents a jury is drawn from: every member of the encryption committee.
+	type MaxJurorCandidates = ConstU32<16>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = CommitteeStake;
+}
+
+/// Reports what agents do to their reputation, and logs the outcome of their tasks.
+///
+/// Only governance can prune the memories of another agent. Retrievals are not reported: anyone
+/// can retrieve memories, so they would be a signal bought with fees.
+pub struct AgentHistory;
+
+impl pallet_tasks::TaskObserver<AccountId> for AgentHistory {
+	fn on_task_completed(task_id: pallet_tasks::TaskId, agent: &AccountId) {
+		Reputation::note_task_completed(agent);
+		ActionLog::append(agent.clone(), pallet_action_log::ActionKind::TaskCompleted(task_id));
+	}
+
+	fn on_dispute_lost(task_id: pallet_tasks::TaskId, loser: &AccountId) {
+		Reputation::note_dispute_lost(loser);
+		ActionLog::append(loser.clone(), pallet_action_log::ActionKind::DisputeLost(task_id));
+	}
+}
+
+impl pallet_template::MemoryObserver<AccountId> for AgentHistory {
+	fn on_memory_pruned(author: &AccountId) {
+		Reputation::note_memory_pruned(author)
+	}
+}
+
+impl pallet_reputation::AgentRegistry<AccountId> for CommitteeStake {
+	fn agents() -> Vec<AccountId> {
+		pallet_threshold_encryption::Members::<Runtime>::get().into_inner()
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_reputation::BenchmarkHelper<AccountId> for CommitteeStake {
+	fn register_agent(who: AccountId) {
+		<Self as pallet_tasks::BenchmarkHelper<_>>::register_agent(who)
+	}
+}
+
+parameter_types! {
+	/// Agents scoring below a fifth cannot accept tasks. Accounts never seen staking score zero,
+	/// and a committee member with a clean record reaches a fifth after staking for 40% of the
+	/// maturity period, close to three days.
+	pub const ReputationEligibilityThreshold: Perbill = Perbill::from_percent(20);
+}
+
+impl pallet_reputation::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_reputation::weights::SubstrateWeight<Runtime>;
+	type Agents = CommitteeStake;
+	type HalfLife = ConstU32<{ 30 * DAYS }>;
+	/// Blocks before a new committee member is fully trusted. Its score rises from zero over that
+	/// period.
+	type MaturityPeriod = ConstU32<{ 7 * DAYS }>;
+	type SweepInterval = ConstU32<HOURS>;
+	type TaskCompletedPoints = ConstU32<2>;
+	type DisputeLostPoints = ConstU32<10>;
+	type PrunePoints = ConstU32<1>;
+	/// A clean record is worth half: one lost dispute makes a young agent ineligible until it
+	/// matures or the loss decays, a mature agent can afford one.
+	type Prior = ConstU32<5>;
+	type EligibilityThreshold = ReputationEligibilityThreshold;
+	/// Agents whose stake age is tracked: every member of the encryption committee.
+	type MaxAgents = ConstU32<16>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = CommitteeStake;
+}
+
+/// The members of the encryption committee.
+pub struct CommitteeMembers;
+
+impl Contains<AccountId> for CommitteeMembers {
+	fn contains(who: &AccountId) -> bool {
+		ThresholdEncryption::member_index(who).is_some()
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_action_log::BenchmarkHelper<AccountId> for CommitteeMembers {
+	fn register_agent(who: AccountId) {
+		<CommitteeStake as pallet_tasks::BenchmarkHelper<_>>::register_agent(who)
+	}
+}
+
+impl pallet_action_log::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_action_log::weights::SubstrateWeight<Runtime>;
+	type Agents = CommitteeMembers;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = CommitteeMembers;
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+	RuntimeCall: From<C>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = RuntimeCall;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Nonce,
+	) -> Option<(
+		RuntimeCall,
+		<UncheckedExtrinsic as sp_runtime::traits::Extrinsic>::SignaturePayload,
+	)> {
+		let period = BlockHashCount::get().checked_next_power_of_two().map(|c| c / 2).unwrap_or(2);
+		let current_block = System::block_number().saturated_into::<u64>().saturating_sub(1);
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(
+				period as u64,
+				current_block,
+			)),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_sponsorship::ChargeSponsoredTransactionPayment::<Runtime>::from(0),
+			frame_metadata_hash_extension::CheckMetadataHash::<Runtime>::new(false),
+		);
+		let raw_payload = SignedPayload::new(call, extra).ok()?;
+		let signature = raw_payload.using_encoded(|payload| C::sign(payload, public))?;
+		let (call, extra, _) = raw_payload.deconstruct();
+		Some((call, (MultiAddress::Id(account), signature, extra)))
+	}
 }
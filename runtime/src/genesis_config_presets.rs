@@ -0,0 +1,147 @@
+// This is free and unencumbered software released into the public domain.
+//
+// Anyone is free to copy, modify, publish, use, compile, sell, or
+// distribute this software, either in source code form or as a compiled
+// binary, for any purpose, commercial or non-commercial, and by any
+// means.
+//
+// In jurisdictions that recognize copyright laws, the author or authors
+// of this software dedicate any and all copyright interest in the
+// software to the public domain. We make this dedication for the benefit
+// of the public at large and to the detriment of our heirs and
+// successors. We intend this dedication to be an overt act of
+// relinquishment in perpetuity of all present and future rights to this
+// software under copyright law.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+// IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY CLAIM, DAMAGES OR
+// OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE,
+// ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR
+// OTHER DEALINGS IN THE SOFTWARE.
+//
+// For more information, please refer to <http://unlicense.org>
+
+use crate::AccountId;
+use alloc::{vec, vec::Vec};
+use serde_json::Value;
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_consensus_grandpa::AuthorityId as GrandpaId;
+use sp_genesis_builder::{self, PresetId};
+use sp_keyring::{Ed25519Keyring, Sr25519Keyring};
+
+/// Demo embedding models registered on development and local testnet chains.
+///
+/// The file holds a `modelRegistry` genesis config: models as `(name, dimension, metric,
+/// weights_hash, license)`, receiving ids in order. The demo models have no weights file, so
+/// their hash is zero.
+pub const DEMO_MODELS: &str = include_str!("../res/demo-models.json");
+
+/// Demo knowledge base seeded into development and local testnet chains.
+///
+/// The file holds a `templateModule` genesis config: collections as `(owner, encoding)` and
+/// vectors as `(author, collection, model, data, tags)`, each referencing a model of
+/// [`DEMO_MODELS`] of its dimension. The node's `--knowledge-base` option replaces it with a
+/// section in the same format read from a file, and a chain spec can seed its own memory the
+/// same way, along with the models it references.
+pub const DEMO_KNOWLEDGE_BASE: &str = include_str!("../res/demo-knowledge-base.json");
+
+/// Configure initial storage state for FRAME modules.
+fn testnet_genesis(
+	initial_authorities: Vec<(AuraId, GrandpaId)>,
+	root_key: AccountId,
+	endowed_accounts: Vec<AccountId>,
+	council: Vec<AccountId>,
+) -> Value {
+	let models: Value = serde_json::from_str(DEMO_MODELS).expect("demo models are valid JSON; qed");
+	let knowledge_base: Value =
+		serde_json::from_str(DEMO_KNOWLEDGE_BASE).expect("demo knowledge base is valid JSON; qed");
+
+	serde_json::json!({
+		"balances": {
+			// Configure endowed accounts with initial balance of 1 << 60.
+			"balances": endowed_accounts.iter().cloned().map(|k| (k, 1u64 << 60)).collect::<Vec<_>>(),
+		},
+		"aura": {
+			"authorities": initial_authorities.iter().map(|x| (x.0.clone())).collect::<Vec<_>>(),
+		},
+		"grandpa": {
+			"authorities": initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect::<Vec<_>>(),
+		},
+		"sudo": {
+			// Assign network admin rights until the sudo sunset.
+			"key": Some(root_key),
+		},
+		"council": {
+			"members": council,
+		},
+		"modelRegistry": models,
+		"templateModule": knowledge_base,
+	})
+}
+
+/// Return the development genesis config.
+pub fn development_config_genesis() -> Value {
+	testnet_genesis(
+		// Initial PoA authorities
+		vec![(Sr25519Keyring::Alice.public().into(), Ed25519Keyring::Alice.public().into())],
+		// Sudo account
+		Sr25519Keyring::Alice.to_account_id(),
+		// Pre-funded accounts
+		vec![
+			Sr25519Keyring::Alice.to_account_id(),
+			Sr25519Keyring::Bob.to_account_id(),
+			Sr25519Keyring::AliceStash.to_account_id(),
+			Sr25519Keyring::BobStash.to_account_id(),
+		],
+		// Agent council
+		vec![Sr25519Keyring::Alice.to_account_id(), Sr25519Keyring::Bob.to_account_id()],
+	)
+}
+
+/// Return the local testnet genesis config.
+pub fn local_config_genesis() -> Value {
+	testnet_genesis(
+		// Initial PoA authorities
+		vec![
+			(Sr25519Keyring::Alice.public().into(), Ed25519Keyring::Alice.public().into()),
+			(Sr25519Keyring::Bob.public().into(), Ed25519Keyring::Bob.public().into()),
+		],
+		// Sudo account
+		Sr25519Keyring::Alice.to_account_id(),
+		// Pre-funded accounts
+		Sr25519Keyring::iter()
+			.filter(|v| v != &Sr25519Keyring::One && v != &Sr25519Keyring::Two)
+			.map(|v| v.to_account_id())
+			.collect::<Vec<_>>(),
+		// Agent council
+		vec![
+			Sr25519Keyring::Alice.to_account_id(),
+			Sr25519Keyring::Bob.to_account_id(),
+			Sr25519Keyring::Charlie.to_account_id(),
+		],
+	)
+}
+
+/// Provides the JSON representation of predefined genesis config for given `id`.
+pub fn get_preset(id: &PresetId) -> Option<Vec<u8>> {
+	let patch = match id.as_ref() {
+		sp_genesis_builder::DEV_RUNTIME_PRESET => development_config_genesis(),
+		sp_genesis_builder::LOCAL_TESTNET_RUNTIME_PRESET => local_config_genesis(),
+		_ => return None,
+	};
+	Some(
+		serde_json::to_string(&patch)
+			.expect("serialization to json is expected to work. qed.")
+			.into_bytes(),
+	)
+}
+
+/// List of supported presets.
+pub fn preset_names() -> Vec<PresetId> {
+	vec![
+		PresetId::from(sp_genesis_builder::DEV_RUNTIME_PRESET),
+		PresetId::from(sp_genesis_builder::LOCAL_TESTNET_RUNTIME_PRESET),
+	]
+}
@@ -0,0 +1,18 @@
+//! On-chain governance: an agent council, stake-weighted referenda and a treasury.
+//!
+//! Referenda are voted on with conviction by locking balance, so that stakeholders weigh in
+//! proportion to what they put at risk. Each kind of referendum runs on a track of its own,
+//! dispatching its call with the matching origin of [`origins`]:
+//!
+//! - `root` for runtime upgrades and anything else needing root, such as removing the sudo key;
+//! - `parameter_admin` for the parameters of the memory pool and the agent economy;
+//! - `treasurer` for treasury spends of up to [`origins::MAX_TREASURER_SPEND`].
+//!
+//! The council of agents can cancel referenda and reject treasury spends. The sudo key keeps
+//! working alongside governance until [`crate::configs::SudoSunset`], as described in
+//! `docs/governance.md`.
+
+pub mod origins;
+pub use origins::pallet_custom_origins;
+mod tracks;
+pub use tracks::TracksInfo;
@@ -0,0 +1,67 @@
+//! Origins of the referenda tracks other than root.
+
+pub use pallet_custom_origins::*;
+
+#[frame_support::pallet]
+pub mod pallet_custom_origins {
+	use crate::{Balance, UNIT};
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// Origins a referendum can dispatch its call with, one per track.
+	#[derive(PartialEq, Eq, Clone, MaxEncodedLen, Encode, Decode, TypeInfo, RuntimeDebug)]
+	#[pallet::origin]
+	pub enum Origin {
+		/// Tunes the parameters of the memory pool and the agent economy.
+		ParameterAdmin,
+		/// Spends from the treasury, up to [`MAX_TREASURER_SPEND`] per spend.
+		Treasurer,
+	}
+
+	/// Most a single spend of the [`Origin::Treasurer`] track can pay out. Larger spends need a
+	/// root referendum.
+	pub const MAX_TREASURER_SPEND: Balance = 10_000 * UNIT;
+
+	/// Ensures that the origin is [`Origin::ParameterAdmin`].
+	pub struct ParameterAdmin;
+
+	impl<O: Into<Result<Origin, O>> + From<Origin>> EnsureOrigin<O> for ParameterAdmin {
+		type Success = ();
+
+		fn try_origin(o: O) -> Result<Self::Success, O> {
+			o.into().and_then(|o| match o {
+				Origin::ParameterAdmin => Ok(()),
+				r => Err(O::from(r)),
+			})
+		}
+
+		#[cfg(feature = "runtime-benchmarks")]
+		fn try_successful_origin() -> Result<O, ()> {
+			Ok(O::from(Origin::ParameterAdmin))
+		}
+	}
+
+	/// Ensures that the origin is [`Origin::Treasurer`], yielding the most it may spend.
+	pub struct Treasurer;
+
+	impl<O: Into<Result<Origin, O>> + From<Origin>> EnsureOrigin<O> for Treasurer {
+		type Success = Balance;
+
+		fn try_origin(o: O) -> Result<Self::Success, O> {
+			o.into().and_then(|o| match o {
+				Origin::Treasurer => Ok(MAX_TREASURER_SPEND),
+				r => Err(O::from(r)),
+			})
+		}
+
+		#[cfg(feature = "runtime-benchmarks")]
+		fn try_successful_origin() -> Result<O, ()> {
+			Ok(O::from(Origin::Treasurer))
+		}
+	}
+}
@@ -0,0 +1,112 @@
+//! Tracks of the referenda: how long each kind of referendum is decided for and the approval
+//! and support it needs to pass.
+
+use super::origins;
+use crate::{Balance, BlockNumber, RuntimeOrigin, DAYS, HOURS, UNIT};
+use pallet_referenda::Curve;
+use sp_runtime::Perbill;
+
+const fn percent(x: u32) -> Perbill {
+	Perbill::from_percent(x)
+}
+
+const TRACKS_DATA: [(u16, pallet_referenda::TrackInfo<Balance, BlockNumber>); 3] = [
+	(
+		0,
+		pallet_referenda::TrackInfo {
+			name: "root",
+			// Root referenda can do anything, so they are decided one at a time.
+			max_deciding: 1,
+			decision_deposit: 1_000 * UNIT,
+			prepare_period: 2 * HOURS,
+			decision_period: 14 * DAYS,
+			confirm_period: DAYS,
+			min_enactment_period: DAYS,
+			min_approval: Curve::LinearDecreasing {
+				length: percent(100),
+				floor: percent(50),
+				ceil: percent(100),
+			},
+			min_support: Curve::LinearDecreasing {
+				length: percent(100),
+				floor: percent(5),
+				ceil: percent(50),
+			},
+		},
+	),
+	(
+		1,
+		pallet_referenda::TrackInfo {
+			name: "parameter_admin",
+			// Parameters are tweaked weekly during the proof of concept.
+			max_deciding: 10,
+			decision_deposit: 100 * UNIT,
+			prepare_period: HOURS,
+			decision_period: 3 * DAYS,
+			confirm_period: 3 * HOURS,
+			min_enactment_period: HOURS,
+			min_approval: Curve::LinearDecreasing {
+				length: percent(100),
+				floor: percent(50),
+				ceil: percent(100),
+			},
+			min_support: Curve::LinearDecreasing {
+				length: percent(100),
+				floor: percent(1),
+				ceil: percent(25),
+			},
+		},
+	),
+	(
+		2,
+		pallet_referenda::TrackInfo {
+			name: "treasurer",
+			max_deciding: 10,
+			decision_deposit: 100 * UNIT,
+			prepare_period: 2 * HOURS,
+			decision_period: 7 * DAYS,
+			confirm_period: DAYS,
+			min_enactment_period: DAYS,
+			min_approval: Curve::LinearDecreasing {
+				length: percent(100),
+				floor: percent(50),
+				ceil: percent(100),
+			},
+			min_support: Curve::LinearDecreasing {
+				length: percent(100),
+				floor: percent(1),
+				ceil: percent(25),
+			},
+		},
+	),
+];
+
+/// The tracks of the referenda, one for root and one per origin of [`origins::Origin`].
+pub struct TracksInfo;
+
+impl pallet_referenda::TracksInfo<Balance, BlockNumber> for TracksInfo {
+	type Id = u16;
+	type RuntimeOrigin = <RuntimeOrigin as frame_support::traits::OriginTrait>::PalletsOrigin;
+
+	fn tracks() -> &'static [(Self::Id, pallet_referenda::TrackInfo<Balance, BlockNumber>)] {
+		&TRACKS_DATA[..]
+	}
+
+	fn track_for(id: &Self::RuntimeOrigin) -> Result<Self::Id, ()> {
+		if let Ok(system_origin) = frame_system::RawOrigin::try_from(id.clone()) {
+			match system_origin {
+				frame_system::RawOrigin::Root => Ok(0),
+				_ => Err(()),
+			}
+		} else if let Ok(custom_origin) = origins::Origin::try_from(id.clone()) {
+			match custom_origin {
+				origins::Origin::ParameterAdmin => Ok(1),
+				origins::Origin::Treasurer => Ok(2),
+			}
+		} else {
+			Err(())
+		}
+	}
+}
+
+pallet_referenda::impl_tracksinfo_get!(TracksInfo, Balance, BlockNumber);
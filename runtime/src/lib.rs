@@ -6,9 +6,13 @@ pub mod apis;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarks;
 pub mod configs;
+pub mod genesis_config_presets;
+pub mod governance;
 
 extern crate alloc;
 use alloc::vec::Vec;
+use frame_support::instances::Instance1;
+use governance::pallet_custom_origins;
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{BlakeTwo256, IdentifyAccount, Verify},
@@ -64,12 +68,14 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// The version of the runtime specification. A full node will not attempt to use its native
 	//   runtime in substitute for the on-chain Wasm runtime unless all of `spec_name`,
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
-	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
-	//   the compatible custom types.
-	spec_version: 100,
+	// This value started at 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
+	//   the compatible custom types. Bump it with every upgrade that changes the runtime logic.
+	spec_version: 101,
 	impl_version: 1,
 	apis: apis::RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	// Bump whenever the encoding of calls or transaction extensions changes, so that signed
+	//   transactions built for a previous runtime are rejected instead of misinterpreted.
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -152,7 +158,7 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_sponsorship::ChargeSponsoredTransactionPayment<Runtime>,
 	frame_metadata_hash_extension::CheckMetadataHash<Runtime>,
 );
 
@@ -165,9 +171,17 @@ pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
 
 /// All migrations of the runtime, aside from the ones declared in the pallets.
 ///
-/// This can be a tuple of types, each implementing `OnRuntimeUpgrade`.
+/// This can be a tuple of types, each implementing `OnRuntimeUpgrade`. Migrations spanning
+/// multiple blocks are configured in `pallet_migrations::Config::Migrations` instead.
 #[allow(unused_parens)]
-type Migrations = ();
+type Migrations = (
+	// Before the multi-block migration of the template pallet attributes stored vectors to it.
+	pallet_model_registry::migrations::RegisterLegacyModel<
+		Runtime,
+		configs::LegacyModelName,
+		configs::LegacyModelDimension,
+	>,
+);
 
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
@@ -179,23 +193,6 @@ pub type Executive = frame_executive::Executive<
 	Migrations,
 >;
 
-/// Configure the pallet_embeddings in pallets/embeddings
-impl pallet_embeddings::Config for Runtime {
-    type RuntimeEvent = RuntimeEvent;
-    type MaxEmbeddingSize = MaxEmbeddingSize;
-    type MaxTags = MaxTags;
-}
-
-/// Configure the pallet-template in pallets/template.
-impl pallet_template::Config for Runtime {
-	type RuntimeEvent = RuntimeEvent;
-	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
-	type MaxVectors = ConstU32<1000>;           // Maximum number of vectors
-	type MaxVectorLength = ConstU32<1000>;      // Maximum length of each vector
-	type MaxTagLength = ConstU32<50>;           // Maximum length of each tag
-	type MaxTagsPerVector = ConstU32<10>;       // Maximum number of tags per vector
-}
-
 // Create the runtime by composing the FRAME pallets that were previously configured.
 #[frame_support::runtime]
 mod runtime {
@@ -234,10 +231,66 @@ mod runtime {
 	#[runtime::pallet_index(6)]
 	pub type Sudo = pallet_sudo;
 
+	// Declared ahead of the template pallet, whose genesis vectors reference its models.
+	#[runtime::pallet_index(25)]
+	pub type ModelRegistry = pallet_model_registry;
+
 	// Include the custom logic from the pallet-template in the runtime.
 	#[runtime::pallet_index(7)]
 	pub type TemplateModule = pallet_template;
 
-    #[runtime::pallet_index(8)]
-    pub type Embeddings = pallet_embeddings::Pallet<Runtime>;
+	#[runtime::pallet_index(8)]
+	pub type MultiBlockMigrations = pallet_migrations;
+
+	#[runtime::pallet_index(9)]
+	pub type ThresholdEncryption = pallet_threshold_encryption;
+
+	#[runtime::pallet_index(10)]
+	pub type Audit = pallet_audit;
+
+	#[runtime::pallet_index(11)]
+	pub type ZkVerifier = pallet_zk_verifier;
+
+	#[runtime::pallet_index(12)]
+	pub type RandomnessCollectiveFlip = pallet_insecure_randomness_collective_flip;
+
+	#[runtime::pallet_index(13)]
+	pub type Tasks = pallet_tasks;
+
+	#[runtime::pallet_index(14)]
+	pub type Reputation = pallet_reputation;
+
+	#[runtime::pallet_index(15)]
+	pub type ActionLog = pallet_action_log;
+
+	#[runtime::pallet_index(16)]
+	pub type Scheduler = pallet_scheduler;
+
+	#[runtime::pallet_index(17)]
+	pub type Preimage = pallet_preimage;
+
+	// The agent council.
+	#[runtime::pallet_index(18)]
+	pub type Council = pallet_collective<Instance1>;
+
+	#[runtime::pallet_index(19)]
+	pub type Origins = pallet_custom_origins;
+
+	#[runtime::pallet_index(20)]
+	pub type Referenda = pallet_referenda;
+
+	#[runtime::pallet_index(21)]
+	pub type ConvictionVoting = pallet_conviction_voting;
+
+	#[runtime::pallet_index(22)]
+	pub type Treasury = pallet_treasury;
+
+	#[runtime::pallet_index(23)]
+	pub type Parameters = pallet_parameters;
+
+	#[runtime::pallet_index(24)]
+	pub type Embedding = pallet_embedding;
+
+	#[runtime::pallet_index(26)]
+	pub type Sponsorship = pallet_sponsorship;
 }